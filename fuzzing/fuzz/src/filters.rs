@@ -28,9 +28,11 @@ fn run_text(filter: Text<'_>) -> Result<(), rinja::Error> {
         TextFilter::Capitalize => filters::capitalize(input)?.to_string(),
         TextFilter::Center(a) => filters::center(input, a)?.to_string(),
         TextFilter::Indent(a) => filters::indent(input, a)?.to_string(),
+        TextFilter::IndentWith(a, b, c) => filters::indent_with(input, a, b, c)?.to_string(),
         TextFilter::Linebreaks => filters::linebreaks(input)?.to_string(),
         TextFilter::LinebreaksBr => filters::linebreaksbr(input)?.to_string(),
         TextFilter::Lowercase => filters::lowercase(input)?.to_string(),
+        TextFilter::Nl2br => filters::nl2br(input)?.to_string(),
         TextFilter::ParagraphBreaks => filters::paragraphbreaks(input)?.to_string(),
         TextFilter::Safe(e) => match e {
             Escaper::Html => filters::safe(input, filters::Html)?.to_string(),
@@ -53,9 +55,13 @@ impl fmt::Display for Scenario<'_> {
             TextFilter::Capitalize => format!("capitalize({input:?})"),
             TextFilter::Center(a) => format!("center({input:?}, {a:?})"),
             TextFilter::Indent(a) => format!("indent({input:?}, {a:?})"),
+            TextFilter::IndentWith(a, b, c) => {
+                format!("indent_with({input:?}, {a:?}, {b:?}, {c:?})")
+            }
             TextFilter::Linebreaks => format!("linebreaks({input:?})"),
             TextFilter::LinebreaksBr => format!("linebreaksbr({input:?})"),
             TextFilter::Lowercase => format!("lowercase({input:?})"),
+            TextFilter::Nl2br => format!("nl2br({input:?})"),
             TextFilter::ParagraphBreaks => format!("paragraphbreaks({input:?})"),
             TextFilter::Safe(e) => match e {
                 Escaper::Html => format!("safe({input:?}, filters::Html)"),
@@ -93,9 +99,11 @@ enum TextFilter {
     Capitalize,
     Center(usize),
     Indent(usize),
+    IndentWith(usize, bool, bool),
     Linebreaks,
     LinebreaksBr,
     Lowercase,
+    Nl2br,
     ParagraphBreaks,
     Safe(Escaper),
     Title,
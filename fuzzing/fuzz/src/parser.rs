@@ -25,6 +25,7 @@ impl<'a> super::Scenario<'a> for Scenario<'a> {
                 expr_end: syntax[3],
                 comment_start: syntax[4],
                 comment_end: syntax[5],
+                whitespace: None,
             }
             .to_syntax()
             .map_err(|_| arbitrary::Error::IncorrectFormat)?
@@ -68,6 +69,7 @@ SyntaxBuilder {{
         expr_end: {expr_end:?},
         comment_start: {comment_start:?},
         comment_end: {comment_end:?},
+        whitespace: None,
     }}.to_syntax().unwrap()",
             ))
         };
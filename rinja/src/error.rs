@@ -25,6 +25,79 @@ pub enum Error {
     /// JSON conversion error
     #[cfg(feature = "serde_json")]
     Json(serde_json::Error),
+    /// A key referenced as `values.<key>` in a template was not passed to
+    /// [`Template::render_with_values()`][crate::Template::render_with_values()]
+    ValueMissing(&'static str),
+    /// A filter like `first` or `last` was applied to an empty iterable
+    EmptyIterable,
+    /// [`Template::render_block()`][crate::Template::render_block()] was called with a name
+    /// that is not listed in [`Template::BLOCKS`][crate::Template::BLOCKS]
+    UnknownBlock,
+    /// The `attr` filter was applied to a value that has no attribute with the given name
+    #[cfg(feature = "alloc")]
+    UnknownAttribute(alloc::string::String),
+    /// The `xmlattr` filter was applied to a key that is not a valid attribute name
+    #[cfg(feature = "alloc")]
+    InvalidAttributeName(alloc::string::String),
+    /// An error returned by a filter, together with the location of the filter call
+    ///
+    /// Errors are only wrapped in this variant if `locate_filter_errors` is enabled in the
+    /// configuration file. Use [`Error::inner()`] to get the error that the filter returned.
+    #[cfg(feature = "alloc")]
+    Located(Box<Error>, Location),
+}
+
+/// The location of a filter call in a template, see [`Error::Located`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    path: &'static str,
+    offset: usize,
+    row: usize,
+    column: usize,
+}
+
+impl Location {
+    #[doc(hidden)]
+    #[inline]
+    pub const fn new(path: &'static str, offset: usize, row: usize, column: usize) -> Self {
+        Self {
+            path,
+            offset,
+            row,
+            column,
+        }
+    }
+
+    /// The path of the template file relative to the crate root, or the name of the struct for
+    /// templates given as `source`
+    #[inline]
+    pub const fn path(&self) -> &'static str {
+        self.path
+    }
+
+    /// The byte offset of the filter call in the template source
+    #[inline]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The line of the filter call, starting at 1
+    #[inline]
+    pub const fn row(&self) -> usize {
+        self.row
+    }
+
+    /// The column of the filter call, counted in characters and starting at 0
+    #[inline]
+    pub const fn column(&self) -> usize {
+        self.column
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.path, self.row, self.column)
+    }
 }
 
 impl Error {
@@ -35,6 +108,36 @@ impl Error {
         Self::Custom(err.into())
     }
 
+    /// The location of the filter call that returned this error, if known
+    #[inline]
+    pub fn location(&self) -> Option<Location> {
+        match self {
+            #[cfg(feature = "alloc")]
+            Error::Located(_, location) => Some(*location),
+            _ => None,
+        }
+    }
+
+    /// The error without its location, i.e. the error inside of an [`Error::Located`]
+    #[inline]
+    pub fn inner(&self) -> &Error {
+        match self {
+            #[cfg(feature = "alloc")]
+            Error::Located(err, _) => err,
+            err => err,
+        }
+    }
+
+    /// Converts this error into the error without its location, see [`Error::inner()`]
+    #[inline]
+    pub fn into_inner(self) -> Error {
+        match self {
+            #[cfg(feature = "alloc")]
+            Error::Located(err, _) => *err,
+            err => err,
+        }
+    }
+
     /// Convert this [`Error`] into a
     /// <code>[Box]&lt;dyn [StdError] + [Send] + [Sync]&gt;</code>
     #[cfg(feature = "alloc")]
@@ -44,6 +147,12 @@ impl Error {
             Error::Custom(err) => err,
             #[cfg(feature = "serde_json")]
             Error::Json(err) => err.into(),
+            err @ (Error::ValueMissing(_)
+            | Error::EmptyIterable
+            | Error::UnknownBlock
+            | Error::UnknownAttribute(_)
+            | Error::InvalidAttributeName(_)) => alloc::string::ToString::to_string(&err).into(),
+            err @ Error::Located(..) => Box::new(err),
         }
     }
 
@@ -70,6 +179,11 @@ impl StdError for Error {
             Error::Custom(err) => Some(err.as_ref()),
             #[cfg(feature = "serde_json")]
             Error::Json(err) => Some(err),
+            Error::ValueMissing(_) | Error::EmptyIterable | Error::UnknownBlock => None,
+            #[cfg(feature = "alloc")]
+            Error::UnknownAttribute(_) | Error::InvalidAttributeName(_) => None,
+            #[cfg(feature = "alloc")]
+            Error::Located(err, _) => err.source(),
         }
     }
 }
@@ -82,6 +196,15 @@ impl fmt::Display for Error {
             Error::Custom(err) => err.fmt(f),
             #[cfg(feature = "serde_json")]
             Error::Json(err) => err.fmt(f),
+            Error::ValueMissing(key) => write!(f, "missing template value `{key}`"),
+            Error::EmptyIterable => f.write_str("cannot get an item of an empty iterable"),
+            Error::UnknownBlock => f.write_str("the template has no block with this name"),
+            #[cfg(feature = "alloc")]
+            Error::UnknownAttribute(name) => write!(f, "unknown attribute `{name}`"),
+            #[cfg(feature = "alloc")]
+            Error::InvalidAttributeName(name) => write!(f, "invalid attribute name {name:?}"),
+            #[cfg(feature = "alloc")]
+            Error::Located(err, location) => write!(f, "{location}: {err}"),
         }
     }
 }
@@ -242,8 +365,8 @@ impl From<Infallible> for Error {
 
 #[cfg(test)]
 const _: () = {
-    trait AssertSendSyncStatic: Send + Sync + 'static {}
-    impl AssertSendSyncStatic for Error {}
+    const fn assert_send_sync_static<T: Send + Sync + 'static>() {}
+    assert_send_sync_static::<Error>();
 };
 
 /// Helper trait to convert a custom `?` call into a [`crate::Result`]
@@ -1,5 +1,8 @@
 use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::Infallible;
 use core::fmt::{self, Write};
+use core::hash::Hash;
 
 use super::MAX_LEN;
 use super::escape::HtmlSafeOutput;
@@ -158,6 +161,39 @@ pub fn linebreaksbr(s: impl fmt::Display) -> Result<HtmlSafeOutput<String>, fmt:
     Ok(HtmlSafeOutput(linebreaksbr(try_to_str!(s => buffer))))
 }
 
+/// Converts all newlines in a piece of plain text to `<br>` followed by a newline
+///
+/// Windows line endings `\r\n` are normalized to `\n`. Unlike [`linebreaksbr()`], the
+/// line breaks are kept in the output, so the generated HTML stays readable.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ lines|nl2br }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     lines: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { lines: "a\r\n<b>" }.to_string(),
+///     "<div>a<br>\n&#60;b&#62;</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn nl2br(s: impl fmt::Display) -> Result<HtmlSafeOutput<String>, fmt::Error> {
+    fn nl2br(s: &str) -> String {
+        s.replace("\r\n", "\n").replace('\n', "<br>\n")
+    }
+
+    let mut buffer;
+    Ok(HtmlSafeOutput(nl2br(try_to_str!(s => buffer))))
+}
+
 /// Replaces only paragraph breaks in plain text with appropriate HTML
 ///
 /// A new line followed by a blank line becomes a paragraph break `<p>`.
@@ -193,6 +229,159 @@ pub fn paragraphbreaks(s: impl fmt::Display) -> Result<HtmlSafeOutput<String>, f
     Ok(HtmlSafeOutput(paragraphbreaks(try_to_str!(s => buffer))))
 }
 
+/// Converts URLs in plain text into clickable links
+///
+/// Words starting with `http://`, `https://` or `www.` are wrapped in an `<a href="…">` tag.
+/// Punctuation around the URL, like in `(see https://example.com).`, is not part of the link.
+///
+/// If `trim_url_limit` is `Some(n)`, then the displayed URL is truncated to `n` characters,
+/// ending in an ellipsis `…`. If `nofollow` is `true`, then `rel="nofollow"` is added to the link.
+/// If `target` is not empty, then it is used as the `target` attribute of the link, and
+/// `rel="noopener"` is added.
+///
+/// The input is expected to be HTML escaped, which the template code does automatically.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ comment|urlize }}</div>
+/// /// <div>{{ comment|urlize(20, nofollow=true, target="_blank") }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     comment: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { comment: "<3 www.rinja.rs, https://rinja.readthedocs.io/" }.to_string(),
+///     "<div>&#60;3 <a href=\"https://www.rinja.rs\">www.rinja.rs</a>, \
+///      <a href=\"https://rinja.readthedocs.io/\">https://rinja.readthedocs.io/</a></div>\n\
+///      <div>&#60;3 <a href=\"https://www.rinja.rs\" rel=\"nofollow noopener\" \
+///      target=\"_blank\">www.rinja.rs</a>, <a href=\"https://rinja.readthedocs.io/\" \
+///      rel=\"nofollow noopener\" target=\"_blank\">https://rinja.readt…</a></div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn urlize(
+    s: impl fmt::Display,
+    trim_url_limit: Option<usize>,
+    nofollow: bool,
+    target: impl fmt::Display,
+) -> Result<HtmlSafeOutput<String>, fmt::Error> {
+    let mut buffer;
+    let s = try_to_str!(s => buffer);
+    let mut target_buffer;
+    let target = try_to_str!(target => target_buffer);
+
+    let mut attrs = String::new();
+    if nofollow || !target.is_empty() {
+        attrs.push_str(" rel=\"");
+        attrs.push_str(match (nofollow, target.is_empty()) {
+            (true, true) => "nofollow",
+            (true, false) => "nofollow noopener",
+            (false, _) => "noopener",
+        });
+        attrs.push('"');
+    }
+    if !target.is_empty() {
+        attrs.push_str(" target=\"");
+        crate::html::write_escaped_str(&mut attrs, target)?;
+        attrs.push('"');
+    }
+
+    let mut output = String::with_capacity(s.len());
+    for word in s.split_inclusive(char::is_whitespace) {
+        let word_end = word.trim_end_matches(char::is_whitespace).len();
+        let (word, space) = word.split_at(word_end);
+        urlize_word(&mut output, word, trim_url_limit, &attrs);
+        output.push_str(space);
+    }
+    Ok(HtmlSafeOutput(output))
+}
+
+fn urlize_word(output: &mut String, word: &str, trim_url_limit: Option<usize>, attrs: &str) {
+    // The input is HTML escaped, so `<`, `>`, `"` and `'` are represented by their entities.
+    // The entities must be tested before `;`.
+    const LEAD: &[&str] = &["&#60;", "&#34;", "&#39;", "("];
+    const TRAIL: &[&str] = &["&#62;", "&#34;", "&#39;", ".", ",", ":", ";", "!", "?", ")"];
+
+    let mut middle = word;
+    while let Some(rest) = LEAD.iter().find_map(|lead| middle.strip_prefix(lead)) {
+        middle = rest;
+    }
+    let lead = &word[..word.len() - middle.len()];
+    while let Some(rest) = TRAIL.iter().find_map(|trail| middle.strip_suffix(trail)) {
+        // a closing parenthesis is part of the URL if it closes a parenthesis in the URL
+        if rest.len() + 1 == middle.len()
+            && middle.ends_with(')')
+            && middle.matches('(').count() >= middle.matches(')').count()
+        {
+            break;
+        }
+        middle = rest;
+    }
+    let trail = &word[lead.len() + middle.len()..];
+
+    let scheme = if ["http://", "https://"].iter().any(|scheme| {
+        middle
+            .strip_prefix(scheme)
+            .is_some_and(|rest| !rest.is_empty())
+    }) {
+        ""
+    } else if middle.len() > 4 && middle.starts_with("www.") {
+        "https://"
+    } else {
+        output.push_str(word);
+        return;
+    };
+
+    output.push_str(lead);
+    output.push_str("<a href=\"");
+    output.push_str(scheme);
+    output.push_str(middle);
+    output.push('"');
+    output.push_str(attrs);
+    output.push('>');
+    match trim_url_limit {
+        Some(limit) => truncate_escaped(output, middle, limit),
+        None => output.push_str(middle),
+    }
+    output.push_str("</a>");
+    output.push_str(trail);
+}
+
+/// Appends at most `limit` characters of the HTML escaped `s`, counting every entity as a single
+/// character, and ending in `…` if the text was truncated.
+fn truncate_escaped(output: &mut String, s: &str, limit: usize) {
+    let mut units = Vec::new();
+    let mut rest = s;
+    while let Some(c) = rest.chars().next() {
+        let len = match rest.strip_prefix("&#") {
+            Some(entity) => match entity.find(';') {
+                Some(end) if end > 0 && entity[..end].bytes().all(|b| b.is_ascii_digit()) => {
+                    end + 3
+                }
+                _ => c.len_utf8(),
+            },
+            None => c.len_utf8(),
+        };
+        units.push(&rest[..len]);
+        rest = &rest[len..];
+    }
+
+    if units.len() <= limit {
+        output.push_str(s);
+    } else {
+        for unit in &units[..limit.saturating_sub(1)] {
+            output.push_str(unit);
+        }
+        output.push('…');
+    }
+}
+
 /// Converts to lowercase
 ///
 /// ```
@@ -355,8 +544,138 @@ pub fn trim<T: fmt::Display>(s: T) -> Result<String> {
     Ok(s)
 }
 
+/// Strip leading and trailing characters that are contained in `chars`
+///
+/// `chars` is treated as a set of characters, e.g. `"/ "` strips slashes and spaces.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <a href="/{{ path|trim("/") }}/">link</a>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     path: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { path: "//docs/" }.to_string(),
+///     "<a href=\"/docs/\">link</a>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn trim_chars(s: impl fmt::Display, chars: impl fmt::Display) -> Result<String, fmt::Error> {
+    trim_with(s, Some(chars), true, true)
+}
+
+/// Strip leading whitespace
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ example|trim_start }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: " Hello\tworld\t" }.to_string(),
+///     "<div>Hello\tworld\t</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn trim_start(s: impl fmt::Display) -> Result<String, fmt::Error> {
+    trim_with(s, None::<&str>, true, false)
+}
+
+/// Strip leading characters that are contained in `chars`
+///
+/// `chars` is treated as a set of characters, like in [`trim_chars()`].
+#[inline]
+pub fn trim_start_chars(
+    s: impl fmt::Display,
+    chars: impl fmt::Display,
+) -> Result<String, fmt::Error> {
+    trim_with(s, Some(chars), true, false)
+}
+
+/// Strip trailing whitespace
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ example|trim_end }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: " Hello\tworld\t" }.to_string(),
+///     "<div> Hello\tworld</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn trim_end(s: impl fmt::Display) -> Result<String, fmt::Error> {
+    trim_with(s, None::<&str>, false, true)
+}
+
+/// Strip trailing characters that are contained in `chars`
+///
+/// `chars` is treated as a set of characters, like in [`trim_chars()`].
+#[inline]
+pub fn trim_end_chars(
+    s: impl fmt::Display,
+    chars: impl fmt::Display,
+) -> Result<String, fmt::Error> {
+    trim_with(s, Some(chars), false, true)
+}
+
+fn trim_with(
+    s: impl fmt::Display,
+    chars: Option<impl fmt::Display>,
+    start: bool,
+    end: bool,
+) -> Result<String, fmt::Error> {
+    let mut buffer;
+    let s = try_to_str!(s => buffer);
+    let mut chars_buffer;
+    let chars = match &chars {
+        Some(chars) => Some(try_to_str!(chars => chars_buffer)),
+        None => None,
+    };
+
+    let is_trimmed = |c: char| match chars {
+        Some(chars) => chars.contains(c),
+        None => c.is_whitespace(),
+    };
+    let mut s = s;
+    if start {
+        s = s.trim_start_matches(is_trimmed);
+    }
+    if end {
+        s = s.trim_end_matches(is_trimmed);
+    }
+    Ok(String::from(s))
+}
+
 /// Indent lines with `width` spaces
 ///
+/// The first line is not indented, but blank lines are. A trailing newline is kept as is.
+/// Use [`indent_with()`] to indent the first line, too, or to skip blank lines.
+///
 /// ```
 /// # #[cfg(feature = "code-in-doc")] {
 /// # use rinja::Template;
@@ -370,14 +689,54 @@ pub fn trim<T: fmt::Display>(s: T) -> Result<String> {
 /// }
 ///
 /// assert_eq!(
-///     Example { example: "hello\nfoo\nbar" }.to_string(),
-///     "<div>hello\n    foo\n    bar</div>"
+///     Example { example: "hello\nfoo\n\nbar" }.to_string(),
+///     "<div>hello\n    foo\n    \n    bar</div>"
 /// );
 /// # }
 /// ```
 #[inline]
 pub fn indent(s: impl fmt::Display, width: usize) -> Result<String, fmt::Error> {
-    fn indent(args: fmt::Arguments<'_>, width: usize) -> Result<String, fmt::Error> {
+    indent_with(s, width, false, true)
+}
+
+/// Indent lines with `width` spaces, optionally including the first line and blank lines
+///
+/// This is the implementation of the `indent` filter if it is called with the arguments
+/// `first=true` to indent the first line, too, or `blank=false` to leave blank lines empty.
+/// `width` defaults to 4, and `blank` defaults to `true`. A trailing newline is kept as is.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ example|indent(2, first=true) }}</div>
+/// /// <div>{{ example|indent(2, blank=false) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "hello\nfoo\n\nbar" }.to_string(),
+///     "<div>  hello\n  foo\n  \n  bar</div>\n<div>hello\n  foo\n\n  bar</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn indent_with(
+    s: impl fmt::Display,
+    width: usize,
+    first: bool,
+    blank: bool,
+) -> Result<String, fmt::Error> {
+    fn indent(
+        args: fmt::Arguments<'_>,
+        width: usize,
+        first: bool,
+        blank: bool,
+    ) -> Result<String, fmt::Error> {
         let mut buffer = String::new();
         let s = if width >= MAX_LEN {
             buffer.write_fmt(args)?;
@@ -396,28 +755,41 @@ pub fn indent(s: impl fmt::Display, width: usize) -> Result<String, fmt::Error>
             buffer.as_str()
         };
 
+        // A trailing newline does not start a new line that could be indented.
+        let (s, trailing_newline) = match s.strip_suffix('\n') {
+            Some(s) => (s, true),
+            None => (s, false),
+        };
         let mut indented = String::new();
-        for (i, c) in s.char_indices() {
-            indented.push(c);
-
-            if c == '\n' && i < s.len() - 1 {
+        for (i, line) in s.split('\n').enumerate() {
+            if i > 0 {
+                indented.push('\n');
+            }
+            if (i > 0 || first) && (blank || !line.is_empty()) {
                 for _ in 0..width {
                     indented.push(' ');
                 }
             }
+            indented.push_str(line);
+        }
+        if trailing_newline {
+            indented.push('\n');
         }
         Ok(indented)
     }
-    indent(format_args!("{s}"), width)
+    indent(format_args!("{s}"), width, first, blank)
 }
 
-/// Capitalize a value. The first character will be uppercase, all others lowercase.
+/// Replaces all occurrences of `from` with `to`
+///
+/// If `from` is empty, the value is returned unchanged. Use [`replacen()`] to replace only the
+/// first occurrences.
 ///
 /// ```
 /// # #[cfg(feature = "code-in-doc")] {
 /// # use rinja::Template;
 /// /// ```jinja
-/// /// <div>{{ example|capitalize }}</div>
+/// /// <div>{{ example|replace("o", "0") }}</div>
 /// /// ```
 /// #[derive(Template)]
 /// #[template(ext = "html", in_doc = true)]
@@ -426,108 +798,889 @@ pub fn indent(s: impl fmt::Display, width: usize) -> Result<String, fmt::Error>
 /// }
 ///
 /// assert_eq!(
-///     Example { example: "hello" }.to_string(),
-///     "<div>Hello</div>"
+///     Example { example: "foo boo" }.to_string(),
+///     "<div>f00 b00</div>"
 /// );
+/// # }
+/// ```
+#[inline]
+pub fn replace(
+    src: impl fmt::Display,
+    from: impl fmt::Display,
+    to: impl fmt::Display,
+) -> Result<String, fmt::Error> {
+    replacen(src, from, to, usize::MAX)
+}
+
+/// Replaces the first `count` occurrences of `from` with `to`
+///
+/// If `from` is empty, the value is returned unchanged.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ example|replace("o", "0", 1) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
 ///
 /// assert_eq!(
-///     Example { example: "hElLO" }.to_string(),
-///     "<div>Hello</div>"
+///     Example { example: "foo boo" }.to_string(),
+///     "<div>f0o boo</div>"
 /// );
 /// # }
 /// ```
 #[inline]
-pub fn capitalize(s: impl fmt::Display) -> Result<String, fmt::Error> {
-    fn capitalize(s: &str) -> Result<String, fmt::Error> {
-        let mut chars = s.chars();
-        if let Some(c) = chars.next() {
-            let mut replacement = String::with_capacity(s.len());
-            replacement.extend(c.to_uppercase());
-            replacement.push_str(&chars.as_str().to_lowercase());
-            Ok(replacement)
-        } else {
-            Ok(String::new())
+pub fn replacen(
+    src: impl fmt::Display,
+    from: impl fmt::Display,
+    to: impl fmt::Display,
+    count: usize,
+) -> Result<String, fmt::Error> {
+    fn replacen(src: &str, from: &str, to: &str, count: usize) -> String {
+        // An empty pattern would match between all characters.
+        match from.is_empty() {
+            true => src.into(),
+            false => src.replacen(from, to, count),
         }
     }
 
-    let mut buffer;
-    capitalize(try_to_str!(s => buffer))
+    let (mut buffer, mut from_buffer, mut to_buffer);
+    Ok(replacen(
+        try_to_str!(src => buffer),
+        try_to_str!(from => from_buffer),
+        try_to_str!(to => to_buffer),
+        count,
+    ))
 }
 
-/// Count the words in that string.
+/// Sort a map, or any other iterable of key-value pairs, by key
+///
+/// The sorting is stable. Use `dictsort(by="value")` to sort by value instead, and
+/// `dictsort(reverse=true)` to sort in descending order.
 ///
 /// ```
 /// # #[cfg(feature = "code-in-doc")] {
+/// # use std::collections::HashMap;
 /// # use rinja::Template;
 /// /// ```jinja
-/// /// <div>{{ example|wordcount }}</div>
+/// /// {% for (k, v) in example|dictsort %}{{ k }}={{ v }} {% endfor %}
 /// /// ```
 /// #[derive(Template)]
 /// #[template(ext = "html", in_doc = true)]
-/// struct Example<'a> {
-///     example: &'a str,
+/// struct Example {
+///     example: HashMap<&'static str, u32>,
 /// }
 ///
 /// assert_eq!(
-///     Example { example: "rinja is sort of cool" }.to_string(),
-///     "<div>5</div>"
+///     Example { example: HashMap::from([("b", 1), ("a", 2)]) }.to_string(),
+///     "a=2 b=1 "
 /// );
 /// # }
 /// ```
-pub fn wordcount(s: impl fmt::Display) -> Result<usize, fmt::Error> {
-    let mut buffer;
-    Ok(try_to_str!(s => buffer).split_whitespace().count())
+#[inline]
+pub fn dictsort<I, K, V>(iter: I, reverse: bool) -> Result<Vec<(K, V)>, Infallible>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Ord,
+{
+    let mut items = iter.into_iter().collect::<Vec<_>>();
+    match reverse {
+        false => items.sort_by(|(a, _), (b, _)| a.cmp(b)),
+        true => items.sort_by(|(a, _), (b, _)| b.cmp(a)),
+    }
+    Ok(items)
 }
 
-/// Return a title cased version of the value. Words will start with uppercase letters, all
-/// remaining characters are lowercase.
+/// Sort a map, or any other iterable of key-value pairs, by value
+///
+/// This function is used for `dictsort(by="value")`. Please see [`dictsort()`].
+#[inline]
+pub fn dictsort_by_value<I, K, V>(iter: I, reverse: bool) -> Result<Vec<(K, V)>, Infallible>
+where
+    I: IntoIterator<Item = (K, V)>,
+    V: Ord,
+{
+    let mut items = iter.into_iter().collect::<Vec<_>>();
+    match reverse {
+        false => items.sort_by(|(_, a), (_, b)| a.cmp(b)),
+        true => items.sort_by(|(_, a), (_, b)| b.cmp(a)),
+    }
+    Ok(items)
+}
+
+/// Sort the elements of an iterable
+///
+/// The sort is stable, i.e. equal elements keep their order, even with `reverse=true`.
+/// Use `sort(attr="field")` to sort the elements by one of their fields instead, and
+/// `sort(case_sensitive=false)` to compare strings (or the displayed elements)
+/// case-insensitively. The elements or the fields need to implement [`Ord`], so e.g. numbers are
+/// sorted numerically.
 ///
 /// ```
 /// # #[cfg(feature = "code-in-doc")] {
 /// # use rinja::Template;
 /// /// ```jinja
-/// /// <div>{{ example|title }}</div>
+/// /// {% for x in numbers|sort(reverse=true) %}{{ x }} {% endfor %}
+/// /// {{ names|sort(case_sensitive=false)|join(",") }}
 /// /// ```
 /// #[derive(Template)]
 /// #[template(ext = "html", in_doc = true)]
 /// struct Example<'a> {
-///     example: &'a str,
+///     numbers: &'a [u32],
+///     names: &'a [&'a str],
 /// }
 ///
 /// assert_eq!(
-///     Example { example: "hello WORLD" }.to_string(),
-///     "<div>Hello World</div>"
+///     Example { numbers: &[2, 10, 1], names: &["bob", "Alice", "carol"] }.to_string(),
+///     "10 2 1 \nAlice,bob,carol"
 /// );
 /// # }
 /// ```
-pub fn title(s: impl fmt::Display) -> Result<String, fmt::Error> {
-    let mut buffer;
-    let s = try_to_str!(s => buffer);
-    let mut need_capitalization = true;
+#[inline]
+pub fn sort<I>(iter: I, reverse: bool) -> Result<Vec<I::Item>, Infallible>
+where
+    I: IntoIterator,
+    I::Item: Ord,
+{
+    let mut items = iter.into_iter().collect::<Vec<_>>();
+    match reverse {
+        false => items.sort(),
+        true => items.sort_by(|a, b| b.cmp(a)),
+    }
+    Ok(items)
+}
 
-    // Sadly enough, we can't mutate a string when iterating over its chars, likely because it could
-    // change the size of a char, "breaking" the char indices.
-    let mut output = String::with_capacity(s.len());
-    for c in s.chars() {
-        if c.is_whitespace() {
-            output.push(c);
-            need_capitalization = true;
-        } else if need_capitalization {
-            match c.is_uppercase() {
-                true => output.push(c),
-                false => output.extend(c.to_uppercase()),
-            }
-            need_capitalization = false;
-        } else {
-            match c.is_lowercase() {
-                true => output.push(c),
-                false => output.extend(c.to_lowercase()),
+/// Sort the elements of an iterable by their `key`
+///
+/// This function is used by the [`sort()`] filter if an `attr` or `case_sensitive` argument
+/// is given. The `key` is calculated only once per element.
+#[inline]
+pub fn sort_by_key<I, F, K>(iter: I, key: F, reverse: bool) -> Result<Vec<I::Item>, Infallible>
+where
+    I: IntoIterator,
+    F: FnMut(&I::Item) -> K,
+    K: Ord,
+{
+    let items = iter.into_iter().collect::<Vec<_>>();
+    let mut keys = items.iter().map(key).enumerate().collect::<Vec<_>>();
+    // The index breaks ties, so the unstable sort gives a stable result.
+    match reverse {
+        false => keys.sort_unstable_by(|(i, a), (j, b)| a.cmp(b).then(i.cmp(j))),
+        true => keys.sort_unstable_by(|(i, a), (j, b)| b.cmp(a).then(i.cmp(j))),
+    }
+    let mut items = items.into_iter().map(Some).collect::<Vec<_>>();
+    Ok(keys
+        .into_iter()
+        .filter_map(|(index, _)| items[index].take())
+        .collect())
+}
+
+/// Remove duplicate elements of an iterable, keeping the first occurrence of each element
+///
+/// Use `unique(attr="field")` to compare the elements by one of their fields instead, and
+/// `unique(case_sensitive=false)` to compare strings (or the displayed elements) case-insensitively.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// {% for tag in tags|unique %}{{ tag }} {% endfor %}
+/// /// {{ tags|unique(case_sensitive=false)|join(",") }}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     tags: &'a [&'a str],
+/// }
+///
+/// assert_eq!(
+///     Example { tags: &["rust", "web", "Rust", "rust"] }.to_string(),
+///     "rust web Rust \nrust,web"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn unique<I>(iter: I) -> Result<Vec<I::Item>, Infallible>
+where
+    I: IntoIterator,
+    I::Item: Eq + Hash,
+{
+    let items = iter.into_iter().collect::<Vec<_>>();
+    let mut seen = Seen::new();
+    let keep = items
+        .iter()
+        .map(|item| seen.insert(item))
+        .collect::<Vec<_>>();
+    drop(seen);
+    Ok(retain(items, keep))
+}
+
+/// Remove elements of an iterable whose `key` was already seen, keeping the first occurrence
+///
+/// This function is used by the [`unique()`] filter if an `attr` or `case_sensitive` argument
+/// is given.
+#[inline]
+pub fn unique_by<I, F, K>(iter: I, mut key: F) -> Result<Vec<I::Item>, Infallible>
+where
+    I: IntoIterator,
+    F: FnMut(&I::Item) -> K,
+    K: Eq + Hash,
+{
+    let items = iter.into_iter().collect::<Vec<_>>();
+    let mut seen = Seen::new();
+    let keep = items
+        .iter()
+        .map(|item| seen.insert(key(item)))
+        .collect::<Vec<_>>();
+    Ok(retain(items, keep))
+}
+
+fn retain<T>(items: Vec<T>, keep: Vec<bool>) -> Vec<T> {
+    items
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(item, keep)| keep.then_some(item))
+        .collect()
+}
+
+#[cfg(feature = "std")]
+type Seen<K> = std::collections::HashSet<K>;
+
+/// Without `std` there is no `HashSet`, so the seen elements are searched linearly.
+#[cfg(not(feature = "std"))]
+struct Seen<K>(Vec<K>);
+
+#[cfg(not(feature = "std"))]
+impl<K: Eq> Seen<K> {
+    fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    fn insert(&mut self, key: K) -> bool {
+        let is_new = !self.0.contains(&key);
+        if is_new {
+            self.0.push(key);
+        }
+        is_new
+    }
+}
+
+/// Select a part of an iterable like Python's slicing `items[start:stop:step]`
+///
+/// Negative `start` and `stop` indices count from the end, out-of-range indices are clamped.
+/// A negative `step` selects the elements in reverse order. A `step` of zero is an error.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// {{ example|slice(1, 4)|join(",") }}
+/// /// {{ example|slice(0, 10, 2)|join(",") }}
+/// /// {{ example|slice(-2)|join(",") }}
+/// /// {{ example|slice(step=-1)|join(",") }}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a [u32],
+/// }
+///
+/// assert_eq!(
+///     Example { example: &[1, 2, 3, 4, 5] }.to_string(),
+///     "2,3,4\n1,3,5\n4,5\n5,4,3,2,1"
+/// );
+/// # }
+/// ```
+pub fn slice<I: IntoIterator>(
+    iter: I,
+    start: Option<isize>,
+    stop: Option<isize>,
+    step: isize,
+) -> Result<Vec<I::Item>> {
+    if step == 0 {
+        return Err(crate::Error::custom(
+            "the `step` of the `slice` filter must not be zero",
+        ));
+    }
+
+    let items = iter.into_iter().collect::<Vec<_>>();
+    let len = items.len() as isize;
+    let (lower, upper) = match step > 0 {
+        true => (0, len),
+        false => (-1, len - 1),
+    };
+    let clamp = |index: Option<isize>, default: isize| match index {
+        None => default,
+        Some(index) if index < 0 => (index + len).max(lower),
+        Some(index) => index.min(upper),
+    };
+    let (start, stop) = match step > 0 {
+        true => (clamp(start, lower), clamp(stop, upper)),
+        false => (clamp(start, upper), clamp(stop, lower)),
+    };
+
+    let mut items = items
+        .into_iter()
+        .enumerate()
+        .filter(|&(index, _)| {
+            let index = index as isize;
+            let in_range = match step > 0 {
+                true => start <= index && index < stop,
+                false => stop < index && index <= start,
+            };
+            in_range && (index - start) % step == 0
+        })
+        .map(|(_, item)| item)
+        .collect::<Vec<_>>();
+    if step < 0 {
+        items.reverse();
+    }
+    Ok(items)
+}
+
+/// Distribute the elements of an iterable into `n` columns
+///
+/// The first column gets the elements `0`, `n`, `2n`…, the second column gets the elements `1`,
+/// `n + 1`, `2n + 1`…, and so on. If the number of elements is not a multiple of `n`, the first
+/// columns get one element more than the last columns. There are always `n` columns, even if
+/// some of them are empty. A count `n` of zero is an error.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// {% for column in example|columns(3) -%}
+/// ///     [{{ column|join(",") }}]
+/// /// {%- endfor %}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a [u32],
+/// }
+///
+/// assert_eq!(
+///     Example { example: &[1, 2, 3, 4, 5, 6, 7] }.to_string(),
+///     "[1,4,7][2,5][3,6]"
+/// );
+/// # }
+/// ```
+pub fn columns<I: IntoIterator>(iter: I, n: usize) -> Result<Vec<Vec<I::Item>>> {
+    if n == 0 {
+        return Err(crate::Error::custom(
+            "the number of columns of the `columns` filter must not be zero",
+        ));
+    }
+
+    let iter = iter.into_iter();
+    let rows = iter.size_hint().0.div_ceil(n);
+    let mut columns = (0..n).map(|_| Vec::with_capacity(rows)).collect::<Vec<_>>();
+    for (index, item) in iter.enumerate() {
+        columns[index % n].push(item);
+    }
+    Ok(columns)
+}
+
+/// Capitalize a value. The first character will be uppercase, all others lowercase.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ example|capitalize }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "hello" }.to_string(),
+///     "<div>Hello</div>"
+/// );
+///
+/// assert_eq!(
+///     Example { example: "hElLO" }.to_string(),
+///     "<div>Hello</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn capitalize(s: impl fmt::Display) -> Result<String, fmt::Error> {
+    fn capitalize(s: &str) -> Result<String, fmt::Error> {
+        let mut chars = s.chars();
+        if let Some(c) = chars.next() {
+            let mut replacement = String::with_capacity(s.len());
+            replacement.extend(c.to_uppercase());
+            replacement.push_str(&chars.as_str().to_lowercase());
+            Ok(replacement)
+        } else {
+            Ok(String::new())
+        }
+    }
+
+    let mut buffer;
+    capitalize(try_to_str!(s => buffer))
+}
+
+/// Removes all HTML tags and comments, and collapses whitespace
+///
+/// The basic HTML entities `&amp;`, `&lt;`, `&gt;`, `&quot;`, `&#39;`, `&apos;` and `&nbsp;`,
+/// and numeric character references like `&#8212;` or `&#x2014;` are decoded, so the result is
+/// plain text. It will be escaped again if it is rendered in an HTML template.
+///
+/// A `>` inside a quoted attribute value does not end a tag, and an unclosed tag removes
+/// everything up to the end of the input. A `<` that is not followed by a letter, `/`, `!` or `?`
+/// is not the start of a tag.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <meta name="description" content="{{ example|striptags }}">
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "<p>Fish &amp;\n  <b>chips</b></p>" }.to_string(),
+///     r#"<meta name="description" content="Fish &#38; chips">"#
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn striptags(s: impl fmt::Display) -> Result<String, fmt::Error> {
+    let mut buffer;
+    Ok(striptags_str(try_to_str!(s => buffer)))
+}
+
+fn striptags_str(s: &str) -> String {
+    // remove tags and comments
+    let mut text = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(idx) = rest.find('<') {
+        text.push_str(&rest[..idx]);
+        let tag = &rest[idx..];
+        let is_tag = tag[1..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || matches!(c, '/' | '!' | '?'));
+        if !is_tag {
+            text.push('<');
+            rest = &tag[1..];
+            continue;
+        }
+        let end = if let Some(comment) = tag.strip_prefix("<!--") {
+            comment.find("-->").map(|end| 4 + end + 3)
+        } else {
+            let mut quote = None;
+            tag.char_indices().find_map(|(i, c)| {
+                match (quote, c) {
+                    (None, '"' | '\'') => quote = Some(c),
+                    (None, '>') => return Some(i + 1),
+                    (Some(q), c) if q == c => quote = None,
+                    _ => {}
+                }
+                None
+            })
+        };
+        // An unclosed tag swallows the rest of the input.
+        rest = end.map_or("", |end| &tag[end..]);
+    }
+    text.push_str(rest);
+
+    // collapse whitespace and decode entities
+    let mut output = String::with_capacity(text.len());
+    for word in text.split_whitespace() {
+        if !output.is_empty() {
+            output.push(' ');
+        }
+        decode_entities(word, &mut output);
+    }
+    output
+}
+
+fn decode_entities(mut s: &str, output: &mut String) {
+    while let Some(idx) = s.find('&') {
+        output.push_str(&s[..idx]);
+        s = &s[idx..];
+        let decoded = s.find(';').and_then(|end| {
+            let c = match &s[1..end] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                "nbsp" => '\u{a0}',
+                entity => {
+                    let num = entity.strip_prefix('#')?;
+                    let num = match num.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16),
+                        None => num.parse(),
+                    };
+                    char::from_u32(num.ok()?)?
+                }
+            };
+            Some((c, end + 1))
+        });
+        match decoded {
+            Some((c, len)) => {
+                output.push(c);
+                s = &s[len..];
+            }
+            None => {
+                output.push('&');
+                s = &s[1..];
+            }
+        }
+    }
+    output.push_str(s);
+}
+
+/// Count the words in that string.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ example|wordcount }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "rinja is sort of cool" }.to_string(),
+///     "<div>5</div>"
+/// );
+/// # }
+/// ```
+pub fn wordcount(s: impl fmt::Display) -> Result<usize, fmt::Error> {
+    let mut buffer;
+    Ok(try_to_str!(s => buffer).split_whitespace().count())
+}
+
+/// Return a title cased version of the value. Words will start with uppercase letters, all
+/// remaining characters are lowercase.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ example|title }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "hello WORLD" }.to_string(),
+///     "<div>Hello World</div>"
+/// );
+/// # }
+/// ```
+pub fn title(s: impl fmt::Display) -> Result<String, fmt::Error> {
+    let mut buffer;
+    let s = try_to_str!(s => buffer);
+    let mut need_capitalization = true;
+
+    // Sadly enough, we can't mutate a string when iterating over its chars, likely because it could
+    // change the size of a char, "breaking" the char indices.
+    let mut output = String::with_capacity(s.len());
+    for c in s.chars() {
+        if c.is_whitespace() {
+            output.push(c);
+            need_capitalization = true;
+        } else if need_capitalization {
+            match c.is_uppercase() {
+                true => output.push(c),
+                false => output.extend(c.to_uppercase()),
+            }
+            need_capitalization = false;
+        } else {
+            match c.is_lowercase() {
+                true => output.push(c),
+                false => output.extend(c.to_lowercase()),
             }
         }
     }
     Ok(output)
 }
 
+/// Looks up an attribute of the value by its name, which is only known at runtime
+///
+/// The value must implement [`Attributes`]. If it has no attribute `name`, an
+/// [`Error::UnknownAttribute`][crate::Error::UnknownAttribute] is returned.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use std::collections::BTreeMap;
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// {% for column in columns %}<td>{{ row|attr(column) }}</td>{% endfor %}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     columns: &'a [&'a str],
+///     row: BTreeMap<&'a str, u32>,
+/// }
+///
+/// assert_eq!(
+///     Example {
+///         columns: &["b", "a"],
+///         row: BTreeMap::from([("a", 1), ("b", 2)]),
+///     }
+///     .to_string(),
+///     "<td>2</td><td>1</td>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn attr<T: Attributes + ?Sized>(
+    value: &T,
+    name: impl fmt::Display,
+) -> Result<&dyn fmt::Display> {
+    let mut buffer;
+    let name = try_to_str!(name => buffer);
+    value
+        .attribute(name)
+        .ok_or_else(|| crate::Error::UnknownAttribute(name.into()))
+}
+
+/// A value with attributes that can be looked up by name, used by the [`attr`] filter
+///
+/// It is implemented for maps and slices of key-value pairs with string keys. For your own
+/// types, you can implement it with a `match`:
+///
+/// ```
+/// use std::fmt::Display;
+///
+/// struct User<'a> {
+///     name: &'a str,
+///     age: u32,
+/// }
+///
+/// impl rinja::filters::Attributes for User<'_> {
+///     fn attribute(&self, name: &str) -> Option<&dyn Display> {
+///         match name {
+///             "name" => Some(&self.name),
+///             "age" => Some(&self.age),
+///             _ => None,
+///         }
+///     }
+/// }
+/// ```
+pub trait Attributes {
+    /// Returns the attribute `name`, or `None` if there is no such attribute.
+    fn attribute(&self, name: &str) -> Option<&dyn fmt::Display>;
+}
+
+const _: () = {
+    use core::borrow::Borrow;
+
+    crate::impl_for_ref! {
+        impl Attributes for T {
+            #[inline]
+            fn attribute(&self, name: &str) -> Option<&dyn fmt::Display> {
+                <T>::attribute(self, name)
+            }
+        }
+    }
+
+    impl<K: Borrow<str>, V: fmt::Display> Attributes for [(K, V)] {
+        fn attribute(&self, name: &str) -> Option<&dyn fmt::Display> {
+            let (_, value) = self.iter().find(|(key, _)| key.borrow() == name)?;
+            Some(value)
+        }
+    }
+
+    impl<K: Borrow<str>, V: fmt::Display, const N: usize> Attributes for [(K, V); N] {
+        #[inline]
+        fn attribute(&self, name: &str) -> Option<&dyn fmt::Display> {
+            self.as_slice().attribute(name)
+        }
+    }
+
+    impl<K: Borrow<str>, V: fmt::Display> Attributes for Vec<(K, V)> {
+        #[inline]
+        fn attribute(&self, name: &str) -> Option<&dyn fmt::Display> {
+            self.as_slice().attribute(name)
+        }
+    }
+
+    impl<K: Borrow<str> + Ord, V: fmt::Display> Attributes for alloc::collections::BTreeMap<K, V> {
+        #[inline]
+        fn attribute(&self, name: &str) -> Option<&dyn fmt::Display> {
+            Some(self.get(name)?)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl<K, V, S> Attributes for std::collections::HashMap<K, V, S>
+    where
+        K: Borrow<str> + Eq + Hash,
+        V: fmt::Display,
+        S: std::hash::BuildHasher,
+    {
+        #[inline]
+        fn attribute(&self, name: &str) -> Option<&dyn fmt::Display> {
+            Some(self.get(name)?)
+        }
+    }
+};
+
+/// Renders a map, or any other iterable of key-value pairs, as XML/HTML attributes
+///
+/// Every entry is rendered as ` key="value"`, i.e. with a leading space, so the output can be
+/// placed directly after the tag name. Entries whose value is `None` or renders to an empty
+/// string are skipped. The keys and values are HTML escaped. If a key is not a valid attribute
+/// name, an [`Error::InvalidAttributeName`][crate::Error::InvalidAttributeName] is returned.
+///
+/// The values must implement [`XmlAttrValue`].
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use std::collections::BTreeMap;
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div{{ attrs|xmlattr }}></div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     attrs: BTreeMap<&'a str, Option<&'a str>>,
+/// }
+///
+/// assert_eq!(
+///     Example {
+///         attrs: BTreeMap::from([
+///             ("class", Some("my <list>")),
+///             ("id", Some("list-1")),
+///             ("title", None),
+///         ]),
+///     }
+///     .to_string(),
+///     r#"<div class="my &#60;list&#62;" id="list-1"></div>"#
+/// );
+/// # }
+/// ```
+pub fn xmlattr<I, K, V>(attrs: I) -> Result<HtmlSafeOutput<String>>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: fmt::Display,
+    V: XmlAttrValue,
+{
+    let mut output = String::new();
+    let mut value_buffer = String::new();
+    for (key, value) in attrs {
+        let Some(value) = value.xml_attr_value() else {
+            continue;
+        };
+        value_buffer.clear();
+        write!(value_buffer, "{value}")?;
+        if value_buffer.is_empty() {
+            continue;
+        }
+
+        let mut key_buffer;
+        let key = try_to_str!(key => key_buffer);
+        if key.is_empty() || !key.chars().all(is_attribute_name_char) {
+            return Err(crate::Error::InvalidAttributeName(key.into()));
+        }
+
+        output.push(' ');
+        crate::html::write_escaped_str(&mut output, key)?;
+        output.push_str("=\"");
+        crate::html::write_escaped_str(&mut output, &value_buffer)?;
+        output.push('"');
+    }
+    Ok(HtmlSafeOutput(output))
+}
+
+// <https://html.spec.whatwg.org/multipage/syntax.html#attributes-2>
+fn is_attribute_name_char(c: char) -> bool {
+    !(c.is_whitespace() || c.is_control() || matches!(c, '"' | '\'' | '/' | '=' | '>'))
+}
+
+/// A value that can be rendered by the [`xmlattr`] filter
+///
+/// It is implemented for strings, characters, booleans, numbers and [`Option`]s thereof.
+/// For your own types, return `None` if the attribute should be omitted:
+///
+/// ```
+/// use std::fmt::Display;
+///
+/// enum Target {
+///     SameTab,
+///     NewTab,
+/// }
+///
+/// impl rinja::filters::XmlAttrValue for Target {
+///     fn xml_attr_value(&self) -> Option<&dyn Display> {
+///         match self {
+///             Target::SameTab => None,
+///             Target::NewTab => Some(&"_blank"),
+///         }
+///     }
+/// }
+/// ```
+pub trait XmlAttrValue {
+    /// Returns the value to render, or `None` if the attribute should be omitted.
+    fn xml_attr_value(&self) -> Option<&dyn fmt::Display>;
+}
+
+const _: () = {
+    crate::impl_for_ref! {
+        impl XmlAttrValue for T {
+            #[inline]
+            fn xml_attr_value(&self) -> Option<&dyn fmt::Display> {
+                <T>::xml_attr_value(self)
+            }
+        }
+    }
+
+    impl<T: XmlAttrValue> XmlAttrValue for Option<T> {
+        #[inline]
+        fn xml_attr_value(&self) -> Option<&dyn fmt::Display> {
+            self.as_ref()?.xml_attr_value()
+        }
+    }
+
+    macro_rules! impl_xml_attr_value {
+        ($($ty:ty)*) => { $(
+            impl XmlAttrValue for $ty {
+                #[inline]
+                fn xml_attr_value(&self) -> Option<&dyn fmt::Display> {
+                    Some(self)
+                }
+            }
+        )* };
+    }
+
+    impl_xml_attr_value! {
+        &str String alloc::borrow::Cow<'_, str> char bool
+        i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize f32 f64
+        core::num::NonZeroI8 core::num::NonZeroI16 core::num::NonZeroI32
+        core::num::NonZeroI64 core::num::NonZeroI128 core::num::NonZeroIsize
+        core::num::NonZeroU8 core::num::NonZeroU16 core::num::NonZeroU32
+        core::num::NonZeroU64 core::num::NonZeroU128 core::num::NonZeroUsize
+    }
+};
+
 #[cfg(test)]
 mod tests {
     use alloc::string::ToString;
@@ -555,6 +1708,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_urlize() {
+        let check = |s: &str, limit, nofollow, target: &str| {
+            urlize(s, limit, nofollow, target).unwrap().to_string()
+        };
+
+        assert_eq!(check("", None, false, ""), "");
+        assert_eq!(check("no links here", None, false, ""), "no links here");
+        assert_eq!(
+            check("see http://a.b/c?d=1&#38;e=2.", None, false, ""),
+            "see <a href=\"http://a.b/c?d=1&#38;e=2\">http://a.b/c?d=1&#38;e=2</a>."
+        );
+        assert_eq!(
+            check("(www.example.com)\nhttps://x.y/(z)", None, false, ""),
+            "(<a href=\"https://www.example.com\">www.example.com</a>)\n\
+             <a href=\"https://x.y/(z)\">https://x.y/(z)</a>"
+        );
+        assert_eq!(
+            check("&#60;https://a.b&#62; https:// www.", None, false, ""),
+            "&#60;<a href=\"https://a.b\">https://a.b</a>&#62; https:// www."
+        );
+        assert_eq!(
+            check("https://a.b/&#60;c&#62;d", Some(14), true, ""),
+            "<a href=\"https://a.b/&#60;c&#62;d\" rel=\"nofollow\">https://a.b/&#60;…</a>"
+        );
+        assert_eq!(
+            check("https://a.b/c", Some(13), false, "<x>"),
+            "<a href=\"https://a.b/c\" rel=\"noopener\" target=\"&#60;x&#62;\">https://a.b/c</a>"
+        );
+        assert_eq!(
+            check("https://a.b/c", Some(0), false, ""),
+            "<a href=\"https://a.b/c\">…</a>"
+        );
+    }
+
+    #[test]
+    fn test_nl2br() {
+        assert_eq!(nl2br("Foo\nBar").unwrap().to_string(), "Foo<br>\nBar");
+        assert_eq!(
+            nl2br("Foo\r\nBar\n\nBaz\r").unwrap().to_string(),
+            "Foo<br>\nBar<br>\n<br>\nBaz\r"
+        );
+    }
+
     #[test]
     fn test_paragraphbreaks() {
         assert_eq!(
@@ -579,6 +1776,8 @@ mod tests {
         assert_eq!(lower("FOO").unwrap().to_string(), "foo");
         assert_eq!(lower("FooBar").unwrap().to_string(), "foobar");
         assert_eq!(lower("foo").unwrap().to_string(), "foo");
+        assert_eq!(lower("ÄÖÜ ÉÈ Ω").unwrap().to_string(), "äöü éè ω");
+        assert_eq!(lower("İ").unwrap().to_string(), "i\u{307}");
     }
 
     #[test]
@@ -587,6 +1786,9 @@ mod tests {
         assert_eq!(upper("FOO").unwrap().to_string(), "FOO");
         assert_eq!(upper("FooBar").unwrap().to_string(), "FOOBAR");
         assert_eq!(upper("foo").unwrap().to_string(), "FOO");
+        assert_eq!(upper("äöü éè ω").unwrap().to_string(), "ÄÖÜ ÉÈ Ω");
+        assert_eq!(upper("straße").unwrap().to_string(), "STRASSE");
+        assert_eq!(upper("ﬁx").unwrap().to_string(), "FIX");
     }
 
     #[test]
@@ -594,6 +1796,21 @@ mod tests {
         assert_eq!(trim(" Hello\tworld\t").unwrap().to_string(), "Hello\tworld");
     }
 
+    #[test]
+    fn test_trim_chars() {
+        assert_eq!(trim_chars("/path/to/", "/").unwrap(), "path/to");
+        assert_eq!(trim_chars("-_-a-_b_-_", "_-").unwrap(), "a-_b");
+        assert_eq!(trim_chars("  a  ", "").unwrap(), "  a  ");
+        assert_eq!(trim_chars("///", "/").unwrap(), "");
+        assert_eq!(trim_chars("«äöü»", "«»").unwrap(), "äöü");
+        assert_eq!(trim_chars("ßaß", 'ß').unwrap(), "a");
+        assert_eq!(trim_start_chars("«äöü»", "«»").unwrap(), "äöü»");
+        assert_eq!(trim_end_chars("«äöü»", "«»").unwrap(), "«äöü");
+        assert_eq!(trim_start(" \u{3000}a\t ").unwrap(), "a\t ");
+        assert_eq!(trim_end(" a\t\u{3000}").unwrap(), " a");
+        assert_eq!(trim_end_chars(100, 0).unwrap(), "1");
+    }
+
     #[test]
     fn test_indent() {
         assert_eq!(indent("hello", 2).unwrap().to_string(), "hello");
@@ -607,6 +1824,77 @@ mod tests {
             indent("hello", 267_332_238_858).unwrap().to_string(),
             "hello"
         );
+        assert_eq!(indent("a\n\nb\n", 2).unwrap(), "a\n  \n  b\n");
+    }
+
+    #[test]
+    fn test_indent_with() {
+        assert_eq!(
+            indent_with("a\n\nb\n", 2, false, false).unwrap(),
+            "a\n\n  b\n"
+        );
+        assert_eq!(
+            indent_with("a\n\nb\n", 2, true, false).unwrap(),
+            "  a\n\n  b\n"
+        );
+        assert_eq!(
+            indent_with("a\n\nb\n", 2, false, true).unwrap(),
+            "a\n  \n  b\n"
+        );
+        assert_eq!(
+            indent_with("a\n\nb\n", 2, true, true).unwrap(),
+            "  a\n  \n  b\n"
+        );
+        assert_eq!(indent_with("\n", 2, true, true).unwrap(), "  \n");
+        assert_eq!(indent_with("", 2, true, false).unwrap(), "");
+    }
+
+    #[test]
+    fn test_dictsort() {
+        let items = [("b", 2), ("c", 1), ("a", 2)];
+        assert_eq!(
+            dictsort(items, false).unwrap(),
+            [("a", 2), ("b", 2), ("c", 1)]
+        );
+        assert_eq!(
+            dictsort(items, true).unwrap(),
+            [("c", 1), ("b", 2), ("a", 2)]
+        );
+        // stable: equal values keep their order
+        assert_eq!(
+            dictsort_by_value(items, false).unwrap(),
+            [("c", 1), ("b", 2), ("a", 2)]
+        );
+        assert_eq!(
+            dictsort_by_value(items, true).unwrap(),
+            [("b", 2), ("a", 2), ("c", 1)]
+        );
+        assert!(dictsort::<_, u8, u8>([], false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_xmlattr() {
+        assert_eq!(
+            xmlattr([("class", Some("a b")), ("title", None), ("id", Some(""))])
+                .unwrap()
+                .0,
+            r#" class="a b""#,
+        );
+        assert_eq!(
+            xmlattr([("data-x", "<\"&'>"), ("tabindex", "1")])
+                .unwrap()
+                .0,
+            r#" data-x="&#60;&#34;&#38;&#39;&#62;" tabindex="1""#,
+        );
+        assert_eq!(xmlattr::<_, &str, &str>([]).unwrap().0, "");
+        for key in ["", "a b", "a=b", "a/", "a>", "\"", "a\n"] {
+            assert!(matches!(
+                xmlattr([(key, 1)]),
+                Err(crate::Error::InvalidAttributeName(name)) if name == key,
+            ));
+        }
+        // invalid keys are only rejected if the attribute is rendered
+        assert_eq!(xmlattr([("a b", None::<u32>)]).unwrap().0, "");
     }
 
     #[test]
@@ -637,6 +1925,136 @@ mod tests {
         assert_eq!(wordcount("foo  bar").unwrap(), 2);
     }
 
+    #[test]
+    fn test_attr() {
+        let pairs = [("a", 1), ("b", 2)];
+        assert_eq!(attr(&pairs, "b").unwrap().to_string(), "2");
+        assert_eq!(attr(&&pairs[..], 'a').unwrap().to_string(), "1");
+        assert!(matches!(
+            attr(&pairs, "c"),
+            Err(crate::Error::UnknownAttribute(name)) if name == "c"
+        ));
+
+        let map = std::collections::HashMap::from([(String::from("x"), "y")]);
+        assert_eq!(attr(&map, "x").unwrap().to_string(), "y");
+    }
+
+    #[test]
+    fn test_replace() {
+        assert_eq!(replace("foo boo", "oo", "ee").unwrap(), "fee bee");
+        assert_eq!(replace("foo", "x", "y").unwrap(), "foo");
+        assert_eq!(replace("foo", "", "y").unwrap(), "foo");
+        assert_eq!(replace(12.5, '.', ",").unwrap(), "12,5");
+        assert_eq!(replacen("aaaa", "a", "b", 2).unwrap(), "bbaa");
+        assert_eq!(replacen("aaaa", "a", "b", 0).unwrap(), "aaaa");
+        assert_eq!(replacen("aaaa", "", "b", 1).unwrap(), "aaaa");
+    }
+
+    #[test]
+    fn test_unique() {
+        assert_eq!(unique([1, 2, 1, 3, 2]).unwrap(), [1, 2, 3]);
+        assert_eq!(unique(&["b", "a", "b"]).unwrap(), [&"b", &"a"]);
+        assert_eq!(unique(Vec::<u32>::new()).unwrap(), Vec::<u32>::new());
+        assert_eq!(
+            unique_by([(1, 'a'), (2, 'b'), (1, 'c')], |(id, _)| *id).unwrap(),
+            [(1, 'a'), (2, 'b')]
+        );
+        assert_eq!(
+            unique_by(["A", "b", "a", "B"], |s| s.to_lowercase()).unwrap(),
+            ["A", "b"]
+        );
+    }
+
+    #[test]
+    fn test_sort() {
+        assert_eq!(sort([3, 10, 1], false).unwrap(), [1, 3, 10]);
+        assert_eq!(sort([3, 10, 1], true).unwrap(), [10, 3, 1]);
+        let items = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+        assert_eq!(
+            sort_by_key(items, |(n, _)| *n, false).unwrap(),
+            [(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]
+        );
+        assert_eq!(
+            sort_by_key(items, |(n, _)| *n, true).unwrap(),
+            [(2, 'a'), (2, 'c'), (1, 'b'), (1, 'd')]
+        );
+        assert_eq!(
+            sort_by_key(["b", "A", "a", "B"], |s| s.to_lowercase(), false).unwrap(),
+            ["A", "a", "b", "B"]
+        );
+    }
+
+    #[test]
+    fn test_columns() {
+        assert_eq!(
+            columns(1..=7, 3).unwrap(),
+            [alloc::vec![1, 4, 7], alloc::vec![2, 5], alloc::vec![3, 6]]
+        );
+        assert_eq!(
+            columns(1..=6, 2).unwrap(),
+            [alloc::vec![1, 3, 5], alloc::vec![2, 4, 6]]
+        );
+        assert_eq!(columns(1..=4, 1).unwrap(), [alloc::vec![1, 2, 3, 4]]);
+        assert_eq!(
+            columns(1..=2, 4).unwrap(),
+            [alloc::vec![1], alloc::vec![2], alloc::vec![], alloc::vec![]]
+        );
+        assert_eq!(
+            columns(0..0, 2).unwrap(),
+            [alloc::vec![], alloc::vec![]] as [Vec<i32>; 2]
+        );
+        assert!(columns(1..=3, 0).is_err());
+    }
+
+    #[test]
+    fn test_slice() {
+        let items = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        assert_eq!(slice(items, Some(1), Some(5), 1).unwrap(), [1, 2, 3, 4]);
+        assert_eq!(slice(items, Some(0), Some(10), 2).unwrap(), [0, 2, 4, 6, 8]);
+        assert_eq!(slice(items, Some(-3), None, 1).unwrap(), [7, 8, 9]);
+        assert_eq!(slice(items, None, Some(-7), 1).unwrap(), [0, 1, 2]);
+        assert_eq!(slice(items, Some(-100), Some(2), 1).unwrap(), [0, 1]);
+        assert_eq!(slice(items, Some(8), Some(100), 1).unwrap(), [8, 9]);
+        assert_eq!(slice(items, Some(5), Some(2), 1).unwrap(), [0; 0]);
+        assert_eq!(slice(items, Some(100), None, 1).unwrap(), [0; 0]);
+        assert_eq!(
+            slice(items, None, None, -1).unwrap(),
+            [9, 8, 7, 6, 5, 4, 3, 2, 1, 0]
+        );
+        assert_eq!(slice(items, Some(7), Some(2), -2).unwrap(), [7, 5, 3]);
+        assert_eq!(slice(items, Some(-2), None, -3).unwrap(), [8, 5, 2]);
+        assert_eq!(slice(items, Some(100), Some(-100), -4).unwrap(), [9, 5, 1]);
+        assert_eq!(slice([0; 0], None, None, -1).unwrap(), [0; 0]);
+        assert!(slice(items, None, None, 0).is_err());
+    }
+
+    #[test]
+    fn test_striptags() {
+        assert_eq!(striptags("").unwrap(), "");
+        assert_eq!(
+            striptags("<p>Hello <b>world</b>!</p>").unwrap(),
+            "Hello world!"
+        );
+        assert_eq!(striptags("a<br>b").unwrap(), "ab");
+        assert_eq!(striptags("  a \n\t b  ").unwrap(), "a b");
+        assert_eq!(striptags("<!-- <p>comment</p> -->text").unwrap(), "text");
+        assert_eq!(
+            striptags(r#"<a title="a > b" href='x>y'>link</a>"#).unwrap(),
+            "link"
+        );
+        assert_eq!(striptags("1 < 2 and 3 <= 4").unwrap(), "1 < 2 and 3 <= 4");
+        assert_eq!(striptags("text <a href=\"unclosed").unwrap(), "text");
+        assert_eq!(
+            striptags("&lt;b&gt; &amp; &quot;q&quot; &#39;s&apos; &#8212;&#x2014;").unwrap(),
+            "<b> & \"q\" 's' \u{2014}\u{2014}"
+        );
+        assert_eq!(striptags("a&nbsp;b").unwrap(), "a\u{a0}b");
+        assert_eq!(
+            striptags("&unknown; & &#xZZ; &#0x;").unwrap(),
+            "&unknown; & &#xZZ; &#0x;"
+        );
+    }
+
     #[test]
     fn test_title() {
         assert_eq!(&title("").unwrap(), "");
@@ -647,11 +2065,15 @@ mod tests {
         assert_eq!(&title("foo  bar ").unwrap(), "Foo  Bar ");
         assert_eq!(&title("fOO").unwrap(), "Foo");
         assert_eq!(&title("fOo BaR").unwrap(), "Foo Bar");
+        assert_eq!(&title("élan ÀPRÈS öl").unwrap(), "Élan Àprès Öl");
+        // Deliberate: like `capitalize`, the first letter is uppercased with `char::to_uppercase`,
+        // which turns "ß" into "SS", because it has no single-character uppercase form.
+        assert_eq!(&title("ßen größe").unwrap(), "SSen Größe");
     }
 
     #[test]
     fn fuzzed_indent_filter() {
         let s = "hello\nfoo\nbar".to_string().repeat(1024);
-        assert_eq!(indent(s.clone(), 4).unwrap().to_string(), s);
+        assert_eq!(indent(s.clone(), 4).unwrap(), s);
     }
 }
@@ -5,7 +5,7 @@ use core::ops::Deref;
 use core::pin::Pin;
 
 use super::MAX_LEN;
-use super::escape::FastWritable;
+use super::escape::{EscapeDisplay, Escaper, FastWritable, Safe};
 use crate::{Error, Result};
 
 /// Limit string length, appends '...' if truncated
@@ -114,6 +114,152 @@ impl<W: fmt::Write> fmt::Write for TruncateWriter<W> {
     }
 }
 
+/// Returns the first item of an iterable
+///
+/// An empty iterable results in an [`Error::EmptyIterable`].
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ example|first }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a [&'a str],
+/// }
+///
+/// assert_eq!(
+///     Example { example: &["foo", "bar", "bazz"] }.to_string(),
+///     "<div>foo</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn first<I: IntoIterator>(input: I) -> Result<I::Item> {
+    input.into_iter().next().ok_or(Error::EmptyIterable)
+}
+
+/// A value that knows its length, used by the `length` filter
+///
+/// The length of a string is its number of characters, the length of a collection is its
+/// number of elements. Other iterables are counted by the filter, if they are not borrowed.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ example|length }} / {{ example[0]|length }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a [&'a str],
+/// }
+///
+/// assert_eq!(
+///     Example { example: &["größe", "bar"] }.to_string(),
+///     "<div>2 / 5</div>"
+/// );
+/// # }
+/// ```
+pub trait Length {
+    /// Returns the number of characters or elements of the value.
+    fn length(&self) -> usize;
+}
+
+const _: () = {
+    crate::impl_for_ref! {
+        impl Length for T {
+            #[inline]
+            fn length(&self) -> usize {
+                <T>::length(self)
+            }
+        }
+    }
+
+    impl<T> Length for Pin<T>
+    where
+        T: Deref,
+        <T as Deref>::Target: Length,
+    {
+        #[inline]
+        fn length(&self) -> usize {
+            self.as_ref().get_ref().length()
+        }
+    }
+
+    impl Length for str {
+        #[inline]
+        fn length(&self) -> usize {
+            self.chars().count()
+        }
+    }
+
+    impl<T> Length for [T] {
+        #[inline]
+        fn length(&self) -> usize {
+            self.len()
+        }
+    }
+
+    impl<T, const N: usize> Length for [T; N] {
+        #[inline]
+        fn length(&self) -> usize {
+            N
+        }
+    }
+
+    /// implement `Length` for collections with a `len()` method
+    macro_rules! impl_length_for_collection {
+        ($($(#[$meta:meta])* $ty:ty $(where [$($bound:tt)*])?,)*) => { $(
+            $(#[$meta])*
+            impl<$($($bound)*)?> Length for $ty {
+                #[inline]
+                fn length(&self) -> usize {
+                    self.len()
+                }
+            }
+        )* };
+    }
+
+    impl_length_for_collection! {
+        #[cfg(feature = "alloc")]
+        alloc::vec::Vec<T> where [T],
+        #[cfg(feature = "alloc")]
+        alloc::collections::VecDeque<T> where [T],
+        #[cfg(feature = "alloc")]
+        alloc::collections::LinkedList<T> where [T],
+        #[cfg(feature = "alloc")]
+        alloc::collections::BinaryHeap<T> where [T],
+        #[cfg(feature = "alloc")]
+        alloc::collections::BTreeSet<T> where [T],
+        #[cfg(feature = "alloc")]
+        alloc::collections::BTreeMap<K, V> where [K, V],
+        #[cfg(feature = "std")]
+        std::collections::HashSet<T, S> where [T, S],
+        #[cfg(feature = "std")]
+        std::collections::HashMap<K, V, S> where [K, V, S],
+    }
+
+    #[cfg(feature = "alloc")]
+    impl Length for alloc::string::String {
+        #[inline]
+        fn length(&self) -> usize {
+            self.as_str().length()
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<B: alloc::borrow::ToOwned + Length + ?Sized> Length for alloc::borrow::Cow<'_, B> {
+        #[inline]
+        fn length(&self) -> usize {
+            B::length(self)
+        }
+    }
+};
+
 /// Joins iterable into a string separated by provided argument
 ///
 /// ```
@@ -178,8 +324,86 @@ where
     }
 }
 
+/// Joins iterable into a string separated by provided argument, escaping each element
+///
+/// In templates that escape their output, e.g. HTML templates, the [`join`](join()) filter is
+/// implemented with this function if it is the last filter of a `{{ … }}` expression: every
+/// element is escaped individually with the escaper of the template. A string literal separator
+/// is trusted and written as is, so it can contain markup, but any other separator is escaped,
+/// too. If another filter is applied to the result of `join`, then [`join()`] is used instead,
+/// and the whole result is escaped once.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <ul><li>{{ names|join("</li><li>") }}</li></ul>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     names: &'a [&'a str],
+/// }
+///
+/// assert_eq!(
+///     Example { names: &["Tom & Jerry", "<script>"] }.to_string(),
+///     "<ul><li>Tom &#38; Jerry</li><li>&#60;script&#62;</li></ul>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn join_escaped<I, S, E>(
+    input: I,
+    separator: S,
+    escaper: E,
+) -> Result<Safe<JoinEscapedFilter<I, S, E>>, Infallible>
+where
+    I: IntoIterator,
+    I::Item: fmt::Display,
+    S: fmt::Display,
+    E: Escaper,
+{
+    Ok(Safe(JoinEscapedFilter(
+        Cell::new(Some((input, separator))),
+        escaper,
+    )))
+}
+
+/// Result of the filter [`join_escaped()`].
+///
+/// ## Note
+///
+/// Like [`JoinFilter`], this struct only produces a string once.
+pub struct JoinEscapedFilter<I, S, E>(Cell<Option<(I, S)>>, E);
+
+impl<I, S, E> fmt::Display for JoinEscapedFilter<I, S, E>
+where
+    I: IntoIterator,
+    I::Item: fmt::Display,
+    S: fmt::Display,
+    E: Escaper,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((iter, separator)) = self.0.take() else {
+            return Ok(());
+        };
+        for (idx, token) in iter.into_iter().enumerate() {
+            if idx > 0 {
+                f.write_fmt(format_args!("{separator}"))?;
+            }
+            f.write_fmt(format_args!("{}", EscapeDisplay(token, self.1)))?;
+        }
+        Ok(())
+    }
+}
+
 /// Centers the value in a field of a given width
 ///
+/// The width is measured in characters. If the value is already at least as wide, it is
+/// written unchanged. Use [`center_fill()`] to pad with something else than spaces.
+/// Without the `alloc` feature, the value is formatted twice: once to count its characters,
+/// and once to write it.
+///
 /// ```
 /// # #[cfg(feature = "code-in-doc")] {
 /// # use rinja::Template;
@@ -200,21 +424,120 @@ where
 /// ```
 #[inline]
 pub fn center<T: fmt::Display>(src: T, width: usize) -> Result<Center<T>, Infallible> {
-    Ok(Center { src, width })
+    Ok(Center {
+        src,
+        width,
+        fill: ' ',
+    })
+}
+
+/// Centers the value in a field of a given width, padded with the character `fill`
+///
+/// `fill` must be exactly one character long, otherwise an error is returned.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ example|center(7, "*") }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "äöü" }.to_string(),
+///     "<div>**äöü**</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn center_fill<T: fmt::Display>(
+    src: T,
+    width: usize,
+    fill: impl fmt::Display,
+) -> Result<Center<T>> {
+    let Some(fill) = single_char(fill) else {
+        #[cfg(feature = "alloc")]
+        return Err(Error::custom(
+            "the `fill` argument of the `center` filter must be exactly one character",
+        ));
+        #[cfg(not(feature = "alloc"))]
+        return Err(Error::Fmt);
+    };
+    Ok(Center { src, width, fill })
+}
+
+/// Returns the only character of `s`, or `None` if it is empty or longer
+fn single_char(s: impl fmt::Display) -> Option<char> {
+    struct SingleChar(Option<char>);
+
+    impl fmt::Write for SingleChar {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            for c in s.chars() {
+                if self.0.replace(c).is_some() {
+                    return Err(fmt::Error);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    let mut c = SingleChar(None);
+    write!(c, "{s}").ok()?;
+    c.0
 }
 
 pub struct Center<T> {
     src: T,
     width: usize,
+    fill: char,
 }
 
 impl<T: fmt::Display> fmt::Display for Center<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.width < MAX_LEN {
-            write!(f, "{: ^1$}", self.src, self.width)
-        } else {
-            write!(f, "{}", self.src)
+        if self.width >= MAX_LEN {
+            return write!(f, "{}", self.src);
+        }
+
+        // Formatting the value once is needed for values that can only be formatted once,
+        // e.g. the output of `join`. Without an allocator, we have to format it twice.
+        #[cfg(feature = "alloc")]
+        let (src, len) = {
+            let mut src = alloc::string::String::new();
+            write!(src, "{}", self.src)?;
+            let len = src.chars().count();
+            (src, len)
+        };
+        #[cfg(not(feature = "alloc"))]
+        let (src, len) = {
+            struct CountChars(usize);
+
+            impl fmt::Write for CountChars {
+                #[inline]
+                fn write_str(&mut self, s: &str) -> fmt::Result {
+                    self.0 += s.chars().count();
+                    Ok(())
+                }
+            }
+
+            let mut len = CountChars(0);
+            write!(len, "{}", self.src)?;
+            (&self.src, len.0)
+        };
+
+        let padding = self.width.saturating_sub(len);
+        let left = padding / 2;
+        for _ in 0..left {
+            f.write_char(self.fill)?;
+        }
+        write!(f, "{src}")?;
+        for _ in left..padding {
+            f.write_char(self.fill)?;
         }
+        Ok(())
     }
 }
 
@@ -448,60 +771,834 @@ impl<S: FastWritable, P: FastWritable> FastWritable for Pluralize<S, P> {
     }
 }
 
-#[cfg(all(test, feature = "alloc"))]
-mod tests {
-    use alloc::string::{String, ToString};
-    use alloc::vec::Vec;
+/// Rounds a floating point number to `precision` decimal places
+///
+/// The `method` can be [`RoundMethod::Common`] (the default in templates), which rounds half
+/// away from zero, [`RoundMethod::Ceil`] or [`RoundMethod::Floor`].
+/// A negative `precision` rounds to tens, hundreds, and so on.
+///
+/// The result has the same type as the input. It is the number closest to the exact decimal
+/// result, so it is displayed without floating point noise, e.g. `2.5` and not `2.5000001`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ price|round(2) }} {{ price|round(0, "ceil") }} {{ price|round(0, "floor") }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example {
+///     price: f64,
+/// }
+///
+/// assert_eq!(
+///     Example { price: 4.2051 }.to_string(),
+///     "<div>4.21 5 4</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn round<T: RoundValue>(
+    value: T,
+    precision: i32,
+    method: RoundMethod,
+) -> Result<T::Output, Infallible> {
+    Ok(value.round_value(precision, method))
+}
 
-    use super::*;
+/// How the [`round`] filter rounds a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundMethod {
+    /// Round half away from zero
+    #[default]
+    Common,
+    /// Round towards positive infinity
+    Ceil,
+    /// Round towards negative infinity
+    Floor,
+}
 
-    #[allow(clippy::needless_borrow)]
-    #[test]
-    fn test_join() {
-        assert_eq!(
-            join((&["hello", "world"]).iter(), ", ")
-                .unwrap()
-                .to_string(),
-            "hello, world"
-        );
-        assert_eq!(
-            join((&["hello"]).iter(), ", ").unwrap().to_string(),
-            "hello"
-        );
+/// A floating point number that can be rounded by the [`round`] filter.
+pub trait RoundValue {
+    /// The type of the rounded number.
+    type Output: fmt::Display;
 
-        let empty: &[&str] = &[];
-        assert_eq!(join(empty.iter(), ", ").unwrap().to_string(), "");
+    /// Rounds the value to `precision` decimal places.
+    fn round_value(&self, precision: i32, method: RoundMethod) -> Self::Output;
+}
 
-        let input: Vec<String> = alloc::vec!["foo".into(), "bar".into(), "bazz".into()];
-        assert_eq!(join(input.iter(), ":").unwrap().to_string(), "foo:bar:bazz");
+const _: () = {
+    crate::impl_for_ref! {
+        impl RoundValue for T {
+            type Output = T::Output;
 
-        let input: &[String] = &["foo".into(), "bar".into()];
-        assert_eq!(join(input.iter(), ":").unwrap().to_string(), "foo:bar");
+            #[inline]
+            fn round_value(&self, precision: i32, method: RoundMethod) -> Self::Output {
+                <T>::round_value(self, precision, method)
+            }
+        }
+    }
 
-        let real: String = "blah".into();
-        let input: Vec<&str> = alloc::vec![&real];
-        assert_eq!(join(input.iter(), ";").unwrap().to_string(), "blah");
+    impl<T> RoundValue for Pin<T>
+    where
+        T: Deref,
+        <T as Deref>::Target: RoundValue,
+    {
+        type Output = <<T as Deref>::Target as RoundValue>::Output;
 
-        assert_eq!(
-            join((&&&&&["foo", "bar"]).iter(), ", ")
-                .unwrap()
-                .to_string(),
-            "foo, bar"
-        );
+        #[inline]
+        fn round_value(&self, precision: i32, method: RoundMethod) -> Self::Output {
+            self.as_ref().get_ref().round_value(precision, method)
+        }
     }
 
-    #[test]
-    fn test_center() {
-        assert_eq!(center("f", 3).unwrap().to_string(), " f ".to_string());
-        assert_eq!(center("f", 4).unwrap().to_string(), " f  ".to_string());
-        assert_eq!(center("foo", 1).unwrap().to_string(), "foo".to_string());
-        assert_eq!(
-            center("foo bar", 8).unwrap().to_string(),
-            "foo bar ".to_string()
-        );
-        assert_eq!(
-            center("foo", 111_669_149_696).unwrap().to_string(),
-            "foo".to_string()
+    impl RoundValue for f64 {
+        type Output = f64;
+
+        #[inline]
+        fn round_value(&self, precision: i32, method: RoundMethod) -> Self::Output {
+            round_f64(*self, precision, method)
+        }
+    }
+
+    impl RoundValue for f32 {
+        type Output = f32;
+
+        #[inline]
+        fn round_value(&self, precision: i32, method: RoundMethod) -> Self::Output {
+            // Every `f32` is exactly representable as `f64`, and converting the result back
+            // yields the `f32` closest to the rounded decimal number.
+            round_f64(f64::from(*self), precision, method) as f32
+        }
+    }
+};
+
+fn round_f64(value: f64, precision: i32, method: RoundMethod) -> f64 {
+    // `f64::round()` and friends are not available in `core`. Every `f64` with a magnitude of
+    // at least 2^52 is an integer, so the conversion to `i64` below is lossless.
+    const INTEGRAL: f64 = 4_503_599_627_370_496.0;
+
+    // 10^22 is the largest power of ten that is exactly representable as `f64`.
+    let exp = precision.unsigned_abs();
+    if exp > 22 {
+        return match precision > 0 {
+            // rounding to more digits than `f64` has cannot change the value
+            true => value,
+            false => round_f64(value / 1e22, precision + 22, method) * 1e22,
+        };
+    }
+    let factor = (0..exp).fold(1.0, |factor, _| factor * 10.0);
+    let scaled = match precision >= 0 {
+        true => value * factor,
+        false => value / factor,
+    };
+    if !scaled.is_finite() || scaled.abs() >= INTEGRAL {
+        // already integral at this precision, infinite or NaN
+        return value;
+    }
+
+    let trunc = scaled as i64 as f64;
+    let rounded = match method {
+        RoundMethod::Common if (scaled - trunc).abs() >= 0.5 => trunc + scaled.signum(),
+        RoundMethod::Ceil if trunc < scaled => trunc + 1.0,
+        RoundMethod::Floor if trunc > scaled => trunc - 1.0,
+        _ => trunc,
+    };
+    // keep the sign of e.g. `-0.2` rounded to `-0`
+    let rounded = rounded.copysign(value);
+    match precision >= 0 {
+        true => rounded / factor,
+        false => rounded * factor,
+    }
+}
+
+/// Returns the absolute value of a number
+///
+/// The absolute value of a signed integer is returned as the unsigned integer of the same size,
+/// so `{{ i32::MIN|abs }}` cannot overflow.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ delta|abs }} {{ ratio|abs }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example {
+///     delta: i32,
+///     ratio: f64,
+/// }
+///
+/// assert_eq!(
+///     Example { delta: -3, ratio: -0.5 }.to_string(),
+///     "<div>3 0.5</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn abs<T: AbsValue>(value: T) -> Result<T::Output, Infallible> {
+    Ok(value.abs_value())
+}
+
+/// A number whose absolute value can be computed by the [`abs`] filter.
+pub trait AbsValue {
+    /// The type of the absolute value.
+    type Output: fmt::Display;
+
+    /// Returns the absolute value.
+    fn abs_value(&self) -> Self::Output;
+}
+
+const _: () = {
+    crate::impl_for_ref! {
+        impl AbsValue for T {
+            type Output = T::Output;
+
+            #[inline]
+            fn abs_value(&self) -> Self::Output {
+                <T>::abs_value(self)
+            }
+        }
+    }
+
+    impl<T> AbsValue for Pin<T>
+    where
+        T: Deref,
+        <T as Deref>::Target: AbsValue,
+    {
+        type Output = <<T as Deref>::Target as AbsValue>::Output;
+
+        #[inline]
+        fn abs_value(&self) -> Self::Output {
+            self.as_ref().get_ref().abs_value()
+        }
+    }
+
+    macro_rules! impl_abs_value {
+        ($($ty:ty => $output:ty, $method:ident;)*) => { $(
+            impl AbsValue for $ty {
+                type Output = $output;
+
+                #[inline]
+                fn abs_value(&self) -> Self::Output {
+                    self.$method()
+                }
+            }
+        )* };
+    }
+
+    impl_abs_value!(
+        i8 => u8, unsigned_abs;
+        i16 => u16, unsigned_abs;
+        i32 => u32, unsigned_abs;
+        i64 => u64, unsigned_abs;
+        i128 => u128, unsigned_abs;
+        isize => usize, unsigned_abs;
+        u8 => u8, clone;
+        u16 => u16, clone;
+        u32 => u32, clone;
+        u64 => u64, clone;
+        u128 => u128, clone;
+        usize => usize, clone;
+        f32 => f32, abs;
+        f64 => f64, abs;
+    );
+};
+
+/// Converts a number or a numeric string into an `i64`
+///
+/// Strings are parsed in the given `base`, which must be in `2..=36`; surrounding whitespace and
+/// a leading `+` or `-` are accepted. Base 16 strings may start with `0x`, base 8 strings with
+/// `0o` and base 2 strings with `0b`. Base 10 strings may also contain a floating point number,
+/// which gets truncated towards zero, just like floating point inputs.
+///
+/// If the value cannot be converted, e.g. because the string is not a number, or the number
+/// does not fit in an `i64`, then `default` is returned. In templates, the default value is `0`,
+/// and the default base is `10`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ count|int }} {{ color|int(base=16) }} {{ missing|int(default=-1) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     count: &'a str,
+///     color: &'a str,
+///     missing: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { count: "4.7", color: "0xff", missing: "n/a" }.to_string(),
+///     "<div>4 255 -1</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn int<T: IntValue>(value: T, default: i64, base: u32) -> Result<i64, Infallible> {
+    Ok(value.int_value(base).unwrap_or(default))
+}
+
+/// A value that can be converted into an integer by the [`int`] filter.
+pub trait IntValue {
+    /// Converts the value into an `i64`, parsing strings in the given `base`.
+    fn int_value(&self, base: u32) -> Option<i64>;
+}
+
+/// Converts a number or a numeric string into an `f64`
+///
+/// Surrounding whitespace of strings is ignored. If the value cannot be converted, then `default`
+/// is returned, which is `0.0` in templates.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ price|float }} {{ count|float }} {{ missing|float(default=1.5) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     price: &'a str,
+///     count: i32,
+///     missing: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { price: " 2.25 ", count: 3, missing: "" }.to_string(),
+///     "<div>2.25 3 1.5</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn float<T: FloatValue>(value: T, default: f64) -> Result<f64, Infallible> {
+    Ok(value.float_value().unwrap_or(default))
+}
+
+/// A value that can be converted into a floating point number by the [`float`] filter.
+pub trait FloatValue {
+    /// Converts the value into an `f64`.
+    fn float_value(&self) -> Option<f64>;
+}
+
+const _: () = {
+    crate::impl_for_ref! {
+        impl IntValue for T {
+            #[inline]
+            fn int_value(&self, base: u32) -> Option<i64> {
+                <T>::int_value(self, base)
+            }
+        }
+    }
+
+    crate::impl_for_ref! {
+        impl FloatValue for T {
+            #[inline]
+            fn float_value(&self) -> Option<f64> {
+                <T>::float_value(self)
+            }
+        }
+    }
+
+    impl<T> IntValue for Pin<T>
+    where
+        T: Deref,
+        <T as Deref>::Target: IntValue,
+    {
+        #[inline]
+        fn int_value(&self, base: u32) -> Option<i64> {
+            self.as_ref().get_ref().int_value(base)
+        }
+    }
+
+    impl<T> FloatValue for Pin<T>
+    where
+        T: Deref,
+        <T as Deref>::Target: FloatValue,
+    {
+        #[inline]
+        fn float_value(&self) -> Option<f64> {
+            self.as_ref().get_ref().float_value()
+        }
+    }
+
+    impl IntValue for str {
+        fn int_value(&self, base: u32) -> Option<i64> {
+            if !(2..=36).contains(&base) {
+                return None;
+            }
+            let s = self.trim();
+            let (negative, digits) = match s.as_bytes().first() {
+                Some(b'-') => (true, &s[1..]),
+                Some(b'+') => (false, &s[1..]),
+                _ => (false, s),
+            };
+            let prefix = match base {
+                16 => Some(["0x", "0X"]),
+                8 => Some(["0o", "0O"]),
+                2 => Some(["0b", "0B"]),
+                _ => None,
+            };
+            let digits = prefix
+                .and_then(|prefix| prefix.iter().find_map(|p| digits.strip_prefix(p)))
+                .unwrap_or(digits);
+            let value = match digits.starts_with(['+', '-']) {
+                true => None,
+                false => u64::from_str_radix(digits, base).ok(),
+            };
+            // negate the unsigned value, so `i64::MIN` does not overflow
+            let value = value.and_then(|value| match negative {
+                true => 0_i64.checked_sub_unsigned(value),
+                false => i64::try_from(value).ok(),
+            });
+            match (value, base) {
+                (Some(value), _) => Some(value),
+                (None, 10) => s.float_value()?.int_value(base),
+                (None, _) => None,
+            }
+        }
+    }
+
+    impl FloatValue for str {
+        #[inline]
+        fn float_value(&self) -> Option<f64> {
+            self.trim().parse().ok()
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl IntValue for alloc::string::String {
+        #[inline]
+        fn int_value(&self, base: u32) -> Option<i64> {
+            self.as_str().int_value(base)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl FloatValue for alloc::string::String {
+        #[inline]
+        fn float_value(&self) -> Option<f64> {
+            self.as_str().float_value()
+        }
+    }
+
+    macro_rules! impl_int_float_value {
+        ($($ty:ty)*) => { $(
+            impl IntValue for $ty {
+                #[inline]
+                fn int_value(&self, _: u32) -> Option<i64> {
+                    i64::try_from(*self).ok()
+                }
+            }
+
+            impl FloatValue for $ty {
+                #[inline]
+                fn float_value(&self) -> Option<f64> {
+                    Some(*self as f64)
+                }
+            }
+        )* };
+    }
+
+    impl_int_float_value!(
+        u8 u16 u32 u64 u128 usize
+        i8 i16 i32 i64 i128 isize
+    );
+
+    macro_rules! impl_float_int_value {
+        ($($ty:ty)*) => { $(
+            impl IntValue for $ty {
+                #[inline]
+                fn int_value(&self, _: u32) -> Option<i64> {
+                    // `i64::MIN` is exactly representable, `i64::MAX` rounds up to `2^63`
+                    const RANGE: core::ops::Range<f64> =
+                        -9.223_372_036_854_776e18..9.223_372_036_854_776e18;
+
+                    let value = f64::from(*self);
+                    RANGE.contains(&value).then_some(value as i64)
+                }
+            }
+
+            impl FloatValue for $ty {
+                #[inline]
+                fn float_value(&self) -> Option<f64> {
+                    Some(f64::from(*self))
+                }
+            }
+        )* };
+    }
+
+    impl_float_int_value!(f32 f64);
+};
+
+/// Returns the smallest item of an iterable
+///
+/// If several items are equally small, the first one is returned. Use `min(attr="field")` to
+/// compare the items by one of their fields, and `map(attr="field")|min` to get the smallest
+/// field value. An empty iterable results in an [`Error::EmptyIterable`].
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ numbers|min }} {{ numbers|max }} {{ numbers|sum }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     numbers: &'a [i32],
+/// }
+///
+/// assert_eq!(
+///     Example { numbers: &[3, -1, 4] }.to_string(),
+///     "<div>-1 4 6</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn min<I>(iter: I) -> Result<I::Item>
+where
+    I: IntoIterator,
+    I::Item: PartialOrd,
+{
+    let iter = iter.into_iter();
+    iter.reduce(|min, item| if item < min { item } else { min })
+        .ok_or(Error::EmptyIterable)
+}
+
+/// Returns the item of an iterable with the smallest `key`
+///
+/// This function is used by the [`min()`] filter if an `attr` argument is given.
+#[inline]
+pub fn min_by_key<I, F, K>(iter: I, mut key: F) -> Result<I::Item>
+where
+    I: IntoIterator,
+    F: FnMut(&I::Item) -> K,
+    K: PartialOrd,
+{
+    let iter = iter.into_iter();
+    iter.reduce(|min, item| if key(&item) < key(&min) { item } else { min })
+        .ok_or(Error::EmptyIterable)
+}
+
+/// Returns the largest item of an iterable
+///
+/// If several items are equally large, the first one is returned. Use `max(attr="field")` to
+/// compare the items by one of their fields, and `map(attr="field")|max` to get the largest
+/// field value. An empty iterable results in an [`Error::EmptyIterable`].
+/// Please see [`min()`] for an example.
+#[inline]
+pub fn max<I>(iter: I) -> Result<I::Item>
+where
+    I: IntoIterator,
+    I::Item: PartialOrd,
+{
+    let iter = iter.into_iter();
+    iter.reduce(|max, item| if item > max { item } else { max })
+        .ok_or(Error::EmptyIterable)
+}
+
+/// Returns the item of an iterable with the largest `key`
+///
+/// This function is used by the [`max()`] filter if an `attr` argument is given.
+#[inline]
+pub fn max_by_key<I, F, K>(iter: I, mut key: F) -> Result<I::Item>
+where
+    I: IntoIterator,
+    F: FnMut(&I::Item) -> K,
+    K: PartialOrd,
+{
+    let iter = iter.into_iter();
+    iter.reduce(|max, item| if key(&item) > key(&max) { item } else { max })
+        .ok_or(Error::EmptyIterable)
+}
+
+/// Adds up the numbers of an iterable
+///
+/// The sum starts at zero, so an empty iterable results in `0`. Like in Rust code, an overflow
+/// panics in debug builds. Use `sum(attr="field")` to add up a field of the items.
+/// Please see [`min()`] for an example.
+#[inline]
+pub fn sum<I>(iter: I) -> Result<<I::Item as SumValue>::Output, Infallible>
+where
+    I: IntoIterator,
+    I::Item: SumValue,
+{
+    Ok(iter
+        .into_iter()
+        .fold(Default::default(), |sum, item| item.add_to(sum)))
+}
+
+/// A number that can be added up by the [`sum`] filter.
+pub trait SumValue {
+    /// The type of the sum, its default value is zero.
+    type Output: Default + fmt::Display;
+
+    /// Adds the value to `sum`.
+    fn add_to(&self, sum: Self::Output) -> Self::Output;
+}
+
+const _: () = {
+    crate::impl_for_ref! {
+        impl SumValue for T {
+            type Output = T::Output;
+
+            #[inline]
+            fn add_to(&self, sum: Self::Output) -> Self::Output {
+                <T>::add_to(self, sum)
+            }
+        }
+    }
+
+    impl<T> SumValue for Pin<T>
+    where
+        T: Deref,
+        <T as Deref>::Target: SumValue,
+    {
+        type Output = <<T as Deref>::Target as SumValue>::Output;
+
+        #[inline]
+        fn add_to(&self, sum: Self::Output) -> Self::Output {
+            self.as_ref().get_ref().add_to(sum)
+        }
+    }
+
+    macro_rules! impl_sum_value {
+        ($($ty:ty)*) => { $(
+            impl SumValue for $ty {
+                type Output = $ty;
+
+                #[inline]
+                fn add_to(&self, sum: Self::Output) -> Self::Output {
+                    sum + *self
+                }
+            }
+        )* };
+    }
+
+    impl_sum_value!(
+        u8 u16 u32 u64 u128 usize
+        i8 i16 i32 i64 i128 isize
+        f32 f64
+    );
+};
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    #[test]
+    fn test_center() {
+        assert_eq!(center("f", 3).unwrap().to_string(), " f ".to_string());
+        assert_eq!(center("f", 4).unwrap().to_string(), " f  ".to_string());
+        assert_eq!(center("foo", 1).unwrap().to_string(), "foo".to_string());
+        assert_eq!(
+            center("foo bar", 8).unwrap().to_string(),
+            "foo bar ".to_string()
+        );
+        assert_eq!(
+            center("foo", 111_669_149_696).unwrap().to_string(),
+            "foo".to_string()
+        );
+        assert_eq!(center("äöü", 5).unwrap().to_string(), " äöü ");
+        assert_eq!(center(42, 6).unwrap().to_string(), "  42  ");
+    }
+
+    #[test]
+    fn test_center_fill() {
+        assert_eq!(center_fill("f", 4, '*').unwrap().to_string(), "*f**");
+        assert_eq!(center_fill("f", 3, "é").unwrap().to_string(), "éfé");
+        assert_eq!(center_fill("äöü", 7, "*").unwrap().to_string(), "**äöü**");
+        assert_eq!(center_fill("foo", 2, "-").unwrap().to_string(), "foo");
+        assert!(center_fill("f", 4, "").is_err());
+        assert!(center_fill("f", 4, "ab").is_err());
+    }
+
+    #[test]
+    fn test_min_max_sum() {
+        assert_eq!(min([3, 1, 2]).unwrap(), 1);
+        assert_eq!(max([3, 1, 2].iter()).unwrap(), &3);
+        assert_eq!(min([1.5, f64::NAN, 0.5]).unwrap(), 0.5);
+        assert!(matches!(max::<[u8; 0]>([]), Err(Error::EmptyIterable)));
+
+        let items = [("a", 2), ("b", 1), ("c", 2)];
+        assert_eq!(min_by_key(items, |(_, n)| *n).unwrap(), ("b", 1));
+        assert_eq!(max_by_key(items.iter(), |(_, n)| *n).unwrap(), &("a", 2));
+
+        assert_eq!(sum([1_u8, 2, 3]).unwrap(), 6);
+        assert_eq!(sum([1.5_f32, 2.0].iter()).unwrap(), 3.5);
+        assert_eq!(sum(Vec::<&&i64>::new()).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_abs() {
+        assert_eq!(abs(-3_i8).unwrap(), 3_u8);
+        assert_eq!(abs(i64::MIN).unwrap(), 1_u64 << 63);
+        assert_eq!(abs(&7_usize).unwrap(), 7);
+        assert_eq!(abs(-0.5_f32).unwrap(), 0.5);
+        assert_eq!(abs(-0.0_f64).unwrap().to_string(), "0");
+    }
+
+    #[test]
+    fn test_int() {
+        assert_eq!(int("42", 0, 10).unwrap(), 42);
+        assert_eq!(int(" -17\n", 0, 10).unwrap(), -17);
+        assert_eq!(int("+5", 0, 10).unwrap(), 5);
+        assert_eq!(int("4.7", 0, 10).unwrap(), 4);
+        assert_eq!(int("-4.7", 0, 10).unwrap(), -4);
+        assert_eq!(int("1e3", 0, 10).unwrap(), 1000);
+        assert_eq!(int("ff", 0, 16).unwrap(), 255);
+        assert_eq!(int("0xFF", 0, 16).unwrap(), 255);
+        assert_eq!(int("-0x10", 0, 16).unwrap(), -16);
+        assert_eq!(int("0b101", 0, 2).unwrap(), 5);
+        assert_eq!(int("0o17", 0, 8).unwrap(), 15);
+        assert_eq!(int("z", 0, 36).unwrap(), 35);
+        assert_eq!(int("-9223372036854775808", 0, 10).unwrap(), i64::MIN);
+        assert_eq!(int("9223372036854775808", -1, 10).unwrap(), -1);
+        assert_eq!(int("1.5", -1, 16).unwrap(), -1);
+        assert_eq!(int("0x-1", -1, 16).unwrap(), -1);
+        assert_eq!(int("--1", -1, 10).unwrap(), -1);
+        assert_eq!(int("", -1, 10).unwrap(), -1);
+        assert_eq!(int("abc", -1, 10).unwrap(), -1);
+        assert_eq!(int("10", -1, 1).unwrap(), -1);
+        assert_eq!(int("10", -1, 37).unwrap(), -1);
+        assert_eq!(int(String::from("12"), 0, 10).unwrap(), 12);
+
+        assert_eq!(int(7_u8, 0, 10).unwrap(), 7);
+        assert_eq!(int(u64::MAX, -1, 10).unwrap(), -1);
+        assert_eq!(int(-2.9_f64, 0, 10).unwrap(), -2);
+        assert_eq!(int(f64::NAN, -1, 10).unwrap(), -1);
+        assert_eq!(int(f64::INFINITY, -1, 10).unwrap(), -1);
+        assert_eq!(int(-9.3e18_f32, -1, 10).unwrap(), -1);
+    }
+
+    #[test]
+    fn test_float() {
+        assert_eq!(float("2.25", 0.0).unwrap(), 2.25);
+        assert_eq!(float(" -1e3 ", 0.0).unwrap(), -1000.0);
+        assert_eq!(float("n/a", 1.5).unwrap(), 1.5);
+        assert_eq!(float(String::from("3"), 0.0).unwrap(), 3.0);
+        assert_eq!(float(-4_i32, 0.0).unwrap(), -4.0);
+        assert_eq!(float(0.5_f32, 0.0).unwrap(), 0.5);
+    }
+
+    #[test]
+    fn test_length() {
+        assert_eq!("größe".length(), 5);
+        assert_eq!(String::from("größe").length(), 5);
+        assert_eq!([1, 2, 3].length(), 3);
+        assert_eq!(Length::length(&&[1, 2][..]), 2);
+        assert_eq!(Vec::<u8>::new().length(), 0);
+        assert_eq!(
+            alloc::collections::BTreeMap::from([(1, 2), (3, 4)]).length(),
+            2
+        );
+    }
+
+    #[allow(clippy::needless_borrow)]
+    #[test]
+    fn test_join() {
+        assert_eq!(
+            join((&["hello", "world"]).iter(), ", ")
+                .unwrap()
+                .to_string(),
+            "hello, world"
+        );
+        assert_eq!(
+            join((&["hello"]).iter(), ", ").unwrap().to_string(),
+            "hello"
+        );
+
+        let empty: &[&str] = &[];
+        assert_eq!(join(empty.iter(), ", ").unwrap().to_string(), "");
+
+        let input: Vec<String> = alloc::vec!["foo".into(), "bar".into(), "bazz".into()];
+        assert_eq!(join(input.iter(), ":").unwrap().to_string(), "foo:bar:bazz");
+
+        let input: &[String] = &["foo".into(), "bar".into()];
+        assert_eq!(join(input.iter(), ":").unwrap().to_string(), "foo:bar");
+
+        let real: String = "blah".into();
+        let input: Vec<&str> = alloc::vec![&real];
+        assert_eq!(join(input.iter(), ";").unwrap().to_string(), "blah");
+
+        assert_eq!(
+            join((&&&&&["foo", "bar"]).iter(), ", ")
+                .unwrap()
+                .to_string(),
+            "foo, bar"
+        );
+    }
+
+    #[test]
+    fn test_join_escaped() {
+        use crate::filters::{Html, Text};
+
+        let input = ["<b>", "Tom & Jerry", "x"];
+        assert_eq!(
+            join_escaped(input.iter(), "</li><li>", Html)
+                .unwrap()
+                .to_string(),
+            "&#60;b&#62;</li><li>Tom &#38; Jerry</li><li>x"
+        );
+        assert_eq!(
+            join_escaped(input.iter(), "<br>", Text)
+                .unwrap()
+                .to_string(),
+            "<b><br>Tom & Jerry<br>x"
+        );
+
+        let empty: &[&str] = &[];
+        assert_eq!(
+            join_escaped(empty.iter(), "<br>", Html)
+                .unwrap()
+                .to_string(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_round() {
+        fn round(value: f64, precision: i32, method: RoundMethod) -> String {
+            super::round(value, precision, method).unwrap().to_string()
+        }
+
+        use RoundMethod::{Ceil, Common, Floor};
+        assert_eq!(round(2.5, 0, Common), "3");
+        assert_eq!(round(-2.5, 0, Common), "-3");
+        assert_eq!(round(2.4999, 0, Common), "2");
+        assert_eq!(round(2.4, 0, Ceil), "3");
+        assert_eq!(round(-2.4, 0, Ceil), "-2");
+        assert_eq!(round(2.6, 0, Floor), "2");
+        assert_eq!(round(-2.4, 0, Floor), "-3");
+        assert_eq!(round(-0.2, 0, Common), "-0");
+        assert_eq!(round(1.23456, 2, Common), "1.23");
+        assert_eq!(round(0.1 + 0.2, 2, Common), "0.3");
+        assert_eq!(round(1.005, 2, Ceil), "1.01");
+        assert_eq!(round(1.999, 2, Floor), "1.99");
+        assert_eq!(round(1234.5, -2, Common), "1200");
+        assert_eq!(round(1250.0, -2, Common), "1300");
+        assert_eq!(round(1201.0, -2, Ceil), "1300");
+        assert_eq!(super::round(1e300, 5, Common).unwrap(), 1e300);
+        assert_eq!(round(1.5, 400, Common), "1.5");
+        assert_eq!(round(1.5, -400, Common), "0");
+        assert_eq!(round(f64::INFINITY, 2, Common), "inf");
+        assert_eq!(round(f64::NAN, 2, Common), "NaN");
+
+        assert_eq!(
+            super::round(2.456_f32, 1, Common).unwrap().to_string(),
+            "2.5"
+        );
+        assert_eq!(
+            super::round(&&0.123_456_f32, 3, Floor).unwrap().to_string(),
+            "0.123"
         );
     }
 }
@@ -0,0 +1,207 @@
+use alloc::format;
+use alloc::string::String;
+use core::ops::Deref;
+use core::pin::Pin;
+
+use crate::{Error, Result};
+
+/// Formats a date, a time or a date and time with a strftime-like `pattern`
+/// (requires `chrono` or `time` feature)
+///
+/// The filter accepts the types of [`chrono`] (with the `chrono` feature), i.e.
+/// [`NaiveDate`][chrono::NaiveDate], [`NaiveTime`][chrono::NaiveTime],
+/// [`NaiveDateTime`][chrono::NaiveDateTime] and [`DateTime`][chrono::DateTime], and the types of
+/// [`time`] (with the `time` feature), i.e. [`Date`][time::Date], [`Time`][time::Time],
+/// [`PrimitiveDateTime`][time::PrimitiveDateTime] and [`OffsetDateTime`][time::OffsetDateTime].
+/// Please see the documentation of [`chrono::format::strftime`] and
+/// [`time::format_description::parse_strftime_borrowed()`] for the supported specifiers.
+///
+/// If the pattern is invalid, or if it uses a field that the value does not have, e.g. the hour
+/// of a date, the rendering fails with an error that contains the pattern.
+///
+/// ```
+/// # #[cfg(all(feature = "code-in-doc", feature = "chrono"))] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ published|date("%Y-%m-%d") }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example {
+///     published: chrono::NaiveDate,
+/// }
+///
+/// assert_eq!(
+///     Example { published: chrono::NaiveDate::from_ymd_opt(2024, 8, 26).unwrap() }.to_string(),
+///     "<div>2024-08-26</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn date<T: DateValue + ?Sized>(value: &T, pattern: &str) -> Result<String> {
+    value.format_date(pattern)
+}
+
+/// A date or time value that can be formatted by the [`date`] filter.
+pub trait DateValue {
+    /// Formats the value with a strftime-like `pattern`.
+    fn format_date(&self, pattern: &str) -> Result<String>;
+}
+
+crate::impl_for_ref! {
+    impl DateValue for T {
+        #[inline]
+        fn format_date(&self, pattern: &str) -> Result<String> {
+            <T>::format_date(self, pattern)
+        }
+    }
+}
+
+impl<T> DateValue for Pin<T>
+where
+    T: Deref,
+    <T as Deref>::Target: DateValue,
+{
+    #[inline]
+    fn format_date(&self, pattern: &str) -> Result<String> {
+        self.as_ref().get_ref().format_date(pattern)
+    }
+}
+
+fn invalid_pattern(pattern: &str, err: impl core::fmt::Display) -> Error {
+    Error::custom(format!("invalid date pattern {pattern:?}: {err}"))
+}
+
+#[cfg(feature = "chrono")]
+const _: () = {
+    use alloc::vec::Vec;
+    use core::fmt::{Display, Write};
+
+    use chrono::format::{Item, StrftimeItems};
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+
+    fn parse_pattern(pattern: &str) -> Result<Vec<Item<'_>>> {
+        StrftimeItems::new(pattern)
+            .parse()
+            .map_err(|err| invalid_pattern(pattern, err))
+    }
+
+    fn write_formatted(pattern: &str, formatted: impl Display) -> Result<String> {
+        let mut dest = String::new();
+        write!(dest, "{formatted}").map_err(|_| {
+            invalid_pattern(
+                pattern,
+                "the value does not have all fields that the pattern uses",
+            )
+        })?;
+        Ok(dest)
+    }
+
+    macro_rules! impl_date_value {
+        ($($ty:ty)*) => { $(
+            impl DateValue for $ty {
+                #[inline]
+                fn format_date(&self, pattern: &str) -> Result<String> {
+                    write_formatted(
+                        pattern,
+                        self.format_with_items(parse_pattern(pattern)?.iter()),
+                    )
+                }
+            }
+        )* };
+    }
+
+    impl_date_value!(NaiveDate NaiveTime NaiveDateTime);
+
+    impl<Tz: TimeZone> DateValue for DateTime<Tz>
+    where
+        Tz::Offset: Display,
+    {
+        #[inline]
+        fn format_date(&self, pattern: &str) -> Result<String> {
+            write_formatted(
+                pattern,
+                self.format_with_items(parse_pattern(pattern)?.iter()),
+            )
+        }
+    }
+};
+
+#[cfg(feature = "time")]
+const _: () = {
+    use time::format_description::parse_strftime_borrowed;
+    use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
+
+    macro_rules! impl_date_value {
+        ($($ty:ty)*) => { $(
+            impl DateValue for $ty {
+                fn format_date(&self, pattern: &str) -> Result<String> {
+                    let items = parse_strftime_borrowed(pattern)
+                        .map_err(|err| invalid_pattern(pattern, err))?;
+                    self.format(items.as_slice())
+                        .map_err(|err| invalid_pattern(pattern, err))
+                }
+            }
+        )* };
+    }
+
+    impl_date_value!(Date Time PrimitiveDateTime OffsetDateTime);
+};
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_date_chrono() {
+        use chrono::{FixedOffset, NaiveDate};
+
+        let day = NaiveDate::from_ymd_opt(2024, 8, 26).unwrap();
+        assert_eq!(day.format_date("%Y-%m-%d").unwrap(), "2024-08-26");
+        assert_eq!(day.format_date("%d.%m.%y").unwrap(), "26.08.24");
+
+        let datetime = day.and_hms_opt(13, 5, 9).unwrap();
+        assert_eq!(date(&datetime, "%F %T").unwrap(), "2024-08-26 13:05:09");
+        assert_eq!(datetime.time().format_date("%H:%M").unwrap(), "13:05");
+
+        let offset = FixedOffset::east_opt(2 * 3600).unwrap();
+        let datetime = datetime.and_local_timezone(offset).unwrap();
+        assert_eq!(
+            date(&&datetime, "%F %T %z").unwrap(),
+            "2024-08-26 13:05:09 +0200"
+        );
+
+        let err = date(&day, "%Y-%Q").unwrap_err().to_string();
+        assert!(err.contains("\"%Y-%Q\""), "{err}");
+        let err = date(&day, "%H:%M").unwrap_err().to_string();
+        assert!(err.contains("\"%H:%M\""), "{err}");
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_date_time() {
+        use time::{Date, Month, Time, UtcOffset};
+
+        let day = Date::from_calendar_date(2024, Month::August, 26).unwrap();
+        assert_eq!(day.format_date("%Y-%m-%d").unwrap(), "2024-08-26");
+        assert_eq!(day.format_date("%d.%m.%y").unwrap(), "26.08.24");
+
+        let datetime = day.with_time(Time::from_hms(13, 5, 9).unwrap());
+        assert_eq!(date(&datetime, "%F %T").unwrap(), "2024-08-26 13:05:09");
+        assert_eq!(datetime.time().format_date("%H:%M").unwrap(), "13:05");
+
+        let datetime = datetime.assume_offset(UtcOffset::from_hms(2, 0, 0).unwrap());
+        assert_eq!(
+            date(&&datetime, "%F %T %z").unwrap(),
+            "2024-08-26 13:05:09 +0200"
+        );
+
+        let err = date(&day, "%Y-%Q").unwrap_err().to_string();
+        assert!(err.contains("\"%Y-%Q\""), "{err}");
+        let err = date(&day, "%H:%M").unwrap_err().to_string();
+        assert!(err.contains("\"%H:%M\""), "{err}");
+    }
+}
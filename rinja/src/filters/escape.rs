@@ -9,6 +9,10 @@ use core::str;
 /// Use this if you want to allow markup in an expression, or if you know
 /// that the expression's contents don't need to be escaped.
 ///
+/// The marker only applies to the expression it is attached to: other
+/// interpolations are still escaped, and any filter applied after `safe`
+/// gets escaped output again.
+///
 /// Rinja will automatically insert the first (`Escaper`) argument,
 /// so this filter only takes a single argument of any type that implements
 /// `Display`.
@@ -69,7 +73,7 @@ pub fn escape<T, E>(text: T, escaper: E) -> Result<Safe<EscapeDisplay<T, E>>, In
     Ok(Safe(EscapeDisplay(text, escaper)))
 }
 
-pub struct EscapeDisplay<T, E>(T, E);
+pub struct EscapeDisplay<T, E>(pub(crate) T, pub(crate) E);
 
 impl<T: fmt::Display, E: Escaper> fmt::Display for EscapeDisplay<T, E> {
     #[inline]
@@ -162,6 +166,37 @@ impl Escaper for Text {
     }
 }
 
+/// Selects the escaper of a template at runtime
+///
+/// A template with the attribute `#[template(escape_field = "field")]` is escaped with [`Html`] or
+/// with [`Text`], depending on the value of `field`. The field can be an `EscapeMode`, or any
+/// `Copy` type that implements <code>[Into]&lt;EscapeMode&gt;</code>.
+///
+/// ```
+/// # use rinja::Template;
+/// # use rinja::filters::EscapeMode;
+/// #[derive(Template)]
+/// #[template(source = "Hello, {{ name }}!", ext = "txt", escape_field = "mode")]
+/// struct Greeting<'a> {
+///     name: &'a str,
+///     mode: EscapeMode,
+/// }
+///
+/// let name = "<Ann>";
+/// let html = Greeting { name, mode: EscapeMode::Html };
+/// assert_eq!(html.to_string(), "Hello, &#60;Ann&#62;!");
+/// let text = Greeting { name, mode: EscapeMode::Text };
+/// assert_eq!(text.to_string(), "Hello, <Ann>!");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum EscapeMode {
+    /// Escape the output with [`Html`]
+    #[default]
+    Html,
+    /// Don't escape the output, like [`Text`]
+    Text,
+}
+
 /// Escapers are used to make generated text safe for printing in some context.
 ///
 /// E.g. in an [`Html`] context, any and all generated text can be used in HTML/XML text nodes and
@@ -244,6 +279,13 @@ impl<'a, T: HtmlSafe + ?Sized> AutoEscape for &AutoEscaper<'a, T, Html> {
 /// If the filter is not used as the last element in the filter chain, then any assumption is void.
 /// Let the next filter decide if the output is safe or not.
 ///
+/// `Safe` can also be the type of a template field, e.g. `body: Safe<String>`, to mark pre-escaped
+/// content once instead of writing `{{ body|safe }}` at every use site.
+///
+/// By wrapping a value in `Safe`, you promise that its string representation is already escaped
+/// correctly for every template it gets rendered in. Never wrap content that was not escaped or
+/// sanitized, e.g. user input, because this opens the door for cross-site scripting (XSS).
+///
 /// ## Example
 ///
 /// ```rust
@@ -10,12 +10,15 @@ use super::FastWritable;
 
 /// Serialize to JSON (requires `json` feature)
 ///
-/// The generated string does not contain ampersands `&`, chevrons `< >`, or apostrophes `'`.
-/// To use it in a `<script>` you can combine it with the safe filter:
+/// The generated string does not contain ampersands `&`, chevrons `< >`, apostrophes `'`,
+/// or the line terminators U+2028 and U+2029.
+/// To use it in a `<script>` you can combine it with the safe filter, or use the `tojson` alias,
+/// whose output is already marked as safe:
 ///
 /// ``` html
 /// <script>
 /// var data = {{data|json|safe}};
+/// var same = {{data|tojson}};
 /// </script>
 /// ```
 ///
@@ -251,18 +254,23 @@ impl<W: fmt::Write + ?Sized> io::Write for JsonWriter<'_, W> {
 fn write<W: fmt::Write + ?Sized>(f: &mut W, bytes: &[u8]) -> fmt::Result {
     let mut last = 0;
     for (index, byte) in bytes.iter().enumerate() {
-        let escaped = match byte {
-            b'&' => Some(br"\u0026"),
-            b'\'' => Some(br"\u0027"),
-            b'<' => Some(br"\u003c"),
-            b'>' => Some(br"\u003e"),
-            _ => None,
+        let (escaped, len) = match byte {
+            b'&' => (r"\u0026", 1),
+            b'\'' => (r"\u0027", 1),
+            b'<' => (r"\u003c", 1),
+            b'>' => (r"\u003e", 1),
+            // U+2028 LINE SEPARATOR and U+2029 PARAGRAPH SEPARATOR are line terminators in
+            // older JavaScript engines, so they must not appear verbatim in a `<script>`.
+            0xe2 => match bytes.get(index + 1..index + 3) {
+                Some([0x80, 0xa8]) => (r"\u2028", 3),
+                Some([0x80, 0xa9]) => (r"\u2029", 3),
+                _ => continue,
+            },
+            _ => continue,
         };
-        if let Some(escaped) = escaped {
-            f.write_str(unsafe { str::from_utf8_unchecked(&bytes[last..index]) })?;
-            f.write_str(unsafe { str::from_utf8_unchecked(escaped) })?;
-            last = index + 1;
-        }
+        f.write_str(unsafe { str::from_utf8_unchecked(&bytes[last..index]) })?;
+        f.write_str(escaped)?;
+        last = index + len;
     }
     f.write_str(unsafe { str::from_utf8_unchecked(&bytes[last..]) })
 }
@@ -288,6 +296,10 @@ mod tests {
             json(vec!["foo", "bar"]).unwrap().to_string(),
             r#"["foo","bar"]"#
         );
+        assert_eq!(
+            json("a\u{2028}b\u{2029}c & 'd'").unwrap().to_string(),
+            r#""a\u2028b\u2029c \u0026 \u0027d\u0027""#
+        );
     }
 
     #[test]
@@ -0,0 +1,170 @@
+use std::fmt::{self, Write};
+use std::string::String;
+
+use pulldown_cmark::{CowStr, Event, Options, Parser, Tag, html};
+
+use super::HtmlSafeOutput;
+
+/// Renders a markdown string to HTML (requires `markdown` feature)
+///
+/// The [CommonMark](https://commonmark.org/) input is rendered with
+/// [`pulldown-cmark`](https://crates.io/crates/pulldown-cmark). Tables, strikethrough
+/// `~~text~~`, task lists `- [x] done`, and footnotes are supported, too.
+/// The output is marked as HTML safe, so it is not escaped a second time.
+///
+/// Markdown allows raw HTML in the input, which is copied to the output as is.
+/// If the input is not trusted, e.g. if it is user-authored content, use `sanitize=true`:
+/// then raw HTML is escaped and shown as text, and links and images may only point to
+/// relative URLs, or to URLs with the scheme `http`, `https`, `mailto` or `tel`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ text|markdown }}</div>
+/// /// <div>{{ text|markdown(sanitize=true) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     text: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { text: "*Hi* <u>you</u> [x](javascript:alert(1))" }.to_string(),
+///     "<div><p><em>Hi</em> <u>you</u> <a href=\"javascript:alert(1)\">x</a></p>\n</div>\n\
+///      <div><p><em>Hi</em> &lt;u&gt;you&lt;/u&gt; <a href=\"#\">x</a></p>\n</div>"
+/// );
+/// # }
+/// ```
+pub fn markdown(
+    s: impl fmt::Display,
+    sanitize: bool,
+) -> Result<HtmlSafeOutput<String>, fmt::Error> {
+    let mut buffer = String::new();
+    let source = match format_args!("{s}").as_str() {
+        Some(source) => source,
+        None => {
+            write!(buffer, "{s}")?;
+            buffer.as_str()
+        }
+    };
+
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+    let parser = Parser::new_ext(source, options);
+
+    let mut output = String::with_capacity(source.len() + source.len() / 2);
+    if sanitize {
+        html::push_html(&mut output, parser.map(sanitize_event));
+    } else {
+        html::push_html(&mut output, parser);
+    }
+    Ok(HtmlSafeOutput(output))
+}
+
+fn sanitize_event(event: Event<'_>) -> Event<'_> {
+    match event {
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: sanitize_url(dest_url),
+            title,
+            id,
+        }),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Image {
+            link_type,
+            dest_url: sanitize_url(dest_url),
+            title,
+            id,
+        }),
+        event => event,
+    }
+}
+
+fn sanitize_url(url: CowStr<'_>) -> CowStr<'_> {
+    // Browsers ignore whitespaces and control characters in the scheme, e.g. `java\tscript:`.
+    let mut scheme = String::new();
+    for c in url.chars() {
+        match c {
+            ':' => break,
+            // the URL is relative
+            '/' | '?' | '#' => return url,
+            c if c.is_ascii_whitespace() || c.is_ascii_control() => {}
+            c => scheme.push(c.to_ascii_lowercase()),
+        }
+    }
+    if !url.contains(':') || matches!(scheme.as_str(), "http" | "https" | "mailto" | "tel") {
+        url
+    } else {
+        CowStr::Borrowed("#")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown() {
+        let render = |s: &str, sanitize| markdown(s, sanitize).unwrap().0;
+
+        assert_eq!(render("", false), "");
+        assert_eq!(
+            render("# Title\n\n* a\n* **b**", false),
+            "<h1>Title</h1>\n<ul>\n<li>a</li>\n<li><strong>b</strong></li>\n</ul>\n"
+        );
+        assert_eq!(
+            render("| a |\n|---|\n| ~~b~~ |", false),
+            "<table><thead><tr><th>a</th></tr></thead><tbody>\n\
+             <tr><td><del>b</del></td></tr>\n</tbody></table>\n"
+        );
+        assert_eq!(
+            render("<script>alert(1)</script>\n\nx <b>y</b>", false),
+            "<script>alert(1)</script>\n<p>x <b>y</b></p>\n"
+        );
+        assert_eq!(
+            render("<script>alert(1)</script>\n\nx <b>y</b>", true),
+            "&lt;script&gt;alert(1)&lt;/script&gt;\n<p>x &lt;b&gt;y&lt;/b&gt;</p>\n"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_url() {
+        for url in [
+            "https://example.com/",
+            "HTTP://example.com/",
+            "mailto:a@example.com",
+            "tel:+1234",
+            "/a:b",
+            "a/b:c",
+            "?a:b",
+            "#a:b",
+            "relative",
+        ] {
+            assert_eq!(&*sanitize_url(url.into()), url);
+        }
+        for url in [
+            "javascript:alert(1)",
+            "JavaScript:alert(1)",
+            "java\tscript:alert(1)",
+            " javascript:alert(1)",
+            "data:text/html,<script>alert(1)</script>",
+            "vbscript:x",
+        ] {
+            assert_eq!(&*sanitize_url(url.into()), "#");
+        }
+    }
+}
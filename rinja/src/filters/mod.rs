@@ -13,26 +13,45 @@
 #[cfg(feature = "alloc")]
 mod alloc;
 mod builtin;
+#[cfg(any(feature = "chrono", feature = "time"))]
+mod date;
 mod escape;
 mod humansize;
 #[cfg(feature = "serde_json")]
 mod json;
+#[cfg(feature = "markdown")]
+mod markdown;
+#[cfg(feature = "rand")]
+mod random;
 #[cfg(feature = "urlencode")]
 mod urlencode;
 
 #[cfg(feature = "alloc")]
 pub use self::alloc::{
-    capitalize, fmt, format, indent, linebreaks, linebreaksbr, lower, lowercase, paragraphbreaks,
-    title, trim, upper, uppercase, wordcount,
+    Attributes, XmlAttrValue, attr, capitalize, columns, dictsort, dictsort_by_value, fmt, format,
+    indent, indent_with, linebreaks, linebreaksbr, lower, lowercase, nl2br, paragraphbreaks,
+    replace, replacen, slice, sort, sort_by_key, striptags, title, trim, trim_chars, trim_end,
+    trim_end_chars, trim_start, trim_start_chars, unique, unique_by, upper, uppercase, urlize,
+    wordcount, xmlattr,
 };
-pub use self::builtin::{PluralizeCount, center, join, pluralize, truncate};
+pub use self::builtin::{
+    AbsValue, FloatValue, IntValue, Length, PluralizeCount, RoundMethod, RoundValue, SumValue, abs,
+    center, center_fill, first, float, int, join, join_escaped, max, max_by_key, min, min_by_key,
+    pluralize, round, sum, truncate,
+};
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub use self::date::{DateValue, date};
 pub use self::escape::{
-    AutoEscape, AutoEscaper, Escaper, FastWritable, Html, HtmlSafe, HtmlSafeOutput, MaybeSafe,
-    Safe, Text, Unsafe, Writable, WriteWritable, e, escape, safe,
+    AutoEscape, AutoEscaper, EscapeMode, Escaper, FastWritable, Html, HtmlSafe, HtmlSafeOutput,
+    MaybeSafe, Safe, Text, Unsafe, Writable, WriteWritable, e, escape, safe,
 };
 pub use self::humansize::filesizeformat;
 #[cfg(feature = "serde_json")]
 pub use self::json::{AsIndent, json, json_pretty};
+#[cfg(feature = "markdown")]
+pub use self::markdown::markdown;
+#[cfg(feature = "rand")]
+pub use self::random::{random, random_seeded, shuffle, shuffle_seeded};
 #[cfg(feature = "urlencode")]
 pub use self::urlencode::{urlencode, urlencode_strict};
 
@@ -0,0 +1,130 @@
+use std::convert::Infallible;
+use std::vec::Vec;
+
+use rand::rngs::StdRng;
+use rand::seq::{IteratorRandom, SliceRandom};
+use rand::{Rng, SeedableRng, thread_rng};
+
+use crate::{Error, Result};
+
+/// Returns a random item of an iterable (requires `rand` feature)
+///
+/// Without a `seed` argument, the item is picked using [`rand::thread_rng()`], so every rendering
+/// can have a different result. With `seed=…` the pick is deterministic, which is useful e.g. in
+/// tests, or to rotate an item once per day by using the current date as seed. The same seed
+/// might result in a different pick after an upgrade of `rand`, though.
+///
+/// An empty iterable results in an [`Error::EmptyIterable`].
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// <div>{{ testimonials|random }}</div>
+/// /// <div>{{ testimonials|random(seed=day) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     testimonials: &'a [&'a str],
+///     day: u64,
+/// }
+///
+/// let example = Example { testimonials: &["great", "good", "fine"], day: 17 };
+/// let first = example.to_string();
+/// assert_eq!(example.to_string().lines().last(), first.lines().last());
+/// # }
+/// ```
+#[inline]
+pub fn random<I: IntoIterator>(iter: I) -> Result<I::Item> {
+    random_with(iter, &mut thread_rng())
+}
+
+/// Returns a random item of an iterable, using a deterministic `seed` (requires `rand` feature)
+///
+/// Please see [`random()`] for more information.
+#[inline]
+pub fn random_seeded<I: IntoIterator>(iter: I, seed: u64) -> Result<I::Item> {
+    random_with(iter, &mut StdRng::seed_from_u64(seed))
+}
+
+fn random_with<I: IntoIterator, R: Rng>(iter: I, rng: &mut R) -> Result<I::Item> {
+    iter.into_iter().choose(rng).ok_or(Error::EmptyIterable)
+}
+
+/// Returns the items of an iterable in a random order (requires `rand` feature)
+///
+/// Without a `seed` argument, the items are shuffled using [`rand::thread_rng()`], so every
+/// rendering can have a different result. With `seed=…` the order is deterministic.
+/// The same seed might result in a different order after an upgrade of `rand`, though.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use rinja::Template;
+/// /// ```jinja
+/// /// {% for ad in ads|shuffle(seed=42) %}<div>{{ ad }}</div>{% endfor %}
+/// /// {{ ads|shuffle|join(", ") }}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     ads: &'a [&'a str],
+/// }
+///
+/// let example = Example { ads: &["a", "b", "c"] };
+/// let first = example.to_string();
+/// assert_eq!(example.to_string().lines().next(), first.lines().next());
+/// # }
+/// ```
+#[inline]
+pub fn shuffle<I: IntoIterator>(iter: I) -> Result<Vec<I::Item>, Infallible> {
+    Ok(shuffle_with(iter, &mut thread_rng()))
+}
+
+/// Returns the items of an iterable in a random order, using a deterministic `seed`
+/// (requires `rand` feature)
+///
+/// Please see [`shuffle()`] for more information.
+#[inline]
+pub fn shuffle_seeded<I: IntoIterator>(iter: I, seed: u64) -> Result<Vec<I::Item>, Infallible> {
+    Ok(shuffle_with(iter, &mut StdRng::seed_from_u64(seed)))
+}
+
+fn shuffle_with<I: IntoIterator, R: Rng>(iter: I, rng: &mut R) -> Vec<I::Item> {
+    let mut items: Vec<_> = iter.into_iter().collect();
+    items.shuffle(rng);
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random() {
+        let items = [1, 2, 3, 4, 5];
+        assert!(items.contains(&random(items).unwrap()));
+        assert_eq!(
+            random_seeded(items, 7).unwrap(),
+            random_seeded(items, 7).unwrap()
+        );
+        assert!(matches!(random([0_u32; 0]), Err(Error::EmptyIterable)));
+        assert!(matches!(
+            random_seeded([0_u32; 0], 7),
+            Err(Error::EmptyIterable)
+        ));
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let items = [1, 2, 3, 4, 5];
+        let mut shuffled = shuffle(items).unwrap();
+        shuffled.sort_unstable();
+        assert_eq!(shuffled, items);
+        assert_eq!(
+            shuffle_seeded(items, 7).unwrap(),
+            shuffle_seeded(items, 7).unwrap()
+        );
+        assert!(shuffle([0_u32; 0]).unwrap().is_empty());
+    }
+}
@@ -5,15 +5,23 @@ pub extern crate alloc;
 pub extern crate core;
 #[cfg(feature = "std")]
 pub extern crate std;
+#[cfg(feature = "tracing")]
+pub extern crate tracing;
 
-use core::cell::Cell;
+use core::cell::{Cell, RefCell};
 use core::fmt;
 use core::iter::{Enumerate, Peekable};
+use core::marker::PhantomData;
 use core::ops::Deref;
 use core::pin::Pin;
+#[cfg(feature = "adaptive-size-hint")]
+use core::sync::atomic::{AtomicUsize, Ordering};
 
 pub use crate::error::{ErrorMarker, ResultConverter};
-use crate::filters::FastWritable;
+use crate::filters::{FastWritable, Length};
+#[cfg(feature = "fluent")]
+pub use crate::l10n::*;
+use crate::{Error, Location, Value, Values};
 
 pub struct TemplateLoop<I>
 where
@@ -59,6 +67,195 @@ pub struct LoopItem {
     pub last: bool,
 }
 
+/// Like [`TemplateLoop`], but also yields copies of the previous and next items.
+///
+/// Used if a loop accesses `loop.previtem` or `loop.nextitem`.
+pub struct TemplateLoopNeighbors<I>
+where
+    I: Iterator,
+{
+    iter: Peekable<Enumerate<I>>,
+    prev: Option<<I as Iterator>::Item>,
+}
+
+impl<I> TemplateLoopNeighbors<I>
+where
+    I: Iterator,
+    <I as Iterator>::Item: Clone,
+{
+    #[inline]
+    pub fn new(iter: I) -> Self {
+        TemplateLoopNeighbors {
+            iter: iter.enumerate().peekable(),
+            prev: None,
+        }
+    }
+}
+
+impl<I> Iterator for TemplateLoopNeighbors<I>
+where
+    I: Iterator,
+    <I as Iterator>::Item: Clone,
+{
+    type Item = (
+        <I as Iterator>::Item,
+        LoopItem,
+        LoopNeighbors<<I as Iterator>::Item>,
+    );
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, item) = self.iter.next()?;
+        let next = self.iter.peek().map(|(_, next)| next.clone());
+        let prev = self.prev.replace(item.clone());
+        let loop_item = LoopItem {
+            index,
+            first: index == 0,
+            last: next.is_none(),
+        };
+        Some((item, loop_item, LoopNeighbors { prev, next }))
+    }
+}
+
+pub struct LoopNeighbors<T> {
+    pub prev: Option<T>,
+    pub next: Option<T>,
+}
+
+/// Used if a loop accesses `loop.length` to count the items of the iterator beforehand.
+///
+/// The length of an [`ExactSizeIterator`] is used directly. Any other iterator is collected into
+/// a [`Vec`](alloc::vec::Vec) first, which needs the feature `alloc`.
+pub struct LoopLength<I>(Cell<Option<I>>);
+
+impl<I: Iterator> LoopLength<I> {
+    #[inline]
+    pub fn new(iter: I) -> Self {
+        Self(Cell::new(Some(iter)))
+    }
+
+    #[inline]
+    fn take(&self) -> I {
+        match self.0.take() {
+            Some(iter) => iter,
+            None => unreachable!("the iterator was already taken"),
+        }
+    }
+}
+
+/// Used internally by rinja to select how the length of a loop is computed
+pub trait LoopLengthKind {
+    type Iter: Iterator;
+
+    fn rinja_loop_length(&self) -> (usize, Self::Iter);
+}
+
+impl<I: ExactSizeIterator> LoopLengthKind for &&LoopLength<I> {
+    type Iter = I;
+
+    #[inline]
+    fn rinja_loop_length(&self) -> (usize, Self::Iter) {
+        let iter = self.take();
+        (iter.len(), iter)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<I: Iterator> LoopLengthKind for &LoopLength<I> {
+    type Iter = alloc::vec::IntoIter<I::Item>;
+
+    #[inline]
+    fn rinja_loop_length(&self) -> (usize, Self::Iter) {
+        let items = self.take().collect::<alloc::vec::Vec<_>>();
+        (items.len(), items.into_iter())
+    }
+}
+
+/// The size hint of a template that is refined by the lengths of its first renders
+///
+/// Used if the `"adaptive-size-hint"` feature is enabled, see [`Template::current_size_hint()`].
+///
+/// [`Template::current_size_hint()`]: crate::Template::current_size_hint
+#[cfg(feature = "adaptive-size-hint")]
+pub struct AdaptiveSizeHint {
+    hint: AtomicUsize,
+    renders: AtomicUsize,
+}
+
+#[cfg(feature = "adaptive-size-hint")]
+impl AdaptiveSizeHint {
+    /// Number of renders whose output length is recorded
+    const SAMPLES: usize = 8;
+
+    #[inline]
+    pub const fn new(size_hint: usize) -> Self {
+        Self {
+            hint: AtomicUsize::new(size_hint),
+            renders: AtomicUsize::new(0),
+        }
+    }
+
+    #[inline]
+    pub fn get(&self) -> usize {
+        self.hint.load(Ordering::Relaxed)
+    }
+
+    /// Records the length of a rendered output, if this is one of the first renders
+    ///
+    /// The first recorded length replaces the compile-time estimate, the later ones can only
+    /// increase the hint.
+    #[inline]
+    pub fn record(&self, len: usize) {
+        if self.renders.load(Ordering::Relaxed) >= Self::SAMPLES {
+            return;
+        }
+        match self.renders.fetch_add(1, Ordering::Relaxed) {
+            0 => self.hint.store(len, Ordering::Relaxed),
+            n if n < Self::SAMPLES => {
+                self.hint.fetch_max(len, Ordering::Relaxed);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A writer that counts the bytes written through it
+///
+/// Used if the `"tracing"` feature is enabled, to record the length of the rendered output.
+#[cfg(feature = "tracing")]
+pub struct CountingWriter<W> {
+    inner: W,
+    len: usize,
+}
+
+#[cfg(feature = "tracing")]
+impl<W: fmt::Write> CountingWriter<W> {
+    #[inline]
+    pub fn new(inner: W) -> Self {
+        Self { inner, len: 0 }
+    }
+
+    #[inline]
+    pub fn bytes_written(&self) -> usize {
+        self.len
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl<W: fmt::Write> fmt::Write for CountingWriter<W> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.len += s.len();
+        self.inner.write_str(s)
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.len += c.len_utf8();
+        self.inner.write_char(c)
+    }
+}
+
 pub struct FmtCell<F> {
     func: Cell<Option<F>>,
     err: Cell<Option<crate::Error>>,
@@ -98,6 +295,71 @@ where
     }
 }
 
+/// Renders the body of a `{% call %}...{% endcall %}` block for `caller()` in the called macro
+pub struct Caller<F>(pub F);
+
+impl<F> FastWritable for Caller<F>
+where
+    F: Fn(&mut dyn fmt::Write) -> crate::Result<()>,
+{
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(&self, dest: &mut W) -> crate::Result<()> {
+        (self.0)(&mut DynWriter(dest))
+    }
+}
+
+impl<F> fmt::Display for Caller<F>
+where
+    F: Fn(&mut dyn fmt::Write) -> crate::Result<()>,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.0)(f).map_err(|_| fmt::Error)
+    }
+}
+
+/// A handle to a macro that calls itself, so it can be called by its own body
+///
+/// Such a macro cannot be expanded inline, so it is rendered by a closure, which gets this handle
+/// and the tuple `A` of the arguments of the macro.
+pub struct RecursiveMacro<'a, A>(&'a RecursiveMacroBody<'a, A>);
+
+type RecursiveMacroBody<'a, A> =
+    dyn Fn(&RecursiveMacro<'a, A>, &mut dyn fmt::Write, A) -> crate::Result<()> + 'a;
+
+impl<A> RecursiveMacro<'_, A> {
+    /// Renders the macro with the arguments `args`
+    #[inline]
+    pub fn call<W: fmt::Write + ?Sized>(&self, dest: &mut W, args: A) -> crate::Result<()> {
+        (self.0)(self, &mut DynWriter(dest), args)
+    }
+}
+
+/// Renders the recursive macro `body` with the arguments `args`
+#[inline]
+pub fn render_recursive_macro<A, F, W>(dest: &mut W, args: A, body: F) -> crate::Result<()>
+where
+    F: Fn(&RecursiveMacro<'_, A>, &mut dyn fmt::Write, A) -> crate::Result<()>,
+    W: fmt::Write + ?Sized,
+{
+    RecursiveMacro(&body).call(dest, args)
+}
+
+/// Lets a possibly unsized writer be used as `&mut dyn fmt::Write`
+struct DynWriter<'a, W: ?Sized>(&'a mut W);
+
+impl<W: fmt::Write + ?Sized> fmt::Write for DynWriter<'_, W> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.write_str(s)
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.0.write_char(c)
+    }
+}
+
 #[inline]
 pub fn get_primitive_value<T: PrimitiveType>(value: T) -> T::Value {
     value.get()
@@ -268,3 +530,364 @@ impl<L: FastWritable, R: FastWritable> FastWritable for Concat<L, R> {
         self.1.write_into(dest)
     }
 }
+
+/// Groups consecutive items with the same key, used by the `group_by` filter.
+#[cfg(feature = "alloc")]
+pub struct GroupBy<I: Iterator, F, K> {
+    iter: core::iter::Fuse<I>,
+    key: F,
+    next: Option<(K, I::Item)>,
+}
+
+#[cfg(feature = "alloc")]
+impl<I, F, K> GroupBy<I, F, K>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> K,
+    K: PartialEq,
+{
+    #[inline]
+    pub fn new<T: IntoIterator<IntoIter = I>>(iter: T, key: F) -> Self {
+        Self {
+            iter: iter.into_iter().fuse(),
+            key,
+            next: None,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<I, F, K> Iterator for GroupBy<I, F, K>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> K,
+    K: PartialEq,
+{
+    type Item = (K, alloc::vec::Vec<I::Item>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, first) = match self.next.take() {
+            Some(next) => next,
+            None => {
+                let item = self.iter.next()?;
+                ((self.key)(&item), item)
+            }
+        };
+        let mut items = alloc::vec![first];
+        for item in self.iter.by_ref() {
+            let item_key = (self.key)(&item);
+            if item_key != key {
+                self.next = Some((item_key, item));
+                break;
+            }
+            items.push(item);
+        }
+        Some((key, items))
+    }
+}
+
+/// Attaches the location of a filter call to the error the filter returned
+///
+/// An error that already has a location, e.g. because a custom filter rendered another template,
+/// keeps its innermost location.
+#[cold]
+pub fn located(
+    err: impl Into<Error>,
+    path: &'static str,
+    offset: usize,
+    row: usize,
+    column: usize,
+) -> Error {
+    let location = Location::new(path, offset, row, column);
+    match err.into() {
+        #[cfg(feature = "alloc")]
+        err @ Error::Located(..) => err,
+        #[cfg(feature = "alloc")]
+        err => Error::Located(alloc::boxed::Box::new(err), location),
+        #[cfg(not(feature = "alloc"))]
+        err => {
+            let _ = location;
+            err
+        }
+    }
+}
+
+/// Look up `key` in the runtime values of [`Template::render_with_values()`]
+///
+/// [`Template::render_with_values()`]: crate::Template::render_with_values()
+#[inline]
+pub fn get_value<'a>(values: &'a dyn Values, key: &'static str) -> Result<&'a dyn Value, Error> {
+    values.get_value(key).ok_or(Error::ValueMissing(key))
+}
+
+/// Marker to select the most efficient way to get the length of a value
+///
+/// Used like `(&&&LengthMarker::of(&value)).rinja_length(value)`: a value that implements
+/// [`Length`] returns its length, an [`ExactSizeIterator`] returns its `len()`, and any other
+/// iterator is counted.
+#[derive(Debug, Clone, Copy)]
+pub struct LengthMarker<T>(PhantomData<T>);
+
+impl<T> LengthMarker<T> {
+    #[inline]
+    pub fn of(_: &T) -> Self {
+        Self(PhantomData)
+    }
+}
+
+pub trait LengthOf {
+    type Value;
+
+    fn rinja_length(self, value: Self::Value) -> usize;
+}
+
+impl<T: IntoIterator> LengthOf for &LengthMarker<T> {
+    type Value = T;
+
+    #[inline]
+    fn rinja_length(self, value: T) -> usize {
+        value.into_iter().count()
+    }
+}
+
+impl<T: IntoIterator<IntoIter: ExactSizeIterator>> LengthOf for &&LengthMarker<T> {
+    type Value = T;
+
+    #[inline]
+    fn rinja_length(self, value: T) -> usize {
+        value.into_iter().len()
+    }
+}
+
+impl<T: Length> LengthOf for &&&LengthMarker<T> {
+    type Value = T;
+
+    #[inline]
+    fn rinja_length(self, value: T) -> usize {
+        value.length()
+    }
+}
+
+/// Marker to select the most efficient way to get the last item of an iterator
+///
+/// Used like `(&&LastMarker::of(&iter)).rinja_last(iter)`: a [`DoubleEndedIterator`] is
+/// iterated from the back, any other iterator is consumed completely.
+#[derive(Debug, Clone, Copy)]
+pub struct LastMarker<I>(PhantomData<I>);
+
+impl<I: Iterator> LastMarker<I> {
+    #[inline]
+    pub fn of(_: &I) -> Self {
+        Self(PhantomData)
+    }
+}
+
+pub trait LastItem {
+    type Iter: Iterator;
+
+    fn rinja_last(self, iter: Self::Iter) -> Result<<Self::Iter as Iterator>::Item, Error>;
+}
+
+impl<I: Iterator> LastItem for &LastMarker<I> {
+    type Iter = I;
+
+    #[inline]
+    fn rinja_last(self, iter: I) -> Result<I::Item, Error> {
+        iter.last().ok_or(Error::EmptyIterable)
+    }
+}
+
+impl<I: DoubleEndedIterator> LastItem for &&LastMarker<I> {
+    type Iter = I;
+
+    #[inline]
+    fn rinja_last(self, mut iter: I) -> Result<I::Item, Error> {
+        iter.next_back().ok_or(Error::EmptyIterable)
+    }
+}
+
+/// Marker to select how the `|reverse` filter reverses a value
+///
+/// Used like `(&&&&&ReverseMarker::of(&value)).rinja_reverse(value)`: a string is reversed by
+/// grapheme cluster, an iterable with a [`DoubleEndedIterator`] is iterated from the back, any
+/// other iterable is collected into a [`Vec`][alloc::vec::Vec] first, and any other
+/// [`Display`][fmt::Display] value is rendered, and reversed like a string.
+#[derive(Debug, Clone, Copy)]
+pub struct ReverseMarker<T>(PhantomData<T>);
+
+impl<T> ReverseMarker<T> {
+    #[inline]
+    pub fn of(_: &T) -> Self {
+        Self(PhantomData)
+    }
+}
+
+pub trait ReverseOf {
+    type Value;
+    type Output;
+
+    fn rinja_reverse(self, value: Self::Value) -> Result<Self::Output, Error>;
+}
+
+#[cfg(feature = "alloc")]
+impl<T: fmt::Display> ReverseOf for &ReverseMarker<T> {
+    type Value = T;
+    type Output = alloc::string::String;
+
+    #[inline]
+    fn rinja_reverse(self, value: T) -> Result<Self::Output, Error> {
+        let mut buf = alloc::string::String::new();
+        fmt::write(&mut buf, format_args!("{value}"))?;
+        Ok(reverse_graphemes(&buf))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: IntoIterator> ReverseOf for &&ReverseMarker<T> {
+    type Value = T;
+    type Output = core::iter::Rev<alloc::vec::IntoIter<T::Item>>;
+
+    #[inline]
+    fn rinja_reverse(self, value: T) -> Result<Self::Output, Error> {
+        let items = value.into_iter().collect::<alloc::vec::Vec<_>>();
+        Ok(items.into_iter().rev())
+    }
+}
+
+// A borrowed field of type e.g. `&[T]` is a `&&[T]`, which does not implement `IntoIterator`.
+impl<'a, T: ?Sized> ReverseOf for &&&ReverseMarker<&'a &T>
+where
+    &'a T: IntoIterator<IntoIter: DoubleEndedIterator>,
+{
+    type Value = &'a &'a T;
+    type Output = core::iter::Rev<<&'a T as IntoIterator>::IntoIter>;
+
+    #[inline]
+    fn rinja_reverse(self, value: Self::Value) -> Result<Self::Output, Error> {
+        Ok((*value).into_iter().rev())
+    }
+}
+
+impl<T: IntoIterator<IntoIter: DoubleEndedIterator>> ReverseOf for &&&&ReverseMarker<T> {
+    type Value = T;
+    type Output = core::iter::Rev<T::IntoIter>;
+
+    #[inline]
+    fn rinja_reverse(self, value: T) -> Result<Self::Output, Error> {
+        Ok(value.into_iter().rev())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: AsRef<str>> ReverseOf for &&&&&ReverseMarker<T> {
+    type Value = T;
+    type Output = alloc::string::String;
+
+    #[inline]
+    fn rinja_reverse(self, value: T) -> Result<Self::Output, Error> {
+        Ok(reverse_graphemes(value.as_ref()))
+    }
+}
+
+/// Reverses a string by grapheme cluster, so combining characters and emoji sequences stay intact
+#[cfg(feature = "unicode-segmentation")]
+fn reverse_graphemes(s: &str) -> alloc::string::String {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    s.graphemes(true).rev().collect()
+}
+
+/// Reverses a string by `char`, because the feature `"unicode-segmentation"` is not enabled
+#[cfg(all(feature = "alloc", not(feature = "unicode-segmentation")))]
+fn reverse_graphemes(s: &str) -> alloc::string::String {
+    s.chars().rev().collect()
+}
+
+/// Marker to answer the tests `is string`, `is number` and `is iterable` for the type of a value
+///
+/// Used like `(&&TypeTestMarker::of(&value)).rinja_is_string()`: the test is `true` if the type
+/// of the value implements [`AsRef<str>`], [`PrimitiveType`] with a [`Number`] value, or
+/// [`IntoIterator`] respectively, and `false` for any other type.
+#[derive(Debug)]
+pub struct TypeTestMarker<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized> TypeTestMarker<T> {
+    #[inline]
+    pub fn of(_: &T) -> Self {
+        Self(PhantomData)
+    }
+}
+
+/// A numeric [`PrimitiveType::Value`], i.e. an integer or a float
+pub trait Number {}
+
+macro_rules! number {
+    ($($ty:ty),* $(,)?) => {$(
+        impl Number for $ty {}
+    )*};
+}
+
+number! {
+    f32, f64,
+    i8, i16, i32, i64, i128, isize,
+    u8, u16, u32, u64, u128, usize,
+}
+
+macro_rules! type_test {
+    ($Trait:ident :: $method:ident, $T:ident: $($bound:tt)*) => {
+        pub trait $Trait {
+            fn $method(self) -> bool;
+        }
+
+        impl<T: ?Sized> $Trait for &TypeTestMarker<T> {
+            #[inline]
+            fn $method(self) -> bool {
+                false
+            }
+        }
+
+        impl<$T: $($bound)*> $Trait for &&TypeTestMarker<$T> {
+            #[inline]
+            fn $method(self) -> bool {
+                true
+            }
+        }
+    };
+}
+
+type_test!(IsString::rinja_is_string, T: AsRef<str> + ?Sized);
+type_test!(IsNumber::rinja_is_number, T: PrimitiveType<Value: Number> + ?Sized);
+type_test!(IsIterable::rinja_is_iterable, T: IntoIterator);
+
+/// Receives the errors of failing `{{ expressions }}` in templates with `error_placeholder`
+///
+/// The callback is wrapped in a [`RefCell`], so the generated code can share the sink with
+/// closures, e.g. the body of a `{% call %}` block.
+pub struct ErrorSink<'a>(RefCell<&'a mut dyn FnMut(Error)>);
+
+impl<'a> ErrorSink<'a> {
+    #[inline]
+    pub fn new(on_error: &'a mut dyn FnMut(Error)) -> Self {
+        Self(RefCell::new(on_error))
+    }
+
+    #[inline]
+    pub fn push(&self, err: Error) {
+        (self.0.borrow_mut())(err);
+    }
+}
+
+/// Renders a template that collects its errors, but returns the first error
+#[inline]
+pub fn fail_fast(
+    render: impl FnOnce(&mut dyn FnMut(Error)) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let mut first_error = None;
+    render(&mut |err| {
+        first_error.get_or_insert(err);
+    })?;
+    match first_error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
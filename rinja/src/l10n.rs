@@ -0,0 +1,80 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt;
+
+use fluent::bundle::FluentBundle;
+use fluent::memoizer::MemoizerKind;
+use fluent::types::FluentNumber;
+pub use fluent::{FluentArgs, FluentResource, FluentValue};
+
+use crate::filters::Escaper;
+use crate::{Error, Result};
+
+/// Writes the message `id` of `bundle` into `dest`
+///
+/// Returns `Ok(false)` if the bundle has no such message, so the `{% trans %}` block can fall back
+/// to its own content.
+pub fn translate<R, M>(
+    bundle: &FluentBundle<R, M>,
+    id: &str,
+    args: &FluentArgs<'_>,
+    dest: &mut (impl fmt::Write + ?Sized),
+) -> Result<bool>
+where
+    R: Borrow<FluentResource>,
+    M: MemoizerKind,
+{
+    let Some(pattern) = bundle.get_message(id).and_then(|msg| msg.value()) else {
+        return Ok(false);
+    };
+    let mut errors = Vec::new();
+    let value = bundle.format_pattern(pattern, Some(args), &mut errors);
+    if let Some(err) = errors.first() {
+        return Err(Error::custom(alloc::format!(
+            "could not format the message `{id}`: {err}"
+        )));
+    }
+    dest.write_str(&value)?;
+    Ok(true)
+}
+
+/// Returns `true` if the argument `name` is the number 1
+///
+/// Used to select the content of a `{% trans %}` block if the bundle has no translation for it.
+pub fn is_singular(args: &FluentArgs<'_>, name: &str) -> bool {
+    matches!(args.get(name), Some(FluentValue::Number(FluentNumber { value, .. })) if *value == 1.0)
+}
+
+/// Converts an argument of a `{% trans %}` block into a [`FluentValue`]
+///
+/// Numbers are passed as numbers, so Fluent can select their plural category.
+/// Everything else is escaped and passed as a string.
+pub struct FluentArg<'a, T: ?Sized>(pub &'a T);
+
+/// Passes a number as a [`FluentValue::Number`]
+pub trait FluentArgNumber {
+    #[allow(missing_docs)]
+    fn rinja_fluent_value(&self, escaper: impl Escaper) -> Result<FluentValue<'static>>;
+}
+
+impl<T: Copy + Into<FluentNumber>> FluentArgNumber for &FluentArg<'_, T> {
+    #[inline]
+    fn rinja_fluent_value(&self, _: impl Escaper) -> Result<FluentValue<'static>> {
+        Ok(FluentValue::Number((*self.0).into()))
+    }
+}
+
+/// Passes any other value as an escaped [`FluentValue::String`]
+pub trait FluentArgString {
+    #[allow(missing_docs)]
+    fn rinja_fluent_value(&self, escaper: impl Escaper) -> Result<FluentValue<'static>>;
+}
+
+impl<T: fmt::Display + ?Sized> FluentArgString for FluentArg<'_, T> {
+    #[inline]
+    fn rinja_fluent_value(&self, escaper: impl Escaper) -> Result<FluentValue<'static>> {
+        let value = crate::filters::escape(self.0, escaper)?;
+        Ok(FluentValue::String(value.to_string().into()))
+    }
+}
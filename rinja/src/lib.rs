@@ -70,19 +70,27 @@ pub mod filters;
 #[doc(hidden)]
 pub mod helpers;
 mod html;
+#[cfg(feature = "fluent")]
+mod l10n;
+mod values;
 
 #[cfg(feature = "alloc")]
 use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 use core::fmt;
 #[cfg(feature = "std")]
 use std::io;
 
+#[cfg(feature = "fluent")]
+pub use fluent;
 pub use rinja_derive::Template;
 
 #[doc(hidden)]
 pub use crate as shared;
-pub use crate::error::{Error, Result};
+pub use crate::error::{Error, Location, Result};
 pub use crate::helpers::PrimitiveType;
+pub use crate::values::{Value, Values};
 
 /// Main `Template` trait; implementations are generally derived
 ///
@@ -94,9 +102,12 @@ pub use crate::helpers::PrimitiveType;
 ///
 /// * [`.render()`][Template::render] (to render the content into a new string),
 /// * [`.render_into()`][Template::render_into] (to render the content into an [`fmt::Write`]
-///   object, e.g. [`String`]) or
+///   object, e.g. [`String`]),
 /// * [`.write_into()`][Template::write_into] (to render the content into an [`io::Write`] object,
-///   e.g. [`Vec<u8>`][alloc::vec::Vec])
+///   e.g. [`Vec<u8>`][alloc::vec::Vec]) or
+/// * [`.render_into_bytes()`][render_into_bytes] (to render the content into a
+///   [`BytesMut`](https://docs.rs/bytes/1/bytes/struct.BytesMut.html) buffer, requires the
+///   `bytes` feature)
 ///
 /// over [`.to_string()`][std::string::ToString::to_string] or [`format!()`][alloc::format].
 /// While `.to_string()` and `format!()` give you the same result, they generally perform much worse
@@ -105,19 +116,260 @@ pub use crate::helpers::PrimitiveType;
 /// `.render()`.
 ///
 /// [dynamic methods calls]: <https://doc.rust-lang.org/stable/std/keyword.dyn.html>
+#[cfg_attr(
+    feature = "bytes",
+    doc = "[render_into_bytes]: Template::render_into_bytes"
+)]
+#[cfg_attr(
+    not(feature = "bytes"),
+    doc = "[render_into_bytes]: https://docs.rs/rinja/latest/rinja/trait.Template.html#method.render_into_bytes"
+)]
 pub trait Template: fmt::Display + filters::FastWritable {
     /// Helper method which allocates a new `String` and renders into it
     #[cfg(feature = "alloc")]
     fn render(&self) -> Result<String> {
         let mut buf = String::new();
-        let _ = buf.try_reserve(Self::SIZE_HINT);
+        let _ = buf.try_reserve(Self::current_size_hint());
         self.render_into(&mut buf)?;
+        #[cfg(feature = "adaptive-size-hint")]
+        if let Some(hint) = Self::adaptive_size_hint() {
+            hint.record(buf.len());
+        }
         Ok(buf)
     }
 
+    /// Like [`render()`][Template::render], but allocates `capacity` bytes in advance instead of
+    /// [`current_size_hint()`][Template::current_size_hint]
+    ///
+    /// Use it if you can estimate the size of the output better than the template can, e.g. if
+    /// the output mostly consists of a loop over a large collection.
+    ///
+    /// ```
+    /// # use rinja::Template;
+    /// #[derive(Template)]
+    /// #[template(source = "{% for row in rows %}<tr><td>{{ row }}</td></tr>{% endfor %}", ext = "html")]
+    /// struct Table<'a> {
+    ///     rows: &'a [u32],
+    /// }
+    ///
+    /// let rows = (0..1000).collect::<Vec<_>>();
+    /// let html = Table { rows: &rows }.render_with_capacity(rows.len() * 22).unwrap();
+    /// assert!(html.starts_with("<tr><td>0</td></tr><tr><td>1</td></tr>"));
+    /// assert!(html.capacity() >= rows.len() * 22);
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn render_with_capacity(&self, capacity: usize) -> Result<String> {
+        let mut buf = String::new();
+        let _ = buf.try_reserve(capacity);
+        self.render_into(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Renders the template, and returns the output together with its
+    /// [`MIME_TYPE`][Template::MIME_TYPE]
+    ///
+    /// This is a convenience method for web handlers that need both the body and the
+    /// `Content-Type` of a response.
+    ///
+    /// ```
+    /// # use rinja::Template;
+    /// #[derive(Template)]
+    /// #[template(source = "<p>{{ name }}</p>", ext = "html")]
+    /// struct Hello<'a> {
+    ///     name: &'a str,
+    /// }
+    ///
+    /// let (body, mime_type) = Hello { name: "world" }.render_response().unwrap();
+    /// assert_eq!(body, "<p>world</p>");
+    /// assert_eq!(mime_type, "text/html; charset=utf-8");
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn render_response(&self) -> Result<(String, &'static str)> {
+        Ok((self.render()?, Self::MIME_TYPE))
+    }
+
     /// Renders the template to the given `writer` fmt buffer
     fn render_into<W: fmt::Write + ?Sized>(&self, writer: &mut W) -> Result<()>;
 
+    /// Renders the template to the end of `buf`, reserving
+    /// [`current_size_hint()`][Template::current_size_hint] additional bytes in advance
+    ///
+    /// Unlike [`render_into()`][Template::render_into], which cannot know that its writer is a
+    /// `String`, this avoids repeated reallocations when many templates are rendered into the
+    /// same buffer.
+    ///
+    /// ```
+    /// # use rinja::Template;
+    /// #[derive(Template)]
+    /// #[template(source = "<li>{{ self.0 }}</li>", ext = "html")]
+    /// struct Item<'a>(&'a str);
+    ///
+    /// let mut buf = String::from("<ul>");
+    /// for item in ["a", "b"] {
+    ///     Item(item).render_append(&mut buf).unwrap();
+    /// }
+    /// buf.push_str("</ul>");
+    /// assert_eq!(buf, "<ul><li>a</li><li>b</li></ul>");
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn render_append(&self, buf: &mut String) -> Result<()> {
+        #[cfg(feature = "adaptive-size-hint")]
+        let start = buf.len();
+        let _ = buf.try_reserve(Self::current_size_hint());
+        self.render_into(buf)?;
+        #[cfg(feature = "adaptive-size-hint")]
+        if let Some(hint) = Self::adaptive_size_hint() {
+            hint.record(buf.len() - start);
+        }
+        Ok(())
+    }
+
+    /// Renders the template to the given `writer` fmt buffer, and returns the number of bytes
+    /// that were written
+    ///
+    /// This is useful e.g. to know the `Content-Length` of a streamed response without measuring
+    /// the output again, or to check how accurate [`SIZE_HINT`][Template::SIZE_HINT] is.
+    ///
+    /// ```
+    /// # use rinja::Template;
+    /// #[derive(Template)]
+    /// #[template(source = "Hello, {{ self.0 }}!", ext = "txt")]
+    /// struct Hello<'a>(&'a str);
+    ///
+    /// let mut buf = String::from("> ");
+    /// assert_eq!(Hello("wörld").render_into_counted(&mut buf).unwrap(), 14);
+    /// assert_eq!(buf, "> Hello, wörld!");
+    /// ```
+    fn render_into_counted<W: fmt::Write + ?Sized>(&self, writer: &mut W) -> Result<usize> {
+        struct Counted<'a, W: fmt::Write + ?Sized> {
+            writer: &'a mut W,
+            count: usize,
+        }
+
+        impl<W: fmt::Write + ?Sized> fmt::Write for Counted<'_, W> {
+            #[inline]
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.writer.write_str(s)?;
+                self.count += s.len();
+                Ok(())
+            }
+
+            #[inline]
+            fn write_char(&mut self, c: char) -> fmt::Result {
+                self.writer.write_char(c)?;
+                self.count += c.len_utf8();
+                Ok(())
+            }
+        }
+
+        let mut counted = Counted { writer, count: 0 };
+        self.render_into(&mut counted)?;
+        Ok(counted.count)
+    }
+
+    /// Helper method which allocates a new `String` and renders into it, making `values`
+    /// available to the template as `values.<key>`
+    #[cfg(feature = "alloc")]
+    fn render_with_values(&self, values: &dyn Values) -> Result<String> {
+        let mut buf = String::new();
+        let _ = buf.try_reserve(Self::current_size_hint());
+        self.render_into_with_values(&mut buf, values)?;
+        #[cfg(feature = "adaptive-size-hint")]
+        if let Some(hint) = Self::adaptive_size_hint() {
+            hint.record(buf.len());
+        }
+        Ok(buf)
+    }
+
+    /// Renders the template to the given `writer` fmt buffer, making `values` available to the
+    /// template as `values.<key>`
+    ///
+    /// Templates that don't reference `values` ignore the argument. A key that is referenced by
+    /// the template, but missing in `values`, results in an [`Error::ValueMissing`].
+    #[inline]
+    fn render_into_with_values<W: fmt::Write + ?Sized>(
+        &self,
+        writer: &mut W,
+        values: &dyn Values,
+    ) -> Result<()> {
+        let _ = values;
+        self.render_into(writer)
+    }
+
+    /// Renders the template, collecting the errors of failing `{{ expressions }}` instead of
+    /// stopping at the first one
+    ///
+    /// Returns the output and the list of errors. Only templates with the attribute
+    /// `error_placeholder = ".."` collect errors: a failing `{{ expression }}` is replaced by the
+    /// placeholder, and rendering continues. Errors in other places, e.g. in the condition of an
+    /// `{% if %}` or in the iterable of a `{% for %}` loop, still stop the rendering. Then the
+    /// output is incomplete, and the error is the last one in the list.
+    ///
+    /// ```
+    /// # use rinja::Template;
+    /// #[derive(Template)]
+    /// #[template(
+    ///     source = "{% for w in widgets %}[{{ w.render()? }}]{% endfor %}",
+    ///     ext = "html",
+    ///     error_placeholder = "unavailable",
+    /// )]
+    /// struct Dashboard<'a> {
+    ///     widgets: &'a [Widget],
+    /// }
+    ///
+    /// struct Widget(Result<u32, std::fmt::Error>);
+    ///
+    /// impl Widget {
+    ///     fn render(&self) -> Result<u32, std::fmt::Error> {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let widgets = [Widget(Ok(1)), Widget(Err(std::fmt::Error)), Widget(Ok(3))];
+    /// let (output, errors) = Dashboard { widgets: &widgets }.render_collecting();
+    /// assert_eq!(output, "[1][unavailable][3]");
+    /// assert_eq!(errors.len(), 1);
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn render_collecting(&self) -> (String, Vec<Error>) {
+        let mut buf = String::new();
+        let _ = buf.try_reserve(Self::current_size_hint());
+        let mut errors = Vec::new();
+        if let Err(err) = self.render_into_collecting(&mut buf, &mut |err| errors.push(err)) {
+            errors.push(err);
+        }
+        (buf, errors)
+    }
+
+    /// Renders the template to the given `writer` fmt buffer, passing the errors of failing
+    /// `{{ expressions }}` to `on_error` instead of stopping at the first one
+    ///
+    /// See [`render_collecting()`][Template::render_collecting].
+    #[inline]
+    fn render_into_collecting<W: fmt::Write + ?Sized>(
+        &self,
+        writer: &mut W,
+        on_error: &mut dyn FnMut(Error),
+    ) -> Result<()> {
+        self.render_into_with_values_collecting(writer, &(), on_error)
+    }
+
+    /// Renders the template to the given `writer` fmt buffer, making `values` available to the
+    /// template, and passing the errors of failing `{{ expressions }}` to `on_error`
+    ///
+    /// See [`render_collecting()`][Template::render_collecting] and
+    /// [`render_into_with_values()`][Template::render_into_with_values].
+    #[inline]
+    fn render_into_with_values_collecting<W: fmt::Write + ?Sized>(
+        &self,
+        writer: &mut W,
+        values: &dyn Values,
+        on_error: &mut dyn FnMut(Error),
+    ) -> Result<()> {
+        let _ = on_error;
+        self.render_into_with_values(writer, values)
+    }
+
     /// Renders the template to the given `writer` io buffer
     #[cfg(feature = "std")]
     fn write_into<W: io::Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
@@ -142,21 +394,207 @@ pub trait Template: fmt::Display + filters::FastWritable {
             Ok(())
         } else {
             let err = wrapped.err.take();
-            Err(err.unwrap_or_else(|| io::Error::new(io::ErrorKind::Other, fmt::Error)))
+            Err(err.unwrap_or_else(|| io::Error::other(fmt::Error)))
+        }
+    }
+
+    /// Renders the template to the given `buf`, e.g. to build the body of an HTTP response
+    ///
+    /// [`current_size_hint()`][Template::current_size_hint] bytes are reserved in advance, and
+    /// the UTF-8 output is written directly into the buffer, without an intermediate `String`.
+    #[cfg(feature = "bytes")]
+    fn render_into_bytes(&self, buf: &mut bytes::BytesMut) -> Result<()> {
+        #[cfg(feature = "adaptive-size-hint")]
+        let start = buf.len();
+        buf.reserve(Self::current_size_hint());
+        self.render_into(buf)?;
+        #[cfg(feature = "adaptive-size-hint")]
+        if let Some(hint) = Self::adaptive_size_hint() {
+            hint.record(buf.len() - start);
         }
+        Ok(())
+    }
+
+    /// Helper method which allocates a new `String` and renders only the block `name` into it
+    ///
+    /// The available blocks have to be selected with `#[template(blocks = ["name", ..])]`, and
+    /// are listed in [`BLOCKS`][Template::BLOCKS]. Any other name results in an
+    /// [`Error::UnknownBlock`]. This is useful if the fragment to render is only known at
+    /// runtime, e.g. from an HTTP header.
+    ///
+    /// ```
+    /// # use rinja::Template;
+    /// #[derive(Template)]
+    /// #[template(
+    ///     ext = "html",
+    ///     source = "<h1>{% block title %}{{ title }}{% endblock %}</h1>\
+    ///               <main>{% block body %}{{ body }}{% endblock %}</main>",
+    ///     blocks = ["title", "body"],
+    /// )]
+    /// struct Page<'a> {
+    ///     title: &'a str,
+    ///     body: &'a str,
+    /// }
+    ///
+    /// let page = Page { title: "Hello", body: "world" };
+    /// assert_eq!(page.render_block("body").unwrap(), "world");
+    /// assert!(page.render_block("footer").is_err());
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn render_block(&self, name: &str) -> Result<String> {
+        let mut buf = String::new();
+        self.render_block_into(name, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Renders only the block `name` to the given `writer` fmt buffer
+    ///
+    /// See [`render_block()`][Template::render_block] for more information.
+    #[inline]
+    fn render_block_into<W: fmt::Write + ?Sized>(&self, name: &str, writer: &mut W) -> Result<()> {
+        let _ = (name, writer);
+        Err(Error::UnknownBlock)
     }
 
     /// Provides a rough estimate of the expanded length of the rendered template. Larger
     /// values result in higher memory usage but fewer reallocations. Smaller values result in the
-    /// opposite. This value only affects [`render`]. It does not take effect when calling
-    /// [`render_into`], [`write_into`], the [`fmt::Display`] implementation, or the blanket
-    /// [`ToString::to_string`] implementation.
+    /// opposite. This value only affects [`render`], [`render_append`] and [`render_into_bytes`].
+    /// It does not take effect when calling [`render_into`], [`write_into`], the [`fmt::Display`]
+    /// implementation, or the blanket [`ToString::to_string`] implementation.
+    ///
+    /// The estimate is computed at compile time from the literal text of the template, so it
+    /// cannot know how long the output of expressions and loops will be. With the
+    /// `"adaptive-size-hint"` feature, the length of the first renders refine the estimate, see
+    /// [`current_size_hint()`][Template::current_size_hint].
     ///
     /// [`render`]: Template::render
+    /// [`render_append`]: Template::render_append
     /// [`render_into`]: Template::render_into
     /// [`write_into`]: Template::write_into
     /// [`ToString::to_string`]: alloc::string::ToString::to_string
+    #[cfg_attr(
+        feature = "bytes",
+        doc = "[`render_into_bytes`]: Template::render_into_bytes"
+    )]
+    #[cfg_attr(
+        not(feature = "bytes"),
+        doc = "[`render_into_bytes`]: https://docs.rs/rinja/latest/rinja/trait.Template.html#method.render_into_bytes"
+    )]
     const SIZE_HINT: usize;
+
+    /// Returns the number of bytes that [`render()`][Template::render] reserves in advance
+    ///
+    /// This is [`SIZE_HINT`][Template::SIZE_HINT], unless the `"adaptive-size-hint"` feature is
+    /// enabled. Then the longest output of the first few renders of this template replaces
+    /// `SIZE_HINT` for all later renders.
+    ///
+    /// ```
+    /// # use rinja::Template;
+    /// #[derive(Template)]
+    /// #[template(source = "Hello, {{ name }}!", ext = "txt")]
+    /// struct Hello<'a> {
+    ///     name: &'a str,
+    /// }
+    ///
+    /// assert!(Hello::current_size_hint() >= "Hello, !".len());
+    /// ```
+    #[inline]
+    fn current_size_hint() -> usize {
+        #[cfg(feature = "adaptive-size-hint")]
+        if let Some(hint) = Self::adaptive_size_hint() {
+            return hint.get();
+        }
+        Self::SIZE_HINT
+    }
+
+    /// Used internally by rinja to refine the size hint if the `"adaptive-size-hint"` feature is
+    /// enabled
+    #[doc(hidden)]
+    #[inline]
+    #[cfg(feature = "adaptive-size-hint")]
+    fn adaptive_size_hint() -> Option<&'static helpers::AdaptiveSizeHint> {
+        None
+    }
+
+    /// Absolute paths of all template files this template was built from
+    ///
+    /// This includes the template itself (unless it was defined with `source`), and every
+    /// template it extends, includes or imports. Use [`dependencies_of()`] to query the list,
+    /// e.g. to emit `cargo:rerun-if-changed` directives.
+    const DEPENDENCIES: &'static [&'static str] = &[];
+
+    /// Byte offsets at which the lines of the template source start
+    ///
+    /// Use [`source_location()`] to turn a byte offset, e.g. of an [`Error::Located`], into a line
+    /// and column.
+    const LINE_STARTS: &'static [usize] = &[0];
+
+    /// Names of the blocks that can be rendered with [`render_block()`][Template::render_block]
+    const BLOCKS: &'static [&'static str] = &[];
+
+    /// Extension of the template, e.g. `Some("html")` for `path = "hello.html"`
+    ///
+    /// For templates defined with `source`, this is the value of the `ext` argument. It is the
+    /// same extension that rinja uses to select the escaper, so e.g. web frameworks can use it to
+    /// pick a file extension or content type for the rendered output.
+    ///
+    /// ```
+    /// # use rinja::Template;
+    /// #[derive(Template)]
+    /// #[template(source = "{{ name }}", ext = "txt")]
+    /// struct Name<'a> {
+    ///     name: &'a str,
+    /// }
+    ///
+    /// assert_eq!(Name::EXTENSION, Some("txt"));
+    /// ```
+    const EXTENSION: Option<&'static str> = None;
+
+    /// Source code of the template, if it was opted into with `expose_source = true`
+    ///
+    /// For templates defined with `path`, this is the content of the file, read at compile time
+    /// and without its final newline, like when rendering. For templates defined with `source`, it
+    /// is the literal. Templates that extend, include or
+    /// import other templates only expose their own source code. The source is not exposed by
+    /// default, so the template code does not end up in the compiled binary unless you ask for it.
+    ///
+    /// ```
+    /// # use rinja::Template;
+    /// #[derive(Template)]
+    /// #[template(source = "Hello, {{ name }}!", ext = "txt", expose_source = true)]
+    /// struct Hello<'a> {
+    ///     name: &'a str,
+    /// }
+    ///
+    /// assert_eq!(Hello::SOURCE, Some("Hello, {{ name }}!"));
+    /// ```
+    const SOURCE: Option<&'static str> = None;
+
+    /// MIME type of the rendered output, e.g. `"text/html; charset=utf-8"` for `path = "hello.html"`
+    ///
+    /// The value of the `mime` attribute is used verbatim if it was given. Otherwise the MIME type
+    /// is guessed from the [`EXTENSION`][Template::EXTENSION], or from the extension before it
+    /// for Jinja-style names like `hello.html.j2`. Unknown extensions result in
+    /// `"application/octet-stream"`.
+    ///
+    /// ```
+    /// # use rinja::Template;
+    /// #[derive(Template)]
+    /// #[template(source = "{{ name }}", ext = "txt")]
+    /// struct Name<'a> {
+    ///     name: &'a str,
+    /// }
+    ///
+    /// #[derive(Template)]
+    /// #[template(source = "{{ name }}", ext = "tmpl", mime = "text/html")]
+    /// struct Page<'a> {
+    ///     name: &'a str,
+    /// }
+    ///
+    /// assert_eq!(Name::MIME_TYPE, "text/plain; charset=utf-8");
+    /// assert_eq!(Page::MIME_TYPE, "text/html");
+    /// ```
+    const MIME_TYPE: &'static str = "application/octet-stream";
 }
 
 impl<T: Template + ?Sized> Template for &T {
@@ -171,13 +609,201 @@ impl<T: Template + ?Sized> Template for &T {
         <T as Template>::render(self)
     }
 
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn render_with_capacity(&self, capacity: usize) -> Result<String> {
+        <T as Template>::render_with_capacity(self, capacity)
+    }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn render_append(&self, buf: &mut String) -> Result<()> {
+        <T as Template>::render_append(self, buf)
+    }
+
+    #[inline]
+    fn render_into_counted<W: fmt::Write + ?Sized>(&self, writer: &mut W) -> Result<usize> {
+        <T as Template>::render_into_counted(self, writer)
+    }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn render_with_values(&self, values: &dyn Values) -> Result<String> {
+        <T as Template>::render_with_values(self, values)
+    }
+
+    #[inline]
+    fn render_into_with_values<W: fmt::Write + ?Sized>(
+        &self,
+        writer: &mut W,
+        values: &dyn Values,
+    ) -> Result<()> {
+        <T as Template>::render_into_with_values(self, writer, values)
+    }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn render_collecting(&self) -> (String, Vec<Error>) {
+        <T as Template>::render_collecting(self)
+    }
+
+    #[inline]
+    fn render_into_collecting<W: fmt::Write + ?Sized>(
+        &self,
+        writer: &mut W,
+        on_error: &mut dyn FnMut(Error),
+    ) -> Result<()> {
+        <T as Template>::render_into_collecting(self, writer, on_error)
+    }
+
+    #[inline]
+    fn render_into_with_values_collecting<W: fmt::Write + ?Sized>(
+        &self,
+        writer: &mut W,
+        values: &dyn Values,
+        on_error: &mut dyn FnMut(Error),
+    ) -> Result<()> {
+        <T as Template>::render_into_with_values_collecting(self, writer, values, on_error)
+    }
+
     #[inline]
     #[cfg(feature = "std")]
     fn write_into<W: io::Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
         <T as Template>::write_into(self, writer)
     }
 
+    #[inline]
+    #[cfg(feature = "bytes")]
+    fn render_into_bytes(&self, buf: &mut bytes::BytesMut) -> Result<()> {
+        <T as Template>::render_into_bytes(self, buf)
+    }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn render_block(&self, name: &str) -> Result<String> {
+        <T as Template>::render_block(self, name)
+    }
+
+    #[inline]
+    fn render_block_into<W: fmt::Write + ?Sized>(&self, name: &str, writer: &mut W) -> Result<()> {
+        <T as Template>::render_block_into(self, name, writer)
+    }
+
     const SIZE_HINT: usize = T::SIZE_HINT;
+
+    #[inline]
+    #[cfg(feature = "adaptive-size-hint")]
+    fn adaptive_size_hint() -> Option<&'static helpers::AdaptiveSizeHint> {
+        T::adaptive_size_hint()
+    }
+
+    const DEPENDENCIES: &'static [&'static str] = T::DEPENDENCIES;
+
+    const LINE_STARTS: &'static [usize] = T::LINE_STARTS;
+
+    const BLOCKS: &'static [&'static str] = T::BLOCKS;
+
+    const EXTENSION: Option<&'static str> = T::EXTENSION;
+
+    const SOURCE: Option<&'static str> = T::SOURCE;
+
+    const MIME_TYPE: &'static str = T::MIME_TYPE;
+}
+
+/// Returns the absolute paths of all template files the template `T` depends on
+///
+/// The list contains the template's own file (unless it was defined with `source`), and all
+/// templates it extends, includes or imports, i.e. every file whose modification should trigger
+/// a recompilation.
+///
+/// ```ignore
+/// #[derive(Template)]
+/// #[template(path = "hello.html")]
+/// struct Example<'a> {
+///     name: &'a str,
+/// }
+///
+/// for path in rinja::dependencies_of::<Example<'_>>() {
+///     println!("cargo:rerun-if-changed={path}");
+/// }
+/// ```
+#[inline]
+pub const fn dependencies_of<T: Template + ?Sized>() -> &'static [&'static str] {
+    T::DEPENDENCIES
+}
+
+/// Returns the line and the column of a byte `offset` in the source of the template `T`
+///
+/// The line starts at 1, the column is counted in bytes and starts at 0.
+/// Only the template's own source is known, not the sources of the templates it extends or
+/// includes, so please check [`Location::path()`] if the offset belongs to an [`Error::Located`].
+///
+/// ```
+/// # use rinja::Template;
+/// #[derive(Template)]
+/// #[template(ext = "txt", source = "Hello,\n{{ name }}!")]
+/// struct Example<'a> {
+///     name: &'a str,
+/// }
+///
+/// assert_eq!(rinja::source_location::<Example<'_>>(0), (1, 0));
+/// assert_eq!(rinja::source_location::<Example<'_>>(10), (2, 3));
+/// ```
+#[inline]
+pub fn source_location<T: Template + ?Sized>(offset: usize) -> (usize, usize) {
+    let line = T::LINE_STARTS.partition_point(|&start| start <= offset);
+    let start = match line.checked_sub(1) {
+        Some(index) => T::LINE_STARTS[index],
+        None => 0,
+    };
+    (line.max(1), offset - start)
+}
+
+/// A template that renders nothing
+///
+/// It can be used as a placeholder or default in generic code, e.g. for a response type that is
+/// generic over `T: Template + Default`, without deriving a template with an empty `source`.
+///
+/// ```
+/// # use rinja::{EmptyTemplate, Template};
+/// #[derive(Default)]
+/// struct Page<T> {
+///     title: &'static str,
+///     body: T,
+/// }
+///
+/// fn render_body<T: Template + Default>(page: &Page<T>) -> rinja::Result<String> {
+///     page.body.render()
+/// }
+///
+/// let page: Page<EmptyTemplate> = Page::default();
+/// assert_eq!(render_body(&page).unwrap(), "");
+/// assert_eq!(EmptyTemplate::SIZE_HINT, 0);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct EmptyTemplate;
+
+impl Template for EmptyTemplate {
+    #[inline]
+    fn render_into<W: fmt::Write + ?Sized>(&self, _: &mut W) -> Result<()> {
+        Ok(())
+    }
+
+    const SIZE_HINT: usize = 0;
+}
+
+impl fmt::Display for EmptyTemplate {
+    #[inline]
+    fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Ok(())
+    }
+}
+
+impl filters::FastWritable for EmptyTemplate {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(&self, _: &mut W) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// [`dyn`-compatible] wrapper trait around [`Template`] implementers
@@ -199,6 +825,12 @@ pub trait DynTemplate {
 
     /// Provides a conservative estimate of the expanded length of the rendered template
     fn size_hint(&self) -> usize;
+
+    /// Extension of the template, see [`Template::EXTENSION`]
+    fn extension(&self) -> Option<&'static str>;
+
+    /// MIME type of the rendered output, see [`Template::MIME_TYPE`]
+    fn mime_type(&self) -> &'static str;
 }
 
 impl<T: Template> DynTemplate for T {
@@ -221,7 +853,17 @@ impl<T: Template> DynTemplate for T {
 
     #[inline]
     fn size_hint(&self) -> usize {
-        <Self as Template>::SIZE_HINT
+        <Self as Template>::current_size_hint()
+    }
+
+    #[inline]
+    fn extension(&self) -> Option<&'static str> {
+        <Self as Template>::EXTENSION
+    }
+
+    #[inline]
+    fn mime_type(&self) -> &'static str {
+        <Self as Template>::MIME_TYPE
     }
 }
 
@@ -324,4 +966,25 @@ mod tests {
         test.dyn_write_into(&mut vec).unwrap();
         assert_eq!(vec, alloc::vec![b't', b'e', b's', b't']);
     }
+
+    #[test]
+    fn empty_template() {
+        use alloc::string::ToString;
+
+        fn render<T: Template + Default>() -> String {
+            T::default().render().unwrap()
+        }
+
+        assert_eq!(render::<EmptyTemplate>(), "");
+        assert_eq!(EmptyTemplate.to_string(), "");
+        assert_eq!(
+            (&EmptyTemplate as &dyn DynTemplate).dyn_render().unwrap(),
+            ""
+        );
+
+        let mut buf = String::from("x");
+        EmptyTemplate.render_append(&mut buf).unwrap();
+        assert_eq!(buf, "x");
+        assert_eq!(EmptyTemplate.render_into_counted(&mut buf).unwrap(), 0);
+    }
 }
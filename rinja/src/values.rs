@@ -0,0 +1,132 @@
+#[cfg(feature = "alloc")]
+use alloc::collections::BTreeMap;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::any::Any;
+use core::borrow::Borrow;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::hash::{BuildHasher, Hash};
+
+/// A value that can be passed to a template at runtime through [`Values`]
+///
+/// This trait is implemented for every `'static` type that implements [`fmt::Display`].
+/// Use [`Value::as_any()`] and [`<dyn Any>::downcast_ref()`](Any#method.downcast_ref) to get
+/// back the concrete type.
+pub trait Value: Any + fmt::Display {
+    /// Returns `self` as [`Any`] to allow a typed lookup
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Any + fmt::Display> Value for T {
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// A collection of values that are passed to a template at runtime
+///
+/// Used with [`Template::render_with_values()`][crate::Template::render_with_values()].
+/// A template can access the values with `{{ values.key }}`.
+///
+/// ```
+/// # use std::collections::HashMap;
+/// # use rinja::Template;
+/// #[derive(Template)]
+/// #[template(ext = "txt", source = "Hello, {{ values.name }}!")]
+/// struct Hello;
+///
+/// let values = HashMap::from([("name", "world")]);
+/// assert_eq!(Hello.render_with_values(&values).unwrap(), "Hello, world!");
+/// ```
+pub trait Values {
+    /// Looks up the value stored for `key`
+    fn get_value<'a>(&'a self, key: &str) -> Option<&'a dyn Value>;
+
+    /// Looks up the value stored for `key`, if it is of type `T`
+    #[inline]
+    fn get_typed<'a, T: Any>(&'a self, key: &str) -> Option<&'a T>
+    where
+        Self: Sized,
+    {
+        self.get_value(key)?.as_any().downcast_ref()
+    }
+}
+
+crate::impl_for_ref! {
+    impl Values for T {
+        #[inline]
+        fn get_value<'a>(&'a self, key: &str) -> Option<&'a dyn Value> {
+            T::get_value(self, key)
+        }
+    }
+}
+
+impl Values for () {
+    #[inline]
+    fn get_value<'a>(&'a self, _: &str) -> Option<&'a dyn Value> {
+        None
+    }
+}
+
+impl<V: Values> Values for Option<V> {
+    #[inline]
+    fn get_value<'a>(&'a self, key: &str) -> Option<&'a dyn Value> {
+        self.as_ref()?.get_value(key)
+    }
+}
+
+impl<K: Borrow<str>, V: Value> Values for [(K, V)] {
+    fn get_value<'a>(&'a self, key: &str) -> Option<&'a dyn Value> {
+        let (_, value) = self.iter().find(|(k, _)| k.borrow() == key)?;
+        Some(value)
+    }
+}
+
+impl<K: Borrow<str>, V: Value, const N: usize> Values for [(K, V); N] {
+    #[inline]
+    fn get_value<'a>(&'a self, key: &str) -> Option<&'a dyn Value> {
+        self.as_slice().get_value(key)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: Borrow<str>, V: Value> Values for Vec<(K, V)> {
+    #[inline]
+    fn get_value<'a>(&'a self, key: &str) -> Option<&'a dyn Value> {
+        self.as_slice().get_value(key)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: Borrow<str> + Ord, V: Value> Values for BTreeMap<K, V> {
+    #[inline]
+    fn get_value<'a>(&'a self, key: &str) -> Option<&'a dyn Value> {
+        Some(self.get(key)?)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Borrow<str> + Eq + Hash, V: Value, S: BuildHasher> Values for HashMap<K, V, S> {
+    #[inline]
+    fn get_value<'a>(&'a self, key: &str) -> Option<&'a dyn Value> {
+        Some(self.get(key)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values_lookup() {
+        let values = [("a", 1_u32), ("b", 2)];
+        assert_eq!(values.get_typed::<u32>("b"), Some(&2));
+        assert_eq!(values.get_typed::<i32>("b"), None);
+        assert!(values.get_value("c").is_none());
+        assert!(().get_value("a").is_none());
+    }
+}
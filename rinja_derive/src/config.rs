@@ -1,10 +1,12 @@
 use std::borrow::{Borrow, Cow};
 use std::collections::btree_map::{BTreeMap, Entry};
+use std::io::Write;
 use std::mem::ManuallyDrop;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 use std::sync::{Arc, OnceLock};
-use std::{env, fs};
+use std::{env, fs, io};
 
 use parser::node::Whitespace;
 use parser::{ParseError, Parsed, Syntax, SyntaxBuilder};
@@ -20,7 +22,13 @@ pub(crate) struct Config {
     pub(crate) syntaxes: BTreeMap<String, SyntaxAndCache<'static>>,
     pub(crate) default_syntax: &'static str,
     pub(crate) escapers: Vec<(Vec<Cow<'static, str>>, Cow<'static, str>)>,
+    pub(crate) mime_escapers: Vec<(Vec<Cow<'static, str>>, Cow<'static, str>)>,
     pub(crate) whitespace: Whitespace,
+    pub(crate) allow_missing_templates: bool,
+    pub(crate) locate_filter_errors: bool,
+    pub(crate) check_block_overrides: bool,
+    pub(crate) loaders: Vec<Loader>,
+    pub(crate) filters: BTreeMap<&'static str, &'static str>,
     // `Config` is self referential and `_key` owns it data, so it must come last
     _key: OwnedConfigKey,
 }
@@ -107,22 +115,38 @@ impl Config {
         let raw = if s.is_empty() {
             RawConfig::default()
         } else {
-            RawConfig::from_toml_str(s)?
+            RawConfig::from_toml_str(s, config_path, config_span)?
         };
 
-        let (dirs, default_syntax, whitespace) = match raw.general {
+        let locate_filter_errors = raw
+            .general
+            .as_ref()
+            .is_some_and(|general| general.locate_filter_errors);
+        let check_block_overrides = raw
+            .general
+            .as_ref()
+            .is_some_and(|general| general.check_block_overrides);
+        let (dirs, default_syntax, whitespace, allow_missing_templates) = match raw.general {
             Some(General {
                 dirs,
                 default_syntax,
                 whitespace,
+                allow_missing_templates,
+                ..
             }) => (
                 dirs.map_or(default_dirs, |v| {
                     v.into_iter().map(|dir| root.join(dir)).collect()
                 }),
                 default_syntax.unwrap_or(DEFAULT_SYNTAX_NAME),
                 whitespace,
+                allow_missing_templates,
+            ),
+            None => (
+                default_dirs,
+                DEFAULT_SYNTAX_NAME,
+                Whitespace::default(),
+                false,
             ),
-            None => (default_dirs, DEFAULT_SYNTAX_NAME, Whitespace::default()),
         };
         let file_info = config_path.map(|path| FileInfo::new(Path::new(path), None, None));
         let whitespace = key.0.template_whitespace.unwrap_or(whitespace);
@@ -132,9 +156,10 @@ impl Config {
                 let name = raw_s.name;
                 match syntaxes.entry(name.to_string()) {
                     Entry::Vacant(entry) => {
-                        entry.insert(raw_s.to_syntax().map(SyntaxAndCache::new).map_err(
-                            |err| CompileError::new_with_span(err, file_info, config_span),
-                        )?);
+                        let syntax = raw_s.to_syntax().map_err(|err| {
+                            CompileError::new_with_span(err, file_info, config_span)
+                        })?;
+                        entry.insert(SyntaxAndCache::new(syntax, raw_s.whitespace));
                     }
                     Entry::Occupied(_) => {
                         return Err(CompileError::new(
@@ -154,34 +179,140 @@ impl Config {
         }
 
         let mut escapers = Vec::new();
-        if let Some(configured) = raw.escaper {
-            for escaper in configured {
-                escapers.push((str_set(&escaper.extensions), escaper.path.into()));
-            }
-        }
         for (extensions, name) in DEFAULT_ESCAPERS {
             escapers.push((
                 str_set(extensions),
                 format!("rinja::filters::{name}").into(),
             ));
         }
+        let mut mime_escapers = Vec::new();
+        for (mime_types, name) in DEFAULT_MIME_ESCAPERS {
+            mime_escapers.push((
+                str_set(mime_types),
+                format!("rinja::filters::{name}").into(),
+            ));
+        }
+        if let Some(configured) = raw.escaper {
+            configure_escapers(
+                &mut escapers,
+                configured
+                    .iter()
+                    .map(|escaper| (escaper.path, &*escaper.extensions)),
+                "extension",
+                file_info,
+            )?;
+            configure_escapers(
+                &mut mime_escapers,
+                configured
+                    .iter()
+                    .map(|escaper| (escaper.path, &*escaper.mime_types)),
+                "MIME type",
+                file_info,
+            )?;
+        }
+
+        let mut loaders: Vec<Loader> = Vec::new();
+        for raw_loader in raw.loader.unwrap_or_default() {
+            if raw_loader.command.is_empty() {
+                return Err(CompileError::new(
+                    "the `command` of a template loader must not be empty",
+                    file_info,
+                ));
+            }
+            for extension in &raw_loader.extensions {
+                if loaders.iter().any(|l| l.extensions.contains(extension)) {
+                    return Err(CompileError::new(
+                        format_args!("extension {extension:?} is configured for two loaders"),
+                        file_info,
+                    ));
+                }
+            }
+            loaders.push(Loader {
+                command: raw_loader.command,
+                extensions: raw_loader.extensions,
+                sources: OnceMap::default(),
+            });
+        }
+
+        let filters = raw.filters.unwrap_or_default();
+        for (&name, &path) in &filters {
+            if syn::parse_str::<syn::Ident>(name).is_err() {
+                return Err(CompileError::new(
+                    format_args!("filter name {name:?} is not a valid identifier"),
+                    file_info,
+                ));
+            }
+            if syn::parse_str::<syn::Path>(path).is_err() {
+                return Err(CompileError::new(
+                    format_args!("the path {path:?} of filter {name:?} is not a valid Rust path"),
+                    file_info,
+                ));
+            }
+        }
 
         Ok(Config {
             dirs,
             syntaxes,
             default_syntax,
             escapers,
+            mime_escapers,
             whitespace,
+            allow_missing_templates,
+            locate_filter_errors,
+            check_block_overrides,
+            loaders,
+            filters,
             _key: key,
         })
     }
 
+    /// Returns the loader that is configured for the extension of `path`, if any
+    pub(crate) fn find_loader(&self, path: &Path) -> Option<&Loader> {
+        let extension = path.extension()?.to_str()?;
+        self.loaders
+            .iter()
+            .find(|loader| loader.extensions.contains(&extension))
+    }
+
     pub(crate) fn find_template(
         &self,
         path: &str,
         start_at: Option<&Path>,
         file_info: Option<FileInfo<'_>>,
     ) -> Result<Arc<Path>, CompileError> {
+        match self.find_optional_template(path, start_at, file_info)? {
+            Some(path) => Ok(path),
+            None => Err(CompileError::new(
+                format_args!(
+                    "template {:?} not found in directories {:?}",
+                    path, self.dirs,
+                ),
+                file_info,
+            )),
+        }
+    }
+
+    /// Like [`Config::find_template()`], but returns `Ok(None)` if the template does not exist
+    /// and `allow_missing_templates` is enabled.
+    pub(crate) fn find_template_allow_missing(
+        &self,
+        path: &str,
+        start_at: Option<&Path>,
+        file_info: Option<FileInfo<'_>>,
+    ) -> Result<Option<Arc<Path>>, CompileError> {
+        match self.allow_missing_templates {
+            true => self.find_optional_template(path, start_at, file_info),
+            false => self.find_template(path, start_at, file_info).map(Some),
+        }
+    }
+
+    /// Like [`Config::find_template()`], but returns `Ok(None)` if the template does not exist.
+    pub(crate) fn find_optional_template(
+        &self,
+        path: &str,
+        start_at: Option<&Path>,
+        file_info: Option<FileInfo<'_>>,
+    ) -> Result<Option<Arc<Path>>, CompileError> {
         let path = 'find_path: {
             if let Some(root) = start_at {
                 let relative = root.with_file_name(path);
@@ -195,27 +326,121 @@ impl Config {
                     break 'find_path rooted;
                 }
             }
-            return Err(CompileError::new(
-                format_args!(
-                    "template {:?} not found in directories {:?}",
-                    path, self.dirs,
-                ),
-                file_info,
-            ));
+            return Ok(None);
         };
         match path.canonicalize() {
-            Ok(path) => Ok(path.into()),
+            Ok(path) => Ok(Some(path.into())),
             Err(err) => Err(CompileError::new(
                 format_args!("could not canonicalize path {path:?}: {err}"),
                 file_info,
             )),
         }
     }
+
+    /// Finds all templates that match `pattern`, sorted by their file name.
+    ///
+    /// Only the file name of the pattern can contain the wildcards `*` and `?`. Like in
+    /// [`Config::find_template()`], the directory of `start_at` is searched first, then the
+    /// template directories. The first directory that contains a match wins.
+    ///
+    /// Only the matched files are tracked as dependencies, because stable Rust cannot track a
+    /// directory. A newly added file that matches the pattern does not trigger a rebuild.
+    pub(crate) fn find_templates(
+        &self,
+        pattern: &str,
+        start_at: Option<&Path>,
+        file_info: Option<FileInfo<'_>>,
+    ) -> Result<Vec<Arc<Path>>, CompileError> {
+        let (dir, file_pattern) = match pattern.rsplit_once('/') {
+            Some((dir, file_pattern)) => (dir, file_pattern),
+            None => ("", pattern),
+        };
+        if dir.contains(['*', '?']) {
+            return Err(CompileError::new(
+                format_args!("only the file name of the pattern {pattern:?} can contain wildcards"),
+                file_info,
+            ));
+        }
+
+        let roots = start_at.map(|root| root.with_file_name(dir));
+        let roots = roots
+            .into_iter()
+            .chain(self.dirs.iter().map(|d| d.join(dir)));
+        for root in roots {
+            let Ok(entries) = fs::read_dir(&root) else {
+                continue;
+            };
+            let mut paths = Vec::new();
+            for entry in entries.flatten() {
+                let name = entry.file_name();
+                let Some(name) = name.to_str() else {
+                    continue;
+                };
+                if wildcard_match(file_pattern, name) && entry.path().is_file() {
+                    paths.push(entry.path());
+                }
+            }
+            if paths.is_empty() {
+                continue;
+            }
+            paths.sort_unstable();
+            return paths
+                .into_iter()
+                .map(|path| match path.canonicalize() {
+                    Ok(path) => Ok(path.into()),
+                    Err(err) => Err(CompileError::new(
+                        format_args!("could not canonicalize path {path:?}: {err}"),
+                        file_info,
+                    )),
+                })
+                .collect();
+        }
+        Err(CompileError::new(
+            format_args!(
+                "no template matches {:?} in directories {:?}",
+                pattern, self.dirs,
+            ),
+            file_info,
+        ))
+    }
+}
+
+/// Matches `name` against a `pattern` that can contain the wildcards `*` (any number of
+/// characters) and `?` (exactly one character).
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    // Position after the last `*`, and the position in `name` it is currently matched up to.
+    let mut backtrack = None;
+    let (mut p, mut n) = (0, 0);
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                backtrack = Some((p, n));
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((bp, bn)) => {
+                    p = bp;
+                    n = bn + 1;
+                    backtrack = Some((bp, bn + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
 }
 
 #[derive(Debug, Default)]
 pub(crate) struct SyntaxAndCache<'a> {
     syntax: Syntax<'a>,
+    /// Whitespace handling of the templates that use this syntax, if configured
+    pub(crate) whitespace: Option<Whitespace>,
     cache: OnceMap<OwnedSyntaxAndCacheKey, Arc<Parsed>>,
 }
 
@@ -251,9 +476,10 @@ impl<'a> Borrow<SyntaxAndCacheKey<'a>> for OwnedSyntaxAndCacheKey {
 }
 
 impl<'a> SyntaxAndCache<'a> {
-    fn new(syntax: Syntax<'a>) -> Self {
+    fn new(syntax: Syntax<'a>, whitespace: Option<Whitespace>) -> Self {
         Self {
             syntax,
+            whitespace,
             cache: OnceMap::default(),
         }
     }
@@ -288,6 +514,111 @@ impl<'a> SyntaxAndCache<'a> {
     }
 }
 
+/// A command that turns the content of a template file into template source, e.g. to render
+/// Markdown to HTML before the template gets parsed
+///
+/// The command is run by the proc-macro, i.e. at compile time, but also whenever an IDE like
+/// rust-analyzer expands the derive macro. Its outputs are cached for the lifetime of the
+/// proc-macro process, keyed by the path *and* the content of the template file, so an edited
+/// file is passed to the command again.
+#[derive(Debug)]
+pub(crate) struct Loader {
+    command: Vec<&'static str>,
+    extensions: Vec<&'static str>,
+    sources: OnceMap<(Arc<Path>, Vec<u8>), Arc<str>>,
+}
+
+impl Loader {
+    /// Runs the command with the file content as its input, unless its output is cached
+    pub(crate) fn load(
+        &self,
+        path: &Arc<Path>,
+        normalize: impl FnOnce(String) -> Arc<str>,
+    ) -> Result<Arc<str>, String> {
+        let content = fs::read(path)
+            .map_err(|err| format!("unable to open template file '{}': {err}", path.display()))?;
+        self.sources.get_or_try_insert(
+            &(Arc::clone(path), content),
+            |key| Ok((key.clone(), normalize(self.run(&key.0, &key.1)?))),
+            Arc::clone,
+        )
+    }
+
+    pub(crate) fn run(&self, path: &Path, input: &[u8]) -> Result<String, String> {
+        let command = self.command.join(" ");
+        let args = self
+            .command
+            .iter()
+            .map(|arg| expand_env(arg))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("invalid template loader `{command}`: {err}"))?;
+        let run = || {
+            let mut child = Command::new(&*args[0])
+                .args(args[1..].iter().map(|arg| &**arg))
+                .current_dir(manifest_root())
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()?;
+            // The input is written in another thread, so the command cannot block on a full
+            // output pipe while we are still writing its input.
+            let mut stdin = child.stdin.take().unwrap();
+            std::thread::scope(|s| {
+                let writer = s.spawn(move || stdin.write_all(input));
+                let output = child.wait_with_output()?;
+                // An error of the writer, e.g. a broken pipe because the command did not read all
+                // of its input, is only relevant if the command succeeded.
+                match writer.join().unwrap() {
+                    Err(err) if output.status.success() => Err(err),
+                    _ => Ok(output),
+                }
+            })
+        };
+        let output = run().map_err(|err: io::Error| {
+            format!(
+                "unable to run template loader `{command}` for '{}': {err}",
+                path.display(),
+            )
+        })?;
+        if !output.status.success() {
+            return Err(format!(
+                "template loader `{command}` failed for '{}' ({}): {}",
+                path.display(),
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim_end(),
+            ));
+        }
+        String::from_utf8(output.stdout).map_err(|_| {
+            format!(
+                "template loader `{command}` returned invalid UTF-8 for '{}'",
+                path.display(),
+            )
+        })
+    }
+}
+
+/// Replaces every `${NAME}` in an argument of a loader with the environment variable `NAME`
+fn expand_env(arg: &str) -> Result<Cow<'_, str>, String> {
+    if !arg.contains("${") {
+        return Ok(Cow::Borrowed(arg));
+    }
+    let mut expanded = String::with_capacity(arg.len());
+    let mut rest = arg;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start + 2..].find('}') else {
+            return Err(format!("unterminated `${{` in {arg:?}"));
+        };
+        let name = &rest[start + 2..start + 2 + len];
+        let value =
+            env::var(name).map_err(|_| format!("the environment variable `{name}` is not set"))?;
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(&value);
+        rest = &rest[start + 3 + len..];
+    }
+    expanded.push_str(rest);
+    Ok(Cow::Owned(expanded))
+}
+
 #[cfg_attr(feature = "config", derive(Deserialize))]
 #[derive(Default)]
 struct RawConfig<'a> {
@@ -295,21 +626,33 @@ struct RawConfig<'a> {
     general: Option<General<'a>>,
     syntax: Option<Vec<SyntaxBuilder<'a>>>,
     escaper: Option<Vec<RawEscaper<'a>>>,
+    loader: Option<Vec<RawLoader<'a>>>,
+    #[cfg_attr(feature = "config", serde(borrow))]
+    filters: Option<BTreeMap<&'a str, &'a str>>,
 }
 
 impl RawConfig<'_> {
     #[cfg(feature = "config")]
-    fn from_toml_str(s: &str) -> Result<RawConfig<'_>, CompileError> {
+    fn from_toml_str<'a>(
+        s: &'a str,
+        config_path: Option<&str>,
+        span: Option<Span>,
+    ) -> Result<RawConfig<'a>, CompileError> {
         basic_toml::from_str(s).map_err(|e| {
-            CompileError::no_file_info(format!("invalid TOML in {CONFIG_FILE_NAME}: {e}"), None)
+            let name = config_path.unwrap_or(CONFIG_FILE_NAME);
+            CompileError::no_file_info(format!("invalid TOML in {name}: {e}"), span)
         })
     }
 
     #[cfg(not(feature = "config"))]
-    fn from_toml_str(_: &str) -> Result<RawConfig<'_>, CompileError> {
+    fn from_toml_str<'a>(
+        _: &'a str,
+        _: Option<&str>,
+        span: Option<Span>,
+    ) -> Result<RawConfig<'a>, CompileError> {
         Err(CompileError::no_file_info(
             "TOML support not available",
-            None,
+            span,
         ))
     }
 }
@@ -321,11 +664,27 @@ struct General<'a> {
     default_syntax: Option<&'a str>,
     #[cfg_attr(feature = "config", serde(default))]
     whitespace: Whitespace,
+    #[cfg_attr(feature = "config", serde(default))]
+    allow_missing_templates: bool,
+    #[cfg_attr(feature = "config", serde(default))]
+    locate_filter_errors: bool,
+    #[cfg_attr(feature = "config", serde(default))]
+    check_block_overrides: bool,
 }
 
 #[cfg_attr(feature = "config", derive(Deserialize))]
 struct RawEscaper<'a> {
     path: &'a str,
+    #[cfg_attr(feature = "config", serde(default))]
+    extensions: Vec<&'a str>,
+    #[cfg_attr(feature = "config", serde(default))]
+    mime_types: Vec<&'a str>,
+}
+
+#[cfg_attr(feature = "config", derive(Deserialize))]
+struct RawLoader<'a> {
+    #[cfg_attr(feature = "config", serde(borrow))]
+    command: Vec<&'a str>,
     extensions: Vec<&'a str>,
 }
 
@@ -356,10 +715,82 @@ pub(crate) fn read_config_file(
     }
 }
 
-fn manifest_root() -> PathBuf {
+pub(crate) fn manifest_root() -> PathBuf {
     env::var_os("CARGO_MANIFEST_DIR").map_or_else(|| PathBuf::from("."), PathBuf::from)
 }
 
+/// Adds the escapers of the configuration file in front of the default `escapers`
+///
+/// Configured escapers with the same path are merged, and if a default escaper is configured,
+/// then the configured keys (extensions or MIME types) are added to its default keys. It is an
+/// error to configure a key for two different escapers. Configured keys take precedence over the
+/// defaults, so they are removed from the keys of all other default escapers.
+fn configure_escapers<'a>(
+    escapers: &mut Vec<(Vec<Cow<'static, str>>, Cow<'static, str>)>,
+    configured: impl Iterator<Item = (&'static str, &'a [&'static str])>,
+    kind: &str,
+    file_info: Option<FileInfo<'_>>,
+) -> Result<(), CompileError> {
+    let mut merged: Vec<(&'static str, Vec<&'static str>)> = Vec::new();
+    for (path, keys) in configured {
+        for &key in keys {
+            let owner = merged
+                .iter()
+                .find(|(_, keys)| keys.contains(&key))
+                .map(|&(owner, _)| owner);
+            match owner {
+                Some(owner) if !is_same_escaper(owner, path) => {
+                    return Err(CompileError::new(
+                        format_args!(
+                            "{kind} {key:?} is configured for two escapers, \
+                            `{owner}` and `{path}`",
+                        ),
+                        file_info,
+                    ));
+                }
+                Some(_) => {}
+                None => match merged
+                    .iter_mut()
+                    .find(|(other, _)| is_same_escaper(other, path))
+                {
+                    Some((_, keys)) => keys.push(key),
+                    None => merged.push((path, vec![key])),
+                },
+            }
+        }
+    }
+
+    for (keys, path) in escapers.iter_mut() {
+        keys.retain(|key| {
+            !merged.iter().any(|(other, other_keys)| {
+                !is_same_escaper(other, path) && other_keys.contains(&key.as_ref())
+            })
+        });
+    }
+    let mut added = Vec::new();
+    for (path, keys) in merged {
+        match escapers
+            .iter_mut()
+            .find(|(_, default)| is_same_escaper(default, path))
+        {
+            Some((default_keys, _)) => {
+                for key in keys {
+                    if !default_keys.contains(&Cow::Borrowed(key)) {
+                        default_keys.push(Cow::Borrowed(key));
+                    }
+                }
+            }
+            None => added.push((str_set(&keys), path.into())),
+        }
+    }
+    escapers.splice(0..0, added);
+    Ok(())
+}
+
+fn is_same_escaper(a: &str, b: &str) -> bool {
+    a.trim_start_matches("::") == b.trim_start_matches("::")
+}
+
 fn str_set(vals: &[&'static str]) -> Vec<Cow<'static, str>> {
     vals.iter().map(|s| Cow::Borrowed(*s)).collect()
 }
@@ -369,12 +800,33 @@ static DEFAULT_SYNTAX_NAME: &str = "default";
 static DEFAULT_ESCAPERS: &[(&[&str], &str)] = &[
     (
         &[
-            "html", "htm", "j2", "jinja", "jinja2", "rinja", "svg", "xml",
+            "html",
+            "htm",
+            "html_attr",
+            "j2",
+            "jinja",
+            "jinja2",
+            "rinja",
+            "svg",
+            "xml",
         ],
         "Html",
     ),
     (&["md", "none", "txt", "yml", ""], "Text"),
 ];
+static DEFAULT_MIME_ESCAPERS: &[(&[&str], &str)] = &[
+    (
+        &[
+            "application/xhtml+xml",
+            "application/xml",
+            "image/svg+xml",
+            "text/html",
+            "text/xml",
+        ],
+        "Html",
+    ),
+    (&["text/markdown", "text/plain", "text/yaml"], "Text"),
+];
 
 #[cfg(test)]
 mod tests {
@@ -391,6 +843,20 @@ mod tests {
         assert_eq!(config.dirs, vec![root]);
     }
 
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*.html", "card.html"));
+        assert!(wildcard_match("*.html", ".html"));
+        assert!(!wildcard_match("*.html", "card.html.txt"));
+        assert!(wildcard_match("c?rd*", "card.html"));
+        assert!(wildcard_match("c?rd*", "cärd"));
+        assert!(!wildcard_match("c?rd*", "crd"));
+        assert!(wildcard_match("*a*b*", "xaxxbx"));
+        assert!(!wildcard_match("*a*b*", "xbxxax"));
+        assert!(wildcard_match("card.html", "card.html"));
+        assert!(!wildcard_match("card.html", "card.htm"));
+    }
+
     #[cfg(feature = "config")]
     #[test]
     fn test_config_dirs() {
@@ -649,7 +1115,15 @@ mod tests {
             (str_set(&["js"]), "::my_filters::Js".into()),
             (
                 str_set(&[
-                    "html", "htm", "j2", "jinja", "jinja2", "rinja", "svg", "xml"
+                    "html",
+                    "htm",
+                    "html_attr",
+                    "j2",
+                    "jinja",
+                    "jinja2",
+                    "rinja",
+                    "svg",
+                    "xml"
                 ]),
                 "rinja::filters::Html".into()
             ),
@@ -660,6 +1134,156 @@ mod tests {
         ]);
     }
 
+    #[cfg(feature = "config")]
+    #[test]
+    fn escape_mime_types() {
+        let config = Config::new(
+            r#"
+            [[escaper]]
+            path = "::my_filters::Js"
+            extensions = ["js"]
+            mime_types = ["text/javascript"]
+
+            [[escaper]]
+            path = "::my_filters::Css"
+            mime_types = ["text/css"]
+        "#,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(config.escapers[0], (str_set(&["js"]), "::my_filters::Js".into()));
+        assert!(config.escapers.iter().all(|(_, path)| path != "::my_filters::Css"));
+        assert_eq!(config.mime_escapers[..2], [
+            (str_set(&["text/javascript"]), "::my_filters::Js".into()),
+            (str_set(&["text/css"]), "::my_filters::Css".into()),
+        ]);
+        assert!(
+            config
+                .mime_escapers
+                .iter()
+                .any(|(mime_types, path)| mime_types.contains(&Cow::Borrowed("text/html"))
+                    && path == "rinja::filters::Html")
+        );
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn escape_extend_default() {
+        let config = Config::new(
+            r#"
+            [[escaper]]
+            path = "rinja::filters::Html"
+            extensions = ["xhtml", "html"]
+
+            [[escaper]]
+            path = "::my_filters::Js"
+            extensions = ["js", "txt"]
+
+            [[escaper]]
+            path = "::rinja::filters::Html"
+            extensions = ["vue"]
+        "#,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(config.escapers, vec![
+            (str_set(&["js", "txt"]), "::my_filters::Js".into()),
+            (
+                str_set(&[
+                    "html",
+                    "htm",
+                    "html_attr",
+                    "j2",
+                    "jinja",
+                    "jinja2",
+                    "rinja",
+                    "svg",
+                    "xml",
+                    "xhtml",
+                    "vue"
+                ]),
+                "rinja::filters::Html".into()
+            ),
+            (
+                str_set(&["md", "none", "yml", ""]),
+                "rinja::filters::Text".into()
+            ),
+        ]);
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn escape_conflicting_extensions() {
+        let err = Config::new(
+            r#"
+            [[escaper]]
+            path = "::my_filters::Js"
+            extensions = ["js"]
+
+            [[escaper]]
+            path = "::my_filters::Ts"
+            extensions = ["ts", "js"]
+        "#,
+            None,
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.msg,
+            r#"extension "js" is configured for two escapers, `::my_filters::Js` and `::my_filters::Ts`"#,
+        );
+
+        let err = Config::new(
+            r#"
+            [[escaper]]
+            path = "::my_filters::Js"
+            mime_types = ["text/javascript"]
+
+            [[escaper]]
+            path = "rinja::filters::Text"
+            mime_types = ["text/javascript"]
+        "#,
+            None,
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(err.msg.starts_with(r#"MIME type "text/javascript" is configured for two"#));
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn invalid_toml_names_config() {
+        let err = Config::new("[general", None, None, None).unwrap_err();
+        assert!(err.msg.starts_with("invalid TOML in rinja.toml: "));
+
+        let err = Config::new("[general", Some("custom.toml"), None, None).unwrap_err();
+        assert!(err.msg.starts_with("invalid TOML in custom.toml: "));
+        assert!(err.msg.ends_with("at line 1 column 9"));
+    }
+
+    #[test]
+    fn escaper_none_is_available() {
+        let config = Config::new("", None, None, None).unwrap();
+        assert!(
+            config
+                .escapers
+                .iter()
+                .any(|(exts, path)| exts.contains(&Cow::Borrowed("none"))
+                    && path == "rinja::filters::Text")
+        );
+        assert!(
+            crate::MsgValidEscapers(&config.escapers)
+                .to_string()
+                .contains(r#""none""#)
+        );
+    }
+
     #[cfg(feature = "config")]
     #[test]
     fn test_whitespace_parsing() {
@@ -724,4 +1348,221 @@ mod tests {
         let config = Config::new(r#""#, None, Some(Whitespace::Minimize), None).unwrap();
         assert_eq!(config.whitespace, Whitespace::Minimize);
     }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_whitespace_in_syntax() {
+        let config = Config::new(
+            r#"
+            [general]
+            whitespace = "suppress"
+
+            [[syntax]]
+            name = "minimized"
+            whitespace = "minimize"
+
+            [[syntax]]
+            name = "plain"
+            "#,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(config.whitespace, Whitespace::Suppress);
+        assert_eq!(
+            config.syntaxes["minimized"].whitespace,
+            Some(Whitespace::Minimize)
+        );
+        assert_eq!(config.syntaxes["plain"].whitespace, None);
+        assert_eq!(config.syntaxes["default"].whitespace, None);
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_loaders() {
+        let config = Config::new(
+            r#"
+            [[loader]]
+            command = ["pandoc", "--from", "markdown"]
+            extensions = ["md", "markdown"]
+            "#,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(config.find_loader(Path::new("a.md")).is_some());
+        assert!(config.find_loader(Path::new("a.markdown")).is_some());
+        assert!(config.find_loader(Path::new("a.html")).is_none());
+        assert!(config.find_loader(Path::new("md")).is_none());
+
+        // `cargo` is the only command that is certainly installed when the tests are run.
+        let path = config.find_template("a.html", None, None).unwrap();
+        let config = Config::new(
+            r#"
+            [[loader]]
+            command = ["${CARGO}", "--version"]
+            extensions = ["md"]
+
+            [[loader]]
+            command = ["${CARGO}", "--no-such-option"]
+            extensions = ["fail"]
+
+            [[loader]]
+            command = ["${RINJA_NO_SUCH_VARIABLE}"]
+            extensions = ["unset"]
+            "#,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let loader = config.find_loader(Path::new("a.md")).unwrap();
+        let output = loader.run(&path, b"").unwrap();
+        assert!(output.starts_with("cargo "), "{output}");
+
+        let loader = config.find_loader(Path::new("a.fail")).unwrap();
+        let err = loader.run(&path, b"").unwrap_err();
+        assert!(
+            err.starts_with("template loader `${CARGO} --no-such-option` failed"),
+            "{err}",
+        );
+
+        let loader = config.find_loader(Path::new("a.unset")).unwrap();
+        let err = loader.run(&path, b"").unwrap_err();
+        assert_eq!(
+            err,
+            "invalid template loader `${RINJA_NO_SUCH_VARIABLE}`: \
+             the environment variable `RINJA_NO_SUCH_VARIABLE` is not set",
+        );
+    }
+
+    #[test]
+    fn test_expand_env() {
+        let cargo = env::var("CARGO").unwrap();
+        assert_eq!(expand_env("--flag").unwrap(), "--flag");
+        assert_eq!(expand_env("${CARGO}").unwrap(), cargo);
+        assert_eq!(
+            expand_env("a${CARGO}b${CARGO}").unwrap(),
+            format!("a{cargo}b{cargo}"),
+        );
+        assert_eq!(
+            expand_env("${CARGO").unwrap_err(),
+            r#"unterminated `${` in "${CARGO""#,
+        );
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_invalid_loaders() {
+        let err = Config::new(
+            "[[loader]]\ncommand = []\nextensions = [\"md\"]",
+            None,
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.msg,
+            "the `command` of a template loader must not be empty"
+        );
+
+        let err = Config::new(
+            r#"
+            [[loader]]
+            command = ["pandoc"]
+            extensions = ["md"]
+
+            [[loader]]
+            command = ["markdown"]
+            extensions = ["txt", "md"]
+            "#,
+            None,
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err.msg, r#"extension "md" is configured for two loaders"#);
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_filters() {
+        let config = Config::new(
+            r#"
+            [filters]
+            shout = "crate::text::shout"
+            len = "::core::primitive::str::len"
+            "#,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(config.filters["shout"], "crate::text::shout");
+        assert_eq!(config.filters["len"], "::core::primitive::str::len");
+        assert!(!config.filters.contains_key("upper"));
+
+        let err = Config::new("[filters]\n\"my-filter\" = \"a::b\"", None, None, None).unwrap_err();
+        assert_eq!(
+            err.msg,
+            r#"filter name "my-filter" is not a valid identifier"#
+        );
+
+        let err = Config::new("[filters]\nshout = \"a::\"", None, None, None).unwrap_err();
+        assert_eq!(
+            err.msg,
+            r#"the path "a::" of filter "shout" is not a valid Rust path"#
+        );
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_allow_missing_templates() {
+        let config = Config::new("", None, None, None).unwrap();
+        assert!(!config.allow_missing_templates);
+        assert!(
+            config
+                .find_template_allow_missing("does-not-exist.html", None, None)
+                .is_err()
+        );
+
+        let config = Config::new(
+            "[general]\nallow_missing_templates = true",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(config.allow_missing_templates);
+        assert_eq!(
+            config
+                .find_template_allow_missing("does-not-exist.html", None, None)
+                .unwrap(),
+            None,
+        );
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_locate_filter_errors() {
+        let config = Config::new("", None, None, None).unwrap();
+        assert!(!config.locate_filter_errors);
+
+        let config =
+            Config::new("[general]\nlocate_filter_errors = true", None, None, None).unwrap();
+        assert!(config.locate_filter_errors);
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_check_block_overrides() {
+        let config = Config::new("", None, None, None).unwrap();
+        assert!(!config.check_block_overrides);
+
+        let config =
+            Config::new("[general]\ncheck_block_overrides = true", None, None, None).unwrap();
+        assert!(config.check_block_overrides);
+    }
 }
@@ -3,20 +3,21 @@ mod node;
 
 use std::borrow::Cow;
 use std::collections::hash_map::HashMap;
-use std::ops::Deref;
+use std::ops::{self, Deref};
 use std::path::Path;
 use std::str;
 use std::sync::Arc;
 
 use parser::node::{Macro, Whitespace};
 use parser::{
-    CharLit, Expr, FloatKind, IntKind, MAX_RUST_KEYWORD_LEN, Num, RUST_KEYWORDS, StrLit, WithSpan,
+    CharLit, Expr, FloatKind, IntKind, MAX_RUST_KEYWORD_LEN, Num, RUST_KEYWORDS, Span, StrLit,
+    WithSpan,
 };
 use rustc_hash::FxBuildHasher;
 
 use crate::heritage::{Context, Heritage};
 use crate::html::write_escaped_str;
-use crate::input::{Source, TemplateInput};
+use crate::input::{Source, TemplateInput, extension};
 use crate::integration::{Buffer, impl_everything, write_header};
 use crate::{CompileError, FileInfo};
 
@@ -69,6 +70,30 @@ struct Generator<'a, 'h> {
     is_in_filter_block: usize,
     /// Set of called macros we are currently in. Used to prevent (indirect) recursions.
     seen_macros: Vec<(&'a Macro<'a>, Option<FileInfo<'a>>)>,
+    /// Macros that call themselves, whose closures we are currently in. The index is used in
+    /// the name of the handle, by which a closure calls itself.
+    recursive_macros: Vec<&'a Macro<'a>>,
+    /// Set if the template accesses runtime values through `values.<key>`.
+    uses_values: bool,
+    /// The escaper that is currently active. Changed by `{% autoescape %}` blocks.
+    escaper: &'a str,
+    /// Set if the body of the current loop accesses `loop.previtem` or `loop.nextitem`.
+    uses_loop_neighbors: bool,
+    /// Set if the body of the current loop accesses `loop.length`.
+    uses_loop_length: bool,
+    /// Loop labels that are targeted by a `{% break label %}` or `{% continue label %}`.
+    used_loop_labels: Vec<&'a str>,
+    /// Set inside of an `{% include ... only %}`, where only the passed values are accessible.
+    isolated: bool,
+    /// Set while expanding a macro that was invoked with a `{% call %}...{% endcall %}` block,
+    /// so the macro can render the block with `caller()`.
+    has_caller: bool,
+    /// Set while generating the body of a `{% call %}...{% endcall %}` block (outside of loops
+    /// nested in it), which cannot `break` out of or `continue` an outer loop.
+    in_caller_body: bool,
+    /// Set while visiting the expression of a `{{ … }}` node, and cleared as soon as a nested
+    /// expression is visited, so a filter knows if its result is written to the output as is.
+    is_output_expr: bool,
 }
 
 impl<'a, 'h> Generator<'a, 'h> {
@@ -94,6 +119,16 @@ impl<'a, 'h> Generator<'a, 'h> {
             },
             is_in_filter_block,
             seen_macros: Vec::new(),
+            recursive_macros: Vec::new(),
+            uses_values: false,
+            escaper: input.escaper,
+            uses_loop_neighbors: false,
+            uses_loop_length: false,
+            used_loop_labels: Vec::new(),
+            isolated: false,
+            has_caller: false,
+            in_caller_body: false,
+            is_output_expr: false,
         }
     }
 
@@ -109,7 +144,10 @@ impl<'a, 'h> Generator<'a, 'h> {
         }
         let size_hint = self.impl_template(ctx, buf, target.unwrap_or("rinja::Template"))?;
         if target.is_none() {
-            impl_everything(self.input.ast, buf);
+            impl_everything(self.input.ast, buf, self.input.display);
+            if self.input.warn_unused {
+                warn_unused_fields(self.input.ast, buf);
+            }
             buf.write("};");
         }
         Ok(size_hint)
@@ -123,15 +161,9 @@ impl<'a, 'h> Generator<'a, 'h> {
         target: &str,
     ) -> Result<usize, CompileError> {
         write_header(self.input.ast, buf, target);
-        buf.write(
-            "fn render_into<RinjaW>(&self, __rinja_writer: &mut RinjaW) -> rinja::Result<()>\
-            where \
-                RinjaW: rinja::helpers::core::fmt::Write + ?rinja::helpers::core::marker::Sized\
-            {\
-                use rinja::filters::{AutoEscape as _, WriteWritable as _};\
-                use rinja::helpers::ResultConverter as _;
-                use rinja::helpers::core::fmt::Write as _;",
-        );
+
+        // The body is generated first, because only then we know if the template uses `values`.
+        let mut body = Buffer::new();
 
         // Make sure the compiler understands that the generated code depends on the template files.
         let mut paths = self
@@ -140,6 +172,7 @@ impl<'a, 'h> Generator<'a, 'h> {
             .map(|path| -> &Path { path })
             .collect::<Vec<_>>();
         paths.sort();
+        let mut dependencies = Vec::with_capacity(paths.len());
         for path in paths {
             // Skip the fake path of templates defined in rust source.
             let path_is_valid = match self.input.source {
@@ -147,29 +180,297 @@ impl<'a, 'h> Generator<'a, 'h> {
                 Source::Source(_) => path != &*self.input.path,
             };
             if path_is_valid {
-                buf.write(format_args!(
+                let canonical = path.canonicalize();
+                let path = canonical.as_deref().unwrap_or(path);
+                body.write(format_args!(
                     "const _: &[rinja::helpers::core::primitive::u8] =\
-                        rinja::helpers::core::include_bytes!({:#?});",
-                    path.canonicalize().as_deref().unwrap_or(path),
+                        rinja::helpers::core::include_bytes!({path:#?});",
                 ));
+                dependencies.push(path.to_string_lossy().into_owned());
             }
         }
 
-        let size_hint = self.impl_template_inner(ctx, buf)?;
+        let size_hint = self.impl_template_body(ctx, &mut body)?;
+
+        if self.input.error_placeholder.is_some() {
+            // In collecting mode the fail-fast methods are wrappers around the collecting one,
+            // that abort at the first reported error.
+            buf.write(
+                "fn render_into<RinjaW>(&self, __rinja_writer: &mut RinjaW) -> rinja::Result<()>\
+                where \
+                    RinjaW: rinja::helpers::core::fmt::Write + ?rinja::helpers::core::marker::Sized\
+                {\
+                    self.render_into_with_values(__rinja_writer, &())\
+                }\
+                fn render_into_with_values<RinjaW>(\
+                    &self,\
+                    __rinja_writer: &mut RinjaW,\
+                    __rinja_values: &dyn rinja::Values,\
+                ) -> rinja::Result<()>\
+                where \
+                    RinjaW: rinja::helpers::core::fmt::Write + ?rinja::helpers::core::marker::Sized\
+                {\
+                    rinja::helpers::fail_fast(|__rinja_on_error| {\
+                        self.render_into_with_values_collecting(\
+                            __rinja_writer,\
+                            __rinja_values,\
+                            __rinja_on_error,\
+                        )\
+                    })\
+                }\
+                fn render_into_with_values_collecting<RinjaW>(\
+                    &self,\
+                    __rinja_writer: &mut RinjaW,\
+                    __rinja_values: &dyn rinja::Values,\
+                    __rinja_on_error: &mut dyn rinja::helpers::core::ops::FnMut(rinja::Error),\
+                ) -> rinja::Result<()>",
+            );
+        } else if self.uses_values {
+            buf.write(
+                "fn render_into<RinjaW>(&self, __rinja_writer: &mut RinjaW) -> rinja::Result<()>\
+                where \
+                    RinjaW: rinja::helpers::core::fmt::Write + ?rinja::helpers::core::marker::Sized\
+                {\
+                    self.render_into_with_values(__rinja_writer, &())\
+                }\
+                fn render_into_with_values<RinjaW>(\
+                    &self,\
+                    __rinja_writer: &mut RinjaW,\
+                    __rinja_values: &dyn rinja::Values,\
+                ) -> rinja::Result<()>",
+            );
+        } else {
+            buf.write(
+                "fn render_into<RinjaW>(&self, __rinja_writer: &mut RinjaW) -> rinja::Result<()>",
+            );
+        }
+        buf.write(
+            "where \
+                RinjaW: rinja::helpers::core::fmt::Write + ?rinja::helpers::core::marker::Sized\
+            {\
+                use rinja::filters::{AutoEscape as _, WriteWritable as _};\
+                use rinja::helpers::ResultConverter as _;
+                use rinja::helpers::core::fmt::Write as _;",
+        );
+        if self.input.error_placeholder.is_some() {
+            buf.write("let __rinja_errors = rinja::helpers::ErrorSink::new(__rinja_on_error);");
+        }
+        // With the `tracing` feature, the body is rendered inside of a span named after the
+        // template, which records the size hint and the length of the output.
+        #[cfg(feature = "tracing")]
+        {
+            let name = match &self.input.source {
+                Source::Path(path) => path.to_string(),
+                Source::Source(_) => self.input.path.to_string_lossy().into_owned(),
+            };
+            buf.write(format_args!(
+                "let __rinja_span = rinja::helpers::tracing::info_span!(\
+                    {name:?},\
+                    size_hint = {size_hint}usize,\
+                    len = rinja::helpers::tracing::field::Empty,\
+                );\
+                let __rinja_writer = &mut rinja::helpers::CountingWriter::new(__rinja_writer);\
+                let __rinja_result = __rinja_span.in_scope(|| -> rinja::Result<()> {{",
+            ));
+        }
+        buf.write(body.into_string());
+        buf.write("rinja::Result::Ok(())");
+        #[cfg(feature = "tracing")]
+        buf.write(
+            "});\
+            __rinja_span.record(\"len\", __rinja_writer.bytes_written());\
+            __rinja_result",
+        );
 
         buf.write(format_args!(
             "\
-                rinja::Result::Ok(())\
             }}\
             const SIZE_HINT: rinja::helpers::core::primitive::usize = {size_hint}usize;",
         ));
+        #[cfg(feature = "adaptive-size-hint")]
+        buf.write(format_args!(
+            "#[inline]\
+            fn adaptive_size_hint() -> rinja::helpers::core::option::Option<\
+                &'static rinja::helpers::AdaptiveSizeHint\
+            > {{\
+                static HINT: rinja::helpers::AdaptiveSizeHint =\
+                    rinja::helpers::AdaptiveSizeHint::new({size_hint}usize);\
+                rinja::helpers::core::option::Option::Some(&HINT)\
+            }}",
+        ));
+        buf.write("const LINE_STARTS: &'static [rinja::helpers::core::primitive::usize] = &[0,");
+        for (offset, _) in ctx.parsed.source().match_indices('\n') {
+            buf.write(format_args!("{},", offset + 1));
+        }
+        buf.write("];");
+        if let Some(extension) = extension(&self.input.path) {
+            buf.write(format_args!(
+                "const EXTENSION: rinja::helpers::core::option::Option<\
+                    &'static rinja::helpers::core::primitive::str\
+                > = rinja::helpers::core::option::Option::Some({extension:?});",
+            ));
+        }
+        buf.write(format_args!(
+            "const MIME_TYPE: &'static rinja::helpers::core::primitive::str = {:?};",
+            self.input.mime_type,
+        ));
+        if self.input.expose_source {
+            buf.write(format_args!(
+                "const SOURCE: rinja::helpers::core::option::Option<\
+                    &'static rinja::helpers::core::primitive::str\
+                > = rinja::helpers::core::option::Option::Some({:?});",
+                ctx.parsed.source(),
+            ));
+        }
+        if !dependencies.is_empty() {
+            buf.write(
+                "const DEPENDENCIES: &'static [&'static rinja::helpers::core::primitive::str] = &[",
+            );
+            for path in dependencies {
+                buf.write(format_args!("{path:?},"));
+            }
+            buf.write("];");
+        }
+        if !self.input.blocks.is_empty() {
+            self.impl_render_block(ctx, buf)?;
+        }
+
+        buf.write('}');
+        Ok(size_hint)
+    }
+
+    // Implement `Template::render_block_into()` and `Template::BLOCKS` for the blocks that were
+    // requested with `#[template(blocks = [..])]`.
+    fn impl_render_block(&self, ctx: &Context<'a>, buf: &mut Buffer) -> Result<(), CompileError> {
+        buf.write(
+            "fn render_block_into<RinjaW>(\
+                &self,\
+                __rinja_block: &rinja::helpers::core::primitive::str,\
+                __rinja_writer: &mut RinjaW,\
+            ) -> rinja::Result<()>\
+            where \
+                RinjaW: rinja::helpers::core::fmt::Write + ?rinja::helpers::core::marker::Sized\
+            {\
+                use rinja::filters::{AutoEscape as _, WriteWritable as _};\
+                use rinja::helpers::ResultConverter as _;
+                use rinja::helpers::core::fmt::Write as _;
+                match __rinja_block {",
+        );
+        for (name, _) in self.input.blocks {
+            // Every block is rendered like a block fragment, i.e. `#[template(block = "name")]`.
+            let input = TemplateInput {
+                block: Some(name),
+                ..self.input.clone()
+            };
+            let mut generator = Generator::new(
+                &input,
+                self.contexts,
+                self.heritage,
+                MapChain::default(),
+                true,
+                0,
+            );
+            let mut body = Buffer::new();
+            generator.impl_template_body(ctx, &mut body)?;
+
+            buf.write(format_args!("{name:?} => {{"));
+            if input.error_placeholder.is_some() {
+                buf.write(
+                    "rinja::helpers::fail_fast(|__rinja_on_error| {\
+                        let __rinja_errors = rinja::helpers::ErrorSink::new(__rinja_on_error);",
+                );
+            }
+            if generator.uses_values {
+                buf.write("let __rinja_values: &dyn rinja::Values = &();");
+            }
+            buf.write(body.into_string());
+            if input.error_placeholder.is_some() {
+                buf.write("rinja::Result::Ok(()) })?;");
+            }
+            buf.write('}');
+        }
+        buf.write(
+            "_ => return rinja::Result::Err(rinja::Error::UnknownBlock),\
+                }\
+                rinja::Result::Ok(())\
+            }\
+            const BLOCKS: &'static [&'static rinja::helpers::core::primitive::str] = &[",
+        );
+        for (name, _) in self.input.blocks {
+            buf.write(format_args!("{name:?},"));
+        }
+        buf.write("];");
+        Ok(())
+    }
+
+    // Generates the body of the template. If the escaper is selected at runtime with
+    // `#[template(escape_field = "..")]`, then the body is generated once for every escaper, and
+    // the field selects which one is rendered.
+    fn impl_template_body(
+        &mut self,
+        ctx: &Context<'a>,
+        buf: &mut Buffer,
+    ) -> Result<usize, CompileError> {
+        let Some(field) = self.input.escape_field else {
+            return self.impl_template_inner(ctx, buf);
+        };
 
+        buf.write(format_args!(
+            "match rinja::filters::EscapeMode::from(self.{field}) {{"
+        ));
+        let mut size_hint = 0;
+        for (mode, escaper) in [
+            ("Html", "rinja::filters::Html"),
+            ("Text", "rinja::filters::Text"),
+        ] {
+            let input = TemplateInput {
+                escaper,
+                escape_field: None,
+                ..self.input.clone()
+            };
+            let mut generator = Generator::new(
+                &input,
+                self.contexts,
+                self.heritage,
+                MapChain::default(),
+                self.buf_writable.discard,
+                0,
+            );
+            let mut body = Buffer::new();
+            size_hint = size_hint.max(generator.impl_template_inner(ctx, &mut body)?);
+            self.uses_values |= generator.uses_values;
+
+            buf.write(format_args!("rinja::filters::EscapeMode::{mode} => {{"));
+            buf.write(body.into_string());
+            buf.write('}');
+        }
         buf.write('}');
         Ok(size_hint)
     }
 
     fn is_var_defined(&self, var_name: &str) -> bool {
-        self.locals.get(var_name).is_some() || self.input.fields.iter().any(|f| f == var_name)
+        self.locals.get(var_name).is_some()
+            || (!self.isolated && self.input.fields.iter().any(|f| f == var_name))
+    }
+
+    /// Resolves the variable `name` to a local variable, or to a field of the template struct
+    fn resolve_var(
+        &self,
+        ctx: &Context<'_>,
+        name: &str,
+        span: Span<'_>,
+    ) -> Result<String, CompileError> {
+        match self.locals.resolve(name) {
+            Some(var) => Ok(var),
+            None if self.isolated => Err(ctx.generate_error(
+                format_args!(
+                    "`{name}` is not defined: a template included with `only` can only access \
+                    the values passed with `with`",
+                ),
+                span,
+            )),
+            None => Ok(format!("self.{}", normalize_identifier(name))),
+        }
     }
 }
 
@@ -196,6 +497,35 @@ const _: () = {
     panic!("unknown cfg!(target_pointer_width)");
 };
 
+/// Emits a warning for every named struct field that the generated code never reads
+fn warn_unused_fields(ast: &syn::DeriveInput, buf: &mut Buffer) {
+    let syn::Data::Struct(data) = &ast.data else {
+        return;
+    };
+    let mark = buf.get_mark();
+    let code = buf.marked_text(mark);
+    let unused = data
+        .fields
+        .iter()
+        .filter_map(|field| field.ident.as_ref())
+        .filter(|ident| !reads_field(code, &ident.to_string()))
+        .collect::<Vec<_>>();
+    for ident in unused {
+        buf.warn(
+            format!("the field `{ident}` is never used by the template"),
+            ident.span(),
+        );
+    }
+}
+
+fn reads_field(code: &str, field: &str) -> bool {
+    code.match_indices("self.").any(|(idx, needle)| {
+        code[idx + needle.len()..]
+            .strip_prefix(field)
+            .is_some_and(|rest| !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_'))
+    })
+}
+
 /// In here, we inspect in the expression if it is a literal, and if it is, whether it
 /// can be escaped at compile time.
 fn compile_time_escape<'a>(expr: &Expr<'a>, escaper: &str) -> Option<Writable<'a>> {
@@ -212,93 +542,9 @@ fn compile_time_escape<'a>(expr: &Expr<'a>, escaper: &str) -> Option<Writable<'a
         _ => return None,
     };
 
-    // for now, we only escape strings, chars, numbers, and bools at compile time
-    let value = match *expr {
-        Expr::StrLit(StrLit {
-            prefix: None,
-            content,
-        }) => {
-            if content.find('\\').is_none() {
-                // if the literal does not contain any backslashes, then it does not need unescaping
-                Cow::Borrowed(content)
-            } else {
-                // the input could be string escaped if it contains any backslashes
-                let input = format!(r#""{content}""#);
-                let input = input.parse().ok()?;
-                let input = syn::parse2::<syn::LitStr>(input).ok()?;
-                Cow::Owned(input.value())
-            }
-        }
-        Expr::CharLit(CharLit {
-            prefix: None,
-            content,
-        }) => {
-            if content.find('\\').is_none() {
-                // if the literal does not contain any backslashes, then it does not need unescaping
-                Cow::Borrowed(content)
-            } else {
-                // the input could be string escaped if it contains any backslashes
-                let input = format!(r#"'{content}'"#);
-                let input = input.parse().ok()?;
-                let input = syn::parse2::<syn::LitChar>(input).ok()?;
-                Cow::Owned(input.value().to_string())
-            }
-        }
-        Expr::NumLit(_, value) => {
-            enum NumKind {
-                Int(Option<IntKind>),
-                Float(Option<FloatKind>),
-            }
-
-            let (orig_value, kind) = match value {
-                Num::Int(value, kind) => (value, NumKind::Int(kind)),
-                Num::Float(value, kind) => (value, NumKind::Float(kind)),
-            };
-            let value = match orig_value.chars().any(|c| c == '_') {
-                true => Cow::Owned(orig_value.chars().filter(|&c| c != '_').collect()),
-                false => Cow::Borrowed(orig_value),
-            };
-
-            fn int<T: ToString, E>(
-                from_str_radix: impl Fn(&str, u32) -> Result<T, E>,
-                value: &str,
-            ) -> Option<String> {
-                Some(from_str_radix(value, 10).ok()?.to_string())
-            }
-
-            let value = match kind {
-                NumKind::Int(Some(IntKind::I8)) => int(i8::from_str_radix, &value)?,
-                NumKind::Int(Some(IntKind::I16)) => int(i16::from_str_radix, &value)?,
-                NumKind::Int(Some(IntKind::I32)) => int(i32::from_str_radix, &value)?,
-                NumKind::Int(Some(IntKind::I64)) => int(i64::from_str_radix, &value)?,
-                NumKind::Int(Some(IntKind::I128)) => int(i128::from_str_radix, &value)?,
-                NumKind::Int(Some(IntKind::Isize)) => int(TargetIsize::from_str_radix, &value)?,
-                NumKind::Int(Some(IntKind::U8)) => int(u8::from_str_radix, &value)?,
-                NumKind::Int(Some(IntKind::U16)) => int(u16::from_str_radix, &value)?,
-                NumKind::Int(Some(IntKind::U32)) => int(u32::from_str_radix, &value)?,
-                NumKind::Int(Some(IntKind::U64)) => int(u64::from_str_radix, &value)?,
-                NumKind::Int(Some(IntKind::U128)) => int(u128::from_str_radix, &value)?,
-                NumKind::Int(Some(IntKind::Usize)) => int(TargetUsize::from_str_radix, &value)?,
-                NumKind::Int(None) => match value.starts_with('-') {
-                    true => int(i128::from_str_radix, &value)?,
-                    false => int(u128::from_str_radix, &value)?,
-                },
-                NumKind::Float(Some(FloatKind::F32)) => value.parse::<f32>().ok()?.to_string(),
-                NumKind::Float(Some(FloatKind::F64) | None) => {
-                    value.parse::<f64>().ok()?.to_string()
-                }
-                // FIXME: implement once `f16` and `f128` are available
-                NumKind::Float(Some(FloatKind::F16 | FloatKind::F128)) => return None,
-            };
-            match value == orig_value {
-                true => Cow::Borrowed(orig_value),
-                false => Cow::Owned(value),
-            }
-        }
-        Expr::BoolLit(true) => Cow::Borrowed("true"),
-        Expr::BoolLit(false) => Cow::Borrowed("false"),
-        _ => return None,
-    };
+    // for now, we only escape strings, chars, numbers, and bools at compile time, and expressions
+    // that only consist of such literals
+    let value = ConstValue::eval(expr)?.into_string();
 
     // escape the un-string-escaped input using the selected escaper
     Some(Writable::Lit(match output {
@@ -314,6 +560,188 @@ fn compile_time_escape<'a>(expr: &Expr<'a>, escaper: &str) -> Option<Writable<'a
     }))
 }
 
+/// The value of an expression that only consists of literals, e.g. `{{ 2 + 3 }}`.
+///
+/// Such expressions have no side effects, so they can be evaluated at compile time, and written
+/// as literal text. We only fold expressions that rustc would accept and evaluate the same way:
+/// anything else, like an overflow or a division by zero, is left for rustc to report.
+enum ConstValue<'a> {
+    Str(Cow<'a, str>),
+    Int(i128, Option<IntKind>),
+    Float(f64, Option<FloatKind>),
+    Bool(bool),
+}
+
+impl<'a> ConstValue<'a> {
+    fn eval(expr: &Expr<'a>) -> Option<Self> {
+        Some(match *expr {
+            Expr::StrLit(StrLit {
+                prefix: None,
+                content,
+            }) => {
+                if content.find('\\').is_none() {
+                    // if the literal does not contain any backslashes, then it does not need unescaping
+                    Self::Str(Cow::Borrowed(content))
+                } else {
+                    // the input could be string escaped if it contains any backslashes
+                    let input = format!(r#""{content}""#);
+                    let input = input.parse().ok()?;
+                    let input = syn::parse2::<syn::LitStr>(input).ok()?;
+                    Self::Str(Cow::Owned(input.value()))
+                }
+            }
+            Expr::CharLit(CharLit {
+                prefix: None,
+                content,
+            }) => {
+                if content.find('\\').is_none() {
+                    // if the literal does not contain any backslashes, then it does not need unescaping
+                    Self::Str(Cow::Borrowed(content))
+                } else {
+                    // the input could be string escaped if it contains any backslashes
+                    let input = format!(r#"'{content}'"#);
+                    let input = input.parse().ok()?;
+                    let input = syn::parse2::<syn::LitChar>(input).ok()?;
+                    Self::Str(Cow::Owned(input.value().to_string()))
+                }
+            }
+            Expr::NumLit(_, Num::Int(value, kind)) => {
+                let value = value.replace('_', "").parse().ok()?;
+                // an unsuffixed literal is accepted as is, because rustc would infer its type
+                Self::Int(value, kind).checked(kind.is_some())?
+            }
+            Expr::NumLit(_, Num::Float(value, kind)) => {
+                let value = value.replace('_', "");
+                let value = match kind {
+                    Some(FloatKind::F32) => value.parse::<f32>().ok()?.into(),
+                    Some(FloatKind::F64) | None => value.parse::<f64>().ok()?,
+                    // FIXME: implement once `f16` and `f128` are available
+                    Some(FloatKind::F16 | FloatKind::F128) => return None,
+                };
+                Self::Float(value, kind)
+            }
+            Expr::BoolLit(value) => Self::Bool(value),
+            Expr::Group(ref expr) => Self::eval(expr)?,
+            Expr::Unary(op, ref expr) => match (op, Self::eval(expr)?) {
+                ("-", Self::Int(value, kind)) if !kind.is_some_and(is_unsigned) => {
+                    Self::Int(value.checked_neg()?, kind).checked(true)?
+                }
+                ("-", Self::Float(value, kind)) => Self::Float(-value, kind),
+                ("!", Self::Bool(value)) => Self::Bool(!value),
+                _ => return None,
+            },
+            Expr::BinOp(op, ref left, ref right) => {
+                Self::eval_bin_op(op, Self::eval(left)?, Self::eval(right)?)?
+            }
+            Expr::Concat(ref exprs) => {
+                let mut concatenated = String::new();
+                for expr in exprs {
+                    concatenated.push_str(&Self::eval(expr)?.into_string());
+                }
+                Self::Str(Cow::Owned(concatenated))
+            }
+            _ => return None,
+        })
+    }
+
+    fn eval_bin_op(op: &str, left: Self, right: Self) -> Option<Self> {
+        match (left, right) {
+            (Self::Int(left, left_kind), Self::Int(right, right_kind)) => {
+                let kind = match (left_kind, right_kind) {
+                    (Some(left_kind), Some(right_kind)) if left_kind != right_kind => return None,
+                    (left_kind, right_kind) => left_kind.or(right_kind),
+                };
+                // both operands must fit into the inferred type, too
+                Self::Int(left, kind).checked(true)?;
+                Self::Int(right, kind).checked(true)?;
+                let value = match op {
+                    "+" => left.checked_add(right)?,
+                    "-" => left.checked_sub(right)?,
+                    "*" => left.checked_mul(right)?,
+                    "/" => left.checked_div(right)?,
+                    "%" => left.checked_rem(right)?,
+                    _ => return None,
+                };
+                Self::Int(value, kind).checked(true)
+            }
+            (Self::Float(left, left_kind), Self::Float(right, right_kind)) => {
+                let kind = match (left_kind, right_kind) {
+                    (Some(left_kind), Some(right_kind)) if left_kind != right_kind => return None,
+                    (left_kind, right_kind) => left_kind.or(right_kind),
+                };
+                let value = match kind {
+                    Some(FloatKind::F32) => float_op(op, left as f32, right as f32)?.into(),
+                    _ => float_op(op, left, right)?,
+                };
+                Some(Self::Float(value, kind))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `None` if the integer value does not fit into its type.
+    ///
+    /// If the type is unknown, rustc would infer it to be `i32`, but only if `infer` is `true`.
+    fn checked(self, infer: bool) -> Option<Self> {
+        let Self::Int(value, kind) = self else {
+            return Some(self);
+        };
+        let fits = match kind {
+            Some(IntKind::I8) => i8::try_from(value).is_ok(),
+            Some(IntKind::I16) => i16::try_from(value).is_ok(),
+            Some(IntKind::I32) => i32::try_from(value).is_ok(),
+            Some(IntKind::I64) => i64::try_from(value).is_ok(),
+            Some(IntKind::I128) => true,
+            Some(IntKind::Isize) => TargetIsize::try_from(value).is_ok(),
+            Some(IntKind::U8) => u8::try_from(value).is_ok(),
+            Some(IntKind::U16) => u16::try_from(value).is_ok(),
+            Some(IntKind::U32) => u32::try_from(value).is_ok(),
+            Some(IntKind::U64) => u64::try_from(value).is_ok(),
+            Some(IntKind::U128) => value >= 0,
+            Some(IntKind::Usize) => TargetUsize::try_from(value).is_ok(),
+            None if infer => i32::try_from(value).is_ok(),
+            None => true,
+        };
+        fits.then_some(self)
+    }
+
+    fn into_string(self) -> Cow<'a, str> {
+        match self {
+            Self::Str(value) => value,
+            Self::Int(value, _) => Cow::Owned(value.to_string()),
+            Self::Float(value, Some(FloatKind::F32)) => Cow::Owned((value as f32).to_string()),
+            Self::Float(value, _) => Cow::Owned(value.to_string()),
+            Self::Bool(true) => Cow::Borrowed("true"),
+            Self::Bool(false) => Cow::Borrowed("false"),
+        }
+    }
+}
+
+fn float_op<T>(op: &str, left: T, right: T) -> Option<T>
+where
+    T: ops::Add<Output = T>
+        + ops::Sub<Output = T>
+        + ops::Mul<Output = T>
+        + ops::Div<Output = T>
+        + ops::Rem<Output = T>,
+{
+    Some(match op {
+        "+" => left + right,
+        "-" => left - right,
+        "*" => left * right,
+        "/" => left / right,
+        "%" => left % right,
+        _ => return None,
+    })
+}
+
+fn is_unsigned(kind: IntKind) -> bool {
+    matches!(
+        kind,
+        IntKind::U8 | IntKind::U16 | IntKind::U32 | IntKind::U64 | IntKind::U128 | IntKind::Usize
+    )
+}
+
 #[derive(Clone, Default)]
 struct LocalMeta {
     refs: Option<String>,
@@ -376,11 +804,6 @@ impl<'a> MapChain<'a> {
             None => name.to_string(),
         })
     }
-
-    fn resolve_or_self(&self, name: &str) -> String {
-        let name = normalize_identifier(name);
-        self.resolve(name).unwrap_or_else(|| format!("self.{name}"))
-    }
 }
 
 impl Default for MapChain<'_> {
@@ -403,9 +826,13 @@ fn is_copyable_within_op(expr: &Expr<'_>, within_op: bool) -> bool {
         | Expr::NumLit(_, _)
         | Expr::StrLit(_)
         | Expr::CharLit(_)
-        | Expr::BinOp(_, _, _) => true,
+        | Expr::BinOp(_, _, _)
+        | Expr::Test(_) => true,
         Expr::Unary(.., expr) => is_copyable_within_op(expr, true),
         Expr::Range(..) => true,
+        Expr::IfElse(_, then, otherwise) => {
+            is_copyable_within_op(then, within_op) && is_copyable_within_op(otherwise, within_op)
+        }
         // The result of a call likely doesn't need to be borrowed,
         // as in that case the call is more likely to return a
         // reference in the first place then.
@@ -1,8 +1,11 @@
 use std::borrow::Cow;
+use std::fmt;
+use std::mem;
 
 use parser::node::CondTest;
 use parser::{
-    CharLit, CharPrefix, Expr, Filter, IntKind, Num, Span, StrLit, StrPrefix, Target, WithSpan,
+    CharLit, CharPrefix, Expr, Filter, IntKind, Num, Span, StrLit, StrPrefix, Target, Test,
+    WithSpan,
 };
 
 use super::{
@@ -11,7 +14,10 @@ use super::{
 };
 use crate::heritage::Context;
 use crate::integration::Buffer;
-use crate::{BUILTIN_FILTERS, BUILTIN_FILTERS_NEED_ALLOC, CompileError, MsgValidEscapers};
+use crate::{
+    BUILTIN_FILTERS, BUILTIN_FILTERS_NEED_ALLOC, CompileError, FILTERS_WITH_NAMED_ARGUMENTS,
+    MsgValidEscapers,
+};
 
 impl<'a> Generator<'a, '_> {
     pub(crate) fn visit_expr_root(
@@ -30,12 +36,13 @@ impl<'a> Generator<'a, '_> {
         buf: &mut Buffer,
         expr: &WithSpan<'_, Expr<'a>>,
     ) -> Result<DisplayWrap, CompileError> {
+        let is_output_expr = mem::take(&mut self.is_output_expr);
         Ok(match **expr {
             Expr::BoolLit(s) => self.visit_bool_lit(buf, s),
             Expr::NumLit(s, _) => self.visit_num_lit(buf, s),
             Expr::StrLit(ref s) => self.visit_str_lit(buf, s),
             Expr::CharLit(ref s) => self.visit_char_lit(buf, s),
-            Expr::Var(s) => self.visit_var(buf, s),
+            Expr::Var(s) => self.visit_var(ctx, buf, s, expr.span())?,
             Expr::Path(ref path) => self.visit_path(buf, path),
             Expr::Array(ref elements) => self.visit_array(ctx, buf, elements)?,
             Expr::Attr(ref obj, name) => self.visit_attr(ctx, buf, obj, name)?,
@@ -43,7 +50,10 @@ impl<'a> Generator<'a, '_> {
             Expr::Filter(Filter {
                 name,
                 ref arguments,
-            }) => self.visit_filter(ctx, buf, name, arguments, expr.span())?,
+            }) => {
+                self.is_output_expr = is_output_expr;
+                self.visit_filter(ctx, buf, name, arguments, expr.span())?
+            }
             Expr::Unary(op, ref inner) => self.visit_unary(ctx, buf, op, inner)?,
             Expr::BinOp(op, ref left, ref right) => self.visit_binop(ctx, buf, op, left, right)?,
             Expr::Range(op, ref left, ref right) => {
@@ -58,9 +68,13 @@ impl<'a> Generator<'a, '_> {
             Expr::FilterSource => self.visit_filter_source(buf),
             Expr::IsDefined(var_name) => self.visit_is_defined(buf, true, var_name)?,
             Expr::IsNotDefined(var_name) => self.visit_is_defined(buf, false, var_name)?,
+            Expr::Test(ref test) => self.visit_test(ctx, buf, test, expr.span())?,
             Expr::As(ref expr, target) => self.visit_as(ctx, buf, expr, target)?,
             Expr::Concat(ref exprs) => self.visit_concat(ctx, buf, exprs)?,
             Expr::LetCond(ref cond) => self.visit_let_cond(ctx, buf, cond)?,
+            Expr::IfElse(ref cond, ref then, ref otherwise) => {
+                self.visit_if_else(ctx, buf, cond, then, otherwise)?
+            }
         })
     }
 
@@ -118,7 +132,7 @@ impl<'a> Generator<'a, '_> {
         expr: &WithSpan<'_, Expr<'a>>,
     ) -> Result<(), CompileError> {
         match &**expr {
-            Expr::BoolLit(_) | Expr::IsDefined(_) | Expr::IsNotDefined(_) => {
+            Expr::BoolLit(_) | Expr::IsDefined(_) | Expr::IsNotDefined(_) | Expr::Test(_) => {
                 self.visit_expr(ctx, buf, expr)?;
             }
             Expr::Unary("!", expr) => {
@@ -160,6 +174,59 @@ impl<'a> Generator<'a, '_> {
         Ok(DisplayWrap::Unwrapped)
     }
 
+    fn visit_test(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        test: &Test<'a>,
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let Test {
+            name,
+            negated,
+            ref arguments,
+        } = *test;
+        let Some((value, args)) = arguments.split_first() else {
+            return Err(ctx.generate_error(format_args!("the `{name}` test needs a value"), node));
+        };
+
+        if negated {
+            buf.write('!');
+        }
+        if let Some(&(_, prefix, suffix)) = UNARY_TESTS.iter().find(|(test, ..)| *test == name) {
+            if !args.is_empty() {
+                return Err(ctx.generate_error(
+                    format_args!("the `{name}` test does not take any arguments"),
+                    node,
+                ));
+            }
+            buf.write(prefix);
+            self.visit_expr(ctx, buf, value)?;
+            buf.write(suffix);
+        } else if let Some(&(_, op)) = COMPARISON_TESTS
+            .iter()
+            .find(|(names, _)| names.contains(&name))
+        {
+            let [arg] = args else {
+                return Err(ctx.generate_error(
+                    format_args!(
+                        "the `{name}` test takes exactly one argument, e.g. `x is {name}(3)`"
+                    ),
+                    node,
+                ));
+            };
+            buf.write('(');
+            self.visit_binop(ctx, buf, op, value, arg)?;
+            buf.write(')');
+        } else {
+            return Err(ctx.generate_error(
+                format_args!("unknown test `{name}`, the supported tests are: {MsgIsTests}"),
+                node,
+            ));
+        }
+        Ok(DisplayWrap::Unwrapped)
+    }
+
     fn visit_as(
         &mut self,
         ctx: &Context<'_>,
@@ -185,7 +252,7 @@ impl<'a> Generator<'a, '_> {
             [] => unreachable!(),
             [expr] => self.visit_expr(ctx, buf, expr),
             exprs => {
-                let (l, r) = exprs.split_at((exprs.len() + 1) / 2);
+                let (l, r) = exprs.split_at(exprs.len().div_ceil(2));
                 buf.write("rinja::helpers::Concat(&(");
                 self.visit_concat(ctx, buf, l)?;
                 buf.write("), &(");
@@ -212,6 +279,32 @@ impl<'a> Generator<'a, '_> {
         self.visit_expr_not_first(ctx, buf, &cond.expr, display_wrap)
     }
 
+    fn visit_if_else(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        cond: &WithSpan<'_, Expr<'a>>,
+        then: &WithSpan<'_, Expr<'a>>,
+        otherwise: &WithSpan<'_, Expr<'a>>,
+    ) -> Result<DisplayWrap, CompileError> {
+        // Both branches must have the same type, so either both or neither get borrowed.
+        let borrow = !is_copyable(then) || !is_copyable(otherwise);
+        let (open, close) = if borrow { ("&(", ")") } else { ("(", ")") };
+
+        buf.write("(if ");
+        self.visit_condition(ctx, buf, cond)?;
+        buf.write(format_args!(" {{ {open}"));
+        let then_wrap = self.visit_expr(ctx, buf, then)?;
+        buf.write(format_args!("{close} }} else {{ {open}"));
+        let otherwise_wrap = self.visit_expr(ctx, buf, otherwise)?;
+        buf.write(format_args!("{close} }})"));
+
+        Ok(match (then_wrap, otherwise_wrap) {
+            (DisplayWrap::Wrapped, DisplayWrap::Wrapped) => DisplayWrap::Wrapped,
+            _ => DisplayWrap::Unwrapped,
+        })
+    }
+
     fn visit_try(
         &mut self,
         ctx: &Context<'_>,
@@ -241,25 +334,91 @@ impl<'a> Generator<'a, '_> {
         args: &[WithSpan<'_, Expr<'a>>],
         node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
+        let is_output_expr = mem::take(&mut self.is_output_expr);
+
+        // Filters of the configuration file take precedence over built-in filters.
+        if let Some(path) = self.input.config.filters.get(name) {
+            return self._visit_configured_filter(ctx, buf, path, args, node);
+        }
+
+        if !FILTERS_WITH_NAMED_ARGUMENTS.contains(&name) {
+            if let Some(arg) = args
+                .iter()
+                .find(|arg| matches!(***arg, Expr::NamedArgument(..)))
+            {
+                return Err(ctx.generate_error(
+                    format_args!("the `{name}` filter does not accept named arguments"),
+                    arg.span(),
+                ));
+            }
+        }
+
         let filter = match name {
+            "attr" => Self::_visit_attr_filter,
+            "center" => Self::_visit_center_filter,
+            "columns" => Self::_visit_columns_filter,
+            // Without the features, a `date` filter of the user is called like any custom filter.
+            "date" if cfg!(any(feature = "chrono", feature = "time")) => Self::_visit_date_filter,
             "deref" => Self::_visit_deref_filter,
+            "dictsort" => Self::_visit_dictsort_filter,
             "escape" | "e" => Self::_visit_escape_filter,
             "filesizeformat" => Self::_visit_humansize,
+            "first" | "last" => Self::_visit_first_last_filter,
+            "float" => Self::_visit_float_filter,
             "fmt" => Self::_visit_fmt_filter,
             "format" => Self::_visit_format_filter,
+            "group_by" => Self::_visit_group_by_filter,
+            "indent" => Self::_visit_indent_filter,
+            "int" => Self::_visit_int_filter,
+            "join" if is_output_expr => Self::_visit_join_output_filter,
             "join" => Self::_visit_join_filter,
             "json" | "tojson" => Self::_visit_json_filter,
-            "linebreaks" | "linebreaksbr" | "paragraphbreaks" => Self::_visit_linebreaks_filter,
+            "length" | "count" => Self::_visit_length_filter,
+            "linebreaks" | "linebreaksbr" | "nl2br" | "paragraphbreaks" => {
+                Self::_visit_linebreaks_filter
+            }
+            "map" => Self::_visit_map_filter,
+            "markdown" => Self::_visit_markdown_filter,
+            "min" | "max" => Self::_visit_min_max_filter,
             "pluralize" => Self::_visit_pluralize_filter,
+            "random" | "shuffle" => Self::_visit_random_filter,
             "ref" => Self::_visit_ref_filter,
+            "replace" => Self::_visit_replace_filter,
+            "reverse" => Self::_visit_reverse_filter,
+            "round" => Self::_visit_round_filter,
             "safe" => Self::_visit_safe_filter,
+            "select" | "reject" | "selectattr" | "rejectattr" => Self::_visit_select_filter,
+            "slice" => Self::_visit_slice_filter,
+            "sort" => Self::_visit_sort_filter,
+            "sum" => Self::_visit_sum_filter,
+            "trim" | "trim_start" | "trim_end" => Self::_visit_trim_filter,
+            "unique" => Self::_visit_unique_filter,
             "urlencode" | "urlencode_strict" => Self::_visit_urlencode,
+            "urlize" => Self::_visit_urlize_filter,
+            "xmlattr" => Self::_visit_xmlattr_filter,
             name if BUILTIN_FILTERS.contains(&name) => Self::_visit_builtin_filter,
             _ => Self::_visit_custom_filter,
         };
         filter(self, ctx, buf, name, args, node)
     }
 
+    /// Writes the `?` operator for the result of a filter call
+    ///
+    /// If `locate_filter_errors` is enabled in the configuration, errors are wrapped in
+    /// [`rinja::Error::Located`], so they tell where the filter call is found in the template.
+    fn write_filter_try(&self, ctx: &Context<'_>, buf: &mut Buffer, node: Span<'_>) {
+        let location = match self.input.config.locate_filter_errors {
+            true => ctx.location_of(node),
+            false => None,
+        };
+        match location {
+            Some((path, offset, row, column)) => buf.write(format_args!(
+                ".map_err(|err| rinja::helpers::located(err, {path:?}, {offset}, {row}, {column}))?",
+            )),
+            None => buf.write('?'),
+        }
+    }
+
     fn _visit_custom_filter(
         &mut self,
         ctx: &Context<'_>,
@@ -273,7 +432,32 @@ impl<'a> Generator<'a, '_> {
         }
         buf.write(format_args!("filters::{name}("));
         self._visit_args(ctx, buf, args)?;
-        buf.write(")?");
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_configured_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        path: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        if let Some(arg) = args
+            .iter()
+            .find(|arg| matches!(***arg, Expr::NamedArgument(..)))
+        {
+            return Err(ctx.generate_error(
+                format_args!("the filter `{path}` does not accept named arguments"),
+                arg.span(),
+            ));
+        }
+        buf.write(format_args!("{path}("));
+        self._visit_args(ctx, buf, args)?;
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
         Ok(DisplayWrap::Unwrapped)
     }
 
@@ -283,11 +467,12 @@ impl<'a> Generator<'a, '_> {
         buf: &mut Buffer,
         name: &str,
         args: &[WithSpan<'_, Expr<'a>>],
-        _node: Span<'_>,
+        node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
         buf.write(format_args!("rinja::filters::{name}("));
         self._visit_args(ctx, buf, args)?;
-        buf.write(")?");
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
         Ok(DisplayWrap::Unwrapped)
     }
 
@@ -311,7 +496,48 @@ impl<'a> Generator<'a, '_> {
             "rinja::filters::HtmlSafeOutput(rinja::filters::{name}(",
         ));
         self._visit_args(ctx, buf, args)?;
-        buf.write(")?)");
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
+        buf.write(')');
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_random_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        if cfg!(not(feature = "rand")) {
+            return Err(ctx.generate_error(
+                format_args!("the `{name}` filter requires the `rand` feature to be enabled"),
+                node,
+            ));
+        }
+
+        let Some((input, args)) = args.split_first() else {
+            return Err(
+                ctx.generate_error(format_args!("the `{name}` filter needs an input"), node)
+            );
+        };
+        let [seed] = collect_filter_args(ctx, name, args, ["seed"])?;
+        match seed {
+            Some(seed) => {
+                buf.write(format_args!("rinja::filters::{name}_seeded("));
+                self._visit_iterable_arg(ctx, buf, input)?;
+                buf.write(",(");
+                self.visit_expr(ctx, buf, seed)?;
+                buf.write(')');
+            }
+            None => {
+                buf.write(format_args!("rinja::filters::{name}("));
+                self._visit_iterable_arg(ctx, buf, input)?;
+            }
+        }
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
         Ok(DisplayWrap::Unwrapped)
     }
 
@@ -321,7 +547,7 @@ impl<'a> Generator<'a, '_> {
         buf: &mut Buffer,
         name: &str,
         args: &[WithSpan<'_, Expr<'a>>],
-        _node: Span<'_>,
+        node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
         // All filters return numbers, and any default formatted number is HTML safe.
         buf.write(format_args!(
@@ -329,7 +555,9 @@ impl<'a> Generator<'a, '_> {
                  rinja::helpers::get_primitive_value(&("
         ));
         self._visit_args(ctx, buf, args)?;
-        buf.write(")) as rinja::helpers::core::primitive::f32)?)");
+        buf.write(")) as rinja::helpers::core::primitive::f32)");
+        self.write_filter_try(ctx, buf, node);
+        buf.write(')');
         Ok(DisplayWrap::Unwrapped)
     }
 
@@ -372,7 +600,8 @@ impl<'a> Generator<'a, '_> {
                 buf.write(',');
                 self._visit_auto_escaped_arg(ctx, buf, value)?;
             }
-            buf.write(")?");
+            buf.write(')');
+            self.write_filter_try(ctx, buf, node);
         }
         Ok(DisplayWrap::Wrapped)
     }
@@ -397,11 +626,150 @@ impl<'a> Generator<'a, '_> {
         ));
         self._visit_args(ctx, buf, args)?;
         // The input is always HTML escaped, regardless of the selected escaper:
-        buf.write("), rinja::filters::Html)).rinja_auto_escape()?)?");
+        buf.write("), rinja::filters::Html)).rinja_auto_escape()?)");
+        self.write_filter_try(ctx, buf, node);
+        // The output is marked as HTML safe, not safe in all contexts:
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_urlize_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        ensure_filter_has_feature_alloc(ctx, name, node)?;
+        let Some((input, args)) = args.split_first() else {
+            return Err(ctx.generate_error("the `urlize` filter needs an input", node));
+        };
+        let [trim_url_limit, nofollow, target] =
+            collect_filter_args(ctx, name, args, ["trim_url_limit", "nofollow", "target"])?;
+
+        buf.write("rinja::filters::urlize(&(&&rinja::filters::AutoEscaper::new(&(");
+        self._visit_arg(ctx, buf, input)?;
+        // The input is always HTML escaped, regardless of the selected escaper:
+        buf.write("), rinja::filters::Html)).rinja_auto_escape()?,");
+        match trim_url_limit {
+            Some(trim_url_limit) => {
+                buf.write("rinja::helpers::core::option::Option::Some(");
+                self.visit_expr(ctx, buf, trim_url_limit)?;
+                buf.write(')');
+            }
+            None => buf.write("rinja::helpers::core::option::Option::None"),
+        }
+        for (arg, default) in [(nofollow, "false"), (target, "\"\"")] {
+            buf.write(',');
+            match arg {
+                Some(arg) => {
+                    buf.write('(');
+                    self.visit_expr(ctx, buf, arg)?;
+                    buf.write(')');
+                }
+                None => buf.write(default),
+            }
+        }
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
+        // The output is marked as HTML safe, not safe in all contexts:
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_xmlattr_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        ensure_filter_has_feature_alloc(ctx, name, node)?;
+        let [input] = args else {
+            return Err(ctx.generate_error(
+                format_args!("unexpected argument(s) in `{name}` filter"),
+                node,
+            ));
+        };
+        buf.write("rinja::filters::xmlattr(");
+        self._visit_iterable_arg(ctx, buf, input)?;
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
         // The output is marked as HTML safe, not safe in all contexts:
         Ok(DisplayWrap::Unwrapped)
     }
 
+    fn _visit_replace_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        ensure_filter_has_feature_alloc(ctx, name, node)?;
+        let Some((input, args)) = args.split_first() else {
+            return Err(ctx.generate_error("the `replace` filter needs an input", node));
+        };
+        let [from, to, count] = collect_filter_args(ctx, name, args, ["from", "to", "count"])?;
+        let (Some(from), Some(to)) = (from, to) else {
+            return Err(ctx.generate_error(
+                "the `replace` filter needs a `from` and a `to` argument",
+                node,
+            ));
+        };
+
+        buf.write(match count {
+            Some(_) => "rinja::filters::replacen(",
+            None => "rinja::filters::replace(",
+        });
+        self._visit_arg(ctx, buf, input)?;
+        buf.write(',');
+        self._visit_arg(ctx, buf, from)?;
+        buf.write(',');
+        self._visit_arg(ctx, buf, to)?;
+        if let Some(count) = count {
+            buf.write(",(");
+            self.visit_expr(ctx, buf, count)?;
+            buf.write(')');
+        }
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_reverse_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let [input] = args else {
+            return Err(
+                ctx.generate_error(format_args!("the `{name}` filter takes no arguments"), node)
+            );
+        };
+        // Like with `|length`, only temporary values are consumed.
+        if is_temporary(input) {
+            buf.write("match (");
+        } else {
+            buf.write("match (&");
+        }
+        self.visit_expr(ctx, buf, input)?;
+        buf.write(
+            ") {\
+                __rinja_value => {\
+                    use rinja::helpers::ReverseOf as _;\
+                    (&&&&&rinja::helpers::ReverseMarker::of(&__rinja_value))\
+                        .rinja_reverse(__rinja_value)",
+        );
+        self.write_filter_try(ctx, buf, node);
+        buf.write("}}");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
     fn _visit_ref_filter(
         &mut self,
         ctx: &Context<'_>,
@@ -436,17 +804,79 @@ impl<'a> Generator<'a, '_> {
         Ok(DisplayWrap::Unwrapped)
     }
 
+    fn _visit_markdown_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        if cfg!(not(feature = "markdown")) {
+            return Err(ctx.generate_error(
+                format_args!("the `{name}` filter requires the `markdown` feature to be enabled"),
+                node,
+            ));
+        }
+        let Some((input, args)) = args.split_first() else {
+            return Err(ctx.generate_error("the `markdown` filter needs an input", node));
+        };
+        let [sanitize] = collect_filter_args(ctx, name, args, ["sanitize"])?;
+
+        buf.write("rinja::filters::markdown(");
+        self._visit_arg(ctx, buf, input)?;
+        buf.write(',');
+        match sanitize {
+            Some(sanitize) => {
+                buf.write('(');
+                self.visit_expr(ctx, buf, sanitize)?;
+                buf.write(')');
+            }
+            None => buf.write("false"),
+        }
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
+        // The output is marked as HTML safe, not safe in all contexts:
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_date_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let [input, pattern] = args else {
+            return Err(ctx.generate_error(
+                format_args!(
+                    "the `{name}` filter needs an input and a pattern, e.g. `date(\"%Y-%m-%d\")`"
+                ),
+                node,
+            ));
+        };
+
+        buf.write("rinja::filters::date(&(");
+        self.visit_expr(ctx, buf, input)?;
+        buf.write("),&(");
+        self.visit_expr(ctx, buf, pattern)?;
+        buf.write("))");
+        self.write_filter_try(ctx, buf, node);
+        Ok(DisplayWrap::Unwrapped)
+    }
+
     fn _visit_json_filter(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
-        _name: &str,
+        name: &str,
         args: &[WithSpan<'_, Expr<'a>>],
         node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
         if cfg!(not(feature = "serde_json")) {
             return Err(ctx.generate_error(
-                "the `json` filter requires the `serde_json` feature to be enabled",
+                format_args!("the `{name}` filter requires the `serde_json` feature to be enabled"),
                 node,
             ));
         }
@@ -454,13 +884,23 @@ impl<'a> Generator<'a, '_> {
         let filter = match args.len() {
             1 => "json",
             2 => "json_pretty",
-            _ => return Err(ctx.generate_error("unexpected argument(s) in `json` filter", node)),
+            _ => {
+                return Err(ctx.generate_error(
+                    format_args!("unexpected argument(s) in `{name}` filter"),
+                    node,
+                ));
+            }
         };
 
         buf.write(format_args!("rinja::filters::{filter}("));
         self._visit_args(ctx, buf, args)?;
-        buf.write(")?");
-        Ok(DisplayWrap::Unwrapped)
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
+        // Like in Jinja, the output of `tojson` is marked as safe.
+        Ok(match name {
+            "tojson" => DisplayWrap::Wrapped,
+            _ => DisplayWrap::Unwrapped,
+        })
     }
 
     fn _visit_safe_filter(
@@ -476,7 +916,8 @@ impl<'a> Generator<'a, '_> {
         }
         buf.write("rinja::filters::safe(");
         self._visit_args(ctx, buf, args)?;
-        buf.write(format_args!(", {})?", self.input.escaper));
+        buf.write(format_args!(", {})", self.escaper));
+        self.write_filter_try(ctx, buf, node);
         Ok(DisplayWrap::Wrapped)
     }
 
@@ -537,7 +978,8 @@ impl<'a> Generator<'a, '_> {
         };
         buf.write("rinja::filters::escape(");
         self._visit_args(ctx, buf, &args[..1])?;
-        buf.write(format_args!(", {escaper})?"));
+        buf.write(format_args!(", {escaper})"));
+        self.write_filter_try(ctx, buf, node);
         Ok(DisplayWrap::Wrapped)
     }
 
@@ -592,58 +1034,1005 @@ impl<'a> Generator<'a, '_> {
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
-        _name: &str,
+        name: &str,
         args: &[WithSpan<'_, Expr<'a>>],
-        _node: Span<'_>,
+        node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
-        buf.write("rinja::filters::join((&");
-        for (i, arg) in args.iter().enumerate() {
-            if i > 0 {
-                buf.write(", &");
-            }
-            self.visit_expr(ctx, buf, arg)?;
-            if i == 0 {
-                buf.write(").into_iter()");
-            }
-        }
-        buf.write(")?");
-        Ok(DisplayWrap::Unwrapped)
+        self._visit_join(ctx, buf, name, args, node, false)
     }
 
-    fn _visit_args(
+    /// `join` as the last filter of a `{{ … }}` expression, so its result is written as is.
+    fn _visit_join_output_filter(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
+        name: &str,
         args: &[WithSpan<'_, Expr<'a>>],
-    ) -> Result<(), CompileError> {
-        for (i, arg) in args.iter().enumerate() {
-            if i > 0 {
-                buf.write(',');
-            }
-            self._visit_arg(ctx, buf, arg)?;
-        }
-        Ok(())
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self._visit_join(ctx, buf, name, args, node, true)
     }
 
-    fn _visit_arg(
+    fn _visit_join(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
-        arg: &WithSpan<'_, Expr<'a>>,
-    ) -> Result<(), CompileError> {
-        self._visit_arg_inner(ctx, buf, arg, false)
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+        is_output_expr: bool,
+    ) -> Result<DisplayWrap, CompileError> {
+        let Some((input, args)) = args.split_first() else {
+            return Err(ctx.generate_error("the `join` filter needs an input", node));
+        };
+        let [separator, attr] = collect_filter_args(ctx, name, args, ["separator", "attr"])?;
+
+        // If the output of `{{ … }}` gets escaped, then only the elements and non-literal
+        // separators are escaped. If another filter is applied to the result, then the whole
+        // string is escaped later on, so the elements must not be escaped twice.
+        let escaper = self.escaper;
+        let escaped = is_output_expr && escaper != "rinja::filters::Text";
+        if escaped {
+            buf.write("rinja::filters::join_escaped(");
+        } else {
+            buf.write("rinja::filters::join(");
+        }
+        match attr {
+            Some(attr) => {
+                let (accessor, is_call) = attr_path_accessor(ctx, name, attr)?;
+                buf.write("rinja::helpers::core::iter::Iterator::map(");
+                self._visit_iterable_arg(ctx, buf, input)?;
+                buf.write(format_args!(
+                    ", |__rinja_item| {}__rinja_item{accessor})",
+                    if is_call { "" } else { "&" },
+                ));
+            }
+            None => self._visit_iterable_arg(ctx, buf, input)?,
+        }
+        match separator {
+            Some(separator) if escaped && !matches!(**separator, Expr::StrLit(_)) => {
+                buf.write(", &rinja::filters::escape(&(");
+                self.visit_expr(ctx, buf, separator)?;
+                buf.write(format_args!("), {escaper})?"));
+            }
+            Some(separator) => {
+                buf.write(", &");
+                self.visit_expr(ctx, buf, separator)?;
+            }
+            None => buf.write(", \"\""),
+        }
+        if escaped {
+            buf.write(format_args!(", {escaper})"));
+        } else {
+            buf.write(')');
+        }
+        self.write_filter_try(ctx, buf, node);
+        if escaped {
+            Ok(DisplayWrap::Wrapped)
+        } else {
+            Ok(DisplayWrap::Unwrapped)
+        }
     }
 
-    fn _visit_arg_inner(
+    fn _visit_dictsort_filter(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
-        arg: &WithSpan<'_, Expr<'a>>,
-        // This parameter is needed because even though Expr::Unary is not copyable, we might still
-        // be able to skip a few levels.
-        need_borrow: bool,
-    ) -> Result<(), CompileError> {
-        if let Expr::Unary(expr @ ("*" | "&"), ref arg) = **arg {
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        ensure_filter_has_feature_alloc(ctx, name, node)?;
+        let Some((input, args)) = args.split_first() else {
+            return Err(ctx.generate_error("the `dictsort` filter needs an input", node));
+        };
+        let [by, reverse] = collect_filter_args(ctx, name, args, ["by", "reverse"])?;
+
+        let func = match by.map(|by| &**by) {
+            None => "dictsort",
+            Some(Expr::StrLit(StrLit {
+                prefix: None,
+                content: "key",
+            })) => "dictsort",
+            Some(Expr::StrLit(StrLit {
+                prefix: None,
+                content: "value",
+            })) => "dictsort_by_value",
+            Some(_) => {
+                return Err(ctx.generate_error(
+                    r#"the `by` argument of the `dictsort` filter must be "key" or "value""#,
+                    by.map_or(node, |by| by.span()),
+                ));
+            }
+        };
+        buf.write(format_args!("rinja::filters::{func}("));
+        self._visit_iterable_arg(ctx, buf, input)?;
+        buf.write(',');
+        match reverse {
+            Some(reverse) => {
+                buf.write('(');
+                self.visit_expr(ctx, buf, reverse)?;
+                buf.write(')');
+            }
+            None => buf.write("false"),
+        }
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_round_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let Some((input, args)) = args.split_first() else {
+            return Err(ctx.generate_error("the `round` filter needs an input", node));
+        };
+        let [precision, method] = collect_filter_args(ctx, name, args, ["precision", "method"])?;
+
+        let method = match method.map(|method| &**method) {
+            None => "Common",
+            Some(Expr::StrLit(StrLit {
+                prefix: None,
+                content: "common",
+            })) => "Common",
+            Some(Expr::StrLit(StrLit {
+                prefix: None,
+                content: "ceil",
+            })) => "Ceil",
+            Some(Expr::StrLit(StrLit {
+                prefix: None,
+                content: "floor",
+            })) => "Floor",
+            Some(_) => {
+                return Err(ctx.generate_error(
+                    r#"the `method` argument of the `round` filter must be "common", "ceil" or "floor""#,
+                    method.map_or(node, |method| method.span()),
+                ));
+            }
+        };
+        buf.write("rinja::filters::round(");
+        self._visit_arg(ctx, buf, input)?;
+        buf.write(',');
+        match precision {
+            Some(precision) => {
+                buf.write('(');
+                self.visit_expr(ctx, buf, precision)?;
+                buf.write(')');
+            }
+            None => buf.write('0'),
+        }
+        buf.write(format_args!(", rinja::filters::RoundMethod::{method})"));
+        self.write_filter_try(ctx, buf, node);
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_int_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let Some((input, args)) = args.split_first() else {
+            return Err(ctx.generate_error("the `int` filter needs an input", node));
+        };
+        let [default, base] = collect_filter_args(ctx, name, args, ["default", "base"])?;
+
+        buf.write("rinja::filters::int(");
+        self._visit_arg(ctx, buf, input)?;
+        buf.write(',');
+        match default {
+            Some(default) => {
+                buf.write("i64::from(");
+                self.visit_expr(ctx, buf, default)?;
+                buf.write(')');
+            }
+            None => buf.write('0'),
+        }
+        buf.write(',');
+        match base {
+            Some(base) => {
+                buf.write('(');
+                self.visit_expr(ctx, buf, base)?;
+                buf.write(')');
+            }
+            None => buf.write("10"),
+        }
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_float_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let Some((input, args)) = args.split_first() else {
+            return Err(ctx.generate_error("the `float` filter needs an input", node));
+        };
+        let [default] = collect_filter_args(ctx, name, args, ["default"])?;
+
+        buf.write("rinja::filters::float(");
+        self._visit_arg(ctx, buf, input)?;
+        buf.write(',');
+        match default {
+            Some(default) => {
+                buf.write("f64::from(");
+                self.visit_expr(ctx, buf, default)?;
+                buf.write(')');
+            }
+            None => buf.write("0.0"),
+        }
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_slice_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        ensure_filter_has_feature_alloc(ctx, name, node)?;
+        let Some((input, args)) = args.split_first() else {
+            return Err(ctx.generate_error("the `slice` filter needs an input", node));
+        };
+        let [start, stop, step] = collect_filter_args(ctx, name, args, ["start", "stop", "step"])?;
+        if let Some(step) = step {
+            if let Expr::NumLit("0", _) = **step {
+                return Err(ctx.generate_error(
+                    "the `step` of the `slice` filter must not be zero",
+                    step.span(),
+                ));
+            }
+        }
+
+        buf.write("rinja::filters::slice(");
+        self._visit_iterable_arg(ctx, buf, input)?;
+        for arg in [start, stop] {
+            buf.write(',');
+            match arg {
+                Some(arg) => {
+                    buf.write("rinja::helpers::core::option::Option::Some(");
+                    self.visit_expr(ctx, buf, arg)?;
+                    buf.write(')');
+                }
+                None => buf.write("rinja::helpers::core::option::Option::None"),
+            }
+        }
+        buf.write(',');
+        match step {
+            Some(step) => {
+                buf.write('(');
+                self.visit_expr(ctx, buf, step)?;
+                buf.write(')');
+            }
+            None => buf.write('1'),
+        }
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_columns_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        ensure_filter_has_feature_alloc(ctx, name, node)?;
+        let Some((input, args)) = args.split_first() else {
+            return Err(ctx.generate_error("the `columns` filter needs an input", node));
+        };
+        let [Some(n)] = collect_filter_args(ctx, name, args, ["n"])? else {
+            return Err(ctx.generate_error(
+                "the `columns` filter needs the number of columns as argument",
+                node,
+            ));
+        };
+        if let Expr::NumLit("0", _) = **n {
+            return Err(ctx.generate_error(
+                "the number of columns of the `columns` filter must not be zero",
+                n.span(),
+            ));
+        }
+
+        buf.write("rinja::filters::columns(");
+        self._visit_iterable_arg(ctx, buf, input)?;
+        buf.write(",(");
+        self.visit_expr(ctx, buf, n)?;
+        buf.write("))");
+        self.write_filter_try(ctx, buf, node);
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_first_last_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let [input] = args else {
+            return Err(
+                ctx.generate_error(format_args!("the `{name}` filter takes no arguments"), node)
+            );
+        };
+        if name == "first" {
+            buf.write("rinja::filters::first(");
+            self._visit_iterable_arg(ctx, buf, input)?;
+            buf.write(')');
+            self.write_filter_try(ctx, buf, node);
+        } else {
+            // Double-ended iterators, e.g. of slices, don't need to be consumed completely.
+            buf.write("match ");
+            self._visit_iterable_arg(ctx, buf, input)?;
+            buf.write(
+                "{\
+                    __rinja_iter => {\
+                        use rinja::helpers::LastItem as _;\
+                        (&&rinja::helpers::LastMarker::of(&__rinja_iter))\
+                            .rinja_last(__rinja_iter)",
+            );
+            self.write_filter_try(ctx, buf, node);
+            buf.write("}}");
+        }
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_length_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let [input] = args else {
+            return Err(
+                ctx.generate_error(format_args!("the `{name}` filter takes no arguments"), node)
+            );
+        };
+        // Borrowed values are never consumed, only temporary iterators are counted.
+        if is_temporary(input) {
+            buf.write("match (");
+        } else {
+            buf.write("match (&");
+        }
+        self.visit_expr(ctx, buf, input)?;
+        buf.write(
+            ") {\
+                __rinja_value => {\
+                    use rinja::helpers::LengthOf as _;\
+                    (&&&rinja::helpers::LengthMarker::of(&__rinja_value))\
+                        .rinja_length(__rinja_value)\
+                }\
+            }",
+        );
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_indent_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        ensure_filter_has_feature_alloc(ctx, name, node)?;
+        let Some((input, args)) = args.split_first() else {
+            return Err(ctx.generate_error("the `indent` filter needs an input", node));
+        };
+        let [width, first, blank] =
+            collect_filter_args(ctx, name, args, ["width", "first", "blank"])?;
+
+        let args = if first.is_none() && blank.is_none() {
+            buf.write("rinja::filters::indent(");
+            &[(width, "4")][..]
+        } else {
+            buf.write("rinja::filters::indent_with(");
+            &[(width, "4"), (first, "false"), (blank, "true")][..]
+        };
+        self._visit_arg(ctx, buf, input)?;
+        for &(arg, default) in args {
+            buf.write(',');
+            match arg {
+                Some(arg) => {
+                    buf.write('(');
+                    self.visit_expr(ctx, buf, arg)?;
+                    buf.write(')');
+                }
+                None => buf.write(default),
+            }
+        }
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_attr_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        ensure_filter_has_feature_alloc(ctx, name, node)?;
+        let [input, attr] = args else {
+            return Err(ctx.generate_error(
+                "the `attr` filter takes exactly one argument: the name of the attribute",
+                node,
+            ));
+        };
+        buf.write("rinja::filters::attr(");
+        self._visit_arg(ctx, buf, input)?;
+        buf.write(',');
+        self._visit_arg(ctx, buf, attr)?;
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_center_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let Some((input, args)) = args.split_first() else {
+            return Err(ctx.generate_error("the `center` filter needs an input", node));
+        };
+        let [width, fill] = collect_filter_args(ctx, name, args, ["width", "fill"])?;
+        let Some(width) = width else {
+            return Err(ctx.generate_error("the `center` filter needs a `width` argument", node));
+        };
+        if let Some(fill) = fill {
+            if let Expr::StrLit(StrLit {
+                prefix: None,
+                content,
+            }) = **fill
+            {
+                if !content.contains('\\') && content.chars().count() != 1 {
+                    return Err(ctx.generate_error(
+                        "the `fill` argument of the `center` filter must be exactly one character",
+                        fill.span(),
+                    ));
+                }
+            }
+        }
+
+        match fill {
+            Some(fill) => {
+                buf.write("rinja::filters::center_fill(");
+                self._visit_arg(ctx, buf, input)?;
+                buf.write(",(");
+                self.visit_expr(ctx, buf, width)?;
+                buf.write("),");
+                self._visit_arg(ctx, buf, fill)?;
+            }
+            None => {
+                buf.write("rinja::filters::center(");
+                self._visit_arg(ctx, buf, input)?;
+                buf.write(",(");
+                self.visit_expr(ctx, buf, width)?;
+                buf.write(')');
+            }
+        }
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_trim_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        ensure_filter_has_feature_alloc(ctx, name, node)?;
+        let Some((input, args)) = args.split_first() else {
+            return Err(
+                ctx.generate_error(format_args!("the `{name}` filter needs an input"), node)
+            );
+        };
+        let [chars] = collect_filter_args(ctx, name, args, ["chars"])?;
+
+        match chars {
+            Some(chars) => {
+                buf.write(format_args!("rinja::filters::{name}_chars("));
+                self._visit_arg(ctx, buf, input)?;
+                buf.write(',');
+                self._visit_arg(ctx, buf, chars)?;
+            }
+            None => {
+                buf.write(format_args!("rinja::filters::{name}("));
+                self._visit_arg(ctx, buf, input)?;
+            }
+        }
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_map_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let (input, kind, value) = match args {
+            [input, arg] => match **arg {
+                Expr::NamedArgument(kind @ ("attr" | "filter"), ref value) => (input, kind, value),
+                _ => {
+                    return Err(ctx.generate_error(
+                        r#"use filter map like `items|map(attr="field")` or `items|map(filter="name")`"#,
+                        arg.span(),
+                    ));
+                }
+            },
+            _ => {
+                return Err(ctx.generate_error(
+                    r#"use filter map like `items|map(attr="field")` or `items|map(filter="name")`"#,
+                    node,
+                ));
+            }
+        };
+
+        if kind == "attr" {
+            let (accessor, is_call) = attr_path_accessor(ctx, name, value)?;
+            buf.write("rinja::helpers::core::iter::Iterator::map(");
+            self._visit_iterable_arg(ctx, buf, input)?;
+            buf.write(format_args!(
+                ", |__rinja_item| {}__rinja_item{accessor})",
+                if is_call { "" } else { "&" },
+            ));
+            return Ok(DisplayWrap::Unwrapped);
+        }
+
+        // Filters can fail, so the mapped values are collected eagerly. This way errors can be
+        // propagated without having to defer them until the values get rendered.
+        ensure_filter_has_feature_alloc(ctx, name, node)?;
+        let filter = match ***value {
+            Expr::StrLit(StrLit {
+                prefix: None,
+                content,
+            }) if !content.is_empty() => content,
+            _ => {
+                return Err(ctx.generate_error(
+                    "the `filter` argument of the `map` filter must be the name of a filter",
+                    value.span(),
+                ));
+            }
+        };
+
+        buf.write(
+            "{\
+                let mut __rinja_mapped = rinja::helpers::alloc::vec::Vec::new();\
+                for __rinja_item in ",
+        );
+        self._visit_iterable_arg(ctx, buf, input)?;
+        buf.write('{');
+        let mut filter_buf = Buffer::new();
+        let display_wrap = self.push_locals(|this| {
+            this.locals
+                .insert(Cow::Borrowed("__rinja_item"), LocalMeta::initialized());
+            this.visit_filter(
+                ctx,
+                &mut filter_buf,
+                filter,
+                &[WithSpan::new_without_span(Expr::Var("__rinja_item"))],
+                value.span(),
+            )
+        })?;
+        let wrap = match display_wrap {
+            DisplayWrap::Wrapped => "rinja::filters::Safe",
+            DisplayWrap::Unwrapped => "",
+        };
+        buf.write(format_args!(
+            "__rinja_mapped.push({wrap}(rinja::helpers::alloc::string::ToString::to_string(\
+                &({filter_buf})\
+            )));\
+            }}\
+            __rinja_mapped.into_iter()\
+            }}",
+        ));
+        Ok(display_wrap)
+    }
+
+    fn _visit_min_max_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let Some((input, args)) = args.split_first() else {
+            return Err(
+                ctx.generate_error(format_args!("the `{name}` filter needs an input"), node)
+            );
+        };
+        let [attr] = collect_filter_args(ctx, name, args, ["attr"])?;
+
+        match attr {
+            Some(attr) => {
+                let (accessor, is_call) = attr_path_accessor(ctx, name, attr)?;
+                buf.write(format_args!("rinja::filters::{name}_by_key("));
+                self._visit_iterable_arg(ctx, buf, input)?;
+                buf.write(format_args!(
+                    ", |__rinja_item| {}__rinja_item{accessor}",
+                    if is_call { "" } else { "&" },
+                ));
+            }
+            None => {
+                buf.write(format_args!("rinja::filters::{name}("));
+                self._visit_iterable_arg(ctx, buf, input)?;
+            }
+        }
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_sum_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let Some((input, args)) = args.split_first() else {
+            return Err(ctx.generate_error("the `sum` filter needs an input", node));
+        };
+        let [attr] = collect_filter_args(ctx, name, args, ["attr"])?;
+
+        buf.write("rinja::filters::sum(");
+        match attr {
+            Some(attr) => {
+                let (accessor, is_call) = attr_path_accessor(ctx, name, attr)?;
+                buf.write("rinja::helpers::core::iter::Iterator::map(");
+                self._visit_iterable_arg(ctx, buf, input)?;
+                buf.write(format_args!(
+                    ", |__rinja_item| {}__rinja_item{accessor})",
+                    if is_call { "" } else { "&" },
+                ));
+            }
+            None => self._visit_iterable_arg(ctx, buf, input)?,
+        }
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_group_by_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        ensure_filter_has_feature_alloc(ctx, name, node)?;
+        let (input, attr) = match args {
+            [input, arg] => match **arg {
+                Expr::NamedArgument("attr", ref attr) => (input, attr),
+                _ => {
+                    return Err(ctx.generate_error(
+                        r#"use filter group_by like `items|group_by(attr="field")`"#,
+                        arg.span(),
+                    ));
+                }
+            },
+            _ => {
+                return Err(ctx.generate_error(
+                    r#"use filter group_by like `items|group_by(attr="field")`"#,
+                    node,
+                ));
+            }
+        };
+        let (accessor, is_call) = attr_path_accessor(ctx, name, attr)?;
+
+        buf.write("rinja::helpers::GroupBy::new(");
+        self._visit_iterable_arg(ctx, buf, input)?;
+        // the key must not borrow from the item, because the items are moved into the groups
+        if is_call {
+            buf.write(format_args!(", |__rinja_item| __rinja_item{accessor})"));
+        } else {
+            buf.write(format_args!(
+                ", |__rinja_item| rinja::helpers::core::clone::Clone::clone(\
+                 &__rinja_item{accessor}))",
+            ));
+        }
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_unique_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        ensure_filter_has_feature_alloc(ctx, name, node)?;
+        let Some((input, args)) = args.split_first() else {
+            return Err(ctx.generate_error("the `unique` filter needs an input", node));
+        };
+        let [attr, case_sensitive] =
+            collect_filter_args(ctx, name, args, ["attr", "case_sensitive"])?;
+
+        match ItemKey::new(ctx, name, attr, case_sensitive)? {
+            Some(key) => {
+                buf.write("rinja::filters::unique_by(");
+                self._visit_iterable_arg(ctx, buf, input)?;
+                buf.write(',');
+                self._visit_item_key(ctx, buf, key)?;
+            }
+            None => {
+                buf.write("rinja::filters::unique(");
+                self._visit_iterable_arg(ctx, buf, input)?;
+            }
+        }
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn _visit_sort_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        ensure_filter_has_feature_alloc(ctx, name, node)?;
+        let Some((input, args)) = args.split_first() else {
+            return Err(ctx.generate_error("the `sort` filter needs an input", node));
+        };
+        let [attr, reverse, case_sensitive] =
+            collect_filter_args(ctx, name, args, ["attr", "reverse", "case_sensitive"])?;
+
+        match ItemKey::new(ctx, name, attr, case_sensitive)? {
+            Some(key) => {
+                buf.write("rinja::filters::sort_by_key(");
+                self._visit_iterable_arg(ctx, buf, input)?;
+                buf.write(',');
+                self._visit_item_key(ctx, buf, key)?;
+            }
+            None => {
+                buf.write("rinja::filters::sort(");
+                self._visit_iterable_arg(ctx, buf, input)?;
+            }
+        }
+        buf.write(',');
+        match reverse {
+            Some(reverse) => {
+                buf.write('(');
+                self.visit_expr(ctx, buf, reverse)?;
+                buf.write(')');
+            }
+            None => buf.write("false"),
+        }
+        buf.write(')');
+        self.write_filter_try(ctx, buf, node);
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    /// Writes the closure that returns the key by which the items are compared
+    fn _visit_item_key(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        key: ItemKey<'_, 'a>,
+    ) -> Result<(), CompileError> {
+        let ItemKey {
+            accessor,
+            is_call,
+            case_sensitive,
+        } = key;
+        let key = format!("{}__rinja_item{accessor}", if is_call { "" } else { "&" });
+
+        buf.write("|__rinja_item| ");
+        match case_sensitive {
+            Ok(true) => buf.write(key),
+            Ok(false) => buf.write(format_args!(
+                "rinja::helpers::alloc::string::ToString::to_string({key}).to_lowercase()"
+            )),
+            Err(case_sensitive) => {
+                buf.write(format_args!(
+                    "{{\
+                        let __rinja_key = rinja::helpers::alloc::string::ToString::to_string({key});\
+                        if ("
+                ));
+                self.visit_expr(ctx, buf, case_sensitive)?;
+                buf.write(") { __rinja_key } else { __rinja_key.to_lowercase() }}");
+            }
+        }
+        Ok(())
+    }
+
+    fn _visit_select_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'_, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let (input, attr, test) = match (name, args) {
+            ("select" | "reject", [input, arg]) => match **arg {
+                Expr::NamedArgument("attr", ref attr) => (input, &**attr, None),
+                _ => {
+                    return Err(ctx.generate_error(
+                        format_args!("use filter {name} like `items|{name}(attr=\"field\")`"),
+                        arg.span(),
+                    ));
+                }
+            },
+            ("selectattr" | "rejectattr", [input, attr]) => (input, attr, None),
+            ("selectattr" | "rejectattr", [input, attr, test]) => (input, attr, Some((test, None))),
+            ("selectattr" | "rejectattr", [input, attr, test, value]) => {
+                (input, attr, Some((test, Some(value))))
+            }
+            ("select" | "reject", _) => {
+                return Err(ctx.generate_error(
+                    format_args!("use filter {name} like `items|{name}(attr=\"field\")`"),
+                    node,
+                ));
+            }
+            _ => {
+                return Err(ctx.generate_error(
+                    format_args!(
+                        "use filter {name} like `items|{name}(\"field\")`, \
+                         `items|{name}(\"field\", \"none\")` or \
+                         `items|{name}(\"field\", \"eq\", value)`"
+                    ),
+                    node,
+                ));
+            }
+        };
+        let (accessor, _) = attr_path_accessor(ctx, name, attr)?;
+
+        buf.write("rinja::helpers::core::iter::Iterator::filter(");
+        self._visit_iterable_arg(ctx, buf, input)?;
+        buf.write(format_args!(
+            ", |__rinja_item| {}(",
+            if name.starts_with("reject") { "!" } else { "" },
+        ));
+        match test {
+            None => buf.write(format_args!(
+                "rinja::helpers::as_bool(&__rinja_item{accessor})"
+            )),
+            Some((test, value)) => {
+                let content = match **test {
+                    Expr::StrLit(StrLit {
+                        prefix: None,
+                        content,
+                    }) => content,
+                    _ => "",
+                };
+                let unary = UNARY_TESTS.iter().find(|(test, ..)| *test == content);
+                let comparison = COMPARISON_TESTS
+                    .iter()
+                    .find(|(names, _)| names.contains(&content));
+                match (value, unary, comparison) {
+                    (None, Some(&(_, prefix, suffix)), _) => {
+                        buf.write(format_args!("{prefix}__rinja_item{accessor}{suffix}"));
+                    }
+                    (Some(value), _, Some(&(_, op))) => {
+                        buf.write(format_args!("__rinja_item{accessor} {op} ("));
+                        self.visit_expr(ctx, buf, value)?;
+                        buf.write(')');
+                    }
+                    (None, None, Some(_)) => {
+                        return Err(ctx.generate_error(
+                            format_args!(
+                                "the test {content:?} needs a value, e.g. \
+                                 `items|{name}(\"field\", {content:?}, value)`"
+                            ),
+                            test.span(),
+                        ));
+                    }
+                    (Some(value), Some(_), None) => {
+                        return Err(ctx.generate_error(
+                            format_args!("the test {content:?} does not take a value"),
+                            value.span(),
+                        ));
+                    }
+                    (_, None, None) => {
+                        return Err(ctx.generate_error(
+                            format_args!(
+                                "unknown test in `{name}` filter, the supported tests are: {}",
+                                MsgComparisonTests,
+                            ),
+                            test.span(),
+                        ));
+                    }
+                }
+            }
+        }
+        buf.write("))");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    /// Writes `arg` as an iterator. Temporaries, i.e. outputs of filters, function calls and ranges,
+    /// are consumed, everything else is borrowed to prevent an attempt of moving.
+    fn _visit_iterable_arg(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        arg: &WithSpan<'_, Expr<'a>>,
+    ) -> Result<(), CompileError> {
+        if is_temporary(arg) {
+            buf.write('(');
+        } else {
+            buf.write("(&");
+        }
+        self.visit_expr(ctx, buf, arg)?;
+        buf.write(").into_iter()");
+        Ok(())
+    }
+
+    fn _visit_args(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'_, Expr<'a>>],
+    ) -> Result<(), CompileError> {
+        for (i, arg) in args.iter().enumerate() {
+            if i > 0 {
+                buf.write(',');
+            }
+            self._visit_arg(ctx, buf, arg)?;
+        }
+        Ok(())
+    }
+
+    fn _visit_arg(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        arg: &WithSpan<'_, Expr<'a>>,
+    ) -> Result<(), CompileError> {
+        self._visit_arg_inner(ctx, buf, arg, false)
+    }
+
+    fn _visit_arg_inner(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        arg: &WithSpan<'_, Expr<'a>>,
+        // This parameter is needed because even though Expr::Unary is not copyable, we might still
+        // be able to skip a few levels.
+        need_borrow: bool,
+    ) -> Result<(), CompileError> {
+        if let Expr::Unary(expr @ ("*" | "&"), ref arg) = **arg {
             buf.write(expr);
             return self._visit_arg_inner(ctx, buf, arg, true);
         }
@@ -673,7 +2062,7 @@ impl<'a> Generator<'a, '_> {
         buf: &mut Buffer,
         arg: &WithSpan<'_, Expr<'a>>,
     ) -> Result<(), CompileError> {
-        if let Some(Writable::Lit(arg)) = compile_time_escape(arg, self.input.escaper) {
+        if let Some(Writable::Lit(arg)) = compile_time_escape(arg, self.escaper) {
             if !arg.is_empty() {
                 buf.write("rinja::filters::Safe(");
                 buf.write_escaped_str(&arg);
@@ -684,10 +2073,7 @@ impl<'a> Generator<'a, '_> {
         } else {
             buf.write("(&&rinja::filters::AutoEscaper::new(");
             self._visit_arg(ctx, buf, arg)?;
-            buf.write(format_args!(
-                ", {})).rinja_auto_escape()?",
-                self.input.escaper
-            ));
+            buf.write(format_args!(", {})).rinja_auto_escape()?", self.escaper));
         }
         Ok(())
     }
@@ -713,9 +2099,27 @@ impl<'a> Generator<'a, '_> {
                 } else if attr == "last" {
                     buf.write("_loop_item.last");
                     return Ok(DisplayWrap::Unwrapped);
+                } else if attr == "length" {
+                    self.uses_loop_length = true;
+                    buf.write("_loop_length");
+                    return Ok(DisplayWrap::Unwrapped);
+                } else if attr == "previtem" {
+                    self.uses_loop_neighbors = true;
+                    buf.write("_loop_neighbors.prev.as_ref()");
+                    return Ok(DisplayWrap::Unwrapped);
+                } else if attr == "nextitem" {
+                    self.uses_loop_neighbors = true;
+                    buf.write("_loop_neighbors.next.as_ref()");
+                    return Ok(DisplayWrap::Unwrapped);
                 } else {
                     return Err(ctx.generate_error("unknown loop variable", obj.span()));
                 }
+            } else if name == "values" && !self.is_var_defined(name) {
+                self.uses_values = true;
+                buf.write(format_args!(
+                    "rinja::helpers::get_value(__rinja_values, {attr:?})?",
+                ));
+                return Ok(DisplayWrap::Unwrapped);
             }
         }
         self.visit_expr(ctx, buf, obj)?;
@@ -785,12 +2189,31 @@ impl<'a> Generator<'a, '_> {
                     );
                 }
             },
+            Expr::Var("super") => {
+                return Err(ctx.generate_error(
+                    "`super()` cannot be used inside of an expression, only as `{{ super() }}`",
+                    left.span(),
+                ));
+            }
+            Expr::Var("caller") if !self.is_var_defined("caller") => {
+                if !self.has_caller {
+                    return Err(ctx.generate_error(
+                        "`caller()` can only be used in a macro that is invoked with \
+                         `{% call %}...{% endcall %}`",
+                        left.span(),
+                    ));
+                }
+                if !args.is_empty() {
+                    return Err(
+                        ctx.generate_error("`caller()` does not take any arguments", left.span())
+                    );
+                }
+                buf.write("rinja::helpers::Caller(&__rinja_caller)");
+                return Ok(DisplayWrap::Wrapped);
+            }
             sub_left => {
                 match sub_left {
-                    Expr::Var(name) => match self.locals.resolve(name) {
-                        Some(resolved) => buf.write(resolved),
-                        None => buf.write(format_args!("self.{}", normalize_identifier(name))),
-                    },
+                    Expr::Var(name) => buf.write(self.resolve_var(ctx, name, left.span())?),
                     _ => {
                         self.visit_expr(ctx, buf, left)?;
                     }
@@ -914,14 +2337,26 @@ impl<'a> Generator<'a, '_> {
         DisplayWrap::Unwrapped
     }
 
-    fn visit_var(&mut self, buf: &mut Buffer, s: &str) -> DisplayWrap {
+    pub(super) fn visit_var(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        s: &str,
+        span: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
         if s == "self" {
+            if self.isolated {
+                return Err(ctx.generate_error(
+                    "`self` is not accessible in a template that was included with `only`",
+                    span,
+                ));
+            }
             buf.write(s);
-            return DisplayWrap::Unwrapped;
+            return Ok(DisplayWrap::Unwrapped);
         }
 
-        buf.write(normalize_identifier(&self.locals.resolve_or_self(s)));
-        DisplayWrap::Unwrapped
+        buf.write(normalize_identifier(&self.resolve_var(ctx, s, span)?));
+        Ok(DisplayWrap::Unwrapped)
     }
 
     fn visit_filter_source(&mut self, buf: &mut Buffer) -> DisplayWrap {
@@ -1065,6 +2500,230 @@ impl<'a> Generator<'a, '_> {
     }
 }
 
+/// The tests that can be used in `selectattr` and `rejectattr`, and the operator they map to.
+const COMPARISON_TESTS: &[(&[&str], &str)] = &[
+    (&["eq", "==", "equalto"], "=="),
+    (&["ne", "!="], "!="),
+    (&["lt", "<", "lessthan"], "<"),
+    (&["le", "<="], "<="),
+    (&["gt", ">", "greaterthan"], ">"),
+    (&["ge", ">="], ">="),
+];
+
+/// Tests without arguments, e.g. `x is none`, with the code to write before and after the value.
+const UNARY_TESTS: &[(&str, &str, &str)] = &[
+    (
+        "none",
+        "rinja::helpers::core::option::Option::is_none(&(",
+        "))",
+    ),
+    (
+        "string",
+        "{ use rinja::helpers::IsString as _; (&&rinja::helpers::TypeTestMarker::of(&(",
+        "))).rinja_is_string() }",
+    ),
+    (
+        "number",
+        "{ use rinja::helpers::IsNumber as _; (&&rinja::helpers::TypeTestMarker::of(&(",
+        "))).rinja_is_number() }",
+    ),
+    (
+        "iterable",
+        "{ use rinja::helpers::IsIterable as _; (&&rinja::helpers::TypeTestMarker::of(&(",
+        "))).rinja_is_iterable() }",
+    ),
+];
+
+struct MsgComparisonTests;
+
+impl fmt::Display for MsgComparisonTests {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (test, ..) in UNARY_TESTS {
+            write!(f, "{test:?}, ")?;
+        }
+        for (i, (names, _)) in COMPARISON_TESTS.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            for (j, name) in names.iter().enumerate() {
+                if j > 0 {
+                    f.write_str(" / ")?;
+                }
+                write!(f, "{name:?}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct MsgIsTests;
+
+impl fmt::Display for MsgIsTests {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("`defined`")?;
+        for (test, ..) in UNARY_TESTS {
+            write!(f, ", `{test}`")?;
+        }
+        for (names, _) in COMPARISON_TESTS {
+            for name in *names {
+                if name.starts_with(char::is_alphabetic) {
+                    write!(f, ", `{name}`")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Assigns the arguments of a filter call (without its input) to the parameters `params`.
+///
+/// Arguments can be given positionally or by name. The parser already made sure that named
+/// arguments come last. Parameters without a matching argument are `None`.
+fn collect_filter_args<'b, 's, 'a, const N: usize>(
+    ctx: &Context<'_>,
+    filter: &str,
+    args: &'b [WithSpan<'s, Expr<'a>>],
+    params: [&str; N],
+) -> Result<[Option<&'b WithSpan<'s, Expr<'a>>>; N], CompileError>
+where
+    'a: 's,
+{
+    let mut values = [None; N];
+    for (pos, arg) in args.iter().enumerate() {
+        let (idx, value) = match **arg {
+            Expr::NamedArgument(name, ref value) => {
+                let Some(idx) = params.iter().position(|&p| p == name) else {
+                    return Err(ctx.generate_error(
+                        format_args!("the `{filter}` filter has no argument named `{name}`"),
+                        arg.span(),
+                    ));
+                };
+                (idx, &**value)
+            }
+            _ if pos >= N => {
+                return Err(ctx.generate_error(
+                    format_args!(
+                        "the `{filter}` filter takes at most {N} argument{}",
+                        if N == 1 { "" } else { "s" },
+                    ),
+                    arg.span(),
+                ));
+            }
+            _ => (pos, arg),
+        };
+        if values[idx].replace(value).is_some() {
+            return Err(ctx.generate_error(
+                format_args!(
+                    "the `{filter}` filter got multiple values for argument `{}`",
+                    params[idx],
+                ),
+                arg.span(),
+            ));
+        }
+    }
+    Ok(values)
+}
+
+/// Converts the value of an `attr="field.method()"` argument into the accessor `.field.method()`.
+///
+/// The returned boolean is `true` if the last segment is a method call, i.e. if the accessed value
+/// is a temporary that cannot be borrowed.
+fn attr_path_accessor(
+    ctx: &Context<'_>,
+    filter: &str,
+    attr: &WithSpan<'_, Expr<'_>>,
+) -> Result<(String, bool), CompileError> {
+    let error = || {
+        ctx.generate_error(
+            format_args!(
+                "the `attr` argument of the `{filter}` filter must be a string literal containing \
+                 a (dotted) path of fields or method calls, like `\"address.city\"`"
+            ),
+            attr.span(),
+        )
+    };
+
+    let Expr::StrLit(StrLit {
+        prefix: None,
+        content,
+    }) = **attr
+    else {
+        return Err(error());
+    };
+
+    let mut accessor = String::with_capacity(content.len() + 1);
+    let mut is_call = false;
+    for segment in content.split('.') {
+        let (field, call) = match segment.strip_suffix("()") {
+            Some(field) => (field, true),
+            None => (segment, false),
+        };
+        let is_valid = match field.as_bytes() {
+            [] => false,
+            // tuple fields
+            [b'0'..=b'9', ..] => !call && field.bytes().all(|c| c.is_ascii_digit()),
+            _ => field
+                .bytes()
+                .all(|c| c.is_ascii_alphanumeric() || c == b'_'),
+        };
+        if !is_valid {
+            return Err(error());
+        }
+        accessor.push('.');
+        accessor.push_str(normalize_identifier(field));
+        if call {
+            accessor.push_str("()");
+        }
+        is_call = call;
+    }
+    Ok((accessor, is_call))
+}
+
+/// Whether the value of `expr` is a temporary, that can be consumed.
+fn is_temporary(expr: &Expr<'_>) -> bool {
+    match expr {
+        Expr::Filter(_) | Expr::Call(..) | Expr::Range(..) => true,
+        Expr::Group(inner) => is_temporary(inner),
+        _ => false,
+    }
+}
+
+/// The `attr` and `case_sensitive` arguments of the `sort` and `unique` filters
+struct ItemKey<'b, 'a> {
+    accessor: String,
+    is_call: bool,
+    /// A literal value, or the expression to evaluate at runtime
+    case_sensitive: Result<bool, &'b WithSpan<'b, Expr<'a>>>,
+}
+
+impl<'b, 'a> ItemKey<'b, 'a> {
+    /// Returns `None` if the items themselves are compared
+    fn new(
+        ctx: &Context<'_>,
+        name: &str,
+        attr: Option<&'b WithSpan<'b, Expr<'a>>>,
+        case_sensitive: Option<&'b WithSpan<'b, Expr<'a>>>,
+    ) -> Result<Option<Self>, CompileError> {
+        let case_sensitive = match case_sensitive {
+            Some(arg) => match **arg {
+                Expr::BoolLit(value) => Ok(value),
+                _ => Err(arg),
+            },
+            None => Ok(true),
+        };
+        let (accessor, is_call) = match attr {
+            Some(attr) => attr_path_accessor(ctx, name, attr)?,
+            None if case_sensitive == Ok(true) => return Ok(None),
+            None => (String::new(), false),
+        };
+        Ok(Some(Self {
+            accessor,
+            is_call,
+            case_sensitive,
+        }))
+    }
+}
+
 fn ensure_filter_has_feature_alloc(
     ctx: &Context<'_>,
     name: &str,
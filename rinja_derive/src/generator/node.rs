@@ -2,12 +2,14 @@ use std::borrow::Cow;
 use std::collections::hash_map::{Entry, HashMap};
 use std::fmt::Write;
 use std::mem;
+use std::path::Path;
+use std::sync::Arc;
 
 use parser::node::{
-    Call, Comment, Cond, CondTest, FilterBlock, If, Include, Let, Lit, Loop, Macro, Match,
-    Whitespace, Ws,
+    AutoEscape, Call, Comment, Cond, CondTest, FilterBlock, If, Include, IncludeAll, Let, Lit,
+    Loop, LoopControl, Macro, MacroArg, Match, SetBlock, Trans, Whitespace, With, Ws,
 };
-use parser::{Expr, Filter, Node, Span, Target, WithSpan};
+use parser::{Expr, Filter, Node, Span, Target, Test, WithSpan};
 use rustc_hash::FxBuildHasher;
 
 use super::{
@@ -36,7 +38,7 @@ impl<'a> Generator<'a, '_> {
         Ok(size_hint)
     }
 
-    fn push_locals<T, F>(&mut self, callback: F) -> Result<T, CompileError>
+    pub(super) fn push_locals<T, F>(&mut self, callback: F) -> Result<T, CompileError>
     where
         F: FnOnce(&mut Self) -> Result<T, CompileError>,
     {
@@ -68,10 +70,23 @@ impl<'a> Generator<'a, '_> {
             self.is_in_filter_block,
         );
         child.buf_writable = buf_writable;
+        child.uses_values = self.uses_values;
+        child.escaper = self.escaper;
+        child.uses_loop_neighbors = self.uses_loop_neighbors;
+        child.uses_loop_length = self.uses_loop_length;
+        child.used_loop_labels = mem::take(&mut self.used_loop_labels);
+        child.isolated = self.isolated;
+        child.has_caller = self.has_caller;
+        child.recursive_macros = self.recursive_macros.clone();
+        child.in_caller_body = self.in_caller_body;
         let res = callback(&mut child);
         Generator {
             locals: self.locals,
             buf_writable: self.buf_writable,
+            uses_values: self.uses_values,
+            uses_loop_neighbors: self.uses_loop_neighbors,
+            uses_loop_length: self.uses_loop_length,
+            used_loop_labels: self.used_loop_labels,
             ..
         } = child;
 
@@ -95,12 +110,21 @@ impl<'a> Generator<'a, '_> {
                 Node::Comment(ref comment) => {
                     self.write_comment(comment);
                 }
-                Node::Expr(ws, ref val) => {
-                    self.write_expr(ws, val);
-                }
+                Node::Expr(ws, ref val) => match **val {
+                    // `{{ super() }}` is the same as `{% call super() %}`
+                    Expr::Call(ref path, ref args)
+                        if args.is_empty() && ***path == Expr::Var("super") =>
+                    {
+                        size_hint += self.write_block(ctx, buf, None, ws, path.span())?;
+                    }
+                    _ => self.write_expr(ws, val),
+                },
                 Node::Let(ref l) => {
                     self.write_let(ctx, buf, l)?;
                 }
+                Node::SetBlock(ref set) => {
+                    self.write_set_block(ctx, buf, set)?;
+                }
                 Node::If(ref i) => {
                     size_hint += self.write_if(ctx, buf, i)?;
                 }
@@ -117,12 +141,24 @@ impl<'a> Generator<'a, '_> {
                 Node::Include(ref i) => {
                     size_hint += self.handle_include(ctx, buf, i)?;
                 }
+                Node::IncludeAll(ref i) => {
+                    size_hint += self.handle_include_all(ctx, buf, i)?;
+                }
                 Node::Call(ref call) => {
                     size_hint += self.write_call(ctx, buf, call)?;
                 }
                 Node::FilterBlock(ref filter) => {
                     size_hint += self.write_filter_block(ctx, buf, filter)?;
                 }
+                Node::AutoEscape(ref autoescape) => {
+                    size_hint += self.write_autoescape(ctx, buf, autoescape)?;
+                }
+                Node::With(ref with) => {
+                    size_hint += self.write_with(ctx, buf, with)?;
+                }
+                Node::Trans(ref trans) => {
+                    size_hint += self.write_trans(ctx, buf, trans)?;
+                }
                 Node::Macro(ref m) => {
                     if level != AstLevel::Top {
                         return Err(ctx.generate_error(
@@ -156,16 +192,17 @@ impl<'a> Generator<'a, '_> {
                     }
                     // No whitespace handling: child template top-level is not used,
                     // except for the blocks defined in it.
+                    if ctx.extends.is_none() {
+                        // The parent template is missing, and `allow_missing_templates` is
+                        // enabled, so the child template is rendered on its own.
+                        size_hint += self.write_missing_placeholder(e.path);
+                    }
                 }
-                Node::Break(ref ws) => {
-                    self.handle_ws(**ws);
-                    self.write_buf_writable(ctx, buf)?;
-                    buf.write("break;");
+                Node::Break(ref control) => {
+                    self.write_loop_control(ctx, buf, "break", control)?;
                 }
-                Node::Continue(ref ws) => {
-                    self.handle_ws(**ws);
-                    self.write_buf_writable(ctx, buf)?;
-                    buf.write("continue;");
+                Node::Continue(ref control) => {
+                    self.write_loop_control(ctx, buf, "continue", control)?;
                 }
             }
         }
@@ -198,6 +235,7 @@ impl<'a> Generator<'a, '_> {
             | Expr::Attr(_, _)
             | Expr::Index(_, _)
             | Expr::Filter(_)
+            | Expr::Test(_)
             | Expr::Range(_, _, _)
             | Expr::Call(_, _)
             | Expr::RustMacro(_, _)
@@ -207,7 +245,8 @@ impl<'a> Generator<'a, '_> {
             | Expr::FilterSource
             | Expr::As(_, _)
             | Expr::Concat(_)
-            | Expr::LetCond(_) => {
+            | Expr::LetCond(_)
+            | Expr::IfElse(_, _, _) => {
                 *only_contains_is_defined = false;
                 (EvaluatedResult::Unknown, WithSpan::new(expr, span))
             }
@@ -553,16 +592,54 @@ impl<'a> Generator<'a, '_> {
             }
 
             let size_hint1 = this.push_locals(|this| {
-                buf.write("for (");
-                this.visit_target(buf, true, true, &loop_block.var);
-                buf.write(", _loop_item) in rinja::helpers::TemplateLoop::new(_iter) {");
+                let mut target_buf = Buffer::new();
+                this.visit_target(&mut target_buf, true, true, &loop_block.var);
+
+                // The body is generated first, because the loop header depends on whether the
+                // body accesses `loop.previtem`, `loop.nextitem` or `loop.length`.
+                let mut body_buf = Buffer::new();
+                body_buf.set_discard(buf.is_discard());
+                let outer_uses_loop_neighbors = mem::replace(&mut this.uses_loop_neighbors, false);
+                let outer_uses_loop_length = mem::replace(&mut this.uses_loop_length, false);
+                let outer_in_caller_body = mem::replace(&mut this.in_caller_body, false);
+                let mut size_hint1 =
+                    this.handle(ctx, &loop_block.body, &mut body_buf, AstLevel::Nested)?;
+                this.handle_ws(loop_block.ws2);
+                size_hint1 += this.write_buf_writable(ctx, &mut body_buf)?;
+                this.in_caller_body = outer_in_caller_body;
+                let uses_loop_neighbors =
+                    mem::replace(&mut this.uses_loop_neighbors, outer_uses_loop_neighbors);
+                let uses_loop_length =
+                    mem::replace(&mut this.uses_loop_length, outer_uses_loop_length);
+
+                if uses_loop_length {
+                    buf.write(
+                        "let (_loop_length, _iter) = {\
+                            use rinja::helpers::LoopLengthKind as _;\
+                            (&&rinja::helpers::LoopLength::new(_iter)).rinja_loop_length()\
+                        };",
+                    );
+                }
 
+                if let Some(label) = loop_block.label {
+                    if let Some(index) = this.used_loop_labels.iter().position(|&l| l == label) {
+                        this.used_loop_labels.swap_remove(index);
+                        buf.write(format_args!("'__rinja_{label}: "));
+                    }
+                }
+                buf.write(format_args!("for ({target_buf}"));
+                if uses_loop_neighbors {
+                    buf.write(
+                        ", _loop_item, _loop_neighbors) in \
+                            rinja::helpers::TemplateLoopNeighbors::new(_iter) {",
+                    );
+                } else {
+                    buf.write(", _loop_item) in rinja::helpers::TemplateLoop::new(_iter) {");
+                }
                 if has_else_nodes {
                     buf.write("_did_loop = true;");
                 }
-                let mut size_hint1 = this.handle(ctx, &loop_block.body, buf, AstLevel::Nested)?;
-                this.handle_ws(loop_block.ws2);
-                size_hint1 += this.write_buf_writable(ctx, buf)?;
+                buf.write(body_buf.into_string());
                 Ok(size_hint1)
             })?;
             buf.write('}');
@@ -588,6 +665,33 @@ impl<'a> Generator<'a, '_> {
         })
     }
 
+    fn write_loop_control(
+        &mut self,
+        ctx: &Context<'a>,
+        buf: &mut Buffer,
+        kind: &str,
+        control: &'a WithSpan<'_, LoopControl<'_>>,
+    ) -> Result<(), CompileError> {
+        if self.in_caller_body {
+            return Err(ctx.generate_error(
+                format_args!("cannot `{kind}` out of a `{{% call %}}` block"),
+                control.span(),
+            ));
+        }
+        self.handle_ws(control.ws);
+        self.write_buf_writable(ctx, buf)?;
+        match control.label {
+            Some(label) => {
+                if !self.used_loop_labels.contains(&label) {
+                    self.used_loop_labels.push(label);
+                }
+                buf.write(format_args!("{kind} '__rinja_{label};"));
+            }
+            None => buf.write(format_args!("{kind};")),
+        }
+        Ok(())
+    }
+
     fn write_call(
         &mut self,
         ctx: &Context<'a>,
@@ -596,33 +700,44 @@ impl<'a> Generator<'a, '_> {
     ) -> Result<usize, CompileError> {
         let Call {
             ws,
-            scope,
             name,
-            ref args,
+            ref caller,
+            ws2,
+            ..
         } = **call;
         if name == "super" {
+            if caller.is_some() {
+                return Err(ctx.generate_error(
+                    "`super()` cannot be called with a `{% call %}...{% endcall %}` block",
+                    call.span(),
+                ));
+            }
             return self.write_block(ctx, buf, None, ws, call.span());
         }
 
-        let (def, own_ctx) = if let Some(s) = scope {
-            let path = ctx.imports.get(s).ok_or_else(|| {
-                ctx.generate_error(format_args!("no import found for scope {s:?}"), call.span())
-            })?;
-            let mctx = self.contexts.get(path).ok_or_else(|| {
-                ctx.generate_error(format_args!("context for {path:?} not found"), call.span())
-            })?;
-            let def = mctx.macros.get(name).ok_or_else(|| {
-                ctx.generate_error(
-                    format_args!("macro {name:?} not found in scope {s:?}"),
-                    call.span(),
-                )
-            })?;
-            (*def, mctx)
-        } else {
-            let def = ctx.macros.get(name).ok_or_else(|| {
-                ctx.generate_error(format_args!("macro {name:?} not found"), call.span())
-            })?;
-            (*def, ctx)
+        let (def, own_ctx) = find_macro(self.contexts, ctx, call)?;
+
+        self.flush_ws(ws); // Cannot handle_ws() here: whitespace from macro definition comes first
+
+        // A macro that calls itself, directly or through other macros, cannot be expanded inline.
+        if let Some(index) = self
+            .recursive_macros
+            .iter()
+            .position(|m| std::ptr::eq(*m, def))
+        {
+            return self.write_recursive_call(ctx, buf, call, def, index);
+        } else if calls_macro(self.contexts, own_ctx, &def.nodes, def, &mut Vec::new()) {
+            return self.write_recursive_macro(ctx, own_ctx, buf, call, def);
+        }
+
+        // The body of a `{% call %}...{% endcall %}` block is rendered by a closure, which is
+        // defined before the arguments of the macro, so it sees the variables of the call site.
+        let caller = match caller {
+            Some(nodes) => {
+                self.write_buf_writable(ctx, buf)?;
+                Some(self.write_caller(ctx, buf, nodes, ws, ws2)?)
+            }
+            None => None,
         };
 
         if self.seen_macros.iter().any(|(s, _)| std::ptr::eq(*s, def)) {
@@ -639,87 +754,40 @@ impl<'a> Generator<'a, '_> {
             self.seen_macros.push((def, ctx.file_info_of(call.span())));
         }
 
-        self.flush_ws(ws); // Cannot handle_ws() here: whitespace from macro definition comes first
         let size_hint = self.push_locals(|this| {
-            macro_call_ensure_arg_count(call, def, ctx)?;
-
             this.write_buf_writable(ctx, buf)?;
             buf.write('{');
+            let mut size_hint = 0;
+            if let Some((caller_buf, caller_size_hint)) = caller {
+                buf.write(caller_buf.into_string());
+                size_hint += caller_size_hint;
+            }
             this.prepare_ws(def.ws1);
 
-            let mut named_arguments: HashMap<&str, _, FxBuildHasher> = HashMap::default();
-            // Since named arguments can only be passed last, we only need to check if the last argument
-            // is a named one.
-            if let Some(Expr::NamedArgument(_, _)) = args.last().map(|expr| &**expr) {
-                // First we check that all named arguments actually exist in the called item.
-                for (index, arg) in args.iter().enumerate().rev() {
-                    let Expr::NamedArgument(arg_name, _) = &**arg else {
-                        break;
+            let mut value = Buffer::new();
+            let exprs = macro_call_args(ctx, call, def)?;
+            for (MacroArg { name: arg, ty, .. }, expr) in def.args.iter().zip(exprs) {
+                if let Some(ty) = ty {
+                    // A typed argument always becomes a new variable, so the compiler checks its
+                    // type. References are taken automatically, so e.g. a `String` can be passed
+                    // as `&str`.
+                    let (before, after) = match ty.starts_with('&') {
+                        true => ("&(", ")"),
+                        false => ("", ""),
                     };
-                    if !def.args.iter().any(|(arg, _)| arg == arg_name) {
-                        return Err(ctx.generate_error(
-                            format_args!("no argument named `{arg_name}` in macro {name:?}"),
-                            call.span(),
-                        ));
-                    }
-                    named_arguments.insert(arg_name, (index, arg));
+                    value.clear();
+                    value.write(this.visit_expr_root(ctx, expr)?);
+                    let arg = normalize_identifier(arg);
+                    buf.write(format_args!("let {arg}: {ty} = {before}{value}{after};"));
+                    this.locals.insert_with_default(Cow::Borrowed(arg));
+                    continue;
                 }
-            }
-
-            let mut value = Buffer::new();
-
-            // Handling both named and unnamed arguments requires to be careful of the named arguments
-            // order. To do so, we iterate through the macro defined arguments and then check if we have
-            // a named argument with this name:
-            //
-            // * If there is one, we add it and move to the next argument.
-            // * If there isn't one, then we pick the next argument (we can do it without checking
-            //   anything since named arguments are always last).
-            let mut allow_positional = true;
-            let mut used_named_args = vec![false; args.len()];
-            for (index, (arg, default_value)) in def.args.iter().enumerate() {
-                let expr = if let Some((index, expr)) = named_arguments.get(arg) {
-                    used_named_args[*index] = true;
-                    allow_positional = false;
-                    expr
-                } else {
-                    match args.get(index) {
-                        Some(arg_expr) if !matches!(**arg_expr, Expr::NamedArgument(_, _)) => {
-                            // If there is already at least one named argument, then it's not allowed
-                            // to use unnamed ones at this point anymore.
-                            if !allow_positional {
-                                return Err(ctx.generate_error(
-                                    format_args!(
-                                        "cannot have unnamed argument (`{arg}`) after named argument \
-                                         in call to macro {name:?}"
-                                    ),
-                                    call.span(),
-                                ));
-                            }
-                            arg_expr
-                        }
-                        Some(arg_expr) if used_named_args[index] => {
-                            let Expr::NamedArgument(name, _) = **arg_expr else { unreachable!() };
-                            return Err(ctx.generate_error(
-                                format_args!("`{name}` is passed more than once"),
-                                call.span(),
-                            ));
-                        }
-                        _ => {
-                            if let Some(default_value) = default_value {
-                                default_value
-                            } else {
-                                return Err(ctx.generate_error(format_args!("missing `{arg}` argument"), call.span()));
-                            }
-                        }
-                    }
-                };
                 match &**expr {
                     // If `expr` is already a form of variable then
                     // don't reintroduce a new variable. This is
                     // to avoid moving non-copyable values.
                     Expr::Var(name) if *name != "self" => {
-                        let var = this.locals.resolve_or_self(name);
+                        let var = this.resolve_var(ctx, name, expr.span())?;
                         this.locals
                             .insert(Cow::Borrowed(arg), LocalMeta::with_ref(var));
                     }
@@ -746,25 +814,170 @@ impl<'a> Generator<'a, '_> {
                         value.write(this.visit_expr_root(ctx, expr)?);
                         // We need to normalize the arg to write it, thus we need to add it to
                         // locals in the normalized manner
-                        let normalized_arg = normalize_identifier(arg);
-                        buf.write(format_args!("let {} = {before}{value}{after};", normalized_arg));
-                        this.locals.insert_with_default(Cow::Borrowed(normalized_arg));
+                        let arg = normalize_identifier(arg);
+                        buf.write(format_args!("let {arg} = {before}{value}{after};"));
+                        this.locals.insert_with_default(Cow::Borrowed(arg));
                     }
                 }
             }
 
-            let mut size_hint = this.handle(own_ctx, &def.nodes, buf, AstLevel::Nested)?;
+            let outer_has_caller = mem::replace(&mut this.has_caller, call.caller.is_some());
+            let outer_in_caller_body = mem::replace(&mut this.in_caller_body, false);
+            size_hint += this.handle(own_ctx, &def.nodes, buf, AstLevel::Nested)?;
 
             this.flush_ws(def.ws2);
             size_hint += this.write_buf_writable(ctx, buf)?;
+            this.has_caller = outer_has_caller;
+            this.in_caller_body = outer_in_caller_body;
             buf.write('}');
             Ok(size_hint)
         })?;
-        self.prepare_ws(ws);
+        self.prepare_ws(if call.caller.is_some() { ws2 } else { ws });
         self.seen_macros.pop();
         Ok(size_hint)
     }
 
+    /// Writes the closure that renders the macro `def`, which calls itself
+    ///
+    /// The closure gets a handle to itself, and the arguments of the macro as a tuple, whose type
+    /// is made of the types of the arguments.
+    fn write_recursive_macro(
+        &mut self,
+        ctx: &Context<'a>,
+        own_ctx: &Context<'a>,
+        buf: &mut Buffer,
+        call: &'a WithSpan<'_, Call<'_>>,
+        def: &'a Macro<'a>,
+    ) -> Result<usize, CompileError> {
+        let mut types = String::from("(");
+        for MacroArg { name, ty, .. } in &def.args {
+            let Some(ty) = ty else {
+                return Err(ctx.generate_error(
+                    format_args!(
+                        "the argument `{name}` of the recursive macro {:?} needs a type, \
+                         e.g. `{name}: &str`",
+                        def.name,
+                    ),
+                    call.span(),
+                ));
+            };
+            write!(types, "{ty},").unwrap();
+        }
+        types.push(')');
+        let args = self.recursive_macro_args(ctx, call, def)?;
+
+        let index = self.recursive_macros.len();
+        self.write_buf_writable(ctx, buf)?;
+        buf.write(format_args!(
+            "rinja::helpers::render_recursive_macro::<{types}, _, _>(__rinja_writer, {args}, \
+                |__rinja_macro_{index}, __rinja_writer, ("
+        ));
+        self.recursive_macros.push(def);
+        self.seen_macros.push((def, ctx.file_info_of(call.span())));
+        let size_hint = self.push_locals(|this| {
+            for arg in &def.args {
+                let arg = normalize_identifier(arg.name);
+                buf.write(format_args!("{arg},"));
+                this.locals.insert_with_default(Cow::Borrowed(arg));
+            }
+            buf.write(")| -> rinja::Result<()> {");
+
+            let outer_has_caller = mem::replace(&mut this.has_caller, false);
+            let outer_in_caller_body = mem::replace(&mut this.in_caller_body, false);
+            this.prepare_ws(def.ws1);
+            let mut size_hint = this.handle(own_ctx, &def.nodes, buf, AstLevel::Nested)?;
+            this.flush_ws(def.ws2);
+            size_hint += this.write_buf_writable(ctx, buf)?;
+            this.has_caller = outer_has_caller;
+            this.in_caller_body = outer_in_caller_body;
+            Ok(size_hint)
+        });
+        self.seen_macros.pop();
+        self.recursive_macros.pop();
+        buf.write("rinja::Result::Ok(()) })?;");
+        self.prepare_ws(call.ws);
+        size_hint
+    }
+
+    /// Writes the call of the recursive macro `def` in its own body, using the handle
+    /// `__rinja_macro_{index}` that the closure of the macro got
+    fn write_recursive_call(
+        &mut self,
+        ctx: &Context<'a>,
+        buf: &mut Buffer,
+        call: &'a WithSpan<'_, Call<'_>>,
+        def: &'a Macro<'a>,
+        index: usize,
+    ) -> Result<usize, CompileError> {
+        let args = self.recursive_macro_args(ctx, call, def)?;
+        self.write_buf_writable(ctx, buf)?;
+        buf.write(format_args!(
+            "__rinja_macro_{index}.call(__rinja_writer, {args})?;"
+        ));
+        self.prepare_ws(call.ws);
+        Ok(0)
+    }
+
+    /// Returns the tuple of the arguments of a call of the recursive macro `def`
+    fn recursive_macro_args(
+        &mut self,
+        ctx: &Context<'a>,
+        call: &'a WithSpan<'_, Call<'_>>,
+        def: &'a Macro<'a>,
+    ) -> Result<String, CompileError> {
+        if call.caller.is_some() {
+            return Err(ctx.generate_error(
+                format_args!(
+                    "the recursive macro {:?} cannot be invoked with a \
+                     `{{% call %}}...{{% endcall %}}` block",
+                    def.name,
+                ),
+                call.span(),
+            ));
+        }
+        let mut args = String::from("(");
+        for (macro_arg, expr) in def.args.iter().zip(macro_call_args(ctx, call, def)?) {
+            // References are taken automatically like for typed arguments of inlined macros.
+            let (before, after) = match macro_arg.ty {
+                Some(ty) if ty.starts_with('&') => ("&(", ")"),
+                _ => ("", ""),
+            };
+            let value = self.visit_expr_root(ctx, expr)?;
+            write!(args, "{before}{value}{after},").unwrap();
+        }
+        args.push(')');
+        Ok(args)
+    }
+
+    /// Writes the closure `__rinja_caller`, which renders the body of a
+    /// `{% call %}...{% endcall %}` block, and returns it together with its size hint
+    fn write_caller(
+        &mut self,
+        ctx: &Context<'a>,
+        outer_buf: &Buffer,
+        nodes: &'a [Node<'_>],
+        ws1: Ws,
+        ws2: Ws,
+    ) -> Result<(Buffer, usize), CompileError> {
+        let mut buf = Buffer::new();
+        buf.set_discard(outer_buf.is_discard());
+        buf.write(
+            "let __rinja_caller = \
+                |__rinja_writer: &mut dyn rinja::helpers::core::fmt::Write| -> rinja::Result<()> {",
+        );
+        let outer_in_caller_body = mem::replace(&mut self.in_caller_body, true);
+        let size_hint = self.push_locals(|this| {
+            this.prepare_ws(ws1);
+            let mut size_hint = this.handle(ctx, nodes, &mut buf, AstLevel::Nested)?;
+            this.flush_ws(ws2);
+            size_hint += this.write_buf_writable(ctx, &mut buf)?;
+            Ok(size_hint)
+        });
+        self.in_caller_body = outer_in_caller_body;
+        buf.write("rinja::Result::Ok(()) };");
+        Ok((buf, size_hint?))
+    }
+
     fn write_filter_block(
         &mut self,
         ctx: &Context<'a>,
@@ -808,7 +1021,7 @@ impl<'a> Generator<'a, '_> {
             DisplayWrap::Wrapped => fmt_left!("{filter_buf}"),
             DisplayWrap::Unwrapped => fmt_right!(
                 "(&&rinja::filters::AutoEscaper::new(&({filter_buf}), {})).rinja_auto_escape()?",
-                self.input.escaper,
+                self.escaper,
             ),
         };
         buf.write(format_args!(
@@ -823,6 +1036,181 @@ impl<'a> Generator<'a, '_> {
         Ok(size_hint)
     }
 
+    fn write_autoescape(
+        &mut self,
+        ctx: &Context<'a>,
+        buf: &mut Buffer,
+        autoescape: &'a WithSpan<'_, AutoEscape<'_>>,
+    ) -> Result<usize, CompileError> {
+        // Expressions are escaped when the buffer is written, so the buffer must be flushed
+        // whenever the active escaper changes.
+        self.handle_ws(autoescape.ws1);
+        let flushed = self.write_buf_writable(ctx, buf)?;
+        let escaper = match (autoescape.enabled, self.input.escaper) {
+            (false, _) => "rinja::filters::Text",
+            (true, "rinja::filters::Text") => "rinja::filters::Html",
+            (true, escaper) => escaper,
+        };
+        let escaper = mem::replace(&mut self.escaper, escaper);
+        let size_hint = self
+            .handle(ctx, &autoescape.nodes, buf, AstLevel::Nested)
+            .and_then(|size_hint| {
+                self.handle_ws(autoescape.ws2);
+                Ok(flushed + size_hint + self.write_buf_writable(ctx, buf)?)
+            });
+        self.escaper = escaper;
+        size_hint
+    }
+
+    fn write_with(
+        &mut self,
+        ctx: &Context<'a>,
+        buf: &mut Buffer,
+        with: &'a WithSpan<'_, With<'_>>,
+    ) -> Result<usize, CompileError> {
+        // The variables are declared in a Rust block, so they are dropped at `endwith`.
+        self.handle_ws(with.ws1);
+        let flushed = self.write_buf_writable(ctx, buf)?;
+        buf.write('{');
+        let size_hint = self.push_locals(|this| {
+            for (var, val) in &with.vars {
+                // The value is evaluated before the variable is declared, so it can refer to
+                // an outer variable of the same name.
+                let mut expr_buf = Buffer::new();
+                this.visit_expr(ctx, &mut expr_buf, val)?;
+                buf.write("let ");
+                this.visit_target(buf, true, true, var);
+                let (before, after) = if !is_copyable(val) {
+                    ("&(", ")")
+                } else {
+                    ("", "")
+                };
+                buf.write(format_args!(" = {before}{expr_buf}{after};"));
+            }
+            let size_hint = this.handle(ctx, &with.nodes, buf, AstLevel::Nested)?;
+            this.handle_ws(with.ws2);
+            Ok(flushed + size_hint + this.write_buf_writable(ctx, buf)?)
+        })?;
+        buf.write('}');
+        Ok(size_hint)
+    }
+
+    fn write_trans(
+        &mut self,
+        ctx: &Context<'a>,
+        buf: &mut Buffer,
+        trans: &'a WithSpan<'_, Trans<'_>>,
+    ) -> Result<usize, CompileError> {
+        if !cfg!(feature = "fluent") {
+            return Err(ctx.generate_error(
+                "the `trans` block requires the `fluent` feature to be enabled",
+                trans.span(),
+            ));
+        }
+        let Some(bundle) = self.input.fluent_bundle else {
+            return Err(ctx.generate_error(
+                "a `trans` block needs a field that holds the Fluent bundle, \
+                 e.g. `#[template(fluent_bundle = \"bundle\")]`",
+                trans.span(),
+            ));
+        };
+
+        // Every variable of the block is passed to Fluent as an argument of the same name.
+        let plural_nodes = trans
+            .plural
+            .as_ref()
+            .map_or(&[][..], |plural| &plural.nodes);
+        let mut args = Vec::new();
+        for node in trans.nodes.iter().chain(plural_nodes) {
+            match node {
+                Node::Lit(_) | Node::Comment(_) => {}
+                Node::Expr(_, expr) => match **expr {
+                    Expr::Var(name) => {
+                        if !args.iter().any(|&(arg, _)| arg == name) {
+                            args.push((name, expr.span()));
+                        }
+                    }
+                    _ => {
+                        return Err(ctx.generate_error(
+                            "only plain variables like `{{ name }}` can be used in a `trans` \
+                             block, use `{% let %}` to give other expressions a name",
+                            expr.span(),
+                        ));
+                    }
+                },
+                node => {
+                    return Err(ctx.generate_error(
+                        "only text and `{{ variable }}` expressions are allowed in a `trans` block",
+                        node.span(),
+                    ));
+                }
+            }
+        }
+        if let Some(plural) = &trans.plural {
+            if !args.iter().any(|&(arg, _)| arg == plural.count) {
+                args.push((plural.count, trans.span()));
+            }
+        }
+
+        let id = match trans.id {
+            Some(id) => id.to_owned(),
+            None => derive_message_id(&trans.nodes).ok_or_else(|| {
+                ctx.generate_error(
+                    "cannot derive a message id from the content of this `trans` block, \
+                     give it an explicit id, e.g. `{% trans \"my-message\" %}`",
+                    trans.span(),
+                )
+            })?,
+        };
+
+        self.handle_ws(trans.ws1);
+        let flushed = self.write_buf_writable(ctx, buf)?;
+        buf.write(format_args!(
+            "{{\
+                use rinja::helpers::{{FluentArgNumber as _, FluentArgString as _}};\
+                let mut __rinja_args = rinja::helpers::FluentArgs::with_capacity({});",
+            args.len(),
+        ));
+        for (name, span) in args {
+            let mut value = Buffer::new();
+            self.visit_var(ctx, &mut value, name, span)?;
+            buf.write(format_args!(
+                "__rinja_args.set({name:?}, \
+                    (&&rinja::helpers::FluentArg(&({value}))).rinja_fluent_value({})?);",
+                self.escaper,
+            ));
+        }
+
+        // If the bundle does not contain the message, the content of the block is rendered.
+        buf.write(format_args!(
+            "if !rinja::helpers::translate(&self.{bundle}, {id:?}, &__rinja_args, __rinja_writer)? {{"
+        ));
+        let size_hint = match &trans.plural {
+            None => {
+                let size_hint = self.handle(ctx, &trans.nodes, buf, AstLevel::Nested)?;
+                self.handle_ws(trans.ws2);
+                size_hint + self.write_buf_writable(ctx, buf)?
+            }
+            Some(plural) => {
+                buf.write(format_args!(
+                    "if rinja::helpers::is_singular(&__rinja_args, {:?}) {{",
+                    plural.count,
+                ));
+                let mut singular = self.handle(ctx, &trans.nodes, buf, AstLevel::Nested)?;
+                self.handle_ws(plural.ws);
+                singular += self.write_buf_writable(ctx, buf)?;
+                buf.write("} else {");
+                let mut plural = self.handle(ctx, &plural.nodes, buf, AstLevel::Nested)?;
+                self.handle_ws(trans.ws2);
+                plural += self.write_buf_writable(ctx, buf)?;
+                buf.write('}');
+                median(&mut [singular, plural])
+            }
+        };
+        buf.write("}}");
+        Ok(flushed + size_hint)
+    }
+
     fn handle_include(
         &mut self,
         ctx: &Context<'a>,
@@ -834,15 +1222,110 @@ impl<'a> Generator<'a, '_> {
         let file_info = ctx
             .path
             .map(|path| FileInfo::of(i.span(), path, ctx.parsed));
-        let path = self
-            .input
-            .config
-            .find_template(i.path, Some(&self.input.path), file_info)?;
+        let config = self.input.config;
+        let path = match i.ignore_missing {
+            true => config.find_optional_template(i.path, Some(&self.input.path), file_info)?,
+            false => {
+                config.find_template_allow_missing(i.path, Some(&self.input.path), file_info)?
+            }
+        };
+        let size_hint = match path {
+            Some(path) if i.vars.is_empty() && !i.only => {
+                self.write_included(ctx, buf, &path, &[], false)?
+            }
+            Some(path) => {
+                // The values are evaluated in the scope of this template, before they are
+                // declared for the included template.
+                let mut names = Vec::with_capacity(i.vars.len());
+                let mut values = Buffer::new();
+                for (name, value) in &i.vars {
+                    names.push(normalize_identifier(name));
+                    let (before, after) = if !is_copyable(value) {
+                        ("&(", ")")
+                    } else {
+                        ("", "")
+                    };
+                    values.write(before);
+                    self.visit_expr(ctx, &mut values, value)?;
+                    values.write(format_args!("{after},"));
+                }
+                buf.write('{');
+                if !names.is_empty() {
+                    buf.write(format_args!("let ({},) = ({values});", names.join(",")));
+                }
+                let size_hint = self.write_included(ctx, buf, &path, &names, i.only)?;
+                buf.write('}');
+                size_hint
+            }
+            None if i.ignore_missing => 0,
+            None => self.write_missing_placeholder(i.path),
+        };
+        self.prepare_ws(i.ws);
+        Ok(size_hint)
+    }
+
+    fn handle_include_all(
+        &mut self,
+        ctx: &Context<'a>,
+        buf: &mut Buffer,
+        i: &'a WithSpan<'_, IncludeAll<'_>>,
+    ) -> Result<usize, CompileError> {
+        self.flush_ws(i.ws);
+        self.write_buf_writable(ctx, buf)?;
+        let file_info = ctx
+            .path
+            .map(|path| FileInfo::of(i.span(), path, ctx.parsed));
+        let paths =
+            self.input
+                .config
+                .find_templates(i.pattern, Some(&self.input.path), file_info)?;
+        let mut size_hint = 0;
+        for path in paths {
+            size_hint += self.write_included(ctx, buf, &path, &[], false)?;
+        }
+        self.prepare_ws(i.ws);
+        Ok(size_hint)
+    }
 
+    /// Writes the placeholder that replaces a missing template if `allow_missing_templates` is
+    /// enabled in the configuration
+    ///
+    /// The placeholder is a comment in the format of the output, selected by its MIME type.
+    /// Nothing is written for formats without inline comments, e.g. plain text or JSON.
+    fn write_missing_placeholder(&mut self, path: &str) -> usize {
+        let mime_type = self.input.mime_type.split(';').next().unwrap_or_default();
+        let placeholder = match mime_type.trim() {
+            "text/html"
+            | "application/xhtml+xml"
+            | "text/xml"
+            | "application/xml"
+            | "image/svg+xml"
+            | "text/markdown" => format!("<!-- missing: {path} -->"),
+            "text/css" | "text/javascript" => format!("/* missing: {path} */"),
+            _ => return 0,
+        };
+        let size_hint = placeholder.len();
+        self.buf_writable
+            .push(Writable::Lit(Cow::Owned(placeholder)));
+        size_hint
+    }
+
+    /// Writes the template `path` as part of this template
+    ///
+    /// The variables `vars` are declared for the included template. If `only` is set, then they
+    /// are the only variables that the included template can access.
+    fn write_included(
+        &mut self,
+        ctx: &Context<'a>,
+        buf: &mut Buffer,
+        path: &Arc<Path>,
+        vars: &[&str],
+        only: bool,
+    ) -> Result<usize, CompileError> {
         // We clone the context of the child in order to preserve their macros and imports.
         // But also add all the imports and macros from this template that don't override the
         // child's ones to preserve this template's context.
-        let child_ctx = &mut self.contexts[&path].clone();
+        let child_ctx = &mut self.contexts[path].clone();
         for (name, mac) in &ctx.macros {
             child_ctx.macros.entry(name).or_insert(mac);
         }
@@ -866,16 +1349,24 @@ impl<'a> Generator<'a, '_> {
             None => child_ctx,
         };
 
-        let size_hint = self.with_child(heritage.as_ref(), |child| {
-            let mut size_hint = 0;
-            size_hint += child.handle(handle_ctx, handle_ctx.nodes, buf, AstLevel::Top)?;
-            size_hint += child.write_buf_writable(handle_ctx, buf)?;
-            Ok(size_hint)
-        })?;
-
-        self.prepare_ws(i.ws);
+        self.with_child(heritage.as_ref(), |child| {
+            let outer_locals = match only {
+                true => Some(mem::take(&mut child.locals)),
+                false => None,
+            };
+            child.isolated |= only;
+            for &var in vars {
+                child.locals.insert_with_default(Cow::Owned(var.to_owned()));
+            }
 
-        Ok(size_hint)
+            let size_hint = child
+                .handle(handle_ctx, handle_ctx.nodes, buf, AstLevel::Top)
+                .and_then(|size_hint| Ok(size_hint + child.write_buf_writable(handle_ctx, buf)?));
+            if let Some(outer_locals) = outer_locals {
+                child.locals = outer_locals;
+            }
+            size_hint
+        })
     }
 
     fn is_shadowing_variable(
@@ -973,6 +1464,76 @@ impl<'a> Generator<'a, '_> {
         Ok(())
     }
 
+    fn write_set_block(
+        &mut self,
+        ctx: &Context<'a>,
+        buf: &mut Buffer,
+        set: &'a WithSpan<'_, SetBlock<'_>>,
+    ) -> Result<(), CompileError> {
+        if !cfg!(feature = "alloc") {
+            return Err(ctx.generate_error(
+                "`{% set %}` blocks require the `alloc` feature to be enabled",
+                set.span(),
+            ));
+        }
+        self.flush_ws(set.ws1);
+        self.write_buf_writable(ctx, buf)?;
+
+        // The body is rendered before the variable is declared, so it can refer to an outer
+        // variable of the same name.
+        let mut body_buf = Buffer::new();
+        body_buf.write(
+            "{\
+                let mut __rinja_set = rinja::helpers::alloc::string::String::new();\
+                {\
+                    let __rinja_writer = &mut __rinja_set;",
+        );
+        self.push_locals(|this| {
+            this.prepare_ws(set.ws1);
+            this.handle(ctx, &set.nodes, &mut body_buf, AstLevel::Nested)?;
+            this.flush_ws(set.ws2);
+            this.write_buf_writable(ctx, &mut body_buf)?;
+            Ok(())
+        })?;
+        body_buf.write('}');
+        if let Some(filters) = &set.filters {
+            let mut filter_buf = Buffer::new();
+            let display_wrap = self.visit_filter(
+                ctx,
+                &mut filter_buf,
+                filters.name,
+                &filters.arguments,
+                set.span(),
+            )?;
+            let filter_buf = match display_wrap {
+                DisplayWrap::Wrapped => fmt_left!("{filter_buf}"),
+                DisplayWrap::Unwrapped => fmt_right!(
+                    "(&&rinja::filters::AutoEscaper::new(&({filter_buf}), {})).rinja_auto_escape()?",
+                    self.escaper,
+                ),
+            };
+            body_buf.write(format_args!(
+                "let {FILTER_SOURCE} = __rinja_set;\
+                let mut __rinja_set = rinja::helpers::alloc::string::String::new();\
+                rinja::helpers::core::fmt::Write::write_fmt(\
+                    &mut __rinja_set,\
+                    rinja::helpers::core::format_args!(\"{{}}\", {filter_buf}),\
+                )?;"
+            ));
+        }
+        // The content was escaped while it was rendered, so it must not be escaped again.
+        body_buf.write("rinja::filters::Safe(__rinja_set)}");
+
+        let shadowed = self.is_shadowing_variable(ctx, &set.var, set.span())?;
+        if shadowed || matches!(&set.var, Target::Name(name) if self.locals.get(name).is_none()) {
+            buf.write("let ");
+        }
+        self.visit_target(buf, true, true, &set.var);
+        buf.write(format_args!(" = {body_buf};"));
+        self.prepare_ws(set.ws2);
+        Ok(())
+    }
+
     // If `name` is `Some`, this is a call to a block definition, and we have to find
     // the first block for that name from the ancestry chain. If name is `None`, this
     // is from a `super()` call, and we can get the name from `self.super_block`.
@@ -1093,7 +1654,7 @@ impl<'a> Generator<'a, '_> {
         };
         for s in items {
             self.buf_writable
-                .push(compile_time_escape(s, self.input.escaper).unwrap_or(Writable::Expr(s)));
+                .push(compile_time_escape(s, self.escaper).unwrap_or(Writable::Expr(s)));
         }
     }
 
@@ -1105,6 +1666,9 @@ impl<'a> Generator<'a, '_> {
     ) -> Result<usize, CompileError> {
         let mut size_hint = 0;
         let items = mem::take(&mut self.buf_writable.buf);
+        if let Some(placeholder) = self.input.error_placeholder {
+            return self.write_buf_writable_collecting(ctx, buf, &items, placeholder);
+        }
         let mut it = items.iter().enumerate().peekable();
 
         while let Some((_, Writable::Lit(s))) = it.peek() {
@@ -1143,12 +1707,13 @@ impl<'a> Generator<'a, '_> {
                     size_hint += 3;
 
                     let mut expr_buf = Buffer::new();
+                    self.is_output_expr = true;
                     let expr = match self.visit_expr(ctx, &mut expr_buf, s)? {
                         DisplayWrap::Wrapped => expr_buf.into_string(),
                         DisplayWrap::Unwrapped => format!(
                             "(&&rinja::filters::AutoEscaper::new(&({expr_buf}), {})).\
                                 rinja_auto_escape()?",
-                            self.input.escaper,
+                            self.escaper,
                         ),
                     };
                     let idx = if is_cacheable(s) {
@@ -1187,6 +1752,53 @@ impl<'a> Generator<'a, '_> {
         Ok(size_hint)
     }
 
+    // With `#[template(error_placeholder = "..")]`, every expression is written on its own, so an
+    // error can be reported to `__rinja_errors` and replaced by the placeholder, instead of
+    // aborting the rendering.
+    fn write_buf_writable_collecting(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        items: &[Writable<'a>],
+        placeholder: &str,
+    ) -> Result<usize, CompileError> {
+        let mut size_hint = 0;
+        for item in items {
+            match item {
+                Writable::Lit(s) => size_hint += buf.write_writer(s),
+                Writable::Expr(s) => {
+                    size_hint += 3;
+
+                    let mut expr_buf = Buffer::new();
+                    self.is_output_expr = true;
+                    let expr = match self.visit_expr(ctx, &mut expr_buf, s)? {
+                        DisplayWrap::Wrapped => expr_buf.into_string(),
+                        DisplayWrap::Unwrapped => format!(
+                            "(&&rinja::filters::AutoEscaper::new(&({expr_buf}), {})).\
+                                rinja_auto_escape()?",
+                            self.escaper,
+                        ),
+                    };
+                    buf.write(format_args!(
+                        "{{\
+                            let __rinja_result = (|| -> rinja::Result<()> {{\
+                                (&&rinja::filters::Writable(&({expr})))\
+                                    .rinja_write(__rinja_writer)?;\
+                                rinja::Result::Ok(())\
+                            }})();\
+                            if let rinja::helpers::core::result::Result::Err(__rinja_err) =\
+                                __rinja_result\
+                            {{\
+                                __rinja_errors.push(__rinja_err);",
+                    ));
+                    buf.write_writer(placeholder);
+                    buf.write("}}");
+                }
+            }
+        }
+        Ok(size_hint)
+    }
+
     fn write_comment(&mut self, comment: &'a WithSpan<'_, Comment<'_>>) {
         self.handle_ws(comment.ws);
     }
@@ -1235,7 +1847,7 @@ impl<'a> Generator<'a, '_> {
     }
 
     fn should_trim_ws(&self, ws: Option<Whitespace>) -> Whitespace {
-        ws.unwrap_or(self.input.config.whitespace)
+        ws.unwrap_or(self.input.whitespace)
     }
 
     // If the previous literal left some trailing whitespace in `next_ws` and the
@@ -1402,6 +2014,37 @@ impl<'a> Conds<'a> {
     }
 }
 
+/// Derives the Fluent message id of a `{% trans %}` block from its content
+///
+/// The words of the text and the names of the variables are lowercased and joined with dashes,
+/// e.g. `Hello, {{ name }}!` becomes `hello-name`.
+fn derive_message_id(nodes: &[Node<'_>]) -> Option<String> {
+    let mut id = String::new();
+    let mut push_words = |text: &str| {
+        for word in text.split(|c: char| !c.is_ascii_alphanumeric()) {
+            if !word.is_empty() {
+                if !id.is_empty() {
+                    id.push('-');
+                }
+                id.push_str(&word.to_ascii_lowercase());
+            }
+        }
+    };
+    for node in nodes {
+        match node {
+            Node::Lit(lit) => push_words(lit.val),
+            Node::Expr(_, expr) => {
+                if let Expr::Var(name) = **expr {
+                    push_words(name);
+                }
+            }
+            _ => {}
+        }
+    }
+    id.starts_with(|c: char| c.is_ascii_alphabetic())
+        .then_some(id)
+}
+
 fn median(sizes: &mut [usize]) -> usize {
     if sizes.is_empty() {
         return 0;
@@ -1414,37 +2057,195 @@ fn median(sizes: &mut [usize]) -> usize {
     }
 }
 
+/// Returns the definition of the macro that `call` calls, and the context it is defined in
+fn find_macro<'a, 'b>(
+    contexts: &'a HashMap<&'a Arc<Path>, Context<'a>, FxBuildHasher>,
+    ctx: &'b Context<'a>,
+    call: &WithSpan<'_, Call<'_>>,
+) -> Result<(&'a Macro<'a>, &'b Context<'a>), CompileError> {
+    let Call { scope, name, .. } = **call;
+    if let Some(s) = scope {
+        let path = ctx.imports.get(s).ok_or_else(|| {
+            ctx.generate_error(format_args!("no import found for scope {s:?}"), call.span())
+        })?;
+        let mctx = contexts.get(path).ok_or_else(|| {
+            ctx.generate_error(format_args!("context for {path:?} not found"), call.span())
+        })?;
+        let def = mctx.macros.get(name).ok_or_else(|| {
+            ctx.generate_error(
+                format_args!("macro {name:?} not found in scope {s:?}"),
+                call.span(),
+            )
+        })?;
+        Ok((*def, mctx))
+    } else {
+        let def = ctx.macros.get(name).ok_or_else(|| {
+            ctx.generate_error(format_args!("macro {name:?} not found"), call.span())
+        })?;
+        Ok((*def, ctx))
+    }
+}
+
+/// Returns `true` if the `nodes` call the macro `target`, directly or through other macros
+///
+/// `visited` contains the macros that were already checked.
+fn calls_macro<'a>(
+    contexts: &'a HashMap<&'a Arc<Path>, Context<'a>, FxBuildHasher>,
+    ctx: &Context<'a>,
+    nodes: &[Node<'_>],
+    target: &Macro<'_>,
+    visited: &mut Vec<&'a Macro<'a>>,
+) -> bool {
+    nodes.iter().any(|node| match node {
+        Node::Call(call) => {
+            if let Some(nodes) = &call.caller {
+                if calls_macro(contexts, ctx, nodes, target, visited) {
+                    return true;
+                }
+            }
+            let Ok((def, def_ctx)) = find_macro(contexts, ctx, call) else {
+                return false;
+            };
+            if std::ptr::eq(def, target) {
+                true
+            } else if visited.iter().any(|m| std::ptr::eq(*m, def)) {
+                false
+            } else {
+                visited.push(def);
+                calls_macro(contexts, def_ctx, &def.nodes, target, visited)
+            }
+        }
+        Node::If(i) => i
+            .branches
+            .iter()
+            .any(|cond| calls_macro(contexts, ctx, &cond.nodes, target, visited)),
+        Node::Loop(l) => {
+            calls_macro(contexts, ctx, &l.body, target, visited)
+                || calls_macro(contexts, ctx, &l.else_nodes, target, visited)
+        }
+        Node::Match(m) => m
+            .arms
+            .iter()
+            .any(|arm| calls_macro(contexts, ctx, &arm.nodes, target, visited)),
+        Node::FilterBlock(f) => calls_macro(contexts, ctx, &f.nodes, target, visited),
+        Node::AutoEscape(a) => calls_macro(contexts, ctx, &a.nodes, target, visited),
+        Node::With(w) => calls_macro(contexts, ctx, &w.nodes, target, visited),
+        Node::SetBlock(set) => calls_macro(contexts, ctx, &set.nodes, target, visited),
+        _ => false,
+    })
+}
+
+/// Matches the arguments of `call` to the arguments of the macro `def`
+///
+/// Returns the expression of each argument of the macro, in the order of their definition.
+fn macro_call_args<'c>(
+    ctx: &Context<'_>,
+    call: &'c WithSpan<'_, Call<'c>>,
+    def: &'c Macro<'c>,
+) -> Result<Vec<&'c WithSpan<'c, Expr<'c>>>, CompileError> {
+    macro_call_ensure_arg_count(call, def, ctx)?;
+
+    let Call { name, ref args, .. } = **call;
+    let mut named_arguments: HashMap<&str, _, FxBuildHasher> = HashMap::default();
+    // Since named arguments can only be passed last, we only need to check if the last argument
+    // is a named one.
+    if let Some(Expr::NamedArgument(_, _)) = args.last().map(|expr| &**expr) {
+        // First we check that all named arguments actually exist in the called item.
+        for (index, arg) in args.iter().enumerate().rev() {
+            let Expr::NamedArgument(arg_name, _) = &**arg else {
+                break;
+            };
+            if !def.args.iter().any(|arg| arg.name == *arg_name) {
+                return Err(ctx.generate_error(
+                    format_args!("no argument named `{arg_name}` in macro {name:?}"),
+                    call.span(),
+                ));
+            }
+            named_arguments.insert(arg_name, (index, arg));
+        }
+    }
+
+    // Handling both named and unnamed arguments requires to be careful of the named arguments
+    // order. To do so, we iterate through the macro defined arguments and then check if we have
+    // a named argument with this name:
+    //
+    // * If there is one, we add it and move to the next argument.
+    // * If there isn't one, then we pick the next argument (we can do it without checking
+    //   anything since named arguments are always last).
+    let mut allow_positional = true;
+    let mut used_named_args = vec![false; args.len()];
+    let mut exprs = Vec::with_capacity(def.args.len());
+    for (index, macro_arg) in def.args.iter().enumerate() {
+        let MacroArg {
+            name: ref arg,
+            default: ref default_value,
+            ..
+        } = *macro_arg;
+        let expr = if let Some((index, expr)) = named_arguments.get(arg) {
+            used_named_args[*index] = true;
+            allow_positional = false;
+            expr
+        } else {
+            match args.get(index) {
+                Some(arg_expr) if !matches!(**arg_expr, Expr::NamedArgument(_, _)) => {
+                    // If there is already at least one named argument, then it's not allowed
+                    // to use unnamed ones at this point anymore.
+                    if !allow_positional {
+                        return Err(ctx.generate_error(
+                            format_args!(
+                                "cannot have unnamed argument (`{arg}`) after named argument \
+                                 in call to macro {name:?}"
+                            ),
+                            call.span(),
+                        ));
+                    }
+                    arg_expr
+                }
+                Some(arg_expr) if used_named_args[index] => {
+                    let Expr::NamedArgument(name, _) = **arg_expr else {
+                        unreachable!()
+                    };
+                    return Err(ctx.generate_error(
+                        format_args!("`{name}` is passed more than once"),
+                        call.span(),
+                    ));
+                }
+                _ => {
+                    if let Some(default_value) = default_value {
+                        default_value
+                    } else {
+                        return Err(ctx.generate_error(
+                            format_args!("missing argument `{arg}` in call to macro {name:?}"),
+                            call.span(),
+                        ));
+                    }
+                }
+            }
+        };
+        exprs.push(expr);
+    }
+    Ok(exprs)
+}
+
 fn macro_call_ensure_arg_count(
     call: &WithSpan<'_, Call<'_>>,
     def: &Macro<'_>,
     ctx: &Context<'_>,
 ) -> Result<(), CompileError> {
-    if call.args.len() == def.args.len() {
-        // exactly enough arguments were provided
+    if call.args.len() <= def.args.len() {
+        // missing arguments are reported by name once the arguments are matched to the parameters
         return Ok(());
     }
 
-    let nb_default_args = def
-        .args
-        .iter()
-        .rev()
-        .take_while(|(_, default_value)| default_value.is_some())
-        .count();
-    if call.args.len() < def.args.len() && call.args.len() >= def.args.len() - nb_default_args {
-        // all missing arguments have a default value, and there weren't too many args
-        return Ok(());
-    }
-
-    // either too many or not enough arguments were provided
-    let (expected_args, extra) = match nb_default_args {
-        0 => (def.args.len(), ""),
-        _ => (nb_default_args, "at least "),
-    };
+    // too many arguments were provided
+    let has_default_values = def.args.iter().any(|arg| arg.default.is_some());
+    let extra = if has_default_values { "at most " } else { "" };
     Err(ctx.generate_error(
         format_args!(
-            "macro {:?} expected {extra}{expected_args} argument{}, found {}",
+            "macro {:?} expected {extra}{} argument{}, found {}",
             def.name,
-            if expected_args != 1 { "s" } else { "" },
+            def.args.len(),
+            if def.args.len() != 1 { "s" } else { "" },
             call.args.len(),
         ),
         call.span(),
@@ -1476,6 +2277,7 @@ fn is_cacheable(expr: &WithSpan<'_, Expr<'_>>) -> bool {
         Expr::Attr(lhs, _) => is_cacheable(lhs),
         Expr::Index(lhs, rhs) => is_cacheable(lhs) && is_cacheable(rhs),
         Expr::Filter(Filter { arguments, .. }) => arguments.iter().all(is_cacheable),
+        Expr::Test(Test { arguments, .. }) => arguments.iter().all(is_cacheable),
         Expr::Unary(_, arg) => is_cacheable(arg),
         Expr::BinOp(_, lhs, rhs) => is_cacheable(lhs) && is_cacheable(rhs),
         Expr::IsDefined(_) | Expr::IsNotDefined(_) => true,
@@ -1489,6 +2291,9 @@ fn is_cacheable(expr: &WithSpan<'_, Expr<'_>>) -> bool {
         Expr::As(expr, _) => is_cacheable(expr),
         Expr::Try(expr) => is_cacheable(expr),
         Expr::Concat(args) => args.iter().all(is_cacheable),
+        Expr::IfElse(cond, then, otherwise) => {
+            is_cacheable(cond) && is_cacheable(then) && is_cacheable(otherwise)
+        }
         // Doesn't make sense in this context.
         Expr::LetCond(_) => false,
         // We have too little information to tell if the expression is pure:
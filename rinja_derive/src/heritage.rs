@@ -4,11 +4,11 @@ use std::path::Path;
 use std::sync::Arc;
 
 use parser::node::{BlockDef, Macro};
-use parser::{Node, Parsed, Span};
+use parser::{Node, Parsed, Span, WithSpan, strip_common};
 use rustc_hash::FxBuildHasher;
 
-use crate::config::Config;
-use crate::{CompileError, FileInfo};
+use crate::config::{Config, manifest_root};
+use crate::{CompileError, ErrorInfo, FileInfo, generate_row_and_column};
 
 pub(crate) struct Heritage<'a, 'h> {
     pub(crate) root: &'h Context<'a>,
@@ -37,6 +37,93 @@ impl<'a, 'h> Heritage<'a, 'h> {
     }
 }
 
+/// Ensures that every block a template overrides is defined in one of its ancestors.
+///
+/// Otherwise, e.g. if the block name contains a typo, the block would silently never be rendered.
+/// Blocks that are nested inside of another block are new definitions, and are not checked.
+/// Only the blocks of the templates in the `extends` chain are available: a block of an included
+/// template is rendered with the included template's own inheritance chain.
+pub(crate) fn check_block_overrides<'a, 'h>(
+    mut ctx: &'h Context<'a>,
+    contexts: &'h HashMap<&'a Arc<Path>, Context<'a>, FxBuildHasher>,
+) -> Result<(), CompileError> {
+    while let Some(path) = &ctx.extends {
+        let mut available = vec![];
+        let mut ancestor = &contexts[path];
+        loop {
+            available.extend(ancestor.blocks.keys().copied());
+            match &ancestor.extends {
+                Some(path) => ancestor = &contexts[path],
+                None => break,
+            }
+        }
+
+        let mut blocks = Vec::new();
+        collect_outer_blocks(ctx.nodes, &mut blocks);
+        for block in blocks {
+            if available.contains(&block.name) {
+                continue;
+            }
+            available.sort_unstable();
+            available.dedup();
+            let available = match available.is_empty() {
+                true => "none".to_owned(),
+                false => available
+                    .iter()
+                    .map(|name| format!("`{name}`"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            };
+            return Err(ctx.generate_error(
+                format_args!(
+                    "block `{}` is not defined in any parent template, so it would never be \
+                     rendered. Available blocks: {available}",
+                    block.name,
+                ),
+                block.span(),
+            ));
+        }
+
+        ctx = &contexts[path];
+    }
+    Ok(())
+}
+
+/// Collects all blocks that are not nested inside of another block.
+fn collect_outer_blocks<'a>(
+    nodes: &'a [Node<'a>],
+    blocks: &mut Vec<&'a WithSpan<'a, BlockDef<'a>>>,
+) {
+    for node in nodes {
+        match node {
+            Node::BlockDef(b) => blocks.push(b),
+            Node::If(i) => {
+                for cond in &i.branches {
+                    collect_outer_blocks(&cond.nodes, blocks);
+                }
+            }
+            Node::AutoEscape(a) => collect_outer_blocks(&a.nodes, blocks),
+            Node::With(w) => collect_outer_blocks(&w.nodes, blocks),
+            Node::SetBlock(set) => collect_outer_blocks(&set.nodes, blocks),
+            Node::Loop(l) => {
+                collect_outer_blocks(&l.body, blocks);
+                collect_outer_blocks(&l.else_nodes, blocks);
+            }
+            Node::Match(m) => {
+                for arm in &m.arms {
+                    collect_outer_blocks(&arm.nodes, blocks);
+                }
+            }
+            Node::Call(call) => {
+                if let Some(caller) = &call.caller {
+                    collect_outer_blocks(caller, blocks);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 type BlockAncestry<'a, 'h> =
     HashMap<&'a str, Vec<(&'h Context<'a>, &'a BlockDef<'a>)>, FxBuildHasher>;
 
@@ -70,6 +157,7 @@ impl<'a> Context<'a> {
         parsed: &'a Parsed,
     ) -> Result<Self, CompileError> {
         let mut extends = None;
+        let mut has_extends = false;
         let mut blocks = HashMap::default();
         let mut macros = HashMap::default();
         let mut imports = HashMap::default();
@@ -81,20 +169,22 @@ impl<'a> Context<'a> {
                 match n {
                     Node::Extends(e) => {
                         ensure_top(top, e.span(), path, parsed, "extends")?;
-                        if extends.is_some() {
+                        if has_extends {
                             return Err(CompileError::new(
                                 "multiple extend blocks found",
                                 Some(FileInfo::of(e.span(), path, parsed)),
                             ));
                         }
-                        extends = Some(config.find_template(
+                        extends = config.find_template_allow_missing(
                             e.path,
                             Some(path),
                             Some(FileInfo::of(e.span(), path, parsed)),
-                        )?);
+                        )?;
+                        has_extends = true;
                     }
                     Node::Macro(m) => {
                         ensure_top(top, m.span(), path, parsed, "macro")?;
+                        ensure_valid_arg_types(m, path, parsed)?;
                         macros.insert(m.name, &**m);
                     }
                     Node::Import(import) => {
@@ -115,6 +205,15 @@ impl<'a> Context<'a> {
                             nested.push(&cond.nodes);
                         }
                     }
+                    Node::AutoEscape(a) => {
+                        nested.push(&a.nodes);
+                    }
+                    Node::With(w) => {
+                        nested.push(&w.nodes);
+                    }
+                    Node::SetBlock(set) => {
+                        nested.push(&set.nodes);
+                    }
                     Node::Loop(l) => {
                         nested.push(&l.body);
                         nested.push(&l.else_nodes);
@@ -124,6 +223,11 @@ impl<'a> Context<'a> {
                             nested.push(&arm.nodes);
                         }
                     }
+                    Node::Call(call) => {
+                        if let Some(caller) = &call.caller {
+                            nested.push(caller);
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -148,6 +252,46 @@ impl<'a> Context<'a> {
     pub(crate) fn file_info_of(&self, node: Span<'a>) -> Option<FileInfo<'a>> {
         self.path.map(|path| FileInfo::of(node, path, self.parsed))
     }
+
+    /// Returns the path, byte offset, row and column of `node`, to locate runtime errors
+    ///
+    /// The path is relative to the crate root, so no paths of the build machine end up in the
+    /// generated binary.
+    pub(crate) fn location_of(&self, node: Span<'_>) -> Option<(String, usize, usize, usize)> {
+        let path = self.path?;
+        let source = self.parsed.source();
+        let node_source = node.as_suffix_of(source)?;
+        let offset = source.len() - node_source.len();
+        let ErrorInfo { row, column, .. } = generate_row_and_column(source, node_source);
+        Some((strip_common(&manifest_root(), path), offset, row, column))
+    }
+}
+
+/// The type annotations of macro arguments are used verbatim in the generated code, so they must
+/// be valid rust types.
+fn ensure_valid_arg_types(
+    m: &WithSpan<'_, Macro<'_>>,
+    path: &Path,
+    parsed: &Parsed,
+) -> Result<(), CompileError> {
+    for arg in &m.args {
+        let Some(ty) = arg.ty else {
+            continue;
+        };
+        let msg = match syn::parse_str::<syn::Type>(ty) {
+            Ok(syn::Type::ImplTrait(_)) => "`impl Trait` cannot be used as a macro argument type",
+            Ok(_) => continue,
+            Err(_) => "not a valid rust type",
+        };
+        return Err(CompileError::new(
+            format!(
+                "invalid type `{ty}` for argument `{}` of macro {:?}: {msg}",
+                arg.name, m.name,
+            ),
+            Some(FileInfo::of(m.span(), path, parsed)),
+        ));
+    }
+    Ok(())
 }
 
 fn ensure_top(
@@ -1,7 +1,6 @@
 use std::borrow::Cow;
 use std::collections::hash_map::{Entry, HashMap};
 use std::fs::read_to_string;
-use std::iter::FusedIterator;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::{Arc, OnceLock};
@@ -9,23 +8,36 @@ use std::sync::{Arc, OnceLock};
 use parser::node::Whitespace;
 use parser::{Node, Parsed};
 use proc_macro2::Span;
+use quote::ToTokens;
 use rustc_hash::FxBuildHasher;
+use syn::parse::{ParseStream, Parser};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{Attribute, Expr, ExprLit, Ident, Lit, LitBool, LitStr, Meta, Token};
+use syn::{Attribute, Expr, ExprLit, Ident, Lit, LitBool, LitStr, Meta, Token, bracketed};
 
 use crate::config::{Config, SyntaxAndCache};
-use crate::{CompileError, FileInfo, MsgValidEscapers, OnceMap};
+use crate::integration::Buffer;
+use crate::{CompileError, FileInfo, MsgValidEscapers, MsgValidMimeTypes, OnceMap};
 
+#[derive(Clone)]
 pub(crate) struct TemplateInput<'a> {
     pub(crate) ast: &'a syn::DeriveInput,
     pub(crate) config: &'a Config,
     pub(crate) syntax: &'a SyntaxAndCache<'a>,
+    pub(crate) whitespace: Whitespace,
     pub(crate) source: &'a Source,
     pub(crate) source_span: Option<Span>,
     pub(crate) block: Option<&'a str>,
+    pub(crate) blocks: &'a [(String, Span)],
     pub(crate) print: Print,
     pub(crate) escaper: &'a str,
+    pub(crate) escape_field: Option<&'a str>,
+    pub(crate) fluent_bundle: Option<&'a str>,
+    pub(crate) display: bool,
+    pub(crate) warn_unused: bool,
+    pub(crate) expose_source: bool,
+    pub(crate) error_placeholder: Option<&'a str>,
+    pub(crate) mime_type: &'a str,
     pub(crate) path: Arc<Path>,
     pub(crate) fields: Vec<String>,
 }
@@ -42,11 +54,21 @@ impl TemplateInput<'_> {
         let TemplateArgs {
             source: (source, source_span),
             block,
+            blocks,
             print,
+            display,
+            warn_unused,
+            expose_source,
+            error_placeholder,
             escaping,
+            escaper,
+            escape_field,
+            fluent_bundle,
+            mime,
             ext,
             ext_span,
             syntax,
+            whitespace,
             ..
         } = args;
 
@@ -59,12 +81,17 @@ impl TemplateInput<'_> {
                 PathBuf::from(format!("{}.{}", ast.ident, ext)).into()
             }
             (&Source::Source(_), None) => {
+                #[cfg(not(feature = "code-in-doc"))]
+                let attrs = "`source` attribute";
+                #[cfg(feature = "code-in-doc")]
+                let attrs = "`source` or `in_doc` attribute";
                 return Err(CompileError::no_file_info(
-                    #[cfg(not(feature = "code-in-doc"))]
-                    "must include `ext` attribute when using `source` attribute",
-                    #[cfg(feature = "code-in-doc")]
-                    "must include `ext` attribute when using `source` or `in_doc` attribute",
-                    None,
+                    format_args!(
+                        "template `{}` must include `ext` attribute when using {attrs}, \
+                        e.g. `ext = \"html\"`",
+                        ast.ident,
+                    ),
+                    Some(ast.ident.span()),
                 ));
             }
         };
@@ -79,31 +106,66 @@ impl TemplateInput<'_> {
             },
         )?;
 
-        // Match extension against defined output formats
-
-        let escaping = escaping
-            .as_deref()
-            .or_else(|| path.extension().and_then(|s| s.to_str()))
-            .unwrap_or_default();
-
-        let escaper = config
-            .escapers
-            .iter()
-            .find_map(|(extensions, path)| {
-                extensions
-                    .contains(&Cow::Borrowed(escaping))
-                    .then_some(path.as_ref())
-            })
-            .ok_or_else(|| {
-                CompileError::no_file_info(
-                    format!(
-                        "no escaper defined for extension '{escaping}'. You can define an escaper \
-                        in the config file (named `rinja.toml` by default). {}",
-                        MsgValidEscapers(&config.escapers),
-                    ),
-                    *ext_span,
-                )
-            })?;
+        // The `whitespace` argument of the template takes precedence over the default of its
+        // syntax, which takes precedence over the default of the configuration file.
+        let whitespace = whitespace
+            .or(syntax.whitespace)
+            .unwrap_or(config.whitespace);
+
+        // Match MIME type or extension against defined output formats
+
+        let escaper = match (escaper, escaping, mime) {
+            (Some(escaper), _, _) => escaper.as_str(),
+            (None, None, Some((mime, mime_span))) => {
+                // Ignore parameters like in `text/html; charset=utf-8`.
+                let essence = mime.split(';').next().unwrap_or_default().trim();
+                config
+                    .mime_escapers
+                    .iter()
+                    .find_map(|(mime_types, path)| {
+                        mime_types
+                            .iter()
+                            .any(|m| m.eq_ignore_ascii_case(essence))
+                            .then_some(path.as_ref())
+                    })
+                    .ok_or_else(|| {
+                        CompileError::no_file_info(
+                            format!(
+                                "no escaper defined for MIME type '{essence}'. You can define an \
+                                escaper in the config file (named `rinja.toml` by default), or \
+                                select one with the `escape` attribute. {}",
+                                MsgValidMimeTypes(&config.mime_escapers),
+                            ),
+                            Some(*mime_span),
+                        )
+                    })?
+            }
+            _ => {
+                let escaping = escaping
+                    .as_deref()
+                    .or_else(|| extension(&path))
+                    .unwrap_or_default();
+
+                config
+                    .escapers
+                    .iter()
+                    .find_map(|(extensions, path)| {
+                        extensions
+                            .contains(&Cow::Borrowed(escaping))
+                            .then_some(path.as_ref())
+                    })
+                    .ok_or_else(|| {
+                        CompileError::no_file_info(
+                            format!(
+                                "no escaper defined for extension '{escaping}'. You can define an \
+                                escaper in the config file (named `rinja.toml` by default). {}",
+                                MsgValidEscapers(&config.escapers),
+                            ),
+                            *ext_span,
+                        )
+                    })?
+            }
+        };
 
         let empty_punctuated = Punctuated::new();
         let fields = match ast.data {
@@ -124,28 +186,88 @@ impl TemplateInput<'_> {
         })
         .collect::<Vec<_>>();
 
+        let escape_field = match escape_field {
+            Some((field, span)) => {
+                if !fields.contains(field) {
+                    return Err(CompileError::no_file_info(
+                        format!(
+                            "the `escape_field` {field:?} is not a named field of `{}`",
+                            ast.ident,
+                        ),
+                        Some(*span),
+                    ));
+                }
+                Some(field.as_str())
+            }
+            None => None,
+        };
+
+        let fluent_bundle = match fluent_bundle {
+            Some((field, span)) => {
+                if !fields.contains(field) {
+                    return Err(CompileError::no_file_info(
+                        format!(
+                            "the `fluent_bundle` {field:?} is not a named field of `{}`",
+                            ast.ident,
+                        ),
+                        Some(*span),
+                    ));
+                }
+                Some(field.as_str())
+            }
+            None => None,
+        };
+
+        // An explicit `mime` attribute is used verbatim, otherwise the MIME type is guessed.
+        let mime_type = match mime {
+            Some((mime, _)) => mime.as_str(),
+            None => extension_to_mime_type(mime_extension(&path)),
+        };
+
         Ok(TemplateInput {
             ast,
             config,
             syntax,
+            whitespace,
             source,
             source_span: *source_span,
             block: block.as_deref(),
+            blocks,
             print: *print,
             escaper,
+            escape_field,
+            fluent_bundle,
+            display: *display,
+            warn_unused: *warn_unused,
+            expose_source: *expose_source,
+            error_placeholder: error_placeholder.as_deref(),
+            mime_type,
             path,
             fields,
         })
     }
 
+    /// Parses the template and all templates it uses (transitively) into `map`
+    ///
+    /// If `allow_missing_templates` is enabled in the configuration, a warning is emitted into
+    /// `buf` for every included or extended template that does not exist.
     pub(crate) fn find_used_templates(
         &self,
         map: &mut HashMap<Arc<Path>, Arc<Parsed>, FxBuildHasher>,
+        buf: &mut Buffer,
     ) -> Result<(), CompileError> {
+        let span = self.source_span.unwrap_or_else(|| self.ast.ident.span());
+        let mut warn_missing = |name: &str, file_info: FileInfo<'_>| {
+            buf.warn(
+                format!("template {name:?} not found, rendering a placeholder instead{file_info}"),
+                span,
+            );
+        };
+
         let (source, source_path) = match &self.source {
             Source::Source(s) => (s.clone(), None),
             Source::Path(_) => (
-                get_template_source(&self.path, None)?,
+                get_template_source(self.config, &self.path, None)?,
                 Some(Arc::clone(&self.path)),
             ),
         };
@@ -180,6 +302,7 @@ impl TemplateInput<'_> {
                             let new_path = e.key();
                             let source = parsed.source();
                             let source = get_template_source(
+                                self.config,
                                 new_path,
                                 Some((
                                     &path,
@@ -195,11 +318,16 @@ impl TemplateInput<'_> {
 
                     match n {
                         Node::Extends(extends) if top => {
-                            let extends = self.config.find_template(
+                            let file_info = FileInfo::of(extends.span(), &path, &parsed);
+                            let Some(extends) = self.config.find_template_allow_missing(
                                 extends.path,
                                 Some(&path),
-                                Some(FileInfo::of(extends.span(), &path, &parsed)),
-                            )?;
+                                Some(file_info),
+                            )?
+                            else {
+                                warn_missing(extends.path, file_info);
+                                continue;
+                            };
                             let dependency_path = (path.clone(), extends.clone());
                             if path == extends {
                                 // We add the path into the graph to have a better looking error.
@@ -225,13 +353,45 @@ impl TemplateInput<'_> {
                         Node::FilterBlock(f) => {
                             nested.push(&f.nodes);
                         }
+                        Node::AutoEscape(a) => {
+                            nested.push(&a.nodes);
+                        }
+                        Node::With(w) => {
+                            nested.push(&w.nodes);
+                        }
+                        Node::SetBlock(set) => {
+                            nested.push(&set.nodes);
+                        }
                         Node::Include(include) => {
-                            let include = self.config.find_template(
-                                include.path,
+                            let file_info = FileInfo::of(include.span(), &path, &parsed);
+                            let found = match include.ignore_missing {
+                                true => self.config.find_optional_template(
+                                    include.path,
+                                    Some(&path),
+                                    Some(file_info),
+                                )?,
+                                false => self.config.find_template_allow_missing(
+                                    include.path,
+                                    Some(&path),
+                                    Some(file_info),
+                                )?,
+                            };
+                            match found {
+                                Some(found) => add_to_check(found)?,
+                                None if !include.ignore_missing => {
+                                    warn_missing(include.path, file_info);
+                                }
+                                None => {}
+                            }
+                        }
+                        Node::IncludeAll(include) => {
+                            for include in self.config.find_templates(
+                                include.pattern,
                                 Some(&path),
                                 Some(FileInfo::of(include.span(), &path, &parsed)),
-                            )?;
-                            add_to_check(include)?;
+                            )? {
+                                add_to_check(include)?;
+                            }
                         }
                         Node::BlockDef(b) => {
                             nested.push(&b.nodes);
@@ -250,10 +410,20 @@ impl TemplateInput<'_> {
                                 nested.push(&arm.nodes);
                             }
                         }
+                        Node::Trans(t) => {
+                            nested.push(&t.nodes);
+                            if let Some(plural) = &t.plural {
+                                nested.push(&plural.nodes);
+                            }
+                        }
+                        Node::Call(call) => {
+                            if let Some(caller) = &call.caller {
+                                nested.push(caller);
+                            }
+                        }
                         Node::Lit(_)
                         | Node::Comment(_)
                         | Node::Expr(_, _)
-                        | Node::Call(_)
                         | Node::Extends(_)
                         | Node::Let(_)
                         | Node::Import(_)
@@ -275,12 +445,22 @@ impl TemplateInput<'_> {
 pub(crate) struct TemplateArgs {
     pub(crate) source: (Source, Option<Span>),
     block: Option<String>,
+    blocks: Vec<(String, Span)>,
     print: Print,
+    display: bool,
+    warn_unused: bool,
+    expose_source: bool,
+    error_placeholder: Option<String>,
     escaping: Option<String>,
+    escaper: Option<String>,
+    escape_field: Option<(String, Span)>,
+    fluent_bundle: Option<(String, Span)>,
+    mime: Option<(String, Span)>,
     ext: Option<String>,
     ext_span: Option<Span>,
     syntax: Option<String>,
     config: Option<String>,
+    config_source: Option<String>,
     pub(crate) whitespace: Option<Whitespace>,
     pub(crate) template_span: Option<Span>,
     pub(crate) config_span: Option<Span>,
@@ -302,6 +482,27 @@ impl TemplateArgs {
                 Some(ast.ident.span()),
             ));
         };
+        if let (Some(_), Some(escaper)) = (&args.escape, &args.escaper) {
+            return Err(CompileError::no_file_info(
+                "template attributes `escape` and `escaper` cannot be used together",
+                Some(escaper.span()),
+            ));
+        }
+        if let Some(escape_field) = &args.escape_field {
+            if args.escape.is_some() || args.escaper.is_some() {
+                return Err(CompileError::no_file_info(
+                    "template attribute `escape_field` cannot be used together with `escape` or \
+                    `escaper`",
+                    Some(escape_field.span()),
+                ));
+            }
+        }
+        if let (Some(_), Some(config_source)) = (&args.config, &args.config_source) {
+            return Err(CompileError::no_file_info(
+                "template attributes `config` and `config_source` cannot be used together",
+                Some(config_source.span()),
+            ));
+        }
         Ok(Self {
             source: match args.source {
                 Some(PartialTemplateArgsSource::Path(s)) => {
@@ -323,15 +524,38 @@ impl TemplateArgs {
                 }
             },
             block: args.block.map(|value| value.value()),
+            blocks: args
+                .blocks
+                .unwrap_or_default()
+                .into_iter()
+                .map(|value| (value.value(), value.span()))
+                .collect(),
             print: args.print.unwrap_or_default(),
+            display: args.display.map_or(true, |value| value.value()),
+            warn_unused: args.warn_unused.is_some_and(|value| value.value()),
+            expose_source: args.expose_source.is_some_and(|value| value.value()),
+            error_placeholder: args.error_placeholder.map(|value| value.value()),
             escaping: args.escape.map(|value| value.value()),
+            escaper: args
+                .escaper
+                .map(|path| path.to_token_stream().to_string().replace(' ', "")),
+            escape_field: args.escape_field.map(|value| (value.value(), value.span())),
+            fluent_bundle: args
+                .fluent_bundle
+                .map(|value| (value.value(), value.span())),
+            mime: args.mime.map(|value| (value.value(), value.span())),
             ext: args.ext.as_ref().map(|value| value.value()),
             ext_span: args.ext.as_ref().map(|value| value.span()),
             syntax: args.syntax.map(|value| value.value()),
             config: args.config.as_ref().map(|value| value.value()),
+            config_source: args.config_source.as_ref().map(|value| value.value()),
             whitespace: args.whitespace,
             template_span: Some(args.template.span()),
-            config_span: args.config.as_ref().map(|value| value.span()),
+            config_span: args
+                .config
+                .as_ref()
+                .or(args.config_source.as_ref())
+                .map(|value| value.span()),
         })
     }
 
@@ -339,12 +563,22 @@ impl TemplateArgs {
         Self {
             source: (Source::Source("".into()), None),
             block: None,
+            blocks: Vec::new(),
             print: Print::default(),
+            display: true,
+            warn_unused: false,
+            expose_source: false,
+            error_placeholder: None,
             escaping: None,
+            escaper: None,
+            escape_field: None,
+            fluent_bundle: None,
+            mime: None,
             ext: Some("txt".to_string()),
             ext_span: None,
             syntax: None,
             config: None,
+            config_source: None,
             whitespace: None,
             template_span: None,
             config_span: None,
@@ -354,6 +588,10 @@ impl TemplateArgs {
     pub(crate) fn config_path(&self) -> Option<&str> {
         self.config.as_deref()
     }
+
+    pub(crate) fn config_source(&self) -> Option<&str> {
+        self.config_source.as_deref()
+    }
 }
 
 /// Try to find the source in the comment, in a `rinja` code block.
@@ -483,13 +721,12 @@ fn collect_rinja_code_blocks(
     let mut had_rinja_code = false;
     for e in Parser::new(&source) {
         match (in_rinja_code, e) {
-            (false, Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(s)))) => {
+            (false, Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(s))))
                 if s.split(",")
-                    .any(|s| JINJA_EXTENSIONS.contains(&s.trim_ascii()))
-                {
-                    in_rinja_code = true;
-                    had_rinja_code = true;
-                }
+                    .any(|s| JINJA_EXTENSIONS.contains(&s.trim_ascii())) =>
+            {
+                in_rinja_code = true;
+                had_rinja_code = true;
             }
             (true, Event::End(TagEnd::CodeBlock)) => in_rinja_code = false,
             (true, Event::Text(text)) => tmpl_source.push_str(&text),
@@ -506,50 +743,21 @@ fn collect_rinja_code_blocks(
     Ok(Source::Source(tmpl_source.into()))
 }
 
-struct ResultIter<I, E>(Result<I, Option<E>>);
-
-impl<I: IntoIterator, E> From<Result<I, E>> for ResultIter<I::IntoIter, E> {
-    fn from(value: Result<I, E>) -> Self {
-        Self(match value {
-            Ok(i) => Ok(i.into_iter()),
-            Err(e) => Err(Some(e)),
-        })
-    }
-}
-
-impl<I: Iterator, E> Iterator for ResultIter<I, E> {
-    type Item = Result<I::Item, E>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        match &mut self.0 {
-            Ok(iter) => Some(Ok(iter.next()?)),
-            Err(err) => Some(Err(err.take()?)),
-        }
-    }
-}
-
-impl<I: FusedIterator, E> FusedIterator for ResultIter<I, E> {}
-
 #[derive(Debug, Clone, Hash, PartialEq)]
 pub(crate) enum Source {
     Path(Arc<str>),
     Source(Arc<str>),
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Hash, Default)]
 pub(crate) enum Print {
     All,
     Ast,
     Code,
+    #[default]
     None,
 }
 
-impl Default for Print {
-    fn default() -> Self {
-        Self::None
-    }
-}
-
 impl FromStr for Print {
     type Err = String;
 
@@ -578,43 +786,66 @@ fn cyclic_graph_error(dependency_graph: &[(Arc<Path>, Arc<Path>)]) -> Result<(),
 }
 
 pub(crate) fn get_template_source(
+    config: &Config,
     tpl_path: &Arc<Path>,
     import_from: Option<(&Arc<Path>, &str, &str)>,
 ) -> Result<Arc<str>, CompileError> {
     static CACHE: OnceLock<OnceMap<Arc<Path>, Arc<str>>> = OnceLock::new();
 
-    CACHE.get_or_init(OnceMap::default).get_or_try_insert(
-        tpl_path,
-        |tpl_path| match read_to_string(tpl_path) {
-            Ok(mut source) => {
-                if source.ends_with('\n') {
-                    let _ = source.pop();
-                }
-                Ok((Arc::clone(tpl_path), Arc::from(source)))
-            }
-            Err(err) => Err(CompileError::new(
-                format_args!(
+    let normalize = |mut source: String| {
+        // Templates checked out on Windows often have CRLF line endings. Like Jinja, we
+        // normalize them, so the output does not depend on the platform.
+        if source.contains("\r\n") {
+            source = source.replace("\r\n", "\n");
+        }
+        if source.ends_with('\n') {
+            let _ = source.pop();
+        }
+        Arc::from(source)
+    };
+    let to_error = |msg: String| {
+        CompileError::new(
+            msg,
+            import_from.map(|(node_file, file_source, node_source)| {
+                FileInfo::new(node_file, Some(file_source), Some(node_source))
+            }),
+        )
+    };
+    match config.find_loader(tpl_path) {
+        Some(loader) => loader.load(tpl_path, normalize).map_err(to_error),
+        None => CACHE.get_or_init(OnceMap::default).get_or_try_insert(
+            tpl_path,
+            |tpl_path| match read_to_string(tpl_path) {
+                Ok(source) => Ok((Arc::clone(tpl_path), normalize(source))),
+                Err(err) => Err(to_error(format!(
                     "unable to open template file '{}': {err}",
                     tpl_path.to_str().unwrap(),
-                ),
-                import_from.map(|(node_file, file_source, node_source)| {
-                    FileInfo::new(node_file, Some(file_source), Some(node_source))
-                }),
-            )),
-        },
-        Arc::clone,
-    )
+                ))),
+            },
+            Arc::clone,
+        ),
+    }
 }
 
 pub(crate) struct PartialTemplateArgs {
     pub(crate) template: Ident,
     pub(crate) source: Option<PartialTemplateArgsSource>,
     pub(crate) block: Option<LitStr>,
+    pub(crate) blocks: Option<Vec<LitStr>>,
     pub(crate) print: Option<Print>,
+    pub(crate) display: Option<LitBool>,
+    pub(crate) warn_unused: Option<LitBool>,
+    pub(crate) expose_source: Option<LitBool>,
+    pub(crate) error_placeholder: Option<LitStr>,
     pub(crate) escape: Option<LitStr>,
+    pub(crate) escaper: Option<syn::Path>,
+    pub(crate) escape_field: Option<LitStr>,
+    pub(crate) fluent_bundle: Option<LitStr>,
+    pub(crate) mime: Option<LitStr>,
     pub(crate) ext: Option<LitStr>,
     pub(crate) syntax: Option<LitStr>,
     pub(crate) config: Option<LitStr>,
+    pub(crate) config_source: Option<LitStr>,
     pub(crate) whitespace: Option<Whitespace>,
 }
 
@@ -658,11 +889,21 @@ const _: () = {
             template: Ident::new("template", Span::call_site()),
             source: None,
             block: None,
+            blocks: None,
             print: None,
+            display: None,
+            warn_unused: None,
+            expose_source: None,
+            error_placeholder: None,
             escape: None,
+            escaper: None,
+            escape_field: None,
+            fluent_bundle: None,
+            mime: None,
             ext: None,
             syntax: None,
             config: None,
+            config_source: None,
             whitespace: None,
         };
         let mut has_data = false;
@@ -705,6 +946,16 @@ const _: () = {
                     None => unreachable!("not possible in syn::Meta::NameValue(…)"),
                 };
 
+                if ident == "blocks" {
+                    ensure_only_once(ident, &mut this.blocks)?;
+                    this.blocks = Some(get_strlit_array(ident, pair.value)?);
+                    continue;
+                } else if ident == "escaper" {
+                    ensure_only_once(ident, &mut this.escaper)?;
+                    this.escaper = Some(get_path(ident, pair.value)?);
+                    continue;
+                }
+
                 let value = get_lit(ident, pair.value)?;
 
                 if ident == "path" {
@@ -739,14 +990,33 @@ const _: () = {
                     set_strlit_pair(ident, value, &mut this.block)?;
                 } else if ident == "print" {
                     set_parseable_string(ident, value, &mut this.print)?;
+                } else if ident == "display" {
+                    ensure_only_once(ident, &mut this.display)?;
+                    this.display = Some(get_boollit(ident, value)?);
+                } else if ident == "warn_unused" {
+                    ensure_only_once(ident, &mut this.warn_unused)?;
+                    this.warn_unused = Some(get_boollit(ident, value)?);
+                } else if ident == "expose_source" {
+                    ensure_only_once(ident, &mut this.expose_source)?;
+                    this.expose_source = Some(get_boollit(ident, value)?);
+                } else if ident == "error_placeholder" {
+                    set_strlit_pair(ident, value, &mut this.error_placeholder)?;
                 } else if ident == "escape" {
                     set_strlit_pair(ident, value, &mut this.escape)?;
+                } else if ident == "escape_field" {
+                    set_strlit_pair(ident, value, &mut this.escape_field)?;
+                } else if ident == "fluent_bundle" {
+                    set_strlit_pair(ident, value, &mut this.fluent_bundle)?;
+                } else if ident == "mime" {
+                    set_strlit_pair(ident, value, &mut this.mime)?;
                 } else if ident == "ext" {
                     set_strlit_pair(ident, value, &mut this.ext)?;
                 } else if ident == "syntax" {
                     set_strlit_pair(ident, value, &mut this.syntax)?;
                 } else if ident == "config" {
                     set_strlit_pair(ident, value, &mut this.config)?;
+                } else if ident == "config_source" {
+                    set_strlit_pair(ident, value, &mut this.config_source)?;
                 } else if ident == "whitespace" {
                     set_parseable_string(ident, value, &mut this.whitespace)?;
                 } else {
@@ -825,6 +1095,38 @@ const _: () = {
         }
     }
 
+    fn get_path(name: &Ident, mut expr: Expr) -> Result<syn::Path, CompileError> {
+        loop {
+            match expr {
+                Expr::Path(syn::ExprPath {
+                    qself: None, path, ..
+                }) => return Ok(path),
+                Expr::Group(group) => expr = *group.expr,
+                v => {
+                    return Err(CompileError::no_file_info(
+                        format!("template attribute `{name}` expects a path, e.g. `my::Escaper`"),
+                        Some(v.span()),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn get_strlit_array(name: &Ident, expr: Expr) -> Result<Vec<LitStr>, CompileError> {
+        let parser = |input: ParseStream<'_>| {
+            let content;
+            bracketed!(content in input);
+            Punctuated::<LitStr, Token![,]>::parse_terminated(&content)
+        };
+        match parser.parse2(expr.to_token_stream()) {
+            Ok(values) => Ok(values.into_iter().collect()),
+            Err(_) => Err(CompileError::no_file_info(
+                format!("template attribute `{name}` expects an array of string literals"),
+                Some(expr.span()),
+            )),
+        }
+    }
+
     fn get_strlit(name: &Ident, value: ExprLit) -> Result<LitStr, CompileError> {
         if let Lit::Str(s) = value.lit {
             Ok(s)
@@ -864,13 +1166,62 @@ const _: () = {
     }
 };
 
+/// The extension of a template path, e.g. `"html"` for `hello.html`, or the `ext` argument of
+/// a template defined with `source`
+pub(crate) fn extension(path: &Path) -> Option<&str> {
+    path.extension()?.to_str()
+}
+
+/// The extension that determines the MIME type, e.g. `"html"` for `hello.html.j2`
+fn mime_extension(path: &Path) -> &str {
+    match extension(path) {
+        Some("j2" | "jinja" | "jinja2" | "rinja") => path
+            .file_stem()
+            .and_then(|stem| extension(Path::new(stem)))
+            .unwrap_or_default(),
+        ext => ext.unwrap_or_default(),
+    }
+}
+
+/// The MIME type of the rendered output for a template extension, used if the template has no
+/// `mime` attribute
+fn extension_to_mime_type(ext: &str) -> &'static str {
+    match ext.to_ascii_lowercase().as_str() {
+        "htm" | "html" => "text/html; charset=utf-8",
+        "xhtml" => "application/xhtml+xml",
+        "xml" => "text/xml; charset=utf-8",
+        "svg" => "image/svg+xml",
+        "" | "none" | "txt" => "text/plain; charset=utf-8",
+        "md" => "text/markdown; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "csv" => "text/csv; charset=utf-8",
+        "yaml" | "yml" => "application/yaml",
+        "toml" => "application/toml",
+        _ => "application/octet-stream",
+    }
+}
+
 #[cfg(feature = "code-in-doc")]
 const JINJA_EXTENSIONS: &[&str] = &["j2", "jinja", "jinja2", "rinja"];
 
 #[test]
 fn get_source() {
-    let path = Config::new("", None, None, None)
-        .and_then(|config| config.find_template("b.html", None, None))
-        .unwrap();
-    assert_eq!(get_template_source(&path, None).unwrap(), "bar".into());
+    let config = Config::new("", None, None, None).unwrap();
+    let path = config.find_template("b.html", None, None).unwrap();
+    assert_eq!(
+        get_template_source(config, &path, None).unwrap(),
+        "bar".into()
+    );
+}
+
+#[test]
+fn get_source_crlf() {
+    let config = Config::new("", None, None, None).unwrap();
+    let path = config.find_template("c-crlf.html", None, None).unwrap();
+    assert_eq!(
+        get_template_source(config, &path, None).unwrap(),
+        "foo\n\rbar\n".into()
+    );
 }
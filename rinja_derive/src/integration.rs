@@ -1,11 +1,17 @@
 use std::fmt::{Arguments, Display, Write};
 
+use proc_macro2::Span;
 use quote::quote;
 use syn::DeriveInput;
 
 /// Implement every integration for the given item
-pub(crate) fn impl_everything(ast: &DeriveInput, buf: &mut Buffer) {
-    impl_display(ast, buf);
+///
+/// The `Display` implementation is skipped if `display` is `false`, e.g. if the user wants to
+/// implement it themselves.
+pub(crate) fn impl_everything(ast: &DeriveInput, buf: &mut Buffer, display: bool) {
+    if display {
+        impl_display(ast, buf);
+    }
     impl_fast_writable(ast, buf);
 }
 
@@ -71,6 +77,8 @@ pub(crate) struct Buffer {
     buf: String,
     discard: bool,
     last_was_write_str: bool,
+    // Warnings that are emitted alongside the generated code
+    warnings: Vec<(String, Span)>,
 }
 
 impl Display for Buffer {
@@ -85,6 +93,7 @@ impl Buffer {
             buf: String::new(),
             discard: false,
             last_was_write_str: false,
+            warnings: Vec::new(),
         }
     }
 
@@ -156,6 +165,15 @@ impl Buffer {
     pub(crate) fn clear(&mut self) {
         self.buf.clear();
         self.last_was_write_str = false;
+        self.warnings.clear();
+    }
+
+    pub(crate) fn warn(&mut self, msg: impl Into<String>, span: Span) {
+        self.warnings.push((msg.into(), span));
+    }
+
+    pub(crate) fn take_warnings(&mut self) -> Vec<(String, Span)> {
+        std::mem::take(&mut self.warnings)
     }
 
     pub(crate) fn get_mark(&mut self) -> usize {
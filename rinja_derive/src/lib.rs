@@ -20,7 +20,7 @@ use std::sync::Mutex;
 
 use config::{Config, read_config_file};
 use generator::template_to_string;
-use heritage::{Context, Heritage};
+use heritage::{Context, Heritage, check_block_overrides};
 use input::{Print, TemplateArgs, TemplateInput};
 use integration::Buffer;
 use parser::{Parsed, strip_common};
@@ -92,6 +92,12 @@ use rustc_hash::FxBuildHasher;
 /// }
 /// ```
 ///
+/// ### blocks
+///
+/// E.g. `blocks = ["title", "body"]`
+///
+/// Allow rendering the listed blocks by themselves with `Template::render_block("name")`.
+///
 /// ### print
 ///
 /// E.g. `print = "code"`
@@ -100,6 +106,25 @@ use rustc_hash::FxBuildHasher;
 /// the generated code (`code`) or `all` for both.
 /// The requested data will be printed to stdout at compile time.
 ///
+/// ### warn_unused
+///
+/// E.g. `warn_unused = true`
+///
+/// Emit a compiler warning for every field of the struct that is never read by the template.
+///
+/// ### expose_source
+///
+/// E.g. `expose_source = true`
+///
+/// Expose the source code of the template as `Template::SOURCE`.
+///
+/// ### error_placeholder
+///
+/// E.g. `error_placeholder = "unavailable"`
+///
+/// Replace failing `{{ expressions }}` by this text instead of stopping the rendering.
+/// Use `Template::render_collecting()` to get the errors.
+///
 /// ### escape
 ///
 /// E.g. `escape = "none"`
@@ -107,6 +132,20 @@ use rustc_hash::FxBuildHasher;
 /// Override the template's extension used for the purpose of determining the escaper for
 /// this template. See the section on configuring custom escapers for more information.
 ///
+/// ### escape_field
+///
+/// E.g. `escape_field = "format"`
+///
+/// Select the escaper at runtime: the template is escaped as HTML or not at all, depending on
+/// the value of the named field, which is converted into a `rinja::filters::EscapeMode`.
+///
+/// ### fluent_bundle
+///
+/// E.g. `fluent_bundle = "bundle"`
+///
+/// Name the field that holds the Fluent bundle, in which `{% trans %}` blocks look up their
+/// messages. Requires the `fluent` feature.
+///
 /// ### syntax
 ///
 /// E.g. `syntax = "foo"`
@@ -138,7 +177,14 @@ pub fn derive_template(input: TokenStream12) -> TokenStream12 {
         }
         ts.into()
     } else {
-        buf.into_string().parse().unwrap()
+        let warnings = buf.take_warnings();
+        let mut ts: TokenStream = buf.into_string().parse().unwrap();
+        ts.extend(
+            warnings
+                .into_iter()
+                .map(|(msg, span)| compile_warning(&msg, span)),
+        );
+        ts.into()
     }
 }
 
@@ -152,6 +198,19 @@ fn compile_error(msgs: impl Iterator<Item = String>, span: Span) -> TokenStream
     }
 }
 
+// Stable Rust offers no way for a proc-macro to emit a warning, but using a deprecated item does.
+fn compile_warning(msg: &str, span: Span) -> TokenStream {
+    quote_spanned! {
+        span =>
+        const _: () = {
+            #[deprecated(note = #msg)]
+            #[allow(non_upper_case_globals)]
+            const __rinja_warning: () = ();
+            __rinja_warning
+        };
+    }
+}
+
 fn build_skeleton(buf: &mut Buffer, ast: &syn::DeriveInput) -> Result<usize, CompileError> {
     let template_args = TemplateArgs::fallback();
     let config = Config::new("", None, None, None)?;
@@ -189,8 +248,14 @@ fn build_template_item(
     template_args: &TemplateArgs,
     target: Option<&str>,
 ) -> Result<usize, CompileError> {
-    let config_path = template_args.config_path();
-    let s = read_config_file(config_path, template_args.config_span)?;
+    let (s, config_path) = match template_args.config_source() {
+        Some(source) => (source.to_owned(), Some("<config_source attribute>")),
+        None => {
+            let config_path = template_args.config_path();
+            let s = read_config_file(config_path, template_args.config_span)?;
+            (s, config_path)
+        }
+    };
     let config = Config::new(
         &s,
         config_path,
@@ -200,7 +265,7 @@ fn build_template_item(
     let input = TemplateInput::new(ast, config, template_args)?;
 
     let mut templates = HashMap::default();
-    input.find_used_templates(&mut templates)?;
+    input.find_used_templates(&mut templates, buf)?;
 
     let mut contexts = HashMap::default();
     for (path, parsed) in &templates {
@@ -209,6 +274,9 @@ fn build_template_item(
 
     let ctx = &contexts[&input.path];
     let heritage = if !ctx.blocks.is_empty() || ctx.extends.is_some() {
+        if input.config.check_block_overrides {
+            check_block_overrides(ctx, &contexts)?;
+        }
         let heritage = Heritage::new(ctx, &contexts);
 
         if let Some(block_name) = input.block {
@@ -224,6 +292,17 @@ fn build_template_item(
     } else {
         None
     };
+    for (block_name, span) in input.blocks {
+        if !heritage
+            .as_ref()
+            .is_some_and(|heritage| heritage.blocks.contains_key(block_name.as_str()))
+        {
+            return Err(CompileError::no_file_info(
+                format!("cannot find block {block_name}"),
+                Some(*span),
+            ));
+        }
+    }
 
     if input.print == Print::Ast || input.print == Print::All {
         eprintln!("{:?}", templates[&input.path].nodes());
@@ -379,6 +458,25 @@ impl fmt::Display for MsgValidEscapers<'_> {
     }
 }
 
+struct MsgValidMimeTypes<'a>(&'a [(Vec<Cow<'a, str>>, Cow<'a, str>)]);
+
+impl fmt::Display for MsgValidMimeTypes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut mime_types = self
+            .0
+            .iter()
+            .flat_map(|(mime_types, _)| mime_types)
+            .map(|x| format!("{x:?}"))
+            .collect::<Vec<_>>();
+        mime_types.sort();
+        write!(
+            f,
+            "The available MIME types are: {}",
+            mime_types.join(", ")
+        )
+    }
+}
+
 #[derive(Debug)]
 struct OnceMap<K, V>([Mutex<HashMap<K, V, FxBuildHasher>>; 8]);
 
@@ -469,13 +567,14 @@ pub(crate) use {fmt_left, fmt_right};
 // This is used by the code generator to decide whether a named filter is part of
 // Rinja or should refer to a local `filters` module.
 const BUILTIN_FILTERS: &[&str] = &[
+    "abs",
     "capitalize",
     "center",
     "indent",
     "lower",
     "lowercase",
+    "striptags",
     "title",
-    "trim",
     "truncate",
     "upper",
     "uppercase",
@@ -483,4 +582,34 @@ const BUILTIN_FILTERS: &[&str] = &[
 ];
 
 // Built-in filters that need the `alloc` feature.
-const BUILTIN_FILTERS_NEED_ALLOC: &[&str] = &["center", "truncate"];
+const BUILTIN_FILTERS_NEED_ALLOC: &[&str] = &["striptags", "truncate"];
+
+// Built-in filters that accept named arguments, e.g. `map(attr="name")`.
+const FILTERS_WITH_NAMED_ARGUMENTS: &[&str] = &[
+    "center",
+    "columns",
+    "dictsort",
+    "float",
+    "group_by",
+    "indent",
+    "int",
+    "join",
+    "map",
+    "markdown",
+    "max",
+    "min",
+    "random",
+    "reject",
+    "replace",
+    "round",
+    "select",
+    "shuffle",
+    "slice",
+    "sort",
+    "sum",
+    "trim",
+    "trim_end",
+    "trim_start",
+    "unique",
+    "urlize",
+];
@@ -19,9 +19,57 @@ fn build_template(ast: &syn::DeriveInput) -> Result<String, crate::CompileError>
 // the code we want to check.
 #[track_caller]
 fn compare(jinja: &str, expected: &str, fields: &[(&str, &str)], size_hint: usize) {
+    compare_with_items(jinja, expected, "", fields, size_hint);
+}
+
+#[track_caller]
+fn compare_with_items(
+    jinja: &str,
+    expected: &str,
+    items: &str,
+    fields: &[(&str, &str)],
+    size_hint: usize,
+) {
     let generated = jinja_to_rust(jinja, fields).unwrap();
 
     let expected: proc_macro2::TokenStream = expected.parse().unwrap();
+    let items: proc_macro2::TokenStream = items.parse().unwrap();
+    let line_starts = jinja
+        .match_indices('\n')
+        .map(|(offset, _)| proc_macro2::Literal::usize_unsuffixed(offset + 1));
+    #[cfg(feature = "adaptive-size-hint")]
+    let adaptive_size_hint = quote::quote! {
+        #[inline]
+        fn adaptive_size_hint() -> rinja::helpers::core::option::Option<
+            &'static rinja::helpers::AdaptiveSizeHint
+        > {
+            static HINT: rinja::helpers::AdaptiveSizeHint =
+                rinja::helpers::AdaptiveSizeHint::new(#size_hint);
+            rinja::helpers::core::option::Option::Some(&HINT)
+        }
+    };
+    #[cfg(not(feature = "adaptive-size-hint"))]
+    let adaptive_size_hint = quote::quote! {};
+    #[cfg(feature = "tracing")]
+    let body = quote::quote! {
+        let __rinja_span = rinja::helpers::tracing::info_span!(
+            "Foo.txt",
+            size_hint = #size_hint,
+            len = rinja::helpers::tracing::field::Empty,
+        );
+        let __rinja_writer = &mut rinja::helpers::CountingWriter::new(__rinja_writer);
+        let __rinja_result = __rinja_span.in_scope(|| -> rinja::Result<()> {
+            #expected
+            rinja::Result::Ok(())
+        });
+        __rinja_span.record("len", __rinja_writer.bytes_written());
+        __rinja_result
+    };
+    #[cfg(not(feature = "tracing"))]
+    let body = quote::quote! {
+        #expected
+        rinja::Result::Ok(())
+    };
     let expected: syn::File = syn::parse_quote! {
         const _: () = {
             extern crate rinja as rinja;
@@ -34,10 +82,15 @@ fn compare(jinja: &str, expected: &str, fields: &[(&str, &str)], size_hint: usiz
                     use rinja::filters::{AutoEscape as _, WriteWritable as _};
                     use rinja::helpers::ResultConverter as _;
                     use rinja::helpers::core::fmt::Write as _;
-                    #expected
-                    rinja::Result::Ok(())
+                    #body
                 }
                 const SIZE_HINT: rinja::helpers::core::primitive::usize = #size_hint;
+                #adaptive_size_hint
+                const LINE_STARTS: &'static [rinja::helpers::core::primitive::usize] = &[0, #(#line_starts,)*];
+                const EXTENSION: rinja::helpers::core::option::Option<&'static rinja::helpers::core::primitive::str> =
+                    rinja::helpers::core::option::Option::Some("txt");
+                const MIME_TYPE: &'static rinja::helpers::core::primitive::str = "text/plain; charset=utf-8";
+                #items
             }
 
             /// Implement the [`format!()`][rinja::helpers::std::format] trait for [`Foo`]
@@ -310,7 +363,7 @@ fn check_includes_only_once() {
     let path1 = path.join("include1.html").canonicalize().unwrap();
     let path2 = path.join("include2.html").canonicalize().unwrap();
     let path3 = path.join("include3.html").canonicalize().unwrap();
-    compare(
+    compare_with_items(
         r#"{% include "include1.html" %}"#,
         &format!(
             r#"const _: &[rinja::helpers::core::primitive::u8] = rinja::helpers::core::include_bytes!({path1:#?});
@@ -318,6 +371,10 @@ fn check_includes_only_once() {
             const _: &[rinja::helpers::core::primitive::u8] = rinja::helpers::core::include_bytes!({path3:#?});
             __rinja_writer.write_str("3333")?;"#
         ),
+        &format!(
+            r#"const DEPENDENCIES: &'static [&'static rinja::helpers::core::primitive::str] =
+                &[{path1:#?}, {path2:#?}, {path3:#?}];"#
+        ),
         &[],
         4,
     );
@@ -782,6 +839,53 @@ A
     );
 }
 
+#[test]
+fn check_constant_folding() {
+    compare(
+        r#"{{ 2 + 3 }} {{ (7 - 10) * 2 }} {{ -(7 / 2) % 3 }} {{ 1.5 * 3.0 }} {{ 250u8 + 5 }}"#,
+        r#"__rinja_writer.write_str("5 -6 0 4.5 255")?;"#,
+        &[],
+        14,
+    );
+
+    compare(
+        r#"{{ "a" ~ ("b" ~ (1 + 2)) }} {{ !false }} {{ ("<" ~ '>') }}"#,
+        r#"__rinja_writer.write_str("ab3 true <>")?;"#,
+        &[],
+        11,
+    );
+
+    // expressions that contain a variable, overflow, divide by zero or mix types are left to rustc
+    compare(
+        r#"{{ x + 1 }}{{ 2147483647 + 1 }}{{ 255u8 + 1 }}{{ 1 / 0 }}{{ 1u8 + 1u16 }}{{ -1u8 }}"#,
+        r#"match (
+            &((&&rinja::filters::AutoEscaper::new(&(self.x + 1), rinja::filters::Text))
+                .rinja_auto_escape()?),
+            &((&&rinja::filters::AutoEscaper::new(&(2147483647 + 1), rinja::filters::Text))
+                .rinja_auto_escape()?),
+            &((&&rinja::filters::AutoEscaper::new(&(255u8 + 1), rinja::filters::Text))
+                .rinja_auto_escape()?),
+            &((&&rinja::filters::AutoEscaper::new(&(1 / 0), rinja::filters::Text))
+                .rinja_auto_escape()?),
+            &((&&rinja::filters::AutoEscaper::new(&(1u8 + 1u16), rinja::filters::Text))
+                .rinja_auto_escape()?),
+            &((&&rinja::filters::AutoEscaper::new(&(-1u8), rinja::filters::Text))
+                .rinja_auto_escape()?),
+        ) {
+            (expr0, expr1, expr2, expr3, expr4, expr5) => {
+                (&&rinja::filters::Writable(expr0)).rinja_write(__rinja_writer)?;
+                (&&rinja::filters::Writable(expr1)).rinja_write(__rinja_writer)?;
+                (&&rinja::filters::Writable(expr2)).rinja_write(__rinja_writer)?;
+                (&&rinja::filters::Writable(expr3)).rinja_write(__rinja_writer)?;
+                (&&rinja::filters::Writable(expr4)).rinja_write(__rinja_writer)?;
+                (&&rinja::filters::Writable(expr5)).rinja_write(__rinja_writer)?;
+            }
+        }"#,
+        &[("x", "u32")],
+        18,
+    );
+}
+
 #[cfg(feature = "code-in-doc")]
 #[test]
 fn test_code_in_comment() {
@@ -1129,3 +1233,26 @@ fn extends_with_whitespace_control() {
         }
     }
 }
+
+// Without the `chrono` and `time` features, `date` is not a built-in filter, so a custom filter
+// with the same name is used instead.
+#[cfg(not(any(feature = "chrono", feature = "time")))]
+#[test]
+fn test_custom_date_filter() {
+    compare(
+        r#"{{ day|date("%Y") }}"#,
+        r#"match (
+            &((&&rinja::filters::AutoEscaper::new(
+                &(filters::date(&(self.day), "%Y")?),
+                rinja::filters::Text,
+            ))
+                .rinja_auto_escape()?),
+        ) {
+            (expr0,) => {
+                (&&rinja::filters::Writable(expr0)).rinja_write(__rinja_writer)?;
+            }
+        }"#,
+        &[("day", "u32")],
+        3,
+    );
+}
@@ -60,6 +60,7 @@ fn check_expr<'a>(
         | Expr::Path(_)
         | Expr::Attr(_, _)
         | Expr::Filter(_)
+        | Expr::Test(_)
         | Expr::NamedArgument(_, _)
         | Expr::Var(_)
         | Expr::RustMacro(_, _)
@@ -76,6 +77,11 @@ fn check_expr<'a>(
             check_expr(elem1, false)?;
             check_expr(elem2, false)
         }
+        Expr::IfElse(cond, then, otherwise) => {
+            check_expr(cond, false)?;
+            check_expr(then, false)?;
+            check_expr(otherwise, false)
+        }
         Expr::Range(_, elem1, elem2) => {
             if let Some(elem1) = elem1 {
                 check_expr(elem1, false)?;
@@ -130,60 +136,71 @@ pub enum Expr<'a> {
     FilterSource,
     IsDefined(&'a str),
     IsNotDefined(&'a str),
+    /// `value is [not] test`, e.g. `x is none` or `x is not gt(3)`, except for `is defined`.
+    Test(Test<'a>),
     Concat(Vec<WithSpan<'a, Expr<'a>>>),
     /// If you have `&& let Some(y)`, this variant handles it.
     LetCond(Box<WithSpan<'a, CondTest<'a>>>),
+    /// Inline conditional `then if cond else otherwise`, stored as `(cond, then, otherwise)`.
+    IfElse(
+        Box<WithSpan<'a, Expr<'a>>>,
+        Box<WithSpan<'a, Expr<'a>>>,
+        Box<WithSpan<'a, Expr<'a>>>,
+    ),
 }
 
 impl<'a> Expr<'a> {
     pub(super) fn arguments(
         i: &mut &'a str,
         level: Level<'_>,
-        is_template_macro: bool,
+        allow_named_arguments: bool,
     ) -> ParseResult<'a, Vec<WithSpan<'a, Self>>> {
         let _level_guard = level.nest(i)?;
         let mut named_arguments = HashSet::new();
         let start = *i;
 
-        preceded(
+        let args: Vec<_> = preceded(
             ws('('),
-            cut_err(terminated(
-                separated(
-                    0..,
-                    ws(move |i: &mut _| {
-                        // Needed to prevent borrowing it twice between this closure and the one
-                        // calling `Self::named_arguments`.
-                        let named_arguments = &mut named_arguments;
-                        let has_named_arguments = !named_arguments.is_empty();
-
-                        let expr = alt((
-                            move |i: &mut _| {
-                                Self::named_argument(
-                                    i,
-                                    level,
-                                    named_arguments,
-                                    start,
-                                    is_template_macro,
-                                )
-                            },
-                            move |i: &mut _| Self::parse(i, level, false),
-                        ))
-                        .parse_next(i)?;
-                        if has_named_arguments && !matches!(*expr, Self::NamedArgument(_, _)) {
-                            Err(winnow::error::ErrMode::Cut(ErrorContext::new(
-                                "named arguments must always be passed last",
+            cut_err(separated(
+                0..,
+                ws(move |i: &mut _| {
+                    // Needed to prevent borrowing it twice between this closure and the one
+                    // calling `Self::named_arguments`.
+                    let named_arguments = &mut named_arguments;
+                    let has_named_arguments = !named_arguments.is_empty();
+
+                    let expr = alt((
+                        move |i: &mut _| {
+                            Self::named_argument(
+                                i,
+                                level,
+                                named_arguments,
                                 start,
-                            )))
-                        } else {
-                            Ok(expr)
-                        }
-                    }),
-                    ',',
-                ),
-                (opt(ws(',')), ')'),
+                                allow_named_arguments,
+                            )
+                        },
+                        move |i: &mut _| Self::parse(i, level, false),
+                    ))
+                    .parse_next(i)?;
+                    if has_named_arguments && !matches!(*expr, Self::NamedArgument(_, _)) {
+                        Err(winnow::error::ErrMode::Cut(ErrorContext::new(
+                            "named arguments must always be passed last",
+                            start,
+                        )))
+                    } else {
+                        Ok(expr)
+                    }
+                }),
+                ',',
             )),
         )
-        .parse_next(i)
+        .parse_next(i)?;
+        // Like in Rust, a trailing comma is only allowed after an argument.
+        if !args.is_empty() {
+            opt(ws(',')).parse_next(i)?;
+        }
+        cut_err(')').parse_next(i)?;
+        Ok(args)
     }
 
     fn named_argument(
@@ -191,11 +208,11 @@ impl<'a> Expr<'a> {
         level: Level<'_>,
         named_arguments: &mut HashSet<&'a str>,
         start: &'a str,
-        is_template_macro: bool,
+        allow_named_arguments: bool,
     ) -> ParseResult<'a, WithSpan<'a, Self>> {
-        if !is_template_macro {
-            // If this is not a template macro, we don't want to parse named arguments so
-            // we instead return an error which will allow to continue the parsing.
+        if !allow_named_arguments {
+            // If named arguments are not allowed here (e.g. in function calls), we don't want to
+            // parse them so we instead return an error which will allow to continue the parsing.
             return fail.parse_next(i);
         }
 
@@ -245,6 +262,23 @@ impl<'a> Expr<'a> {
             }),
         ))
         .parse_next(i)?;
+
+        // If there is no `else`, then this `if` belongs to the surrounding node, e.g. `{% for %}`.
+        let if_else = opt((
+            ws(keyword("if")),
+            move |i: &mut _| Self::or(i, level),
+            ws(keyword("else")),
+            move |i: &mut _| Self::parse(i, level, false),
+        ))
+        .parse_next(i)?;
+        let expr = match if_else {
+            Some((_, cond, _, otherwise)) => WithSpan::new(
+                Self::IfElse(Box::new(cond), Box::new(expr), Box::new(otherwise)),
+                start,
+            ),
+            None => expr,
+        };
+
         check_expr(&expr, allow_underscore)?;
         Ok(expr)
     }
@@ -329,17 +363,32 @@ impl<'a> Expr<'a> {
             }
         }
 
-        let rhs = opt(terminated(opt(keyword("not")), ws(keyword("defined")))).parse_next(i)?;
-        let ctor = match rhs {
-            None => {
-                return Err(winnow::error::ErrMode::Cut(ErrorContext::new(
-                    "expected `defined` or `not defined` after `is`",
-                    // We use `start` to show the whole `var is` thing instead of the current token.
-                    start,
-                )));
-            }
-            Some(None) => Self::IsDefined,
-            Some(Some(_)) => Self::IsNotDefined,
+        let negated = opt(ws(keyword("not"))).parse_next(i)?.is_some();
+        let Some(name) = opt(ws(identifier)).parse_next(i)? else {
+            return Err(winnow::error::ErrMode::Cut(ErrorContext::new(
+                "expected the name of a test after `is`, e.g. `defined` or `not none`",
+                // We use `start` to show the whole `var is` thing instead of the current token.
+                start,
+            )));
+        };
+        if name != "defined" {
+            let mut arguments = opt(|i: &mut _| Self::arguments(i, level, false))
+                .parse_next(i)?
+                .unwrap_or_default();
+            arguments.insert(0, lhs);
+            return Ok(WithSpan::new(
+                Self::Test(Test {
+                    name,
+                    negated,
+                    arguments,
+                }),
+                start,
+            ));
+        }
+
+        let ctor = match negated {
+            false => Self::IsDefined,
+            true => Self::IsNotDefined,
         };
         let var_name = match *lhs {
             Self::Var(var_name) => var_name,
@@ -508,6 +557,7 @@ impl<'a> Expr<'a> {
             | Self::Try(_)
             | Self::NamedArgument(_, _)
             | Self::Filter(_)
+            | Self::Test(_)
             | Self::Attr(_, _)
             | Self::Index(_, _)
             | Self::Tuple(_)
@@ -515,7 +565,8 @@ impl<'a> Expr<'a> {
             | Self::BinOp(_, _, _)
             | Self::Path(_)
             | Self::Concat(_)
-            | Self::LetCond(_) => false,
+            | Self::LetCond(_)
+            | Self::IfElse(_, _, _) => false,
         }
     }
 }
@@ -550,6 +601,14 @@ pub struct Filter<'a> {
     pub arguments: Vec<WithSpan<'a, Expr<'a>>>,
 }
 
+/// A test like `none` in `x is not none`; the tested value is the first argument.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Test<'a> {
+    pub name: &'a str,
+    pub negated: bool,
+    pub arguments: Vec<WithSpan<'a, Expr<'a>>>,
+}
+
 enum Suffix<'a> {
     Attr(&'a str),
     Index(WithSpan<'a, Expr<'a>>),
@@ -3,7 +3,8 @@
 #![deny(unreachable_pub)]
 
 use std::borrow::Cow;
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
 use std::env::current_dir;
 use std::ops::{Deref, DerefMut};
 use std::path::Path;
@@ -18,7 +19,7 @@ use winnow::stream::{AsChar, Stream as _};
 use winnow::token::{any, one_of, take_till, take_while};
 
 pub mod expr;
-pub use expr::{Expr, Filter};
+pub use expr::{Expr, Filter, Test};
 mod memchr_splitter;
 pub mod node;
 pub use node::Node;
@@ -115,7 +116,9 @@ impl<'a> Ast<'a> {
         let state = State {
             syntax,
             loop_depth: Cell::new(0),
+            loop_labels: RefCell::new(Vec::new()),
             level: Level(&level),
+            calls_without_body: RefCell::new(HashSet::new()),
         };
         match Node::parse_template(&mut src, &state) {
             Ok(nodes) if src.is_empty() => Ok(Self { nodes }),
@@ -183,7 +186,7 @@ impl<'a> Span<'a> {
 impl<'a> From<&'a str> for Span<'a> {
     #[inline]
     fn from(value: &'a str) -> Self {
-        Self(value[..0].as_bytes().try_into().unwrap())
+        Self(value.as_bytes()[..0].try_into().unwrap())
     }
 }
 
@@ -737,7 +740,10 @@ fn path_or_identifier<'a>(i: &mut &'a str) -> ParseResult<'a, PathOrIdentifier<'
 struct State<'a, 'l> {
     syntax: &'l Syntax<'a>,
     loop_depth: Cell<usize>,
+    loop_labels: RefCell<Vec<String>>,
     level: Level<'l>,
+    /// Positions (as length of the remaining input) of `{% call %}` tags without `{% endcall %}`
+    calls_without_body: RefCell<HashSet<usize>>,
 }
 
 impl State<'_, '_> {
@@ -779,17 +785,27 @@ impl State<'_, '_> {
         self.syntax.expr_end.value(()).parse_next(i)
     }
 
-    fn enter_loop(&self) {
+    fn enter_loop(&self, label: Option<&str>) {
         self.loop_depth.set(self.loop_depth.get() + 1);
+        if let Some(label) = label {
+            self.loop_labels.borrow_mut().push(label.to_owned());
+        }
     }
 
-    fn leave_loop(&self) {
+    fn leave_loop(&self, label: Option<&str>) {
         self.loop_depth.set(self.loop_depth.get() - 1);
+        if label.is_some() {
+            self.loop_labels.borrow_mut().pop();
+        }
     }
 
     fn is_in_loop(&self) -> bool {
         self.loop_depth.get() > 0
     }
+
+    fn has_loop_label(&self, label: &str) -> bool {
+        self.loop_labels.borrow().iter().any(|l| l == label)
+    }
 }
 
 #[derive(Default, Hash, PartialEq, Clone, Copy)]
@@ -863,6 +879,7 @@ pub struct SyntaxBuilder<'a> {
     pub expr_end: Option<&'a str>,
     pub comment_start: Option<&'a str>,
     pub comment_end: Option<&'a str>,
+    pub whitespace: Option<node::Whitespace>,
 }
 
 impl<'a> SyntaxBuilder<'a> {
@@ -1025,7 +1042,7 @@ fn filter<'a>(
     let _level_guard = level.nest(i)?;
     cut_err((
         ws(identifier),
-        opt(|i: &mut _| Expr::arguments(i, level, false)),
+        opt(|i: &mut _| Expr::arguments(i, level, true)),
     ))
     .parse_next(i)
 }
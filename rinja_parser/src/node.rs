@@ -1,3 +1,4 @@
+use std::cell::Cell;
 use std::collections::HashSet;
 use std::str::{self, FromStr};
 
@@ -22,18 +23,23 @@ pub enum Node<'a> {
     Expr(Ws, WithSpan<'a, Expr<'a>>),
     Call(WithSpan<'a, Call<'a>>),
     Let(WithSpan<'a, Let<'a>>),
+    SetBlock(WithSpan<'a, SetBlock<'a>>),
     If(WithSpan<'a, If<'a>>),
     Match(WithSpan<'a, Match<'a>>),
     Loop(Box<WithSpan<'a, Loop<'a>>>),
     Extends(WithSpan<'a, Extends<'a>>),
     BlockDef(WithSpan<'a, BlockDef<'a>>),
     Include(WithSpan<'a, Include<'a>>),
+    IncludeAll(WithSpan<'a, IncludeAll<'a>>),
     Import(WithSpan<'a, Import<'a>>),
     Macro(WithSpan<'a, Macro<'a>>),
     Raw(WithSpan<'a, Raw<'a>>),
-    Break(WithSpan<'a, Ws>),
-    Continue(WithSpan<'a, Ws>),
+    Break(WithSpan<'a, LoopControl<'a>>),
+    Continue(WithSpan<'a, LoopControl<'a>>),
     FilterBlock(WithSpan<'a, FilterBlock<'a>>),
+    AutoEscape(WithSpan<'a, AutoEscape<'a>>),
+    With(WithSpan<'a, With<'a>>),
+    Trans(WithSpan<'a, Trans<'a>>),
 }
 
 impl<'a> Node<'a> {
@@ -92,12 +98,19 @@ impl<'a> Node<'a> {
 
         let func = match tag {
             "call" => |i: &mut _, s| Call::parse(i, s).map(Self::Call),
-            "let" | "set" => |i: &mut _, s| Let::parse(i, s).map(Self::Let),
+            "let" | "set" => |i: &mut _, s| {
+                alt((
+                    |i: &mut _| SetBlock::parse(i, s).map(Self::SetBlock),
+                    |i: &mut _| Let::parse(i, s).map(Self::Let),
+                ))
+                .parse_next(i)
+            },
             "if" => |i: &mut _, s| If::parse(i, s).map(Self::If),
             "for" => |i: &mut _, s| Loop::parse(i, s).map(|n| Self::Loop(Box::new(n))),
             "match" => |i: &mut _, s| Match::parse(i, s).map(Self::Match),
             "extends" => |i: &mut _, _s| Extends::parse(i).map(Self::Extends),
-            "include" => |i: &mut _, _s| Include::parse(i).map(Self::Include),
+            "include" => |i: &mut _, s| Include::parse(i, s).map(Self::Include),
+            "include_all" => |i: &mut _, _s| IncludeAll::parse(i).map(Self::IncludeAll),
             "import" => |i: &mut _, _s| Import::parse(i).map(Self::Import),
             "block" => |i: &mut _, s| BlockDef::parse(i, s).map(Self::BlockDef),
             "macro" => |i: &mut _, s| Macro::parse(i, s).map(Self::Macro),
@@ -105,6 +118,9 @@ impl<'a> Node<'a> {
             "break" => |i: &mut _, s| Self::r#break(i, s),
             "continue" => |i: &mut _, s| Self::r#continue(i, s),
             "filter" => |i: &mut _, s| FilterBlock::parse(i, s).map(Self::FilterBlock),
+            "autoescape" => |i: &mut _, s| AutoEscape::parse(i, s).map(Self::AutoEscape),
+            "with" => |i: &mut _, s| With::parse(i, s).map(Self::With),
+            "trans" => |i: &mut _, s| Trans::parse(i, s).map(Self::Trans),
             _ => return fail.parse_next(&mut start),
         };
 
@@ -128,36 +144,52 @@ impl<'a> Node<'a> {
         let mut p = (
             opt(Whitespace::parse),
             ws(keyword("break")),
+            opt(ws(identifier)),
             opt(Whitespace::parse),
         );
 
         let start = *i;
-        let (pws, _, nws) = p.parse_next(i)?;
+        let (pws, _, label, nws) = p.parse_next(i)?;
         if !s.is_in_loop() {
             return Err(winnow::error::ErrMode::Cut(ErrorContext::new(
                 "you can only `break` inside a `for` loop",
                 start,
             )));
         }
-        Ok(Self::Break(WithSpan::new(Ws(pws, nws), start)))
+        LoopControl::check_label(s, "break", label, start)?;
+        Ok(Self::Break(WithSpan::new(
+            LoopControl {
+                ws: Ws(pws, nws),
+                label,
+            },
+            start,
+        )))
     }
 
     fn r#continue(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, Self> {
         let mut p = (
             opt(Whitespace::parse),
             ws(keyword("continue")),
+            opt(ws(identifier)),
             opt(Whitespace::parse),
         );
 
         let start = *i;
-        let (pws, _, nws) = p.parse_next(i)?;
+        let (pws, _, label, nws) = p.parse_next(i)?;
         if !s.is_in_loop() {
             return Err(winnow::error::ErrMode::Cut(ErrorContext::new(
                 "you can only `continue` inside a `for` loop",
                 start,
             )));
         }
-        Ok(Self::Continue(WithSpan::new(Ws(pws, nws), start)))
+        LoopControl::check_label(s, "continue", label, start)?;
+        Ok(Self::Continue(WithSpan::new(
+            LoopControl {
+                ws: Ws(pws, nws),
+                label,
+            },
+            start,
+        )))
     }
 
     fn expr(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, Self> {
@@ -200,18 +232,23 @@ impl<'a> Node<'a> {
             Self::Expr(_, span) => span.span,
             Self::Call(span) => span.span,
             Self::Let(span) => span.span,
+            Self::SetBlock(span) => span.span,
             Self::If(span) => span.span,
             Self::Match(span) => span.span,
             Self::Loop(span) => span.span,
             Self::Extends(span) => span.span,
             Self::BlockDef(span) => span.span,
             Self::Include(span) => span.span,
+            Self::IncludeAll(span) => span.span,
             Self::Import(span) => span.span,
             Self::Macro(span) => span.span,
             Self::Raw(span) => span.span,
             Self::Break(span) => span.span,
             Self::Continue(span) => span.span,
             Self::FilterBlock(span) => span.span,
+            Self::AutoEscape(span) => span.span,
+            Self::With(span) => span.span,
+            Self::Trans(span) => span.span,
         }
     }
 }
@@ -251,7 +288,7 @@ fn unexpected_tag<'a>(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, ()>
 fn unexpected_raw_tag<'a>(kind: Option<&'static str>, i: &mut &'a str) -> ParseResult<'a, ()> {
     let tag = peek(ws(identifier)).parse_next(i)?;
     let msg = match tag {
-        "end" | "elif" | "else" | "when" => match kind {
+        "end" | "elif" | "else" | "when" | "pluralize" => match kind {
             Some(kind) => {
                 format!("node `{tag}` was not expected in the current context: `{kind}` block")
             }
@@ -504,6 +541,7 @@ pub struct Loop<'a> {
     pub ws1: Ws,
     pub var: Target<'a>,
     pub iter: WithSpan<'a, Expr<'a>>,
+    pub label: Option<&'a str>,
     pub cond: Option<WithSpan<'a, Expr<'a>>>,
     pub body: Vec<Node<'a>>,
     pub ws2: Ws,
@@ -513,14 +551,19 @@ pub struct Loop<'a> {
 
 impl<'a> Loop<'a> {
     fn parse(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, WithSpan<'a, Self>> {
-        fn content<'a>(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, Vec<Node<'a>>> {
-            s.enter_loop();
+        fn content<'a>(
+            i: &mut &'a str,
+            s: &State<'_, '_>,
+            label: Option<&str>,
+        ) -> ParseResult<'a, Vec<Node<'a>>> {
+            s.enter_loop(label);
             let result = (|i: &mut _| Node::many(i, s)).parse_next(i);
-            s.leave_loop();
+            s.leave_loop(label);
             result
         }
 
         let start = *i;
+        let label = Cell::new(None);
         let if_cond = preceded(
             ws(keyword("if")),
             cut_node(
@@ -553,7 +596,7 @@ impl<'a> Loop<'a> {
             let (body, (_, pws, else_block, _, nws)) = cut_node(
                 Some("for"),
                 (
-                    |i: &mut _| content(i, s),
+                    |i: &mut _| content(i, s, label.get()),
                     cut_node(
                         Some("for"),
                         (
@@ -581,7 +624,11 @@ impl<'a> Loop<'a> {
                     cut_node(
                         Some("for"),
                         (
-                            ws(|i: &mut _| Expr::parse(i, s.level, true)),
+                            |i: &mut _| {
+                                let (iter, new_label) = Self::iter_and_label(i, s)?;
+                                label.set(new_label);
+                                Ok(iter)
+                            },
                             opt(if_cond),
                             opt(Whitespace::parse),
                             |i: &mut _| s.tag_block_end(i),
@@ -599,6 +646,7 @@ impl<'a> Loop<'a> {
                 ws1: Ws(pws1, nws1),
                 var,
                 iter,
+                label: label.get(),
                 cond,
                 body,
                 ws2: Ws(pws2, nws3),
@@ -608,17 +656,135 @@ impl<'a> Loop<'a> {
             start,
         ))
     }
+
+    /// Parses the iterable of the loop, and an optional loop label, e.g. `items as outer`
+    ///
+    /// `as` is the cast operator in expressions, so `items as outer` cannot be parsed as a single
+    /// expression. If parsing the iterable fails, we try to split off a label at every `as`.
+    fn iter_and_label(
+        i: &mut &'a str,
+        s: &State<'_, '_>,
+    ) -> ParseResult<'a, (WithSpan<'a, Expr<'a>>, Option<&'a str>)> {
+        let label = |i: &mut &'a str| {
+            let start = i.trim_start();
+            let label = opt(preceded(ws(keyword("as")), ws(identifier))).parse_next(i)?;
+            match label {
+                Some(label) if s.has_loop_label(label) => {
+                    Err(winnow::error::ErrMode::Cut(ErrorContext::new(
+                        format!("the loop label `{label}` is already used by an enclosing loop"),
+                        start,
+                    )))
+                }
+                label => Ok(label),
+            }
+        };
+
+        let start = *i;
+        let err = match ws(|i: &mut _| Expr::parse(i, s.level, true)).parse_next(i) {
+            Ok(iter) => return Ok((iter, label(i)?)),
+            Err(err) => err,
+        };
+
+        for (index, _) in start.match_indices("as") {
+            let (mut head, mut tail) = start.split_at(index);
+            if !head.ends_with(|c: char| c.is_ascii_whitespace()) {
+                continue;
+            }
+            let Ok(iter) = ws(|i: &mut _| Expr::parse(i, s.level, true)).parse_next(&mut head)
+            else {
+                continue;
+            };
+            if !head.is_empty() {
+                continue;
+            }
+            if let Some(label) = label(&mut tail)? {
+                *i = tail;
+                return Ok((iter, Some(label)));
+            }
+        }
+
+        *i = start;
+        Err(err)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct LoopControl<'a> {
+    pub ws: Ws,
+    pub label: Option<&'a str>,
+}
+
+impl LoopControl<'_> {
+    fn check_label<'a>(
+        s: &State<'_, '_>,
+        kind: &str,
+        label: Option<&str>,
+        start: &'a str,
+    ) -> Result<(), crate::ParseErr<'a>> {
+        match label {
+            Some(label) if !s.has_loop_label(label) => {
+                Err(winnow::error::ErrMode::Cut(ErrorContext::new(
+                    format!(
+                        "cannot `{kind} {label}`, there is no enclosing loop labeled `{label}`"
+                    ),
+                    start,
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
 pub struct Macro<'a> {
     pub ws1: Ws,
     pub name: &'a str,
-    pub args: Vec<(&'a str, Option<WithSpan<'a, Expr<'a>>>)>,
+    pub args: Vec<MacroArg<'a>>,
     pub nodes: Vec<Node<'a>>,
     pub ws2: Ws,
 }
 
+/// An argument of a macro definition, e.g. `title: &str = "Untitled"`
+#[derive(Debug, PartialEq)]
+pub struct MacroArg<'a> {
+    pub name: &'a str,
+    /// The optional type annotation, verbatim, e.g. `&str`
+    pub ty: Option<&'a str>,
+    pub default: Option<WithSpan<'a, Expr<'a>>>,
+}
+
+/// Parses the type annotation of a macro argument, i.e. everything until the next `,`, `=` or `)`
+/// that is not nested in `<>`, `()` or `[]`
+fn macro_arg_type<'a>(i: &mut &'a str) -> ParseResult<'a, &'a str> {
+    let start = *i;
+    let mut depth = 0_usize;
+    let mut end = start.len();
+    let mut prev = '\0';
+    for (idx, c) in start.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            // The `>` of a `->` does not close a `<`.
+            '>' if prev == '-' => {}
+            '>' | ')' | ']' if depth > 0 => depth -= 1,
+            ',' | '=' | ')' | '%' | '{' | '}' if depth == 0 => {
+                end = idx;
+                break;
+            }
+            _ => {}
+        }
+        prev = c;
+    }
+    let ty = start[..end].trim();
+    if ty.is_empty() {
+        return Err(winnow::error::ErrMode::Cut(ErrorContext::new(
+            "expected a type after `:` in macro argument",
+            start,
+        )));
+    }
+    *i = &start[end..];
+    Ok(ty)
+}
+
 fn check_duplicated_name<'a>(
     names: &mut HashSet<&'a str>,
     arg_name: &'a str,
@@ -636,11 +802,7 @@ fn check_duplicated_name<'a>(
 impl<'a> Macro<'a> {
     fn parse(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, WithSpan<'a, Self>> {
         let level = s.level;
-        #[allow(clippy::type_complexity)]
-        let parameters = |i: &mut _| -> ParseResult<
-            '_,
-            Option<Vec<(&str, Option<WithSpan<'_, Expr<'_>>>)>>,
-        > {
+        let parameters = |i: &mut _| -> ParseResult<'_, Option<Vec<MacroArg<'_>>>> {
             let args = opt(preceded(
                 '(',
                 (
@@ -649,8 +811,14 @@ impl<'a> Macro<'a> {
                             1..,
                             (
                                 ws(identifier),
+                                opt(preceded(':', ws(macro_arg_type))),
                                 opt(preceded('=', ws(|i: &mut _| Expr::parse(i, level, false)))),
-                            ),
+                            )
+                                .map(|(name, ty, default)| MacroArg {
+                                    name,
+                                    ty,
+                                    default,
+                                }),
                             ',',
                         ),
                         opt(','),
@@ -695,12 +863,14 @@ impl<'a> Macro<'a> {
             let mut names = HashSet::new();
 
             let mut iter = params.iter();
-            while let Some((arg_name, default_value)) = iter.next() {
+            while let Some(arg) = iter.next() {
+                let arg_name = arg.name;
                 check_duplicated_name(&mut names, arg_name, start_s)?;
-                if default_value.is_some() {
-                    for (new_arg_name, default_value) in iter.by_ref() {
+                if arg.default.is_some() {
+                    for new_arg in iter.by_ref() {
+                        let new_arg_name = new_arg.name;
                         check_duplicated_name(&mut names, new_arg_name, start_s)?;
-                        if default_value.is_none() {
+                        if new_arg.default.is_none() {
                             return Err(winnow::error::ErrMode::Cut(ErrorContext::new(
                                 format!(
                                     "all arguments following `{arg_name}` should have a default \
@@ -773,7 +943,7 @@ impl<'a> FilterBlock<'a> {
                 Some("filter"),
                 (
                     ws(identifier),
-                    opt(|i: &mut _| Expr::arguments(i, s.level, false)),
+                    opt(|i: &mut _| Expr::arguments(i, s.level, true)),
                     repeat(0.., |i: &mut _| {
                         #[allow(clippy::explicit_auto_deref)] // false positive
                         level_guard.nest(*i)?;
@@ -835,6 +1005,221 @@ impl<'a> FilterBlock<'a> {
     }
 }
 
+/// An `{% autoescape true %}` or `{% autoescape false %}` block, which enables or disables the
+/// escaping of the expressions in its body.
+#[derive(Debug, PartialEq)]
+pub struct AutoEscape<'a> {
+    pub ws1: Ws,
+    pub enabled: bool,
+    pub nodes: Vec<Node<'a>>,
+    pub ws2: Ws,
+}
+
+impl<'a> AutoEscape<'a> {
+    fn parse(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, WithSpan<'a, Self>> {
+        let start_s = *i;
+        let mut start = (
+            opt(Whitespace::parse),
+            ws(keyword("autoescape")),
+            cut_node(
+                Some("autoescape"),
+                (
+                    ws(alt((
+                        keyword("true").value(true),
+                        keyword("false").value(false),
+                    ))),
+                    opt(Whitespace::parse),
+                    |i: &mut _| s.tag_block_end(i),
+                ),
+            ),
+        );
+        let (pws1, _, (enabled, nws1, _)) = start.parse_next(i)?;
+
+        let mut end = cut_node(
+            Some("autoescape"),
+            (
+                |i: &mut _| Node::many(i, s),
+                cut_node(
+                    Some("autoescape"),
+                    (
+                        |i: &mut _| check_block_start(i, start_s, s, "autoescape", "endautoescape"),
+                        opt(Whitespace::parse),
+                        end_node("autoescape", "endautoescape"),
+                        opt(Whitespace::parse),
+                    ),
+                ),
+            ),
+        );
+        let (nodes, (_, pws2, _, nws2)) = end.parse_next(i)?;
+
+        Ok(WithSpan::new(
+            Self {
+                ws1: Ws(pws1, nws1),
+                enabled,
+                nodes,
+                ws2: Ws(pws2, nws2),
+            },
+            start_s,
+        ))
+    }
+}
+
+/// A `{% with name = expr, other = expr2 %}` block, whose variables are only visible in its body
+#[derive(Debug, PartialEq)]
+pub struct With<'a> {
+    pub ws1: Ws,
+    pub vars: Vec<(Target<'a>, WithSpan<'a, Expr<'a>>)>,
+    pub nodes: Vec<Node<'a>>,
+    pub ws2: Ws,
+}
+
+impl<'a> With<'a> {
+    fn parse(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, WithSpan<'a, Self>> {
+        let start_s = *i;
+        let var = (
+            ws(|i: &mut _| Target::parse(i, s)),
+            preceded(ws('='), ws(|i: &mut _| Expr::parse(i, s.level, false))),
+        );
+        let mut start = (
+            opt(Whitespace::parse),
+            ws(keyword("with")),
+            cut_node(
+                Some("with"),
+                (
+                    separated(1.., var, ws(',')),
+                    opt(ws(',')),
+                    opt(Whitespace::parse),
+                    |i: &mut _| s.tag_block_end(i),
+                ),
+            ),
+        );
+        let (pws1, _, (vars, _, nws1, _)) = start.parse_next(i)?;
+
+        let mut end = cut_node(
+            Some("with"),
+            (
+                |i: &mut _| Node::many(i, s),
+                cut_node(
+                    Some("with"),
+                    (
+                        |i: &mut _| check_block_start(i, start_s, s, "with", "endwith"),
+                        opt(Whitespace::parse),
+                        end_node("with", "endwith"),
+                        opt(Whitespace::parse),
+                    ),
+                ),
+            ),
+        );
+        let (nodes, (_, pws2, _, nws2)) = end.parse_next(i)?;
+
+        Ok(WithSpan::new(
+            Self {
+                ws1: Ws(pws1, nws1),
+                vars,
+                nodes,
+                ws2: Ws(pws2, nws2),
+            },
+            start_s,
+        ))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Trans<'a> {
+    pub ws1: Ws,
+    pub id: Option<&'a str>,
+    pub nodes: Vec<Node<'a>>,
+    pub plural: Option<Plural<'a>>,
+    pub ws2: Ws,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Plural<'a> {
+    pub ws: Ws,
+    pub count: &'a str,
+    pub nodes: Vec<Node<'a>>,
+}
+
+impl<'a> Trans<'a> {
+    fn parse(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, WithSpan<'a, Self>> {
+        let start_s = *i;
+        let mut start = (
+            opt(Whitespace::parse),
+            ws(keyword("trans")),
+            cut_node(
+                Some("trans"),
+                (
+                    opt(ws(str_lit_without_prefix)),
+                    opt(Whitespace::parse),
+                    |i: &mut _| s.tag_block_end(i),
+                ),
+            ),
+        );
+        let (pws1, _, (id, nws1, _)) = start.parse_next(i)?;
+
+        let plural = |i: &mut _| {
+            let mut p = preceded(
+                ws(keyword("pluralize")),
+                cut_node(
+                    Some("trans-pluralize"),
+                    (
+                        ws(identifier),
+                        opt(Whitespace::parse),
+                        delimited(
+                            |i: &mut _| s.tag_block_end(i),
+                            |i: &mut _| Node::many(i, s),
+                            |i: &mut _| s.tag_block_start(i),
+                        ),
+                        opt(Whitespace::parse),
+                    ),
+                ),
+            );
+            let (count, nws, nodes, pws) = p.parse_next(i)?;
+            Ok((count, nws, nodes, pws))
+        };
+
+        let mut end = cut_node(
+            Some("trans"),
+            (
+                |i: &mut _| Node::many(i, s),
+                cut_node(
+                    Some("trans"),
+                    (
+                        |i: &mut _| check_block_start(i, start_s, s, "trans", "endtrans"),
+                        opt(Whitespace::parse),
+                        opt(plural),
+                        end_node("trans", "endtrans"),
+                        opt(Whitespace::parse),
+                    ),
+                ),
+            ),
+        );
+        let (nodes, (_, pws2, plural, _, nws2)) = end.parse_next(i)?;
+
+        let (plural, ws2) = match plural {
+            Some((count, nws, nodes, pws)) => (
+                Some(Plural {
+                    ws: Ws(pws2, nws),
+                    count,
+                    nodes,
+                }),
+                Ws(pws, nws2),
+            ),
+            None => (None, Ws(pws2, nws2)),
+        };
+        Ok(WithSpan::new(
+            Self {
+                ws1: Ws(pws1, nws1),
+                id,
+                nodes,
+                plural,
+                ws2,
+            },
+            start_s,
+        ))
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Import<'a> {
     pub ws: Ws,
@@ -875,6 +1260,11 @@ pub struct Call<'a> {
     pub scope: Option<&'a str>,
     pub name: &'a str,
     pub args: Vec<WithSpan<'a, Expr<'a>>>,
+    /// The body of a `{% call %}...{% endcall %}` block, which the macro renders with
+    /// `caller()`. `None` if the call has no body.
+    pub caller: Option<Vec<Node<'a>>>,
+    /// The whitespace control of the `{% endcall %}` tag, if the call has a body.
+    pub ws2: Ws,
 }
 
 impl<'a> Call<'a> {
@@ -896,16 +1286,56 @@ impl<'a> Call<'a> {
         let (pws, _, (scope, name, args, nws)) = p.parse_next(i)?;
         let scope = scope.map(|(scope, _)| scope);
         let args = args.unwrap_or_default();
+        let (caller, ws2) = match Self::caller(i, s)? {
+            Some((nodes, ws2)) => (Some(nodes), ws2),
+            None => (None, Ws(None, None)),
+        };
         Ok(WithSpan::new(
             Self {
                 ws: Ws(pws, nws),
                 scope,
                 name,
                 args,
+                caller,
+                ws2,
             },
             start,
         ))
     }
+
+    /// Parses the body of a `{% call %}...{% endcall %}` block, if there is one
+    ///
+    /// `{% call %}` can be used with or without a body, so we look ahead for the matching
+    /// `{% endcall %}`. If there is none, the input is reset and the call has no body.
+    /// Positions without a body are remembered, so nested calls are not parsed over and over.
+    fn caller(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, Option<(Vec<Node<'a>>, Ws)>> {
+        let start = *i;
+        if s.calls_without_body.borrow().contains(&start.len()) {
+            return Ok(None);
+        }
+
+        let loop_depth = s.loop_depth.get();
+        let loop_labels = s.loop_labels.borrow().len();
+        let mut p = (
+            |i: &mut _| s.tag_block_end(i),
+            |i: &mut _| Node::many(i, s),
+            |i: &mut _| s.tag_block_start(i),
+            opt(Whitespace::parse),
+            ws(keyword("endcall")),
+            opt(Whitespace::parse),
+        );
+        match p.parse_next(i) {
+            Ok((_, nodes, _, pws2, _, nws2)) => Ok(Some((nodes, Ws(pws2, nws2)))),
+            Err(winnow::error::ErrMode::Backtrack(_)) => {
+                *i = start;
+                s.loop_depth.set(loop_depth);
+                s.loop_labels.borrow_mut().truncate(loop_labels);
+                s.calls_without_body.borrow_mut().insert(start.len());
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -1118,28 +1548,53 @@ pub struct Raw<'a> {
 impl<'a> Raw<'a> {
     fn parse(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, WithSpan<'a, Self>> {
         let start = *i;
-        let endraw = (
-            |i: &mut _| s.tag_block_start(i),
-            opt(Whitespace::parse),
-            ws(keyword("endraw")), // sic: ignore `{% end %}` in raw blocks
-            opt(Whitespace::parse),
-            peek(|i: &mut _| s.tag_block_end(i)),
-        );
+        let marker = |i: &mut &'a str| {
+            let marker_start = *i;
+            let marker = str_lit_without_prefix.parse_next(i)?;
+            match (identifier, eof).take().parse_next(&mut { marker }) {
+                Ok(marker) => Ok(marker),
+                Err(_) => Err(winnow::error::ErrMode::Cut(ErrorContext::new(
+                    "the end marker of a `raw` block must be an identifier, e.g. `{% raw \"end\" %}`",
+                    marker_start,
+                ))),
+            }
+        };
 
-        let mut p = (
+        let (pws1, _, (marker, nws1, _)) = (
             opt(Whitespace::parse),
             ws(keyword("raw")),
             cut_node(
                 Some("raw"),
-                (
-                    opt(Whitespace::parse),
-                    |i: &mut _| s.tag_block_end(i),
-                    skip_till(Splitter1::new(s.syntax.block_start), endraw).with_taken(),
-                ),
+                (opt(ws(marker)), opt(Whitespace::parse), |i: &mut _| {
+                    s.tag_block_end(i)
+                }),
             ),
-        );
+        )
+            .parse_next(i)?;
 
-        let (pws1, _, (nws1, _, ((new_i, (_, pws2, _, nws2, _)), contents))) = p.parse_next(i)?;
+        // A custom end marker lets the block contain `{% endraw %}`.
+        let endraw = (
+            |i: &mut _| s.tag_block_start(i),
+            opt(Whitespace::parse),
+            ws(keyword(marker.unwrap_or("endraw"))), // sic: ignore `{% end %}` in raw blocks
+            opt(Whitespace::parse),
+            peek(|i: &mut _| s.tag_block_end(i)),
+        );
+        let Ok(((new_i, (_, pws2, _, nws2, _)), contents)) =
+            skip_till(Splitter1::new(s.syntax.block_start), endraw)
+                .with_taken()
+                .parse_next(i)
+        else {
+            return Err(winnow::error::ErrMode::Cut(ErrorContext::new(
+                format!(
+                    "unterminated `raw` block: `{} {} {}` not found",
+                    s.syntax.block_start,
+                    marker.unwrap_or("endraw"),
+                    s.syntax.block_end,
+                ),
+                start,
+            )));
+        };
         *i = new_i;
         let lit = Lit::split_ws_parts(contents);
         let ws1 = Ws(pws1, nws1);
@@ -1211,6 +1666,89 @@ impl<'a> Let<'a> {
     }
 }
 
+/// A `{% set name %}...{% endset %}` block, which renders its body into a string and binds it
+/// to a variable. The content can be post-processed like in a `{% filter %}` block, e.g.
+/// `{% set name|trim %}`.
+#[derive(Debug, PartialEq)]
+pub struct SetBlock<'a> {
+    pub ws1: Ws,
+    pub var: Target<'a>,
+    pub filters: Option<Filter<'a>>,
+    pub nodes: Vec<Node<'a>>,
+    pub ws2: Ws,
+}
+
+impl<'a> SetBlock<'a> {
+    fn parse(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, WithSpan<'a, Self>> {
+        let start_s = *i;
+        let mut level_guard = s.level.guard();
+        let mut start = (
+            opt(Whitespace::parse),
+            ws(keyword("set")),
+            ws(identifier),
+            repeat(0.., |i: &mut _| {
+                #[allow(clippy::explicit_auto_deref)] // false positive
+                level_guard.nest(*i)?;
+                let start = *i;
+                filter(i, s.level).map(|(name, params)| (name, params, start))
+            })
+            .map(|v: Vec<_>| v),
+            opt(Whitespace::parse),
+            |i: &mut _| s.tag_block_end(i),
+        );
+        let (pws1, _, name, filters, nws1, _) = start.parse_next(i)?;
+
+        let mut end = (
+            |i: &mut _| Node::many(i, s),
+            cut_node(
+                Some("set"),
+                (
+                    |i: &mut _| check_block_start(i, start_s, s, "set", "endset"),
+                    opt(Whitespace::parse),
+                    end_node("set", "endset"),
+                    opt(Whitespace::parse),
+                ),
+            ),
+        );
+        let body_start = *i;
+        let (nodes, (_, pws2, _, nws2)) = match end.parse_next(i) {
+            Ok(end) => end,
+            // Without filters, `{% set name %}` can also be the declaration of a variable that
+            // gets initialized later on, so the caller tries to parse it as `Let` instead.
+            Err(_) if filters.is_empty() => {
+                *i = body_start;
+                return fail.parse_next(i);
+            }
+            Err(err) => return Err(err),
+        };
+
+        let mut filter_chain: Option<Filter<'a>> = None;
+        for (filter_name, args, span) in filters {
+            let source = match filter_chain {
+                Some(filters) => WithSpan::new(Expr::Filter(filters), span),
+                None => WithSpan::new(Expr::FilterSource, start_s),
+            };
+            let mut arguments = args.unwrap_or_default();
+            arguments.insert(0, source);
+            filter_chain = Some(Filter {
+                name: filter_name,
+                arguments,
+            });
+        }
+
+        Ok(WithSpan::new(
+            Self {
+                ws1: Ws(pws1, nws1),
+                var: Target::Name(name),
+                filters: filter_chain,
+                nodes,
+                ws2: Ws(pws2, nws2),
+            },
+            start_s,
+        ))
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct If<'a> {
     pub ws: Ws,
@@ -1258,6 +1796,14 @@ impl<'a> If<'a> {
             start,
         )];
         branches.extend(elifs);
+        if let Some(pos) = branches.iter().position(|branch| branch.cond.is_none()) {
+            if let Some(branch) = branches.get(pos + 1) {
+                return Err(winnow::error::ErrMode::Cut(ErrorContext::new(
+                    "unreachable branch: `else` must be the last branch of an `if` block",
+                    branch.span,
+                )));
+            }
+        }
 
         Ok(WithSpan::new(
             Self {
@@ -1273,24 +1819,79 @@ impl<'a> If<'a> {
 pub struct Include<'a> {
     pub ws: Ws,
     pub path: &'a str,
+    /// Set by `{% include "path" ignore missing %}`: render nothing if the template does not
+    /// exist instead of failing.
+    pub ignore_missing: bool,
+    /// The values passed with `{% include "path" with name = expr, other = expr2 %}`, which are
+    /// only visible in the included template.
+    pub vars: Vec<(&'a str, WithSpan<'a, Expr<'a>>)>,
+    /// Set by `{% include "path" with name = expr only %}`: the included template can access
+    /// nothing but the passed values.
+    pub only: bool,
 }
 
 impl<'a> Include<'a> {
-    fn parse(i: &mut &'a str) -> ParseResult<'a, WithSpan<'a, Self>> {
+    fn parse(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, WithSpan<'a, Self>> {
         let start = *i;
+        let var = (
+            ws(identifier),
+            preceded(ws('='), ws(|i: &mut _| Expr::parse(i, s.level, false))),
+        );
         let mut p = (
             opt(Whitespace::parse),
             ws(keyword("include")),
             cut_node(
                 Some("include"),
-                (ws(str_lit_without_prefix), opt(Whitespace::parse)),
+                (
+                    ws(str_lit_without_prefix),
+                    opt((ws(keyword("ignore")), ws(keyword("missing")))),
+                    opt(preceded(
+                        ws(keyword("with")),
+                        terminated(separated(1.., var, ws(',')), opt(ws(','))),
+                    )),
+                    opt(ws(keyword("only"))),
+                    opt(Whitespace::parse),
+                ),
             ),
         );
-        let (pws, _, (path, nws)) = p.parse_next(i)?;
+        let (pws, _, (path, ignore_missing, vars, only, nws)) = p.parse_next(i)?;
         Ok(WithSpan::new(
             Self {
                 ws: Ws(pws, nws),
                 path,
+                ignore_missing: ignore_missing.is_some(),
+                vars: vars.unwrap_or_default(),
+                only: only.is_some(),
+            },
+            start,
+        ))
+    }
+}
+
+/// An `{% include_all "dir/*.html" %}` node, which includes every template whose file name
+/// matches the pattern.
+#[derive(Debug, PartialEq)]
+pub struct IncludeAll<'a> {
+    pub ws: Ws,
+    pub pattern: &'a str,
+}
+
+impl<'a> IncludeAll<'a> {
+    fn parse(i: &mut &'a str) -> ParseResult<'a, WithSpan<'a, Self>> {
+        let start = *i;
+        let mut p = (
+            opt(Whitespace::parse),
+            ws(keyword("include_all")),
+            cut_node(
+                Some("include_all"),
+                (ws(str_lit_without_prefix), opt(Whitespace::parse)),
+            ),
+        );
+        let (pws, _, (pattern, nws)) = p.parse_next(i)?;
+        Ok(WithSpan::new(
+            Self {
+                ws: Ws(pws, nws),
+                pattern,
             },
             start,
         ))
@@ -1,6 +1,7 @@
-use crate::node::{Lit, Whitespace, Ws};
+use crate::node::{Include, Lit, Whitespace, Ws};
 use crate::{
-    Ast, Expr, Filter, InnerSyntax, Node, Num, Span, StrLit, Syntax, SyntaxBuilder, WithSpan,
+    Ast, Expr, Filter, InnerSyntax, Node, Num, Span, StrLit, Syntax, SyntaxBuilder, Target, Test,
+    WithSpan,
 };
 
 impl<T> WithSpan<'static, T> {
@@ -900,6 +901,44 @@ fn test_missing_space_after_kw() {
     );
 }
 
+#[test]
+fn test_parse_trans() {
+    let syntax = Syntax::default();
+    let nodes = Ast::from_str("{% trans %}Hello {{ name }}{% endtrans %}", None, &syntax)
+        .unwrap()
+        .nodes;
+    let Node::Trans(ref trans) = nodes[0] else {
+        panic!("expected a trans block, found {nodes:?}");
+    };
+    assert_eq!(trans.id, None);
+    assert_eq!(trans.nodes.len(), 2);
+    assert_eq!(trans.plural, None);
+
+    let nodes = Ast::from_str(
+        "{% trans \"apples\" %}One apple{%- pluralize count +%}{{ count }} apples{% endtrans %}",
+        None,
+        &syntax,
+    )
+    .unwrap()
+    .nodes;
+    let Node::Trans(ref trans) = nodes[0] else {
+        panic!("expected a trans block, found {nodes:?}");
+    };
+    assert_eq!(trans.id, Some("apples"));
+    assert_eq!(trans.nodes.len(), 1);
+    let plural = trans.plural.as_ref().unwrap();
+    assert_eq!(plural.count, "count");
+    assert_eq!(
+        plural.ws,
+        Ws(Some(Whitespace::Suppress), Some(Whitespace::Preserve))
+    );
+    assert_eq!(plural.nodes.len(), 2);
+
+    assert!(Ast::from_str("{% trans %}Hello", None, &syntax).is_err());
+    assert!(Ast::from_str("{% trans %}{% pluralize %}{% endtrans %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% pluralize count %}", None, &syntax).is_err());
+}
+
 #[test]
 fn test_parse_array() {
     let syntax = Syntax::default();
@@ -1153,3 +1192,491 @@ fn fuzzed_excessive_filter_block() {
         Some("your template code is too deeply nested, or the last expression is too complex"),
     );
 }
+
+#[test]
+fn test_if_chain_is_flat() {
+    let syntax = Syntax::default();
+    let nodes = Ast::from_str(
+        "{% if a %}{% else if b %}{% elif c %}{%- else  if let Some(d) = e %}{% elif f -%}\
+         {% else %}{% endif %}",
+        None,
+        &syntax,
+    )
+    .unwrap()
+    .nodes;
+    let [Node::If(ref chain)] = nodes[..] else {
+        panic!("expected a single if node, found {nodes:?}");
+    };
+    assert_eq!(chain.branches.len(), 6);
+    assert!(chain.branches.iter().all(|branch| branch.nodes.is_empty()));
+    let has_cond = chain
+        .branches
+        .iter()
+        .map(|branch| branch.cond.is_some())
+        .collect::<Vec<_>>();
+    assert_eq!(has_cond, [true, true, true, true, true, false]);
+    assert_eq!(chain.branches[3].ws, Ws(Some(Whitespace::Suppress), None));
+    assert_eq!(chain.branches[4].ws, Ws(None, Some(Whitespace::Suppress)));
+
+    assert!(Ast::from_str("{% if a %}{% elif %}{% endif %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% if a %}{% else elif b %}{% endif %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% if a %}{% else %}{% elif b %}{% endif %}", None, &syntax).is_err());
+}
+
+#[test]
+fn test_parse_if_else_expr() {
+    let syntax = Syntax::default();
+    assert_eq!(
+        Ast::from_str("{{ a if b || c else d if e else f }}", None, &syntax)
+            .unwrap()
+            .nodes,
+        vec![Node::Expr(
+            Ws(None, None),
+            WithSpan::no_span(Expr::IfElse(
+                WithSpan::no_span(Expr::BinOp(
+                    "||",
+                    WithSpan::no_span(Expr::Var("b")).into(),
+                    WithSpan::no_span(Expr::Var("c")).into(),
+                ))
+                .into(),
+                WithSpan::no_span(Expr::Var("a")).into(),
+                WithSpan::no_span(Expr::IfElse(
+                    WithSpan::no_span(Expr::Var("e")).into(),
+                    WithSpan::no_span(Expr::Var("d")).into(),
+                    WithSpan::no_span(Expr::Var("f")).into(),
+                ))
+                .into(),
+            )),
+        )],
+    );
+
+    // Without `else`, the `if` belongs to the `for` loop.
+    let nodes = Ast::from_str("{% for x in a if b %}{% endfor %}", None, &syntax)
+        .unwrap()
+        .nodes;
+    let Node::Loop(ref loop_node) = nodes[0] else {
+        panic!("expected a loop, found {nodes:?}");
+    };
+    assert_eq!(*loop_node.iter, Expr::Var("a"));
+    assert_eq!(loop_node.cond.as_deref(), Some(&Expr::Var("b")));
+
+    assert!(Ast::from_str("{{ a if b }}", None, &syntax).is_err());
+}
+
+#[test]
+fn test_parse_autoescape() {
+    let syntax = Syntax::default();
+    let nodes = Ast::from_str(
+        "{% autoescape false %}{{ a }}{%- endautoescape +%}",
+        None,
+        &syntax,
+    )
+    .unwrap()
+    .nodes;
+    let Node::AutoEscape(ref autoescape) = nodes[0] else {
+        panic!("expected an autoescape block, found {nodes:?}");
+    };
+    assert!(!autoescape.enabled);
+    assert_eq!(autoescape.ws1, Ws(None, None));
+    assert_eq!(
+        autoescape.ws2,
+        Ws(Some(Whitespace::Suppress), Some(Whitespace::Preserve))
+    );
+    assert_eq!(autoescape.nodes.len(), 1);
+
+    assert!(Ast::from_str("{% autoescape %}{% endautoescape %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% autoescape yes %}{% endautoescape %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% autoescape true %}", None, &syntax).is_err());
+}
+
+#[test]
+fn test_parse_with() {
+    let syntax = Syntax::default();
+    let nodes = Ast::from_str(
+        "{% with a = 1, (b, c) = d, %}{{ a }}{%- endwith +%}",
+        None,
+        &syntax,
+    )
+    .unwrap()
+    .nodes;
+    let Node::With(ref with) = nodes[0] else {
+        panic!("expected a with block, found {nodes:?}");
+    };
+    assert_eq!(with.vars.len(), 2);
+    assert_eq!(with.vars[0].0, Target::Name("a"));
+    assert_eq!(
+        with.vars[1].0,
+        Target::Tuple(vec![], vec![Target::Name("b"), Target::Name("c")])
+    );
+    assert_eq!(with.ws1, Ws(None, None));
+    assert_eq!(
+        with.ws2,
+        Ws(Some(Whitespace::Suppress), Some(Whitespace::Preserve))
+    );
+    assert_eq!(with.nodes.len(), 1);
+
+    assert!(Ast::from_str("{% with %}{% endwith %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% with a %}{% endwith %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% with a = 1 %}", None, &syntax).is_err());
+}
+
+#[test]
+fn test_parse_set_block() {
+    let syntax = Syntax::default();
+    let nodes = Ast::from_str(
+        "{% set nav|trim|upper -%}<a>{%- endset %}",
+        None,
+        &syntax,
+    )
+    .unwrap()
+    .nodes;
+    let Node::SetBlock(ref set) = nodes[0] else {
+        panic!("expected a set block, found {nodes:?}");
+    };
+    assert_eq!(set.var, Target::Name("nav"));
+    assert_eq!(set.filters.as_ref().unwrap().name, "upper");
+    assert_eq!(set.ws1, Ws(None, Some(Whitespace::Suppress)));
+    assert_eq!(set.ws2, Ws(Some(Whitespace::Suppress), None));
+    assert_eq!(set.nodes.len(), 1);
+
+    // without an `endset`, this is the declaration of a variable
+    let nodes = Ast::from_str("{% set nav %}{{ nav }}", None, &syntax)
+        .unwrap()
+        .nodes;
+    assert!(matches!(nodes[0], Node::Let(_)));
+    assert!(Ast::from_str("{% let nav %}{% endset %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% set nav|trim %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% set (a, b) %}{% endset %}", None, &syntax).is_err());
+}
+
+#[test]
+fn test_trailing_comma_in_arguments() {
+    let syntax = Syntax::default();
+    for src in [
+        "{% call button(label, href,) %}",
+        "{% call button(label, href = \"/\" ,\n) %}",
+        "{% call scope::button(label,) %}",
+        "{{ a|filter(b,) }}",
+        "{{ a|filter(b, c = 1,) }}",
+        "{{ f(a, b,) }}",
+        "{{ a.method(b ,) }}",
+        "{% filter f(a,) %}{% endfilter %}",
+        "{% macro button(label, href,) %}{% endmacro %}",
+        "{% macro button(label, href = \"/\",\n) %}{% endmacro %}",
+    ] {
+        if let Err(err) = Ast::from_str(src, None, &syntax) {
+            panic!("{src:?} should parse, got: {err}");
+        }
+    }
+
+    for src in [
+        "{% call button(label,,) %}",
+        "{% call button(,) %}",
+        "{{ a|filter(b,,) }}",
+        "{{ f(,) }}",
+        "{% macro button(label,,) %}{% endmacro %}",
+        "{% macro button(,) %}{% endmacro %}",
+    ] {
+        assert!(
+            Ast::from_str(src, None, &syntax).is_err(),
+            "{src:?} should not parse"
+        );
+    }
+}
+
+#[test]
+fn test_loop_labels() {
+    let syntax = Syntax::default();
+    let nodes = Ast::from_str(
+        "{% for x in items as outer if x.visible %}\
+            {% for y in x.children as inner %}{% break outer %}{% continue -%}{% endfor %}\
+        {% endfor %}",
+        None,
+        &syntax,
+    )
+    .unwrap()
+    .nodes;
+    let Node::Loop(ref outer) = nodes[0] else {
+        panic!("expected a loop, found {nodes:?}");
+    };
+    assert_eq!(outer.label, Some("outer"));
+    assert_eq!(*outer.iter, Expr::Var("items"));
+    assert!(outer.cond.is_some());
+    let Node::Loop(ref inner) = outer.body[0] else {
+        panic!("expected a loop, found {:?}", outer.body);
+    };
+    assert_eq!(inner.label, Some("inner"));
+    let Node::Break(ref control) = inner.body[0] else {
+        panic!("expected a break, found {:?}", inner.body);
+    };
+    assert_eq!(control.label, Some("outer"));
+    let Node::Continue(ref control) = inner.body[1] else {
+        panic!("expected a continue, found {:?}", inner.body);
+    };
+    assert_eq!(control.label, None);
+    assert_eq!(control.ws, Ws(None, Some(Whitespace::Suppress)));
+
+    // A cast to a primitive type is not a loop label.
+    let nodes = Ast::from_str("{% for x in 0..n as usize %}{% endfor %}", None, &syntax)
+        .unwrap()
+        .nodes;
+    let Node::Loop(ref l) = nodes[0] else {
+        panic!("expected a loop, found {nodes:?}");
+    };
+    assert_eq!(l.label, None);
+    let nodes = Ast::from_str(
+        "{% for x in 0..n as usize as outer %}{% endfor %}",
+        None,
+        &syntax,
+    )
+    .unwrap()
+    .nodes;
+    let Node::Loop(ref l) = nodes[0] else {
+        panic!("expected a loop, found {nodes:?}");
+    };
+    assert_eq!(l.label, Some("outer"));
+
+    assert!(Ast::from_str("{% for x in y as %}{% endfor %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% for x in y as a b %}{% endfor %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% for x in y %}{% break a %}{% endfor %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{{ x as core }}", None, &syntax).is_err());
+}
+
+#[test]
+fn test_include_ignore_missing() {
+    let syntax = Syntax::default();
+    let nodes = Ast::from_str(
+        r#"{% include "a.html" %}{% include "b.html" ignore missing -%}"#,
+        None,
+        &syntax,
+    )
+    .unwrap()
+    .nodes;
+    let [Node::Include(ref a), Node::Include(ref b)] = nodes[..] else {
+        panic!("expected two includes, found {nodes:?}");
+    };
+    assert_eq!((a.path, a.ignore_missing), ("a.html", false));
+    assert_eq!((b.path, b.ignore_missing), ("b.html", true));
+    assert_eq!(b.ws, Ws(None, Some(Whitespace::Suppress)));
+
+    assert!(Ast::from_str(r#"{% include "a.html" ignore %}"#, None, &syntax).is_err());
+}
+
+#[test]
+fn test_include_with() {
+    let syntax = Syntax::default();
+    let nodes = Ast::from_str(
+        "{% include \"a.html\" with title = \"Hi\", count = n + 1 %}\
+        {% include \"b.html\" ignore missing with x = y, only -%}\
+        {% include \"c.html\" only %}",
+        None,
+        &syntax,
+    )
+    .unwrap()
+    .nodes;
+    let [Node::Include(ref a), Node::Include(ref b), Node::Include(ref c)] = nodes[..] else {
+        panic!("expected three includes, found {nodes:?}");
+    };
+    fn names<'a>(i: &Include<'a>) -> Vec<&'a str> {
+        i.vars.iter().map(|(name, _)| *name).collect()
+    }
+    assert_eq!(names(a), ["title", "count"]);
+    assert!(matches!(*a.vars[0].1, Expr::StrLit(_)));
+    assert!(matches!(*a.vars[1].1, Expr::BinOp("+", _, _)));
+    assert!(!a.only);
+    assert_eq!(names(b), ["x"]);
+    assert!(b.ignore_missing && b.only);
+    assert_eq!(b.ws, Ws(None, Some(Whitespace::Suppress)));
+    assert!(names(c).is_empty());
+    assert!(c.only);
+
+    assert!(Ast::from_str(r#"{% include "a.html" with %}"#, None, &syntax).is_err());
+    assert!(Ast::from_str(r#"{% include "a.html" with x %}"#, None, &syntax).is_err());
+    assert!(Ast::from_str(r#"{% include "a.html" only with x = 1 %}"#, None, &syntax).is_err());
+}
+
+#[test]
+fn test_raw_custom_end() {
+    let syntax = Syntax::default();
+    let nodes = Ast::from_str(
+        "{% raw \"END\" -%} {% raw %}{{ x }}{% endraw %} {%- END %}",
+        None,
+        &syntax,
+    )
+    .unwrap()
+    .nodes;
+    let [Node::Raw(ref raw)] = nodes[..] else {
+        panic!("expected a raw block, found {nodes:?}");
+    };
+    assert_eq!(raw.lit.lws, " ");
+    assert_eq!(raw.lit.val, "{% raw %}{{ x }}{% endraw %}");
+    assert_eq!(raw.lit.rws, " ");
+    assert_eq!(raw.ws1, Ws(None, Some(Whitespace::Suppress)));
+    assert_eq!(raw.ws2, Ws(Some(Whitespace::Suppress), None));
+
+    assert!(Ast::from_str(r#"{% raw "END" %}{% endraw %}"#, None, &syntax).is_err());
+    assert!(Ast::from_str(r#"{% raw "" %}{% endraw %}"#, None, &syntax).is_err());
+    assert!(Ast::from_str(r#"{% raw "a b" %}{% a b %}"#, None, &syntax).is_err());
+}
+
+#[test]
+fn test_multiline_expr() {
+    let syntax = Syntax::default();
+    let multiline = Ast::from_str(
+        "{{-\n  user.posts()\n    |map(attr=\"title\")\n    |join(\n      \", \"\n    )\n-}}",
+        None,
+        &syntax,
+    )
+    .unwrap();
+    let single_line = Ast::from_str(
+        "{{- user.posts()|map(attr=\"title\")|join(\", \") -}}",
+        None,
+        &syntax,
+    )
+    .unwrap();
+    assert_eq!(multiline.nodes, single_line.nodes);
+    assert!(matches!(
+        multiline.nodes.as_slice(),
+        [Node::Expr(
+            Ws(Some(Whitespace::Suppress), Some(Whitespace::Suppress)),
+            _
+        )]
+    ));
+}
+
+#[test]
+fn test_call_with_caller() {
+    let syntax = Syntax::default();
+    let nodes = Ast::from_str(
+        "{% call dialog(title) -%} body {%- endcall %}{% call a() %}",
+        None,
+        &syntax,
+    )
+    .unwrap()
+    .nodes;
+    let [Node::Call(ref dialog), Node::Call(ref a)] = nodes[..] else {
+        panic!("expected two calls, found {nodes:?}");
+    };
+    assert_eq!(dialog.name, "dialog");
+    assert_eq!(dialog.ws, Ws(None, Some(Whitespace::Suppress)));
+    assert_eq!(dialog.ws2, Ws(Some(Whitespace::Suppress), None));
+    assert!(matches!(dialog.caller.as_deref(), Some([Node::Lit(_)])));
+    assert_eq!(a.name, "a");
+    assert!(a.caller.is_none());
+
+    // An `{% endcall %}` belongs to the innermost `{% call %}`.
+    let nodes = Ast::from_str("{% call a() %}{% call b() %}x{% endcall %}", None, &syntax)
+        .unwrap()
+        .nodes;
+    let [Node::Call(ref a), Node::Call(ref b)] = nodes[..] else {
+        panic!("expected two calls, found {nodes:?}");
+    };
+    assert!(a.caller.is_none());
+    assert!(b.caller.is_some());
+
+    let nodes = Ast::from_str(
+        "{% if x %}{% call a() %}{% else %}{% call b() %}{% endcall %}{% endif %}",
+        None,
+        &syntax,
+    )
+    .unwrap()
+    .nodes;
+    let [Node::If(ref cond)] = nodes[..] else {
+        panic!("expected an if, found {nodes:?}");
+    };
+    assert!(matches!(
+        cond.branches[0].nodes[..],
+        [Node::Call(ref a)] if a.caller.is_none()
+    ));
+    assert!(matches!(
+        cond.branches[1].nodes[..],
+        [Node::Call(ref b)] if b.caller.as_deref() == Some(&[])
+    ));
+
+    assert!(Ast::from_str("{% endcall %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% call a() %}{% endcall x %}", None, &syntax).is_err());
+}
+
+#[test]
+fn test_parse_is_test() {
+    let syntax = Syntax::default();
+    assert_eq!(
+        Ast::from_str("{{ x is none && y is not gt(3) }}", None, &syntax)
+            .unwrap()
+            .nodes,
+        vec![Node::Expr(
+            Ws(None, None),
+            WithSpan::no_span(Expr::BinOp(
+                "&&",
+                WithSpan::no_span(Expr::Test(Test {
+                    name: "none",
+                    negated: false,
+                    arguments: vec![WithSpan::no_span(Expr::Var("x"))],
+                }))
+                .into(),
+                WithSpan::no_span(Expr::Test(Test {
+                    name: "gt",
+                    negated: true,
+                    arguments: vec![
+                        WithSpan::no_span(Expr::Var("y")),
+                        WithSpan::no_span(Expr::NumLit("3", Num::Int("3", None))),
+                    ],
+                }))
+                .into(),
+            )),
+        )],
+    );
+    assert_eq!(
+        Ast::from_str("{{ x is not defined }}", None, &syntax)
+            .unwrap()
+            .nodes,
+        vec![Node::Expr(
+            Ws(None, None),
+            WithSpan::no_span(Expr::IsNotDefined("x")),
+        )],
+    );
+
+    assert!(Ast::from_str("{{ x is }}", None, &syntax).is_err());
+    assert!(Ast::from_str("{{ x is not }}", None, &syntax).is_err());
+    assert!(Ast::from_str("{{ x is eq(y=3) }}", None, &syntax).is_err());
+}
+
+#[test]
+fn test_macro_arg_types() {
+    let syntax = Syntax::default();
+    let ast = Ast::from_str(
+        "{% macro card(title: &str, f : impl Fn(u32) -> Option<u32>, count, \
+            items: &[(u32, Vec<&str>)] = []) %}{% endmacro %}",
+        None,
+        &syntax,
+    )
+    .unwrap();
+    let [Node::Macro(m)] = &*ast.nodes else {
+        panic!("expected a single macro, got {:?}", ast.nodes);
+    };
+    let args = m
+        .args
+        .iter()
+        .map(|arg| (arg.name, arg.ty, arg.default.is_some()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        args,
+        [
+            ("title", Some("&str"), false),
+            ("f", Some("impl Fn(u32) -> Option<u32>"), false),
+            ("count", None, false),
+            ("items", Some("&[(u32, Vec<&str>)]"), true),
+        ]
+    );
+
+    assert!(Ast::from_str("{% macro card(title:) %}{% endmacro %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% macro card(title: = 1) %}{% endmacro %}", None, &syntax).is_err());
+    assert!(
+        Ast::from_str(
+            "{% macro card(title: Vec<u32) %}{% endmacro %}",
+            None,
+            &syntax
+        )
+        .is_err()
+    );
+}
@@ -1,5 +1,8 @@
 #![no_std]
 
+extern crate alloc;
+
+use alloc::string::{String, ToString};
 use core::fmt;
 use core::str::Utf8Error;
 
@@ -49,6 +52,40 @@ fn hello_world() {
     assert!(err.is::<CustomError>());
 }
 
+#[test]
+fn render_to_string() {
+    #[derive(Template)]
+    #[template(
+        ext = "html",
+        source = "{% for item in items|reverse %}<li>{{ item }}</li>{% endfor %}",
+        error_placeholder = "?"
+    )]
+    struct List<'a> {
+        items: &'a [&'a str],
+    }
+
+    let tmpl = List {
+        items: &["a", "<b>"],
+    };
+    assert_eq!(tmpl.render().unwrap(), "<li>&#60;b&#62;</li><li>a</li>");
+
+    let mut buf = String::from("<ul>");
+    tmpl.render_append(&mut buf).unwrap();
+    assert_eq!(buf, "<ul><li>&#60;b&#62;</li><li>a</li>");
+
+    let (body, mime_type) = tmpl.render_response().unwrap();
+    assert_eq!(body, "<li>&#60;b&#62;</li><li>a</li>");
+    assert_eq!(mime_type, "text/html; charset=utf-8");
+
+    let (body, errors) = tmpl.render_collecting();
+    assert_eq!(body, "<li>&#60;b&#62;</li><li>a</li>");
+    assert!(errors.is_empty());
+
+    let tmpl: &dyn rinja::DynTemplate = &tmpl;
+    assert_eq!(tmpl.dyn_render().unwrap(), "<li>&#60;b&#62;</li><li>a</li>");
+    assert_eq!(tmpl.to_string(), "<li>&#60;b&#62;</li><li>a</li>");
+}
+
 struct Cursor<'a> {
     data: &'a mut [u8],
     pos: usize,
@@ -45,6 +45,20 @@ fn hello_world() {
     assert_matches!(tmpl.render_into(&mut cursor), Err(rinja::Error::Fmt));
 }
 
+#[test]
+fn center() {
+    #[derive(Template)]
+    #[template(ext = "txt", source = r#"[{{ s|center(7) }}][{{ s|center(7, "*") }}]"#)]
+    struct Center<'a> {
+        s: &'a str,
+    }
+
+    let mut buffer = [0; 32];
+    let mut cursor = Cursor::new(&mut buffer);
+    assert_matches!(Center { s: "äöü" }.render_into(&mut cursor), Ok(()));
+    assert_eq!(cursor.finalize(), Ok("[  äöü  ][**äöü**]"));
+}
+
 struct Cursor<'a> {
     data: &'a mut [u8],
     pos: usize,
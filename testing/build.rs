@@ -1,7 +1,12 @@
+use std::env::consts::EXE_SUFFIX;
 use std::env::var_os;
 use std::path::PathBuf;
+use std::process::Command;
 
 fn main() {
+    build_test_loader();
+
+    println!("cargo::rerun-if-env-changed=RUSTUP_TOOLCHAIN");
     let Some(toolchain) = var_os("RUSTUP_TOOLCHAIN") else {
         println!("cargo::warning=`RUSTUP_TOOLCHAIN` unset");
         return;
@@ -24,3 +29,19 @@ fn main() {
         );
     }
 }
+
+// The template loader of `tests/loader.rs` must exist when the test is compiled, which cargo
+// does not guarantee for the binaries of the package, so it is compiled here.
+fn build_test_loader() {
+    let rustc = var_os("RUSTC").unwrap();
+    let out_dir = PathBuf::from(var_os("OUT_DIR").unwrap());
+    let source = "loader/rinja-test-loader.rs";
+    println!("cargo::rerun-if-changed={source}");
+    let status = Command::new(rustc)
+        .args(["--edition=2021", "-o"])
+        .arg(out_dir.join(format!("rinja-test-loader{EXE_SUFFIX}")))
+        .arg(source)
+        .status()
+        .unwrap();
+    assert!(status.success(), "could not compile {source}");
+}
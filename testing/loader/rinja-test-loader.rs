@@ -0,0 +1,17 @@
+//! A template loader for `tests/loader.rs`, that turns Markdown headings into HTML
+//!
+//! It is compiled by `build.rs`, so it exists before the tests are compiled.
+
+use std::io::{Read, Write, stdin, stdout};
+
+fn main() {
+    let mut input = String::new();
+    stdin().read_to_string(&mut input).unwrap();
+    let mut stdout = stdout().lock();
+    for line in input.lines() {
+        match line.strip_prefix("# ") {
+            Some(heading) => writeln!(stdout, "<h1>{heading}</h1>").unwrap(),
+            None => writeln!(stdout, "{line}").unwrap(),
+        }
+    }
+}
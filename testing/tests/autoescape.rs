@@ -0,0 +1,143 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(
+    source = "{{ s }} {% autoescape false %}{{ s }}{% endautoescape %} {{ s }}",
+    ext = "html"
+)]
+struct AutoEscapeFalse<'a> {
+    s: &'a str,
+}
+
+#[test]
+fn test_autoescape_false() {
+    let t = AutoEscapeFalse { s: "<b>" };
+    assert_eq!(t.render().unwrap(), "&#60;b&#62; <b> &#60;b&#62;");
+}
+
+#[derive(Template)]
+#[template(
+    source = "{{ s }} {% autoescape true %}{{ s }}{% endautoescape %} {{ s }}",
+    ext = "txt"
+)]
+struct AutoEscapeTrue<'a> {
+    s: &'a str,
+}
+
+#[test]
+fn test_autoescape_true() {
+    let t = AutoEscapeTrue { s: "<b>" };
+    assert_eq!(t.render().unwrap(), "<b> &#60;b&#62; <b>");
+}
+
+#[derive(Template)]
+#[template(
+    source = "{% autoescape false -%}
+        {{ s }}
+        {%- autoescape true %}{{ s }}{% endautoescape -%}
+        {{ s }}
+        {%- if true %}{{ s|escape }}{% endif -%}
+    {%- endautoescape %}{{ s }}",
+    ext = "html"
+)]
+struct AutoEscapeNested<'a> {
+    s: &'a str,
+}
+
+#[test]
+fn test_autoescape_nested() {
+    let t = AutoEscapeNested { s: "&" };
+    assert_eq!(t.render().unwrap(), "&&#38;&&#38;&#38;");
+}
+
+#[derive(Template)]
+#[template(
+    source = "{% autoescape false %}{% for s in list %}{{ s }}{% endfor %}{% endautoescape %}",
+    ext = "html"
+)]
+struct AutoEscapeLoop<'a> {
+    list: &'a [&'a str],
+}
+
+#[test]
+fn test_autoescape_loop() {
+    let t = AutoEscapeLoop {
+        list: &["<a>", "<b>"],
+    };
+    assert_eq!(t.render().unwrap(), "<a><b>");
+}
+
+#[derive(Template)]
+#[template(
+    source = r#"{% autoescape false -%}
+        {{ s }} {{ s|e }} {{ s|escape("html") }} {{ s|e("txt") }}
+    {%- endautoescape %} {{ s|e("none") }} {{ s|e("html") }}"#,
+    ext = "html"
+)]
+struct AutoEscapeFilter<'a> {
+    s: &'a str,
+}
+
+// The `escape` filter escapes a single value in a region without escaping,
+// and it can select an escaper other than the template's default.
+#[test]
+fn test_autoescape_escape_filter() {
+    let t = AutoEscapeFilter { s: "<b>" };
+    assert_eq!(
+        t.render().unwrap(),
+        "<b> &#60;b&#62; &#60;b&#62; <b> <b> &#60;b&#62;"
+    );
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Html,
+    Plain,
+}
+
+impl From<Format> for rinja::filters::EscapeMode {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Html => Self::Html,
+            Format::Plain => Self::Text,
+        }
+    }
+}
+
+#[derive(Template)]
+#[template(
+    source = "{% block greeting %}Hi {{ name }}{{ \"<3\" }}{% endblock %}
+{{ text|linebreaksbr }} {% autoescape false %}{{ name }}{% endautoescape %}",
+    ext = "txt",
+    escape_field = "format",
+    blocks = ["greeting"]
+)]
+struct EscapeField<'a> {
+    name: &'a str,
+    text: &'a str,
+    format: Format,
+}
+
+#[test]
+fn test_escape_field() {
+    let html = EscapeField {
+        name: "<Ann>",
+        text: "a&b\nc",
+        format: Format::Html,
+    };
+    assert_eq!(
+        html.render().unwrap(),
+        "Hi &#60;Ann&#62;&#60;3\na&#38;b<br/>c <Ann>"
+    );
+    assert_eq!(
+        html.render_block("greeting").unwrap(),
+        "Hi &#60;Ann&#62;&#60;3"
+    );
+
+    let plain = EscapeField {
+        format: Format::Plain,
+        ..html
+    };
+    assert_eq!(plain.render().unwrap(), "Hi <Ann><3\na&#38;b<br/>c <Ann>");
+    assert_eq!(plain.render_block("greeting").unwrap(), "Hi <Ann><3");
+}
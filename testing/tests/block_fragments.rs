@@ -163,3 +163,47 @@ fn test_variable_inheritance_in_block() {
 
     assert_eq!(Y.render().unwrap(), "--> 42\n42");
 }
+
+/// Tests selecting the block to render at runtime with `render_block()`.
+#[test]
+fn test_render_block() {
+    #[derive(Template)]
+    #[template(path = "fragment-super.html", blocks = ["body", "other_body"])]
+    struct RenderBlock<'a> {
+        name: &'a str,
+    }
+
+    let t = RenderBlock { name: "world" };
+    assert_eq!(RenderBlock::BLOCKS, ["body", "other_body"]);
+    assert_eq!(
+        t.render_block("body").unwrap(),
+        "\n<p>Hello world!</p>\n\n<p>Parent body content</p>\n\n"
+    );
+    assert_eq!(
+        t.render_block("other_body").unwrap(),
+        "\n<p>Don't render me.</p>\n\n"
+    );
+    assert!(matches!(
+        t.render_block("title"),
+        Err(rinja::Error::UnknownBlock)
+    ));
+    // The whole template is rendered as usual.
+    assert!(t.render().unwrap().starts_with("<html>"));
+}
+
+/// Tests that a template without selected blocks cannot render any block.
+#[test]
+fn test_render_block_without_blocks() {
+    #[derive(Template)]
+    #[template(path = "fragment-simple.html")]
+    struct NoBlocks<'a> {
+        name: &'a str,
+    }
+
+    let t = NoBlocks { name: "world" };
+    assert!(NoBlocks::BLOCKS.is_empty());
+    assert!(matches!(
+        t.render_block("body"),
+        Err(rinja::Error::UnknownBlock)
+    ));
+}
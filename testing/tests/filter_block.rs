@@ -330,3 +330,42 @@ fn filter_block_custom_errors() {
     };
     assert_eq!(template.render().unwrap_err().to_string(), "🐢");
 }
+
+mod filters {
+    pub fn shout(s: impl std::fmt::Display) -> rinja::Result<String> {
+        Ok(format!("{s}!"))
+    }
+}
+
+// This test checks that built-in and custom filters can be mixed in a filter block, and that
+// they are applied left to right, even if they change the length of the content.
+#[test]
+fn filter_block_chaining_custom_filters() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% filter trim|truncate(5)|shout|upper %}
+    {{ v }} and more
+{% endfilter %}"#,
+        ext = "txt"
+    )]
+    struct G {
+        v: &'static str,
+    }
+
+    let template = G { v: "pika" };
+    assert_eq!(template.render().unwrap(), "PIKA ...!");
+
+    #[derive(Template)]
+    #[template(
+        source = r#"{% filter shout|truncate(3)|lower %}{{ v }}{% endfilter %}"#,
+        ext = "txt"
+    )]
+    struct H {
+        v: &'static str,
+    }
+
+    let template = H { v: "AB" };
+    assert_eq!(template.render().unwrap(), "ab!");
+    let template = H { v: "ABCD" };
+    assert_eq!(template.render().unwrap(), "abc...");
+}
@@ -49,6 +49,30 @@ fn filter_opt_escaper_none() {
     );
 }
 
+#[test]
+fn filter_escaper_none_passes_values_verbatim() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ a }} {{ a|escape }} {{ a|e(\"html\") }} {{ a ~ b }}",
+        ext = "html",
+        escape = "none"
+    )]
+    struct EscaperNone<'a> {
+        a: &'a str,
+        b: String,
+    }
+
+    let t = EscaperNone {
+        a: "<a href='x'>&</a>",
+        b: "\"b\"".to_string(),
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "<a href='x'>&</a> <a href='x'>&</a> &#60;a href=&#39;x&#39;&#62;&#38;&#60;/a&#62; \
+         <a href='x'>&</a>\"b\""
+    );
+}
+
 #[test]
 fn filter_opt_escaper_html() {
     #[derive(Template)]
@@ -74,6 +98,38 @@ fn filter_opt_escaper_html() {
     );
 }
 
+#[test]
+fn filter_escape_html_attr() {
+    #[derive(Template)]
+    #[template(
+        source = r#"<a title="{{ title }}" alt='{{ title }}'>{{ title|e("html_attr") }}</a>"#,
+        ext = "html"
+    )]
+    struct AttrHtml<'a> {
+        title: &'a str,
+    }
+
+    #[derive(Template)]
+    #[template(
+        source = r#"<a title="{{ title|e("html_attr") }}">{{ title }}</a>"#,
+        ext = "txt"
+    )]
+    struct AttrTxt<'a> {
+        title: &'a str,
+    }
+
+    let title = r#"" onclick="x()" '"#;
+    assert_eq!(
+        AttrHtml { title }.render().unwrap(),
+        "<a title=\"&#34; onclick=&#34;x()&#34; &#39;\" alt='&#34; onclick=&#34;x()&#34; &#39;'>\
+         &#34; onclick=&#34;x()&#34; &#39;</a>"
+    );
+    assert_eq!(
+        AttrTxt { title }.render().unwrap(),
+        r#"<a title="&#34; onclick=&#34;x()&#34; &#39;">" onclick="x()" '</a>"#
+    );
+}
+
 #[test]
 fn filter_format() {
     #[derive(Template)]
@@ -98,12 +154,40 @@ fn filter_fmt() {
     assert_eq!(t.render().unwrap(), "\"formatted\"");
 }
 
+#[test]
+fn filter_fmt_spec() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ x|fmt("{:.2}") }} {{ x|fmt("{:08.3}") }} {{ n|fmt("{:>4}") }}|{{ n|fmt("{:<4}") }}| {{ n|fmt("{:^5}") }} {{ n|fmt("{:#x}") }} {{ n|fmt("{:+}") }} {{ x|fmt("{:e}") }}"#,
+        ext = "txt"
+    )]
+    struct FmtSpec {
+        x: f64,
+        n: i32,
+    }
+
+    let t = FmtSpec { x: 1.23456, n: 42 };
+    assert_eq!(
+        t.render().unwrap(),
+        "1.23 0001.235   42|42  |  42   0x2a +42 1.23456e0"
+    );
+}
+
 mod filters {
     pub fn myfilter(s: &str) -> ::rinja::Result<String> {
         Ok(s.replace("oo", "aa"))
     }
+    // for test_filter_error_location
+    pub fn fail(_: impl ::std::fmt::Display) -> ::rinja::Result<String> {
+        Err(::rinja::Error::custom("it failed"))
+    }
+    // for test_filter_custom_error
+    pub fn parse_count(s: &str) -> ::rinja::Result<u32> {
+        s.parse()
+            .map_err(|source| ::rinja::Error::custom(super::CountError { source }))
+    }
     // for test_nested_filter_ref
-    pub fn mytrim(s: &dyn (::std::fmt::Display)) -> ::rinja::Result<String> {
+    pub fn mytrim(s: &dyn ::std::fmt::Display) -> ::rinja::Result<String> {
         Ok(s.to_string().trim().to_owned())
     }
 }
@@ -120,6 +204,33 @@ fn test_my_filter() {
     assert_eq!(t.render().unwrap(), "faa");
 }
 
+// for test_configured_filters
+mod text {
+    pub fn shout(s: impl ::std::fmt::Display) -> ::rinja::Result<String> {
+        Ok(format!("{}!", s.to_string().to_uppercase()))
+    }
+
+    pub fn wrap(s: impl ::std::fmt::Display, left: char, right: char) -> ::rinja::Result<String> {
+        Ok(format!("{left}{s}{right}"))
+    }
+}
+
+#[test]
+fn test_configured_filters() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ s|shout }} {{ s|wrap('<', '>') }} {{ s|shout|wrap('[', ']') }}",
+        ext = "html",
+        config = "filters-config.toml"
+    )]
+    struct Configured<'a> {
+        s: &'a str,
+    }
+
+    let t = Configured { s: "hi" };
+    assert_eq!(t.render().unwrap(), "HI! &#60;hi&#62; [HI!]");
+}
+
 #[test]
 fn test_join() {
     #[derive(Template)]
@@ -148,6 +259,98 @@ fn test_vec_join() {
     assert_eq!(t.render().unwrap(), "foo, bar, bazz");
 }
 
+#[test]
+fn test_join_attr() {
+    struct Address {
+        city: &'static str,
+    }
+
+    struct User {
+        name: &'static str,
+        address: Address,
+    }
+
+    impl User {
+        fn shout(&self) -> String {
+            self.name.to_uppercase()
+        }
+    }
+
+    #[derive(Template)]
+    #[template(
+        source = r#"[{{ users|join(", ", attr="name") }}]
+[{{ users|join(separator=" / ", attr="address.city") }}]
+[{{ users|join(attr="shout()", separator=sep) }}]
+[{{ users|join(attr="name") }}]
+[{{ nobody|join(", ", attr="name") }}]
+[{{ none|join(", ") }}]"#,
+        ext = "html"
+    )]
+    struct JoinAttr<'a> {
+        users: Vec<User>,
+        nobody: Vec<User>,
+        none: Vec<&'a str>,
+        sep: &'a str,
+    }
+
+    let t = JoinAttr {
+        users: vec![
+            User {
+                name: "Ann",
+                address: Address { city: "<Oslo>" },
+            },
+            User {
+                name: "Bob",
+                address: Address { city: "Rome" },
+            },
+        ],
+        nobody: vec![],
+        none: vec![],
+        sep: "-",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "[Ann, Bob]\n[&#60;Oslo&#62; / Rome]\n[ANN-BOB]\n[AnnBob]\n[]\n[]"
+    );
+}
+
+#[test]
+fn test_join_escaped() {
+    #[derive(Template)]
+    #[template(
+        source = r#"<ul><li>{{ names|join("</li><li>") }}</li></ul>
+{{ names|join(sep) }}|{{ names|join("<br>")|safe }}
+{{ names|join(", ")|upper }}|{{ names|join(sep)|lower }}
+{% autoescape false %}{{ names|join("<br>") }}{% endautoescape %}"#,
+        ext = "html"
+    )]
+    struct JoinHtml<'a> {
+        names: &'a [&'a str],
+        sep: &'a str,
+    }
+
+    #[derive(Template)]
+    #[template(source = r#"{{ names|join("</li><li>") }}"#, ext = "txt")]
+    struct JoinText<'a> {
+        names: &'a [&'a str],
+    }
+
+    let names = &["Tom & Jerry", "<script>", "Ann"];
+    assert_eq!(
+        JoinHtml { names, sep: "<hr>" }.render().unwrap(),
+        "<ul><li>Tom &#38; Jerry</li><li>&#60;script&#62;</li><li>Ann</li></ul>\n\
+         Tom &#38; Jerry&#60;hr&#62;&#60;script&#62;&#60;hr&#62;Ann|\
+         Tom & Jerry<br><script><br>Ann\n\
+         TOM &#38; JERRY, &#60;SCRIPT&#62;, ANN|\
+         tom &#38; jerry&#60;hr&#62;&#60;script&#62;&#60;hr&#62;ann\n\
+         Tom & Jerry<br><script><br>Ann"
+    );
+    assert_eq!(
+        JoinText { names }.render().unwrap(),
+        "Tom & Jerry</li><li><script></li><li>Ann"
+    );
+}
+
 #[cfg(feature = "serde_json")]
 #[test]
 fn test_json() {
@@ -315,6 +518,27 @@ fn test_json_attribute() {
     );
 }
 
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_tojson_script() {
+    #[derive(Template)]
+    #[template(
+        source = r#"<script>var data = {{ data|tojson }};</script>"#,
+        ext = "html"
+    )]
+    struct ToJsonScriptTemplate<'a> {
+        data: &'a [&'a str],
+    }
+
+    let t = ToJsonScriptTemplate {
+        data: &["</script>", "a & b", "\u{2028}\u{2029}"],
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        r#"<script>var data = ["\u003c/script\u003e","a \u0026 b","\u2028\u2029"];</script>"#
+    );
+}
+
 #[cfg(feature = "serde_json")]
 #[test]
 fn test_json_attribute2() {
@@ -422,6 +646,81 @@ fn test_linebreaks() {
     );
 }
 
+#[test]
+fn test_nl2br() {
+    #[derive(Template)]
+    #[template(source = r#"{{ s|nl2br }}"#, ext = "html")]
+    struct Nl2br {
+        s: &'static str,
+    }
+
+    assert_eq!(
+        Nl2br {
+            s: "<script>\r\nalert('Hello, world!')\n</script>"
+        }
+        .render()
+        .unwrap(),
+        "&#60;script&#62;<br>\nalert(&#39;Hello, world!&#39;)<br>\n&#60;/script&#62;",
+    );
+
+    // Already escaped input is not escaped twice.
+    #[derive(Template)]
+    #[template(source = r#"{{ s|escape|nl2br }}"#, ext = "html")]
+    struct Nl2brEscaped {
+        s: &'static str,
+    }
+
+    assert_eq!(
+        Nl2brEscaped { s: "a <b>\nc" }.render().unwrap(),
+        "a &#60;b&#62;<br>\nc",
+    );
+}
+
+#[test]
+fn test_urlize() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ s|urlize }}
+{{ s|urlize(nofollow=true) }}
+{{ s|urlize(16, target=target) }}"#,
+        ext = "html"
+    )]
+    struct Urlize<'a> {
+        s: &'a str,
+        target: &'a str,
+    }
+
+    assert_eq!(
+        Urlize {
+            s: "<b>Go to https://example.com/?a=1&b=2, or www.example.org! </b>",
+            target: "_blank",
+        }
+        .render()
+        .unwrap(),
+        "&#60;b&#62;Go to <a href=\"https://example.com/?a=1&#38;b=2\">\
+         https://example.com/?a=1&#38;b=2</a>, or \
+         <a href=\"https://www.example.org\">www.example.org</a>! &#60;/b&#62;\n\
+         &#60;b&#62;Go to <a href=\"https://example.com/?a=1&#38;b=2\" rel=\"nofollow\">\
+         https://example.com/?a=1&#38;b=2</a>, or \
+         <a href=\"https://www.example.org\" rel=\"nofollow\">www.example.org</a>! &#60;/b&#62;\n\
+         &#60;b&#62;Go to <a href=\"https://example.com/?a=1&#38;b=2\" rel=\"noopener\" \
+         target=\"_blank\">https://example…</a>, or <a href=\"https://www.example.org\" \
+         rel=\"noopener\" target=\"_blank\">www.example.org</a>! &#60;/b&#62;",
+    );
+
+    // Already escaped input is not escaped twice.
+    #[derive(Template)]
+    #[template(source = r#"{{ s|escape|urlize }}"#, ext = "html")]
+    struct UrlizeEscaped<'a> {
+        s: &'a str,
+    }
+
+    assert_eq!(
+        UrlizeEscaped { s: "'http://a.b/'" }.render().unwrap(),
+        "&#39;<a href=\"http://a.b/\">http://a.b/</a>&#39;",
+    );
+}
+
 // Regression tests for <https://github.com/rinja-rs/rinja/issues/215>.
 #[test]
 fn test_filesizeformat() {
@@ -450,3 +749,1184 @@ fn test_whitespace_around_filter_operator() {
 
     assert_eq!(S.render().unwrap(), "12\n8\n4");
 }
+
+#[test]
+fn test_safe_is_scoped_to_expression() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ a }}{{ b|safe }}{{ a }} {{ a ~ b|safe }} {{ b|safe|upper }}{{ b }}"#,
+        ext = "html"
+    )]
+    struct Html<'a> {
+        a: &'a str,
+        b: &'a str,
+    }
+
+    let t = Html { a: "<a>", b: "<b>" };
+    assert_eq!(
+        t.render().unwrap(),
+        "&#60;a&#62;<b>&#60;a&#62; &#60;a&#62;<b> &#60;B&#62;&#60;b&#62;"
+    );
+
+    #[derive(Template)]
+    #[template(source = r#"{{ a }}{{ b|safe }}{{ a|e("html") }}"#, ext = "txt")]
+    struct Text<'a> {
+        a: &'a str,
+        b: &'a str,
+    }
+
+    let t = Text { a: "<a>", b: "<b>" };
+    assert_eq!(t.render().unwrap(), "<a><b>&#60;a&#62;");
+}
+
+#[test]
+fn test_map_attr() {
+    struct Address {
+        city: &'static str,
+    }
+
+    struct User {
+        name: &'static str,
+        address: Address,
+    }
+
+    impl User {
+        fn shout(&self) -> String {
+            self.name.to_uppercase()
+        }
+    }
+
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ users|map(attr="name")|join(", ") }}
+{{ users|map(attr="address.city")|join(", ") }}
+{{ users|map(attr="shout()")|join(", ") }}
+{% for name in users|map(attr="name") %}[{{ name }}]{% endfor %}"#,
+        ext = "html"
+    )]
+    struct MapAttr {
+        users: Vec<User>,
+    }
+
+    let t = MapAttr {
+        users: vec![
+            User {
+                name: "Ann",
+                address: Address { city: "<Oslo>" },
+            },
+            User {
+                name: "Bob",
+                address: Address { city: "Rome" },
+            },
+        ],
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "Ann, Bob\n&#60;Oslo&#62;, Rome\nANN, BOB\n[Ann][Bob]"
+    );
+}
+
+#[test]
+fn test_map_filter() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ names|map(filter="upper")|join(", ") }}
+{% for name in names|map(filter="capitalize") %}[{{ name }}]{% endfor %}"#,
+        ext = "html"
+    )]
+    struct MapFilter<'a> {
+        names: &'a [&'a str],
+    }
+
+    let t = MapFilter {
+        names: &["ann", "<bob>"],
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "ANN, &#60;BOB&#62;\n[Ann][&#60;bob&#62;]"
+    );
+}
+
+#[test]
+fn test_select_reject() {
+    struct User {
+        name: &'static str,
+        role: &'static str,
+        age: u32,
+        active: bool,
+    }
+
+    impl User {
+        fn is_adult(&self) -> bool {
+            self.age >= 18
+        }
+    }
+
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ users|select(attr="active")|map(attr="name")|join(",") }}
+{{ users|reject(attr="active")|map(attr="name")|join(",") }}
+{{ users|selectattr("is_adult()")|map(attr="name")|join(",") }}
+{{ users|selectattr("role", "eq", "admin")|map(attr="name")|join(",") }}
+{{ users|rejectattr("role", "==", role)|map(attr="name")|join(",") }}
+{% for user in users|selectattr("age", "gt", 20) %}{{ user.name }}{% endfor %}"#,
+        ext = "html"
+    )]
+    struct Select<'a> {
+        users: &'a [User],
+        role: &'a str,
+    }
+
+    let users = [
+        User {
+            name: "Ann",
+            role: "admin",
+            age: 30,
+            active: true,
+        },
+        User {
+            name: "Bob",
+            role: "user",
+            age: 17,
+            active: false,
+        },
+        User {
+            name: "Eve",
+            role: "user",
+            age: 20,
+            active: true,
+        },
+    ];
+    let t = Select {
+        users: &users,
+        role: "user",
+    };
+    assert_eq!(t.render().unwrap(), "Ann,Eve\nBob\nAnn,Eve\nAnn\nAnn\nAnn");
+}
+
+#[test]
+fn test_group_by() {
+    struct Address {
+        city: &'static str,
+    }
+
+    struct Record {
+        name: &'static str,
+        category: &'static str,
+        address: Address,
+    }
+
+    #[derive(Template)]
+    #[template(
+        source = r#"
+{%- for (category, items) in records|group_by(attr="category") -%}
+[{{ category }}:{% for item in items %} {{ item.name }}{% endfor %}]
+{%- endfor %}
+{% for (city, items) in records|group_by(attr="address.city") -%}
+[{{ city }}: {{ items.len() }}]
+{%- endfor %}"#,
+        ext = "txt"
+    )]
+    struct GroupBy {
+        records: Vec<Record>,
+    }
+
+    let record = |name, category, city| Record {
+        name,
+        category,
+        address: Address { city },
+    };
+    let t = GroupBy {
+        records: vec![
+            record("apple", "fruit", "Oslo"),
+            record("pear", "fruit", "Rome"),
+            record("carrot", "vegetable", "Rome"),
+            record("cherry", "fruit", "Rome"),
+        ],
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "[fruit: apple pear][vegetable: carrot][fruit: cherry]\n[Oslo: 1][Rome: 3]"
+    );
+
+    let t = GroupBy { records: vec![] };
+    assert_eq!(t.render().unwrap(), "\n");
+}
+
+#[test]
+fn test_group_by_owned_items() {
+    #[derive(Clone)]
+    struct Record {
+        name: String,
+        category: String,
+    }
+
+    #[derive(Template)]
+    #[template(
+        source = r#"
+{%- for (category, items) in records.clone()|group_by(attr="category") -%}
+[{{ category }}:{% for item in items %} {{ item.name }}{% endfor %}]
+{%- endfor %}"#,
+        ext = "txt"
+    )]
+    struct GroupBy {
+        records: Vec<Record>,
+    }
+
+    let record = |name: &str, category: &str| Record {
+        name: name.to_owned(),
+        category: category.to_owned(),
+    };
+    let t = GroupBy {
+        records: vec![
+            record("apple", "fruit"),
+            record("pear", "fruit"),
+            record("carrot", "vegetable"),
+        ],
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "[fruit: apple pear][vegetable: carrot]"
+    );
+}
+
+#[test]
+fn test_pluralize() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ a }} item{{ a|pluralize }}, {{ b }} item{{ b|pluralize }}, \
+                  {{ c }} pon{{ c|pluralize(\"y\", \"ies\") }}, \
+                  {{ d }} pon{{ d|pluralize(\"y\", \"ies\") }}",
+        ext = "txt"
+    )]
+    struct Pluralize {
+        a: u8,
+        b: i64,
+        c: usize,
+        d: i128,
+    }
+
+    let t = Pluralize {
+        a: 1,
+        b: 0,
+        c: 1,
+        d: 2,
+    };
+    assert_eq!(t.render().unwrap(), "1 item, 0 items, 1 pony, 2 ponies");
+
+    let t = Pluralize {
+        a: 2,
+        b: 1,
+        c: 12,
+        d: 1,
+    };
+    assert_eq!(t.render().unwrap(), "2 items, 1 item, 12 ponies, 1 pony");
+}
+
+#[test]
+fn test_indent() {
+    #[derive(Template)]
+    #[template(
+        source = "[{{ s|indent }}]\n[{{ s|indent(2, first=true) }}]\n\
+                  [{{ s|indent(width=n, blank=false) }}]\n[{{ s|indent(1, true, false) }}]",
+        ext = "txt"
+    )]
+    struct Indent<'a> {
+        s: &'a str,
+        n: usize,
+    }
+
+    let t = Indent {
+        s: "a\n\nb\n",
+        n: 3,
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "[a\n    \n    b\n]\n[  a\n  \n  b\n]\n[a\n\n   b\n]\n[ a\n\n b\n]"
+    );
+}
+
+#[test]
+fn test_case_filters_unicode() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ s|upper }}|{{ s|lower }}|{{ s|capitalize }}|{{ s|title }}",
+        ext = "txt"
+    )]
+    struct Case<'a> {
+        s: &'a str,
+    }
+
+    let t = Case {
+        s: "ärger über die STRAßE",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "ÄRGER ÜBER DIE STRASSE|ärger über die straße|Ärger über die straße|\
+         Ärger Über Die Straße"
+    );
+}
+
+#[test]
+fn test_first_last() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ v|first }}-{{ v|last }} {{ s|first }}-{{ s|last }} \
+                  {{ v|map(attr=\"len()\")|first }}-{{ v|map(attr=\"len()\")|last }} \
+                  {{ (1..=n)|first }}-{{ (1..=n)|last }}",
+        ext = "txt"
+    )]
+    struct FirstLast<'a> {
+        v: Vec<String>,
+        s: &'a [char],
+        n: u32,
+    }
+
+    let t = FirstLast {
+        v: vec!["a".to_owned(), "bb".to_owned(), "ccc".to_owned()],
+        s: &['x', 'y'],
+        n: 4,
+    };
+    assert_eq!(t.render().unwrap(), "a-ccc x-y 1-3 1-4");
+
+    let t = FirstLast {
+        v: vec![],
+        s: &['x', 'y'],
+        n: 4,
+    };
+    assert!(matches!(t.render(), Err(rinja::Error::EmptyIterable)));
+}
+
+#[test]
+fn test_last_of_non_double_ended_iterator() {
+    #[derive(Template)]
+    #[template(source = "{{ self.words()|last }}", ext = "txt")]
+    struct Last<'a> {
+        s: &'a str,
+    }
+
+    impl Last<'_> {
+        // `SkipWhile` is not a `DoubleEndedIterator`.
+        fn words(&self) -> impl Iterator<Item = &str> {
+            self.s.split(' ').skip_while(|w| w.is_empty())
+        }
+    }
+
+    assert_eq!(Last { s: "a b c" }.render().unwrap(), "c");
+    assert!(matches!(
+        Last { s: "" }.render(),
+        Err(rinja::Error::EmptyIterable)
+    ));
+}
+
+#[test]
+fn test_dictsort() {
+    use std::collections::HashMap;
+
+    #[derive(Template)]
+    #[template(
+        source = "{% for (k, v) in map|dictsort %}{{ k }}={{ v }} {% endfor %}|\
+                  {% for (k, v) in map|dictsort(reverse=true) %}{{ k }}={{ v }} {% endfor %}|\
+                  {% for (k, v) in map|dictsort(by=\"value\") %}{{ k }}={{ v }} {% endfor %}|\
+                  {% for (k, v) in map|dictsort(by=\"value\", reverse=true) %}{{ k }}={{ v }} \
+                  {% endfor %}",
+        ext = "txt"
+    )]
+    struct DictSort {
+        map: HashMap<String, u32>,
+    }
+
+    let t = DictSort {
+        map: HashMap::from([
+            ("b".to_owned(), 1),
+            ("a".to_owned(), 3),
+            ("c".to_owned(), 2),
+        ]),
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "a=3 b=1 c=2 |c=2 b=1 a=3 |b=1 c=2 a=3 |a=3 c=2 b=1 "
+    );
+}
+
+#[test]
+fn test_striptags() {
+    #[derive(Template)]
+    #[template(
+        source = r#"<meta content="{{ html|striptags }}">{{ html|striptags|safe }}"#,
+        ext = "html"
+    )]
+    struct StripTags<'a> {
+        html: &'a str,
+    }
+
+    let t = StripTags {
+        html: "<h1 class=\"x>y\">Title</h1>\n<p>Fish &amp; <i>chips</i> &lt;3</p><!-- hidden -->",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        r#"<meta content="Title Fish &#38; chips &#60;3">Title Fish & chips <3"#
+    );
+}
+
+#[test]
+fn test_round() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ a|round }} {{ a|round(2) }} {{ a|round(0, "ceil") }} {{ a|round(1, "floor") }} {{ b|round(precision=1) }} {{ b|round(method="floor") }} {{ c|round(-2, method="common") }}"#,
+        ext = "txt"
+    )]
+    struct Round {
+        a: f64,
+        b: f32,
+        c: f64,
+    }
+
+    let t = Round {
+        a: 2.456,
+        b: 2.45,
+        c: 1250.0,
+    };
+    assert_eq!(t.render().unwrap(), "2 2.46 3 2.4 2.5 2 1300");
+}
+
+#[test]
+fn test_trim_variants() {
+    #[derive(Template)]
+    #[template(
+        source = r#"[{{ s|trim }}] [{{ s|trim_start }}] [{{ s|trim_end }}] [{{ path|trim("/") }}] [{{ path|trim_start("/") }}] [{{ path|trim_end(chars="/") }}] [{{ quoted|trim(chars) }}]"#,
+        ext = "txt"
+    )]
+    struct Trim<'a> {
+        s: &'a str,
+        path: &'a str,
+        quoted: &'a str,
+        chars: &'a str,
+    }
+
+    let t = Trim {
+        s: " \u{a0}a b\t ",
+        path: "//a/b/",
+        quoted: "«„äöü“»",
+        chars: "«»„“",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "[a b] [a b\t ] [ \u{a0}a b] [a/b] [a/b/] [//a/b] [äöü]"
+    );
+}
+
+#[test]
+fn test_center() {
+    #[derive(Template)]
+    #[template(
+        ext = "txt",
+        source = r#"[{{ s|center(7) }}]
+[{{ s|center(7, "*") }}]
+[{{ s|center(width=2, fill="*") }}]
+[{{ items|join(",")|center(9, fill) }}]"#
+    )]
+    struct Center<'a> {
+        s: &'a str,
+        items: &'a [u32],
+        fill: char,
+    }
+
+    let t = Center {
+        s: "äöü",
+        items: &[1, 2, 3],
+        fill: '·',
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "[  äöü  ]\n[**äöü**]\n[äöü]\n[··1,2,3··]"
+    );
+}
+
+#[test]
+fn test_slice() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% for x in items|slice(1, 3) %}{{ x }}{% endfor %} {{ items|slice(0, 10, 2)|join(",") }} {{ items|slice(-2)|join(",") }} {{ items|slice(stop=-3)|join(",") }} {{ items|slice(step=-2)|join(",") }} {{ items|slice(start, 100)|join(",") }}"#,
+        ext = "txt"
+    )]
+    struct Slice {
+        items: Vec<String>,
+        start: isize,
+    }
+
+    let t = Slice {
+        items: vec!["a".into(), "b".into(), "c".into(), "d".into(), "e".into()],
+        start: 3,
+    };
+    assert_eq!(t.render().unwrap(), "bc a,c,e d,e a,b e,c,a d,e");
+}
+
+#[test]
+fn test_unique() {
+    struct User {
+        name: &'static str,
+        email: &'static str,
+    }
+
+    #[derive(Template)]
+    #[template(
+        ext = "txt",
+        source = r#"{% for x in items|unique %}{{ x }}{% endfor %}
+{{ tags|unique|join(",") }} {{ tags|unique(case_sensitive=false)|join(",") }} {{ tags|unique(case_sensitive=sensitive)|join(",") }}
+{{ users|unique(attr="email")|map(attr="name")|join(",") }}
+{{ users|unique(attr="email", case_sensitive=false)|map(attr="name")|join(",") }}"#
+    )]
+    struct Unique<'a> {
+        items: &'a [u32],
+        tags: Vec<&'a str>,
+        sensitive: bool,
+        users: Vec<User>,
+    }
+
+    let t = Unique {
+        items: &[3, 1, 3, 2, 1],
+        tags: vec!["rust", "Rust", "web", "rust"],
+        sensitive: false,
+        users: vec![
+            User {
+                name: "a",
+                email: "a@example.com",
+            },
+            User {
+                name: "b",
+                email: "A@example.com",
+            },
+            User {
+                name: "c",
+                email: "a@example.com",
+            },
+        ],
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "312\nrust,Rust,web rust,web rust,web\na,b\na"
+    );
+}
+
+#[test]
+fn test_markdown() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ text|markdown }}
+{{ text|markdown(sanitize=true) }}
+{{ "**<b>**"|markdown(sanitize) }}"#,
+        ext = "html"
+    )]
+    struct Markdown<'a> {
+        text: &'a str,
+        sanitize: bool,
+    }
+
+    assert_eq!(
+        Markdown {
+            text: "Hello, **<i>world</i>**! [Rinja](https://rinja.rs) [Bad](javascript:bad())",
+            sanitize: false,
+        }
+        .render()
+        .unwrap(),
+        "<p>Hello, <strong><i>world</i></strong>! <a href=\"https://rinja.rs\">Rinja</a> \
+         <a href=\"javascript:bad()\">Bad</a></p>\n\n\
+         <p>Hello, <strong>&lt;i&gt;world&lt;/i&gt;</strong>! <a href=\"https://rinja.rs\">Rinja</a> \
+         <a href=\"#\">Bad</a></p>\n\n\
+         <p><strong><b></strong></p>\n",
+    );
+}
+
+#[test]
+fn test_random() {
+    #[derive(Template)]
+    #[template(
+        ext = "txt",
+        source = r#"{{ items|random }} {{ items|random(seed=seed) }} {{ items|random(seed=7) }}
+{% for x in items|shuffle(seed) %}{{ x }}{% endfor %} {{ items|shuffle|join(",") }}"#
+    )]
+    struct Random<'a> {
+        items: &'a [&'a str],
+        seed: u64,
+    }
+
+    let t = Random {
+        items: &["a", "b", "c", "d"],
+        seed: 7,
+    };
+    let output = t.render().unwrap();
+    let (first, second) = output.split_once('\n').unwrap();
+    let picked: Vec<&str> = first.split(' ').collect();
+    assert_eq!(picked.len(), 3);
+    assert!(picked.iter().all(|x| t.items.contains(x)));
+    assert_eq!(picked[1], picked[2]);
+
+    let (seeded, shuffled) = second.split_once(' ').unwrap();
+    let mut seeded: Vec<char> = seeded.chars().collect();
+    let mut shuffled: Vec<&str> = shuffled.split(',').collect();
+    seeded.sort_unstable();
+    shuffled.sort_unstable();
+    assert_eq!(seeded, ['a', 'b', 'c', 'd']);
+    assert_eq!(shuffled, t.items);
+    assert_eq!(
+        t.render().unwrap().split_once('\n').unwrap().1[..4],
+        second[..4]
+    );
+
+    #[derive(Template)]
+    #[template(ext = "txt", source = "{{ items|random }}")]
+    struct Empty<'a> {
+        items: &'a [&'a str],
+    }
+
+    assert!(matches!(
+        Empty { items: &[] }.render(),
+        Err(rinja::Error::EmptyIterable)
+    ));
+}
+
+#[test]
+fn test_filter_error_location() {
+    #[derive(Template)]
+    #[template(
+        ext = "txt",
+        source = "{% if true %}\n  {{ s|upper|fail }}{% endif %}",
+        config_source = "[general]\nlocate_filter_errors = true"
+    )]
+    struct FilterError<'a> {
+        s: &'a str,
+    }
+
+    let err = FilterError { s: "a" }.render().unwrap_err();
+    let location = err.location().unwrap();
+    assert_eq!(location.path(), "FilterError.txt");
+    assert_eq!(location.offset(), 19);
+    assert_eq!((location.row(), location.column()), (2, 5));
+    assert_eq!(
+        rinja::source_location::<FilterError<'_>>(location.offset()),
+        (2, 5)
+    );
+    assert_eq!(err.to_string(), "FilterError.txt:2:5: it failed");
+    assert!(matches!(err.inner(), rinja::Error::Custom(_)));
+    assert!(
+        matches!(err, rinja::Error::Located(err, _) if matches!(*err, rinja::Error::Custom(_)))
+    );
+
+    #[derive(Template)]
+    #[template(ext = "txt", source = "{% if true %}\n  {{ s|upper|fail }}{% endif %}")]
+    struct NotLocated<'a> {
+        s: &'a str,
+    }
+
+    let err = NotLocated { s: "a" }.render().unwrap_err();
+    assert!(err.location().is_none());
+    assert_eq!(err.to_string(), "it failed");
+    assert!(matches!(err, rinja::Error::Custom(_)));
+}
+
+#[test]
+fn test_length() {
+    use std::collections::HashMap;
+
+    #[derive(Template)]
+    #[template(
+        ext = "txt",
+        source = "{{ s|length }} {{ v|length }} {{ m|count }} {{ a|length }} \
+            {{ big()|length }} {{ (0..4)|length }} \
+            {{ s|length + 1 }}\
+            {% if v|length > 2 %} long{% endif %}"
+    )]
+    struct Length<'a> {
+        s: &'a str,
+        v: Vec<u32>,
+        m: HashMap<&'a str, u32>,
+        a: &'a [&'a str],
+    }
+
+    impl Length<'_> {
+        fn big(&self) -> impl Iterator<Item = &u32> {
+            self.v.iter().filter(|x| **x > 1)
+        }
+    }
+
+    let t = Length {
+        s: "größe",
+        v: vec![1, 2, 3],
+        m: HashMap::from([("a", 1), ("b", 2)]),
+        a: &[],
+    };
+    assert_eq!(t.render().unwrap(), "5 3 2 0 2 4 6 long");
+    // The collections were only borrowed.
+    assert_eq!(t.v.len(), 3);
+}
+
+#[test]
+fn test_attr() {
+    use std::collections::HashMap;
+    use std::fmt::Display;
+
+    struct User<'a> {
+        name: &'a str,
+        age: u32,
+    }
+
+    impl rinja::filters::Attributes for User<'_> {
+        fn attribute(&self, name: &str) -> Option<&dyn Display> {
+            match name {
+                "name" => Some(&self.name),
+                "age" => Some(&self.age),
+                _ => None,
+            }
+        }
+    }
+
+    #[derive(Template)]
+    #[template(
+        ext = "html",
+        source = "{% for c in columns %}[{{ user|attr(c) }}|{{ map|attr(c) }}]{% endfor %}\
+            {{ user|attr(\"name\")|upper }}"
+    )]
+    struct Table<'a> {
+        columns: Vec<String>,
+        user: User<'a>,
+        map: HashMap<&'a str, &'a str>,
+    }
+
+    let mut t = Table {
+        columns: vec!["name".into(), "age".into()],
+        user: User {
+            name: "<b>",
+            age: 42,
+        },
+        map: HashMap::from([("name", "x"), ("age", "y")]),
+    };
+    assert_eq!(t.render().unwrap(), "[&#60;b&#62;|x][42|y]&#60;B&#62;");
+
+    t.columns.push("email".into());
+    let err = t.render().unwrap_err();
+    assert!(matches!(
+        &err,
+        rinja::Error::UnknownAttribute(name) if name == "email"
+    ));
+    assert_eq!(err.to_string(), "unknown attribute `email`");
+}
+
+#[test]
+fn test_xmlattr() {
+    use std::collections::BTreeMap;
+
+    #[derive(Template)]
+    #[template(
+        ext = "html",
+        source = r#"{%- macro button(label, attrs) -%}
+            <button{{ attrs|xmlattr }}>{{ label }}</button>
+        {%- endmacro -%}
+        {%- call button("Save", attrs) -%}
+        <a href="/"{{ links|xmlattr }}></a>"#
+    )]
+    struct Component<'a> {
+        attrs: BTreeMap<&'a str, Option<String>>,
+        links: BTreeMap<String, u32>,
+    }
+
+    let mut t = Component {
+        attrs: BTreeMap::from([
+            ("class", Some("btn \"primary\"".to_owned())),
+            ("disabled", None),
+            ("title", Some(String::new())),
+            ("type", Some("submit".to_owned())),
+        ]),
+        links: BTreeMap::new(),
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        r#"<button class="btn &#34;primary&#34;" type="submit">Save</button><a href="/"></a>"#
+    );
+
+    t.links.insert("tab index".to_owned(), 1);
+    let err = t.render().unwrap_err();
+    assert!(matches!(
+        &err,
+        rinja::Error::InvalidAttributeName(name) if name == "tab index"
+    ));
+    assert_eq!(err.to_string(), r#"invalid attribute name "tab index""#);
+}
+
+#[test]
+fn test_min_max_sum() {
+    struct Item {
+        name: &'static str,
+        price: f64,
+        quantity: u32,
+    }
+
+    impl Item {
+        fn total(&self) -> f64 {
+            self.price * f64::from(self.quantity)
+        }
+    }
+
+    #[derive(Template)]
+    #[template(
+        ext = "txt",
+        source = "{{ items|sum(attr=\"total()\") }} {{ items|sum(attr=\"quantity\") }} \
+            {{ (items|min(attr=\"price\")).name }} {{ (items|max(attr=\"total()\")).name }} \
+            {{ items|map(attr=\"price\")|max }} {{ [3, 1, 2]|min }} {{ (1_u8..5)|sum }}"
+    )]
+    struct Invoice {
+        items: Vec<Item>,
+    }
+
+    let items = vec![
+        Item {
+            name: "pen",
+            price: 1.5,
+            quantity: 4,
+        },
+        Item {
+            name: "ink",
+            price: 7.0,
+            quantity: 1,
+        },
+        Item {
+            name: "pad",
+            price: 2.0,
+            quantity: 3,
+        },
+    ];
+    assert_eq!(Invoice { items }.render().unwrap(), "19 8 pen ink 7 1 10");
+
+    #[derive(Template)]
+    #[template(ext = "txt", source = "{{ numbers|sum }}{{ numbers|max }}")]
+    struct Empty {
+        numbers: Vec<u8>,
+    }
+
+    let err = Empty { numbers: vec![] }.render().unwrap_err();
+    assert!(matches!(err, rinja::Error::EmptyIterable));
+    assert_eq!(
+        Empty {
+            numbers: vec![1, 2]
+        }
+        .render()
+        .unwrap(),
+        "32"
+    );
+}
+
+#[test]
+fn test_replace() {
+    #[derive(Template)]
+    #[template(
+        ext = "html",
+        source = "{{ s|replace(\"o\", \"<0>\") }} {{ s|replace(\"o\", b, 1) }} \
+            {{ n|replace(from=\".\", to=\",\") }} {{ s|replace(\"\", \"x\") }} \
+            {{ s|replace(\"o\", \"0\", count=n_max) }}"
+    )]
+    struct Replace<'a> {
+        s: &'a str,
+        b: char,
+        n: f32,
+        n_max: usize,
+    }
+
+    let t = Replace {
+        s: "foo",
+        b: 'e',
+        n: 1.5,
+        n_max: 0,
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "f&#60;0&#62;&#60;0&#62; feo 1,5 foo foo"
+    );
+}
+
+#[derive(Debug)]
+struct CountError {
+    source: std::num::ParseIntError,
+}
+
+impl std::fmt::Display for CountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("invalid count")
+    }
+}
+
+impl std::error::Error for CountError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+// A custom error returned by a filter is available through `source()`, with its own source chain.
+#[test]
+fn test_filter_custom_error() {
+    use std::error::Error as _;
+
+    #[derive(Template)]
+    #[template(ext = "txt", source = "{{ s|parse_count }}")]
+    struct CustomError<'a> {
+        s: &'a str,
+    }
+
+    assert_eq!(CustomError { s: "42" }.render().unwrap(), "42");
+
+    let err = CustomError { s: "x" }.render().unwrap_err();
+    assert_eq!(err.to_string(), "invalid count");
+    let source = err.source().unwrap();
+    assert!(source.is::<CountError>());
+    assert!(source.source().unwrap().is::<std::num::ParseIntError>());
+}
+
+#[test]
+fn test_sort() {
+    struct Post {
+        title: &'static str,
+        date: u32,
+    }
+
+    #[derive(Template)]
+    #[template(
+        ext = "txt",
+        source = r#"{% for x in numbers|sort %}{{ x }} {% endfor %}
+{{ numbers|sort(reverse=true)|join(",") }} {{ numbers|sort(reverse=reverse)|join(",") }}
+{{ names|sort|join(",") }} {{ names|sort(case_sensitive=false)|join(",") }}
+{% for post in posts|sort(attr="date", reverse=true) %}{{ post.title }}{% endfor %}
+{{ posts|sort(attr="title", case_sensitive=false)|map(attr="date")|join(",") }}"#
+    )]
+    struct Sort<'a> {
+        numbers: &'a [u32],
+        reverse: bool,
+        names: Vec<&'a str>,
+        posts: Vec<Post>,
+    }
+
+    let t = Sort {
+        numbers: &[10, 2, 33],
+        reverse: false,
+        names: vec!["b", "C", "a"],
+        posts: vec![
+            Post {
+                title: "b",
+                date: 1,
+            },
+            Post {
+                title: "A",
+                date: 3,
+            },
+            Post {
+                title: "c",
+                date: 1,
+            },
+        ],
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "2 10 33 \n33,10,2 2,10,33\nC,a,b a,b,C\nAbc\n3,1,1"
+    );
+}
+
+// A field of type `Safe<T>` is not escaped, wherever it is used.
+#[test]
+fn test_safe_field() {
+    use rinja::filters::Safe;
+
+    #[derive(Template)]
+    #[template(
+        source = "{{ body }}|{{ body|upper }}|{{ title }}|{{ body.0.len() }}",
+        ext = "html"
+    )]
+    struct Page<'a> {
+        body: Safe<&'a str>,
+        title: &'a str,
+    }
+
+    let page = Page {
+        body: Safe("<p>hi</p>"),
+        title: "<p>",
+    };
+    assert_eq!(
+        page.render().unwrap(),
+        "<p>hi</p>|&#60;P&#62;HI&#60;/P&#62;|&#60;p&#62;|9"
+    );
+}
+
+#[test]
+fn test_abs_int_float() {
+    #[derive(Template)]
+    #[template(
+        ext = "txt",
+        source = "{{ delta|abs }} {{ ratio|abs }} {{ count|int + 1 }} {{ color|int(base=16) }} \
+            {{ bad|int }} {{ bad|int(default=-1) }} {{ bad|int(default=fallback) }} \
+            {{ count|float / 2.0 }} {{ bad|float(default=0) }} {{ 7|float }}"
+    )]
+    struct Stats<'a> {
+        delta: i32,
+        ratio: f64,
+        count: &'a str,
+        color: String,
+        bad: &'a str,
+        fallback: i32,
+    }
+
+    let t = Stats {
+        delta: -4,
+        ratio: -2.5,
+        count: "5",
+        color: "0x1F".into(),
+        bad: "?",
+        fallback: 9,
+    };
+    assert_eq!(t.render().unwrap(), "4 2.5 6 31 0 -1 9 2.5 0 7");
+}
+
+#[test]
+fn test_columns() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% for column in items|columns(3) %}[{{ column|join(",") }}]{% endfor %}
+{% for column in items|columns(n=count) %}[{{ column|join(",") }}]{% endfor %}
+{{ items|columns(10)|length }} {{ none|columns(2)|length }}"#,
+        ext = "txt"
+    )]
+    struct Columns<'a> {
+        items: Vec<&'a str>,
+        none: Vec<&'a str>,
+        count: usize,
+    }
+
+    let t = Columns {
+        items: vec!["a", "b", "c", "d", "e", "f", "g"],
+        none: vec![],
+        count: 2,
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "[a,d,g][b,e][c,f]\n[a,c,e,g][b,d,f]\n10 2"
+    );
+
+    let t = Columns { count: 0, ..t };
+    assert!(t.render().is_err());
+}
+
+#[test]
+fn test_reverse() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% for x in items|reverse %}{{ x }},{% endfor %}
+{%- for x in (1..4)|reverse %}{{ x }};{% endfor %}
+{%- for x in items|unique|reverse %}{{ x }}.{% endfor %}
+{{ name|reverse }}|{{ "<a>"|reverse }}|{{ 1230|reverse }}|{{ name|upper|reverse }}"#,
+        ext = "html"
+    )]
+    struct Reverse<'a> {
+        items: Vec<u32>,
+        name: &'a str,
+    }
+
+    let t = Reverse {
+        items: vec![1, 2, 2, 3],
+        name: "rinja",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "3,2,2,1,3;2;1;3.2.1.\najnir|&#62;a&#60;|0321|AJNIR",
+    );
+    // The items are not consumed.
+    assert_eq!(t.items, [1, 2, 2, 3]);
+
+    #[derive(Template)]
+    #[template(
+        source = "{% for x in slice|reverse %}{{ x }}{% endfor %}-{{ vec|reverse|join(\",\") }}",
+        ext = "txt"
+    )]
+    struct ReverseRef<'a> {
+        slice: &'a [&'a str],
+        vec: &'a Vec<u32>,
+    }
+
+    let t = ReverseRef {
+        slice: &["a", "b", "c"],
+        vec: &vec![1, 2, 3],
+    };
+    assert_eq!(t.render().unwrap(), "cba-3,2,1");
+}
+
+#[test]
+fn test_reverse_graphemes() {
+    #[derive(Template)]
+    #[template(source = "{{ s|reverse }}", ext = "txt")]
+    struct Reverse<'a> {
+        s: &'a str,
+    }
+
+    // Combining characters stay with their base character.
+    assert_eq!(
+        Reverse {
+            s: "cafe\u{301} noe\u{308}l"
+        }
+        .render()
+        .unwrap(),
+        "le\u{308}on e\u{301}fac",
+    );
+    // Emoji with skin tones, ZWJ sequences and flags are not torn apart.
+    assert_eq!(
+        Reverse {
+            s: "👍🏽👨‍👩‍👧🇩🇪🇫🇷"
+        }
+        .render()
+        .unwrap(),
+        "🇫🇷🇩🇪👨‍👩‍👧👍🏽",
+    );
+    assert_eq!(Reverse { s: "" }.render().unwrap(), "");
+}
+
+#[test]
+fn test_date() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ day|date("%Y-%m-%d") }} {{ at|date("%d.%m.%Y %H:%M") }} {{ at|date(fmt) }}"#,
+        ext = "html"
+    )]
+    struct Date<'a> {
+        day: chrono::NaiveDate,
+        at: &'a time::PrimitiveDateTime,
+        fmt: String,
+    }
+
+    let at = time::macros::datetime!(2024-08-26 13:05:09);
+    let t = Date {
+        day: chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+        at: &at,
+        fmt: "<%T>".to_owned(),
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "2024-02-29 26.08.2024 13:05 &#60;13:05:09&#62;"
+    );
+}
+
+#[test]
+fn test_date_invalid_pattern() {
+    #[derive(Template)]
+    #[template(source = "{{ day|date(fmt) }}", ext = "txt")]
+    struct Date<'a> {
+        day: chrono::NaiveDate,
+        fmt: &'a str,
+    }
+
+    let day = chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+    let err = Date { day, fmt: "%Y-%Q" }.render().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        r#"invalid date pattern "%Y-%Q": bad or unsupported format string"#
+    );
+    // The pattern is valid, but a date has no hour:
+    let err = Date { day, fmt: "%H:%M" }.render().unwrap_err().to_string();
+    assert!(
+        err.starts_with(r#"invalid date pattern "%H:%M": "#),
+        "{err}"
+    );
+}
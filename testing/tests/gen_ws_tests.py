@@ -87,8 +87,7 @@ def write_cond(conds, active_branch, sign, invert):
             expected += after[0] * (not nws)
             expected += after[1]
 
-    # FIXME: Rinja does not include whitespace before eof
-    # expected += lits[-1][2]
+    expected += lits[-1][2]
 
     return code, expected
 
@@ -197,6 +196,66 @@ fn test_match_ws() {
     f.write("}\n")
 
 
+def escape(s):
+    return s.replace("\n", "\\n")
+
+
+def write_tag(ws, tag):
+    pws, nws = ws
+    return f"{{%{handle_ws(pws)} {tag} {handle_ws(nws)}%}}"
+
+
+def write_tag_tests(f):
+    f.write("""
+macro_rules! test_tag {
+    ($source:literal, $rendered:expr) => {{
+        #[derive(Template)]
+        #[template(source = $source, ext = "txt")]
+        struct TagWs;
+
+        assert_eq!(TagWs.render().unwrap(), $rendered);
+    }};
+}
+
+#[rustfmt::skip]
+#[test]
+fn test_tag_ws() {
+""")
+
+    before, body, after = "before \n ", " \n body \n ", " \n after"
+    # The macro definition is placed after the tested tags, so it does not add any whitespace.
+    suffix = "{% macro m() %}{% endmacro %}"
+
+    block_tags = [
+        ("for _ in 0..1", "endfor"),
+        ("filter lower", "endfilter"),
+        ("autoescape false", "endautoescape"),
+        ("with x = 1", "endwith"),
+        ("block b", "endblock"),
+        ("raw", "endraw"),
+    ]
+    for i, (start, end) in enumerate(block_tags):
+        if i > 0:
+            f.write("\n")
+        for x in product([False, True], repeat=4):
+            code = before + write_tag(x[0:2], start) + body + write_tag(x[2:4], end) + after
+            expected = "".join([
+                trim(before, (False, x[0])),
+                trim(body, (x[1], x[2])),
+                trim(after, (x[3], False)),
+            ])
+            f.write(f'    test_tag!("{escape(code)}", "{escape(expected)}");\n')
+
+    for tag in ["let x = 1", "call m()"]:
+        f.write("\n")
+        for x in product([False, True], repeat=2):
+            code = before + write_tag(x, tag) + after + suffix
+            expected = trim(before, (False, x[0])) + trim(after, (x[1], False))
+            f.write(f'    test_tag!("{escape(code)}", "{escape(expected)}");\n')
+
+    f.write("}\n")
+
+
 if __name__ == "__main__":
     with open("ws.rs", "w") as f:
         f.write("// This file is auto generated by gen_ws_tests.py\n\n")
@@ -204,3 +263,4 @@ if __name__ == "__main__":
         write_cond_tests(f, "", "")
         write_cond_tests(f, "_inverted", ', config = "test_trim.toml"')
         write_match_tests(f)
+        write_tag_tests(f)
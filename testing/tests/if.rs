@@ -24,3 +24,119 @@ else
     assert_eq!(If { s: "c" }.render().unwrap(), "c");
     assert_eq!(If { s: "d" }.render().unwrap(), "else");
 }
+
+#[test]
+fn test_if_long_chain() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{%- if n == 0 -%}
+zero
+{%- elif n == 1 -%}
+one
+{%- else if n == 2 -%}
+two
+{%- else  if n == 3 -%}
+three
+{%- elif let Some(4) = opt -%}
+four
+{%- else if let Some(x) = opt -%}
+{% if *x > 10 %}big{% elif *x > 5 %}medium{% else %}small{% endif %}
+{%- elif n % 2 == 0 -%}
+even
+{%- endif -%}"#,
+        ext = "txt"
+    )]
+    struct Chain {
+        n: u32,
+        opt: Option<u32>,
+    }
+
+    let render = |n, opt| Chain { n, opt }.render().unwrap();
+    assert_eq!(render(0, None), "zero");
+    assert_eq!(render(1, None), "one");
+    assert_eq!(render(2, None), "two");
+    assert_eq!(render(3, Some(4)), "three");
+    assert_eq!(render(5, Some(4)), "four");
+    assert_eq!(render(5, Some(11)), "big");
+    assert_eq!(render(5, Some(6)), "medium");
+    assert_eq!(render(5, Some(5)), "small");
+    assert_eq!(render(6, None), "even");
+    assert_eq!(render(7, None), "");
+}
+
+#[test]
+fn test_if_else_expr() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ "yes" if flag else "no" }}
+{{ a if flag else b }}
+{{ 1 if flag else 2 }} {{ (1 if flag else 2) + 10 }}
+{{ "small" if n < 10 else "medium" if n < 100 else "large" }}
+{{ "<b>"|safe if flag else "<i>"|safe }}
+{% let x = a if !flag else b %}{{ x }}
+{%- for i in [1, 3] %} {{ i if flag else 0 }}{% endfor %}"#,
+        ext = "html"
+    )]
+    struct IfElse {
+        flag: bool,
+        n: u32,
+        a: String,
+        b: String,
+    }
+
+    let mut t = IfElse {
+        flag: true,
+        n: 5,
+        a: "<a>".to_string(),
+        b: "b".to_string(),
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "yes\n&#60;a&#62;\n1 11\nsmall\n<b>\nb 1 3"
+    );
+
+    t.flag = false;
+    t.n = 500;
+    assert_eq!(
+        t.render().unwrap(),
+        "no\nb\n2 12\nlarge\n<i>\n&#60;a&#62; 0 0"
+    );
+}
+
+#[test]
+fn test_if_else_expr_short_circuits() {
+    use std::cell::Cell;
+
+    #[derive(Template)]
+    #[template(source = r#"{{ a() if flag else b() }}"#, ext = "txt")]
+    struct ShortCircuit {
+        flag: bool,
+        calls: Cell<u32>,
+    }
+
+    impl ShortCircuit {
+        fn a(&self) -> &'static str {
+            self.calls.set(self.calls.get() + 1);
+            "a"
+        }
+
+        fn b(&self) -> &'static str {
+            self.calls.set(self.calls.get() + 10);
+            "b"
+        }
+    }
+
+    let t = ShortCircuit {
+        flag: true,
+        calls: Cell::new(0),
+    };
+    assert_eq!(t.render().unwrap(), "a");
+    assert_eq!(t.calls.get(), 1);
+
+    let t = ShortCircuit {
+        flag: false,
+        calls: Cell::new(0),
+    };
+    assert_eq!(t.render().unwrap(), "b");
+    assert_eq!(t.calls.get(), 10);
+}
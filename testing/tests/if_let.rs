@@ -128,3 +128,50 @@ else
     assert_eq!(Elif { s: None }.render().unwrap(), "empty");
     assert_eq!(Elif { s: Some("tada") }.render().unwrap(), "tada");
 }
+
+#[test]
+fn test_if_let_patterns() {
+    #[derive(Template)]
+    #[template(
+        source = r#"
+            {%- if let Some((name, _)) = pair %}{{ name }}{% endif %}|
+            {%- if let Some(Some(value)) = nested %}{{ value }}{% endif %}|
+            {%- if let Some(count) = counts.get("a") %}{{ count }}{% endif %}|
+            {%- if let Some(first) = self.first() %}{{ first }}{% endif %}|
+            {%- if let Ok(code) or Err(code) = status %}{{ code }}{% endif %}|
+            {%- if let Some(name) = name %}[{{ name }}]{% endif %} {{ name.is_some() }}"#,
+        ext = "txt"
+    )]
+    struct IfLetPatterns<'a> {
+        pair: Option<(&'a str, u32)>,
+        nested: Option<Option<u32>>,
+        counts: std::collections::HashMap<&'a str, u32>,
+        status: Result<u16, u16>,
+        name: Option<&'a str>,
+    }
+
+    impl IfLetPatterns<'_> {
+        fn first(&self) -> Option<u32> {
+            self.nested.flatten()
+        }
+    }
+
+    let t = IfLetPatterns {
+        pair: Some(("pair", 1)),
+        nested: Some(Some(2)),
+        counts: [("a", 3)].into_iter().collect(),
+        status: Err(404),
+        name: Some("name"),
+    };
+    // `name` is bound in the block only, afterwards it is the field again.
+    assert_eq!(t.render().unwrap(), "pair|2|3|2|404|[name] true");
+
+    let t = IfLetPatterns {
+        pair: None,
+        nested: Some(None),
+        counts: Default::default(),
+        status: Ok(200),
+        name: None,
+    };
+    assert_eq!(t.render().unwrap(), "||||200| false");
+}
@@ -102,3 +102,199 @@ fn block_in_include() {
         "block_in_partial: from partial!\n"
     );
 }
+
+// With `check_block_overrides`, a child template can only override the blocks of its `extends`
+// chain: `block_in_include_extended.html` is rejected because it overrides `block_in_partial`, see
+// `tests/ui/block-override-typo.rs`.
+#[test]
+fn block_in_include_checked() {
+    #[derive(Template)]
+    #[template(
+        path = "block_in_include_override.html",
+        config = "check-block-overrides.toml"
+    )]
+    struct TmplOverride;
+
+    assert_eq!(
+        TmplOverride.render().unwrap(),
+        "block_in_base: from extended!\nblock_in_partial: from partial!\n"
+    );
+}
+
+#[test]
+fn test_include_dependencies() {
+    #[derive(Template)]
+    #[template(path = "include.html")]
+    struct IncludeTemplate<'a> {
+        strs: &'a [&'a str],
+    }
+
+    #[derive(Template)]
+    #[template(source = r#"{% include "included.html" %}"#, ext = "html")]
+    struct IncludeSource<'a> {
+        s: &'a str,
+    }
+
+    let templates = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("templates");
+    let include = templates.join("include.html").canonicalize().unwrap();
+    let included = templates.join("included.html").canonicalize().unwrap();
+
+    assert_eq!(
+        rinja::dependencies_of::<IncludeTemplate<'_>>(),
+        [include.to_str().unwrap(), included.to_str().unwrap()],
+    );
+    assert_eq!(
+        rinja::dependencies_of::<IncludeSource<'_>>(),
+        [included.to_str().unwrap()],
+    );
+}
+
+#[test]
+fn test_include_all() {
+    #[derive(Template)]
+    #[template(path = "include-all.html")]
+    struct IncludeAll<'a> {
+        title: &'a str,
+        body: &'a str,
+    }
+
+    let t = IncludeAll {
+        title: "Hello",
+        body: "<world>",
+    };
+    assert_eq!(t.render().unwrap(), "<h1>Hello</h1><p>&#60;world&#62;</p>");
+}
+
+#[test]
+fn test_include_all_source() {
+    #[derive(Template)]
+    #[template(
+        source = "[{% include_all \"components/?-*\" %}] [{% include_all \"components/*.txt\" -%} ]",
+        ext = "txt"
+    )]
+    struct IncludeAllSource<'a> {
+        title: &'a str,
+        body: &'a str,
+    }
+
+    let t = IncludeAllSource {
+        title: "Hello",
+        body: "world",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "[<h1>Hello</h1><p>world</p>] [not a component]"
+    );
+}
+
+#[test]
+fn test_include_ignore_missing() {
+    #[derive(Template)]
+    #[template(
+        source = r#"[{% include "does-not-exist.html" ignore missing %}] [{% include "included.html" ignore missing %}]"#,
+        ext = "html"
+    )]
+    struct IncludeIgnoreMissing<'a> {
+        s: &'a str,
+    }
+
+    let t = IncludeIgnoreMissing { s: "foo" };
+    assert_eq!(t.render().unwrap(), "[] [INCLUDED: foo]");
+
+    let templates = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("templates");
+    let included = templates.join("included.html").canonicalize().unwrap();
+    assert_eq!(
+        rinja::dependencies_of::<IncludeIgnoreMissing<'_>>(),
+        [included.to_str().unwrap()],
+    );
+}
+
+#[test]
+fn test_include_with() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% include "included/widget.html" with title = name|upper %}
+{% include "included/widget.html" with title = name, count = count + 1 only %}
+{% include "included/widget.html" with title = "<x>" only %}
+{% let title = "outer" -%}
+{% include "included/widget.html" with title = "inner", %} {{ title }}"#,
+        ext = "html"
+    )]
+    struct IncludeWith<'a> {
+        name: &'a str,
+        count: u32,
+    }
+
+    let t = IncludeWith {
+        name: "Alice",
+        count: 7,
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "<b>ALICE</b> (7)\n<b>Alice</b> (8)\n<b>&#60;x&#62;</b>\n<b>inner</b> (7) outer"
+    );
+}
+
+// Every missing template emits a deprecation warning as a compile-time diagnostic.
+#[allow(deprecated)]
+#[test]
+fn test_allow_missing_templates() {
+    #[derive(Template)]
+    #[template(
+        source = r#"[{% include "does-not-exist.html" %}] [{% include "included.html" %}]"#,
+        ext = "html",
+        config = "allow-missing-templates.toml"
+    )]
+    struct MissingInclude<'a> {
+        s: &'a str,
+    }
+
+    let t = MissingInclude { s: "foo" };
+    assert_eq!(
+        t.render().unwrap(),
+        "[<!-- missing: does-not-exist.html -->] [INCLUDED: foo]"
+    );
+
+    #[derive(Template)]
+    #[template(
+        source = r#"{% extends "does-not-exist.html" %}{% block content %}[{{ s }}]{% endblock %}"#,
+        ext = "html",
+        config = "allow-missing-templates.toml"
+    )]
+    struct MissingExtends<'a> {
+        s: &'a str,
+    }
+
+    let t = MissingExtends { s: "foo" };
+    assert_eq!(
+        t.render().unwrap(),
+        "<!-- missing: does-not-exist.html -->[foo]"
+    );
+
+    #[derive(Template)]
+    #[template(
+        source = r#"var x = [{% include "does-not-exist.js" %}];"#,
+        ext = "js",
+        escape = "none",
+        config = "allow-missing-templates.toml"
+    )]
+    struct MissingJs;
+
+    assert_eq!(
+        MissingJs.render().unwrap(),
+        "var x = [/* missing: does-not-exist.js */];"
+    );
+
+    #[derive(Template)]
+    #[template(
+        source = r#"[{% include "does-not-exist.txt" %}] [{{ s }}]"#,
+        ext = "txt",
+        config = "allow-missing-templates.toml"
+    )]
+    struct MissingTxt<'a> {
+        s: &'a str,
+    }
+
+    let t = MissingTxt { s: "foo" };
+    assert_eq!(t.render().unwrap(), "[] [foo]");
+}
@@ -354,3 +354,43 @@ fn test_named_end() {
     let n = NamedBlocks { title: "title" };
     assert_eq!(n.render().unwrap(), "title\n\ntadam\nCopyright 2017");
 }
+
+#[test]
+fn test_check_block_overrides() {
+    #[derive(Template)]
+    #[template(path = "named-end-blocks.html", config = "check-block-overrides.toml")]
+    struct NamedBlocks<'a> {
+        title: &'a str,
+    }
+
+    let n = NamedBlocks { title: "title" };
+    assert_eq!(n.render().unwrap(), "title\n\ntadam\nCopyright 2017");
+}
+
+// `{{ super() }}` renders the block of the parent template, which can call `super()` itself.
+#[test]
+fn test_super_expr() {
+    #[derive(Template)]
+    #[template(path = "super-expr-mid.txt")]
+    struct SuperExprMid;
+
+    #[derive(Template)]
+    #[template(path = "super-expr-kid.txt")]
+    struct SuperExprKid;
+
+    assert_eq!(SuperExprMid.render().unwrap(), "<base.js mid.js>");
+    assert_eq!(SuperExprKid.render().unwrap(), "<base.js mid.js kid.js>");
+}
+
+// Blocks in the body of a `{% call %}` can be overridden like any other block.
+#[test]
+fn test_block_in_call_body() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% extends "block-in-call-base.html" %}{% block content %}child {{ super() }}{% endblock %}"#,
+        ext = "html"
+    )]
+    struct BlockInCallBody;
+
+    assert_eq!(BlockInCallBody.render().unwrap(), "<main>child base</main>");
+}
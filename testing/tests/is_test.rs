@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use rinja::Template;
+
+#[test]
+fn test_is_none() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ a is none }} {{ a is not none }} {{ b is none }}|\
+                  {% for x in items %}{% if x is none %}-{% else %}{{ x.unwrap() }}{% endif %}\
+                  {% endfor %}",
+        ext = "txt"
+    )]
+    struct IsNone<'a> {
+        a: Option<&'a str>,
+        b: Option<u32>,
+        items: Vec<Option<u32>>,
+    }
+
+    let t = IsNone {
+        a: None,
+        b: Some(1),
+        items: vec![Some(1), None, Some(3)],
+    };
+    assert_eq!(t.render().unwrap(), "true false false|1-3");
+}
+
+#[test]
+fn test_is_type() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ s is string }} {{ owned is string }} {{ n is string }}|\
+                  {{ n is number }} {{ f is number }} {{ s is number }} {{ flag is number }}|\
+                  {{ list is iterable }} {{ map is iterable }} {{ s is iterable }} \
+                  {{ n is not iterable }}|\
+                  {% for x in list %}{{ x is number }}{% endfor %}",
+        ext = "txt"
+    )]
+    struct IsType<'a> {
+        s: &'a str,
+        owned: String,
+        n: u32,
+        f: f64,
+        flag: bool,
+        list: Vec<u8>,
+        map: HashMap<&'a str, u8>,
+    }
+
+    let t = IsType {
+        s: "a",
+        owned: "b".to_owned(),
+        n: 1,
+        f: 1.5,
+        flag: true,
+        list: vec![1],
+        map: HashMap::new(),
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "true true false|true true false false|true true false true|true"
+    );
+}
+
+#[test]
+fn test_is_comparison() {
+    #[derive(Template)]
+    #[template(
+        source = "{% for x in 1..4 %}\
+                  {% if x is eq(2) %}={% endif %}\
+                  {% if x is not equalto(2) %}!{% endif %}\
+                  {% if x is ne(limit) %}~{% endif %}\
+                  {% if x is lt(2) %}<{% endif %}\
+                  {% if x is le(2) %}[{% endif %}\
+                  {% if x is gt(limit) %}>{% endif %}\
+                  {% if x is ge(2) %}]{% endif %}\
+                  {% if x is lessthan(3) %}({% endif %}\
+                  {% if x is greaterthan(1 + 1) %}^{% endif %} \
+                  {% endfor %}",
+        ext = "txt"
+    )]
+    struct Comparison {
+        limit: i32,
+    }
+
+    assert_eq!(
+        Comparison { limit: 2 }.render().unwrap(),
+        "!~<[( =[]( !~>]^ "
+    );
+}
+
+#[test]
+fn test_selectattr_with_tests() {
+    struct Item {
+        name: &'static str,
+        label: Option<&'static str>,
+    }
+
+    #[derive(Template)]
+    #[template(
+        source = r#"{% for item in items|selectattr("label", "none") %}{{ item.name }}{% endfor %}|
+{%- for item in items|rejectattr("label", "none") %}{{ item.name }}{% endfor %}|
+{%- for item in items|selectattr("name", "string") %}{{ item.name }}{% endfor %}"#,
+        ext = "txt"
+    )]
+    struct Items {
+        items: Vec<Item>,
+    }
+
+    let t = Items {
+        items: vec![
+            Item {
+                name: "a",
+                label: None,
+            },
+            Item {
+                name: "b",
+                label: Some("B"),
+            },
+            Item {
+                name: "c",
+                label: None,
+            },
+        ],
+    };
+    assert_eq!(t.render().unwrap(), "ac|b|abc");
+}
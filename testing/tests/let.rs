@@ -51,3 +51,72 @@ fn underscore_ident2() {
 
     assert_eq!(X.render().unwrap(), "hey\nhoy\nmatched");
 }
+
+#[test]
+fn set_block() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% set link -%}
+            <a href="{{ url }}">{{ name }}</a>
+        {%- endset %}{{ link }} {{ link }}"#,
+        ext = "html"
+    )]
+    struct Link<'a> {
+        url: &'a str,
+        name: &'a str,
+    }
+
+    let link = Link {
+        url: "/?a=1&b=2",
+        name: "<b>",
+    };
+    assert_eq!(
+        link.render().unwrap(),
+        r#"<a href="/?a=1&#38;b=2">&#60;b&#62;</a> <a href="/?a=1&#38;b=2">&#60;b&#62;</a>"#,
+    );
+}
+
+#[test]
+fn set_block_filters() {
+    #[derive(Template)]
+    #[template(
+        source = "{% set greeting | trim | upper %}  hello {{ name }}  {% endset %}[{{ greeting }}]",
+        ext = "txt"
+    )]
+    struct Greeting<'a> {
+        name: &'a str,
+    }
+
+    assert_eq!(
+        Greeting { name: "world" }.render().unwrap(),
+        "[HELLO WORLD]"
+    );
+}
+
+// The body of the block can use the variable it shadows.
+#[test]
+fn set_block_shadowing() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% let a = "x" %}{% set a %}[{{ a }}]{% endset %}{% set a %}({{ a }}){% endset %}{{ a }}"#,
+        ext = "txt"
+    )]
+    struct Shadow;
+
+    assert_eq!(Shadow.render().unwrap(), "([x])");
+}
+
+// Without an `endset`, `set` still declares a variable that is initialized later on.
+#[test]
+fn set_declaration() {
+    #[derive(Template)]
+    #[template(
+        source = "{% set x %}{% if y %}{% set x = 1 %}{% else %}{% set x = 2 %}{% endif %}{{ x }}",
+        ext = "txt"
+    )]
+    struct Decl {
+        y: bool,
+    }
+
+    assert_eq!(Decl { y: false }.render().unwrap(), "2");
+}
@@ -0,0 +1,22 @@
+use rinja::Template;
+
+// The Markdown heading is turned into HTML before the template gets parsed. This is done by the
+// loader `loader/rinja-test-loader.rs`, which is compiled by `build.rs`.
+#[test]
+fn test_loader() {
+    #[derive(Template)]
+    #[template(path = "loader.md", config = "loader-config.toml")]
+    struct Loaded<'a> {
+        name: &'a str,
+        place: &'a str,
+    }
+
+    let t = Loaded {
+        name: "world",
+        place: "rinja",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "<h1>Hello, world!</h1>\nWelcome to rinja."
+    );
+}
@@ -349,6 +349,51 @@ fn test_loop_continue() {
     assert_eq!(t.render().unwrap(), "x1yx2yx4yx5y");
 }
 
+#[test]
+fn test_loop_labels() {
+    #[derive(Template)]
+    #[template(
+        source = "\
+            {%- for row in rows as outer -%}\
+                [{%- for cell in row -%}\
+                    {%- if *cell == 0 %}{% continue outer %}{% endif -%}\
+                    {%- if *cell < 0 %}{% break outer %}{% endif -%}\
+                    {{ cell }}\
+                {%- endfor -%}]\
+            {%- endfor -%}",
+        ext = "txt"
+    )]
+    struct Labels<'a> {
+        rows: &'a [&'a [i32]],
+    }
+
+    let t = Labels {
+        rows: &[&[1, 2], &[3, 0, 4], &[5], &[6, -1, 7], &[8]],
+    };
+    assert_eq!(t.render().unwrap(), "[12][3[5][6");
+}
+
+#[test]
+fn test_loop_labels_unused_and_with_condition() {
+    #[derive(Template)]
+    #[template(
+        source = "\
+            {%- for a in 0..3 as outer if a % 2 == 0 -%}\
+                {%- for b in 0..3 as inner -%}\
+                    {%- if b > a %}{% continue outer %}{% endif -%}\
+                    {%- for c in 0..1 as unused %}{{ a }}{{ b }}{{ c }} {% endfor -%}\
+                    {%- if b == 2 %}{% break inner %}{% endif -%}\
+                {%- endfor -%}\
+            {%- else -%}\
+                empty\
+            {%- endfor -%}",
+        ext = "txt"
+    )]
+    struct Labels;
+
+    assert_eq!(Labels.render().unwrap(), "000 200 210 220 ");
+}
+
 #[test]
 fn test_loop_break_continue() {
     #[derive(Template)]
@@ -440,6 +485,44 @@ fn test_for_in_if() {
     assert_eq!(t.render().unwrap(), ":(");
 }
 
+#[test]
+fn test_for_in_if_loop_variables() {
+    struct User {
+        name: &'static str,
+        active: bool,
+    }
+
+    #[derive(Template)]
+    #[template(
+        source = "{% for u in users if u.active -%}
+            {{ loop.index }}:{{ u.name }}{% if !loop.last %}, {% endif %}
+        {%- else -%}
+            nobody
+        {%- endfor %}",
+        ext = "txt"
+    )]
+    struct ForInIfUsers {
+        users: Vec<User>,
+    }
+
+    let user = |name, active| User { name, active };
+    let t = ForInIfUsers {
+        users: vec![
+            user("a", false),
+            user("b", true),
+            user("c", false),
+            user("d", true),
+            user("e", false),
+        ],
+    };
+    assert_eq!(t.render().unwrap(), "1:b, 2:d");
+
+    let t = ForInIfUsers {
+        users: vec![user("a", false), user("b", false)],
+    };
+    assert_eq!(t.render().unwrap(), "nobody");
+}
+
 // This is a regression test for <https://github.com/rinja-rs/rinja/issues/150>.
 // The loop didn't drop its locals context, creating a bug where a field could
 // not be retrieved although it existed.
@@ -464,3 +547,208 @@ fn test_loop_locals() {
     let t = LoopLocalsContext { bla: 10 };
     assert_eq!(t.render().unwrap(), "10");
 }
+
+#[test]
+fn test_loop_previtem_nextitem() {
+    struct Event {
+        date: &'static str,
+        name: &'static str,
+    }
+
+    #[derive(Template)]
+    #[template(
+        source = "
+{%- for event in events -%}
+    {%- if let Some(prev) = loop.previtem -%}
+        {%- if prev.date != event.date %}[{{ event.date }}]{% endif -%}
+    {%- else -%}
+        [{{ event.date }}]
+    {%- endif -%}
+    {{ event.name }}
+    {%- if loop.nextitem.is_some() %},{% endif -%}
+{%- endfor -%}",
+        ext = "txt"
+    )]
+    struct DateHeaders<'a> {
+        events: &'a [Event],
+    }
+
+    let events = [
+        Event {
+            date: "mon",
+            name: "a",
+        },
+        Event {
+            date: "mon",
+            name: "b",
+        },
+        Event {
+            date: "tue",
+            name: "c",
+        },
+    ];
+    let t = DateHeaders { events: &events };
+    assert_eq!(t.render().unwrap(), "[mon]a,b,[tue]c");
+}
+
+#[test]
+fn test_loop_previtem_nextitem_owned_and_nested() {
+    #[derive(Template)]
+    #[template(
+        source = "
+{%- for s in strings.clone() if !s.is_empty() -%}
+    {%- if let Some(p) = loop.previtem %}{{ p }}{% else %}-{% endif -%}
+    <{{ s }}>
+    {%- if let Some(n) = loop.nextitem %}{{ n }}{% else %}-{% endif -%}
+    {%- for i in 0..2 -%}
+        ({{ loop.previtem.is_none() }})
+    {%- endfor %} {% endfor -%}",
+        ext = "txt"
+    )]
+    struct Owned {
+        strings: Vec<String>,
+    }
+
+    let t = Owned {
+        strings: vec!["a".into(), "".into(), "b".into()],
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "-<a>b(true)(false) a<b>-(true)(false) "
+    );
+}
+
+#[test]
+fn test_for_destructuring_hash_map() {
+    #[derive(Template)]
+    #[template(
+        source = "{% for (name, age) in people %}{{ name }}={{ age }}\n{% endfor %}",
+        ext = "txt"
+    )]
+    struct People<'a> {
+        people: &'a std::collections::HashMap<&'a str, u32>,
+    }
+
+    let people = [("alice", 31), ("bob", 27)].into_iter().collect();
+    let output = People { people: &people }.render().unwrap();
+    let mut lines = output.lines().collect::<Vec<_>>();
+    lines.sort_unstable();
+    assert_eq!(lines, ["alice=31", "bob=27"]);
+}
+
+#[test]
+fn test_for_destructuring_vec_of_tuples() {
+    #[derive(Template)]
+    #[template(
+        source = "{% for (id, (name, tags)) in rows %}{{ id }}:{{ name }}[{{ tags.len() }}] {% endfor %}\
+                  {% for (id, name) in pairs %}{{ id }}={{ name }} {% endfor %}",
+        ext = "txt"
+    )]
+    struct Rows {
+        rows: Vec<(i32, (String, Vec<u8>))>,
+        pairs: Vec<(i32, String)>,
+    }
+
+    let t = Rows {
+        rows: vec![(1, ("a".into(), vec![1, 2])), (2, ("b".into(), vec![]))],
+        pairs: vec![(-1, "x".into()), (7, "y".into())],
+    };
+    assert_eq!(t.render().unwrap(), "1:a[2] 2:b[0] -1=x 7=y ");
+}
+
+#[test]
+fn test_for_destructuring_struct() {
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    struct Named<'a>(&'a str, Point);
+
+    #[derive(Template)]
+    #[template(
+        source = "{% for Point { x, y: height } in points %}({{ x }},{{ height }}){% endfor %} \
+                  {% for Named(name, Point { x, .. }) in named %}{{ name }}@{{ x }} {% endfor %}",
+        ext = "txt"
+    )]
+    struct Points<'a> {
+        points: Vec<Point>,
+        named: &'a [Named<'a>],
+    }
+
+    let t = Points {
+        points: vec![Point { x: 1, y: 2 }, Point { x: -3, y: 4 }],
+        named: &[Named("a", Point { x: 5, y: 0 })],
+    };
+    assert_eq!(t.render().unwrap(), "(1,2)(-3,4) a@5 ");
+}
+
+#[test]
+fn test_loop_length() {
+    #[derive(Template)]
+    #[template(
+        source = r#"
+{%- for name in names -%}
+    {{ name }} ({{ loop.index }} of {{ loop.length }}){% if !loop.last %}, {% endif -%}
+{%- endfor %}
+{% for name in names if name.len() > 3 -%}
+    {{ loop.index }}/{{ loop.length }}{% if loop.index == loop.length %}!{% endif %}
+{%- endfor %}
+{% for i in 0..count -%}
+    {% for c in chars.chars() -%}
+        {{ i }}{{ c }}{{ loop.length }}
+    {%- endfor %}{{ loop.length }}|
+{%- else -%}
+    empty
+{%- endfor %}"#,
+        ext = "txt"
+    )]
+    struct LoopLength<'a> {
+        names: &'a [&'a str],
+        chars: &'a str,
+        count: u64,
+    }
+
+    let t = LoopLength {
+        names: &["Ann", "Bruce", "Chloe"],
+        chars: "ab",
+        count: 2,
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "Ann (1 of 3), Bruce (2 of 3), Chloe (3 of 3)\n1/22/2!\n0a20b22|1a21b22|"
+    );
+
+    let t = LoopLength {
+        names: &[],
+        chars: "",
+        count: 0,
+    };
+    assert_eq!(t.render().unwrap(), "\n\nempty");
+}
+
+#[test]
+fn test_ranges() {
+    #[derive(Template)]
+    #[template(
+        source = r#"
+{%- for i in 0..n %}{{ i }}{% if !loop.last %},{% endif %}{% endfor %}
+{% for i in 0..=n %}{{ i }}{% endfor %}
+{% for i in (0..n).rev() %}{{ loop.index }}:{{ i }}{% if loop.last %}.{% endif %} {% endfor %}
+{% for i in (1..=n).rev() %}{{ i }}/{{ loop.length }} {% endfor %}
+{% for i in -2..2 %}{{ i }} {% endfor %}
+{% for i in (-n..-1).rev() %}{{ i }} {% endfor %}
+{% for i in n..0 %}{{ i }}{% else %}empty{% endfor %}
+{% for i in (0..0).rev() %}{{ i }}{% else %}empty{% endfor %}
+{% for i in 0..=0 %}[{{ i }}]{% endfor %}"#,
+        ext = "txt"
+    )]
+    struct Ranges {
+        n: i32,
+    }
+
+    assert_eq!(
+        Ranges { n: 3 }.render().unwrap(),
+        "0,1,2\n0123\n1:2 2:1 3:0. \n3/3 2/3 1/3 \n-2 -1 0 1 \n-2 -3 \nempty\nempty\n[0]"
+    );
+}
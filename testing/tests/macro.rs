@@ -195,6 +195,31 @@ fn test_default_value() {
     );
 }
 
+// This test ensures that keyword arguments can be passed in any order, and that
+// positional and keyword arguments can be mixed with default values.
+#[test]
+fn test_keyword_arguments_any_order() {
+    #[derive(Template)]
+    #[template(
+        source = "{%- macro card(title, body, footer=\"(none)\") -%}
+[{{ title }}|{{ body }}|{{ footer }}]
+{%- endmacro -%}
+
+{%- call card(title=\"a\", body=\"b\") -%}
+{%- call card(body=\"c\", title=\"d\") -%}
+{%- call card(\"e\", footer=\"f\", body=\"g\") -%}
+{%- call card(footer=\"h\", body=\"i\", title=\"j\") -%}
+",
+        ext = "html"
+    )]
+    struct MacroKeywordArguments;
+
+    assert_eq!(
+        MacroKeywordArguments.render().unwrap(),
+        "[a|b|(none)][d|c|(none)][e|g|f][j|i|h]"
+    );
+}
+
 // This test ensures that the mix of named argument and default value generates
 // the expected result.
 #[test]
@@ -358,3 +383,180 @@ fn test_rust_keywords_as_args_with_default_expr() {
     // primarily checking for compilation
     assert_eq!(MacroRustKwArgsDefaultExpr.render().unwrap(), "3\n1\n1\n");
 }
+
+struct TreeNode<'a> {
+    name: &'a str,
+    children: Vec<TreeNode<'a>>,
+}
+
+fn tree() -> TreeNode<'static> {
+    TreeNode {
+        name: "<root>",
+        children: vec![
+            TreeNode {
+                name: "a",
+                children: vec![TreeNode {
+                    name: "a.1",
+                    children: vec![],
+                }],
+            },
+            TreeNode {
+                name: "b",
+                children: vec![],
+            },
+        ],
+    }
+}
+
+#[test]
+fn test_recursive_macro() {
+    #[derive(Template)]
+    #[template(
+        source = "
+{%- macro tree(node: &TreeNode, depth: usize) -%}
+    <li data-depth=\"{{ depth }}\">{{ node.name }}
+    {%- if !node.children.is_empty() %}<ul>
+        {%- for child in node.children %}{% call tree(child, depth + 1) %}{% endfor -%}
+    </ul>{% endif %}</li>
+{%- endmacro -%}
+<ul>{% call tree(root, 0) %}</ul>",
+        ext = "html"
+    )]
+    struct Tree<'a> {
+        root: &'a TreeNode<'a>,
+    }
+
+    assert_eq!(
+        Tree { root: &tree() }.render().unwrap(),
+        "<ul><li data-depth=\"0\">&#60;root&#62;<ul>\
+            <li data-depth=\"1\">a<ul><li data-depth=\"2\">a.1</li></ul></li>\
+            <li data-depth=\"1\">b</li>\
+        </ul></li></ul>"
+    );
+}
+
+// Two macros that call each other, the recursive one is rendered by a closure.
+#[test]
+fn test_recursive_macro_indirect() {
+    #[derive(Template)]
+    #[template(
+        source = "
+{%- macro item(node: &TreeNode) -%}
+    {{ node.name }}{% if !node.children.is_empty() %}({% call list(node.children) %}){% endif %}
+{%- endmacro -%}
+{%- macro list(nodes: &[TreeNode]) -%}
+    {% for node in nodes %}{% if !loop.first %}, {% endif %}{% call item(node) %}{% endfor %}
+{%- endmacro -%}
+{% call item(root) %} {% call list(root.children) %}",
+        ext = "txt"
+    )]
+    struct Tree<'a> {
+        root: &'a TreeNode<'a>,
+    }
+
+    assert_eq!(
+        Tree { root: &tree() }.render().unwrap(),
+        "<root>(a(a.1), b) a(a.1), b"
+    );
+}
+
+// The macros of three templates that import each other call each other.
+#[test]
+fn test_recursive_macro_across_imports() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% import "macro-recursion-1.html" as first %}{% call first::some_macro(4) %}"#,
+        ext = "txt"
+    )]
+    struct AcrossImports;
+
+    assert_eq!(AcrossImports.render().unwrap(), "1:4 2:3 3:2 1:1 2:0");
+}
+
+#[test]
+fn test_macro_caller() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{%- macro dialog(title) -%}
+<div><h1>{{ title }}</h1>{{ caller() }}{{ caller() }}</div>
+{%- endmacro -%}
+{%- macro plain() %}[{% call dialog("inner") %}{{ caller() }}{% endcall %}]{% endmacro -%}
+{% call dialog(title|upper) -%}
+    <p>{{ title }} {{ text }}</p>
+{%- endcall %}
+{% for i in 1..=2 -%}
+    {% call plain() %}{{ i }}{% endcall %}
+{%- endfor %}
+{% call plain() %}{% endcall %}"#,
+        ext = "html"
+    )]
+    struct MacroCaller<'a> {
+        title: &'a str,
+        text: &'a str,
+    }
+
+    let t = MacroCaller {
+        title: "Hi",
+        text: "<b>",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "<div><h1>HI</h1><p>Hi &#60;b&#62;</p><p>Hi &#60;b&#62;</p></div>\n\
+        [<div><h1>inner</h1>11</div>][<div><h1>inner</h1>22</div>]\n\
+        [<div><h1>inner</h1></div>]"
+    );
+}
+
+#[test]
+fn test_macro_caller_include() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{%- macro dialog() -%}
+<div>{{ caller() }}</div>
+{%- endmacro -%}
+{% call dialog() %}{% include "included.html" %}{% endcall %}"#,
+        ext = "html"
+    )]
+    struct MacroCallerInclude<'a> {
+        s: &'a str,
+    }
+
+    assert_eq!(
+        MacroCallerInclude { s: "<b>" }.render().unwrap(),
+        "<div>INCLUDED: &#60;b&#62;</div>"
+    );
+}
+
+#[test]
+fn test_macro_arg_types() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{%- macro card(title: &str, count: usize, tags: &[&str] = [], note = "") -%}
+<h2>{{ title }} ({{ count }})</h2>
+{%- for tag in tags %}[{{ tag }}]{% endfor %}{{ note }}
+{% endmacro -%}
+{%- call card(name, items.len()) %}{% endcall -%}
+{%- call card("literal", 2 * 3, tags, note = "!") %}{% endcall -%}
+{%- for (i, item) in items.iter().enumerate() -%}
+    {%- call card(item, i + 1, tags = tags) %}{% endcall -%}
+{%- endfor -%}"#,
+        ext = "html"
+    )]
+    struct MacroArgTypes<'a> {
+        name: String,
+        items: Vec<String>,
+        tags: &'a [&'a str],
+    }
+
+    let t = MacroArgTypes {
+        name: "<items>".to_owned(),
+        items: vec!["a".to_owned()],
+        tags: &["x", "y"],
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "<h2>&#60;items&#62; (1)</h2>\n\
+        <h2>literal (6)</h2>[x][y]!\n\
+        <h2>a (1)</h2>[x][y]\n"
+    );
+}
@@ -38,3 +38,85 @@ fn test_render_in_place() {
         "Section 1: A=A\nB=B\nSection 2: C=C\nD=D\nSection 3 for:\n* A=1\nB=2\n* A=A\nB=B\n* A=a\nB=b\n"
     );
 }
+
+#[cfg(feature = "bytes")]
+#[test]
+fn test_render_into_bytes() {
+    #[derive(Template)]
+    #[template(source = "Hello, {{ name }}!", ext = "html")]
+    struct Hello<'a> {
+        name: &'a str,
+    }
+
+    let mut buf = bytes::BytesMut::from(&b"> "[..]);
+    Hello { name: "<world>" }
+        .render_into_bytes(&mut buf)
+        .unwrap();
+    assert_eq!(&buf[..], b"> Hello, &#60;world&#62;!");
+    assert!(buf.capacity() >= <Hello<'_> as Template>::SIZE_HINT);
+}
+
+#[test]
+fn test_render_append() {
+    #[derive(Template)]
+    #[template(source = "Hello, {{ name }}!", ext = "html")]
+    struct Hello<'a> {
+        name: &'a str,
+    }
+
+    let mut buf = String::from("> ");
+    Hello { name: "<world>" }.render_append(&mut buf).unwrap();
+    assert_eq!(buf, "> Hello, &#60;world&#62;!");
+    assert!(buf.capacity() >= 2 + <Hello<'_> as Template>::SIZE_HINT);
+
+    buf.clear();
+    for name in ["a", "b"] {
+        Template::render_append(&&Hello { name }, &mut buf).unwrap();
+    }
+    assert_eq!(buf, "Hello, a!Hello, b!");
+}
+
+#[test]
+fn test_render_into_counted() {
+    #[derive(Template)]
+    #[template(
+        source = "Hello, {{ name }}!{% for c in chars %}{{ c }}{% endfor %}",
+        ext = "html"
+    )]
+    struct Hello<'a> {
+        name: &'a str,
+        chars: &'a [char],
+    }
+
+    let tmpl = Hello {
+        name: "<wörld>",
+        chars: &['ä', '&'],
+    };
+    let mut buf = String::from("> ");
+    assert_eq!(tmpl.render_into_counted(&mut buf).unwrap(), 31);
+    assert_eq!(buf, "> Hello, &#60;wörld&#62;!ä&#38;");
+    assert_eq!(buf.len(), 2 + 31);
+
+    let mut buf = String::new();
+    let count = Template::render_into_counted(&&tmpl, &mut buf).unwrap();
+    assert_eq!(count, buf.len());
+}
+
+#[test]
+fn test_render_with_capacity() {
+    #[derive(Template)]
+    #[template(source = "{% for i in items %}{{ i }},{% endfor %}", ext = "txt")]
+    struct Items<'a> {
+        items: &'a [u32],
+    }
+
+    let items = (0..100).collect::<Vec<_>>();
+    let tmpl = Items { items: &items };
+    let rendered = tmpl.render_with_capacity(1000).unwrap();
+    assert_eq!(rendered, tmpl.render().unwrap());
+    assert!(rendered.capacity() >= 1000);
+
+    // a capacity that is too small only causes reallocations
+    let rendered = Template::render_with_capacity(&&tmpl, 0).unwrap();
+    assert_eq!(rendered.len(), 290);
+}
@@ -391,6 +391,18 @@ fn test_comment() {
     assert_eq!(t.render().unwrap(), "  ");
 }
 
+#[test]
+fn test_nested_comment() {
+    #[derive(Template)]
+    #[template(
+        source = "a{# {{ missing }} {# b {# {{ also_missing }} #} c #} d #}e{# f #}g",
+        ext = "txt"
+    )]
+    struct NestedCommentTemplate;
+
+    assert_eq!(NestedCommentTemplate.render().unwrap(), "aeg");
+}
+
 #[test]
 fn test_negation() {
     #[derive(Template)]
@@ -471,6 +483,18 @@ fn test_raw_ws() {
     assert_eq!(template.render().unwrap(), "<{{hello}}>\n<{{bye}}>");
 }
 
+#[test]
+fn test_raw_custom_end() {
+    #[derive(Template)]
+    #[template(path = "raw-custom-end.html")]
+    struct RawTemplateCustomEnd;
+
+    assert_eq!(
+        RawTemplateCustomEnd.render().unwrap(),
+        "{% raw %}\n  {{ name }}\n{% endraw %}"
+    );
+}
+
 mod without_import_on_derive {
     #[derive(rinja::Template)]
     #[template(source = "foo", ext = "txt")]
@@ -632,3 +656,322 @@ fn test_concat_inner() {
 
     assert_eq!(ConcatInner { a: "'" }.to_string(), "%3C%27%3E");
 }
+
+#[test]
+fn test_concat_operands() {
+    #[derive(Template)]
+    #[template(
+        ext = "html",
+        source = r#"{{ "Hello " ~ name ~ "!" }}|{{ "n=" ~ n * 2 ~ '.' }}|{{ name ~ html|safe }}
+            {%- let label = "item-" ~ n -%}
+            |{{ label|upper }}"#
+    )]
+    struct ConcatOperands<'a> {
+        name: &'a str,
+        html: &'a str,
+        n: u32,
+    }
+
+    let tmpl = ConcatOperands {
+        name: "<World>",
+        html: "<b>",
+        n: 21,
+    };
+    assert_eq!(
+        tmpl.render().unwrap(),
+        "Hello &#60;World&#62;!|n=42.|&#60;World&#62;<b>|ITEM-21"
+    );
+}
+
+#[test]
+fn test_config_source() {
+    #[derive(Template)]
+    #[template(
+        source = "  [% if ok %]  ok [[ name ]]  [% endif %]  ",
+        ext = "txt",
+        syntax = "square",
+        config_source = r#"
+[general]
+whitespace = "suppress"
+
+[[syntax]]
+name = "square"
+block_start = "[%"
+block_end = "%]"
+expr_start = "[["
+expr_end = "]]"
+"#
+    )]
+    struct ConfigSource<'a> {
+        ok: bool,
+        name: &'a str,
+    }
+
+    let t = ConfigSource {
+        ok: true,
+        name: "there",
+    };
+    assert_eq!(t.render().unwrap(), "okthere");
+}
+
+#[test]
+fn test_mime_selects_escaper() {
+    #[derive(Template)]
+    #[template(source = "{{ s }}", ext = "tmpl", mime = "text/html; charset=utf-8")]
+    struct MimeHtml<'a> {
+        s: &'a str,
+    }
+
+    #[derive(Template)]
+    #[template(source = "{{ s }}", ext = "tmpl", mime = "text/html", escape = "none")]
+    struct MimeEscapeOverride<'a> {
+        s: &'a str,
+    }
+
+    #[derive(Template)]
+    #[template(source = "{{ s }}", ext = "html", mime = "text/plain")]
+    struct MimeOverridesExt<'a> {
+        s: &'a str,
+    }
+
+    #[derive(Template)]
+    #[template(
+        source = "{{ s }}",
+        ext = "tmpl",
+        mime = "Text/X-Custom",
+        config_source = r#"
+[[escaper]]
+path = "rinja::filters::Html"
+mime_types = ["text/x-custom"]
+"#
+    )]
+    struct MimeFromConfig<'a> {
+        s: &'a str,
+    }
+
+    assert_eq!(MimeHtml { s: "<&>" }.render().unwrap(), "&#60;&#38;&#62;");
+    assert_eq!(MimeEscapeOverride { s: "<&>" }.render().unwrap(), "<&>");
+    assert_eq!(MimeOverridesExt { s: "<&>" }.render().unwrap(), "<&>");
+    assert_eq!(
+        MimeFromConfig { s: "<&>" }.render().unwrap(),
+        "&#60;&#38;&#62;"
+    );
+}
+
+#[test]
+fn test_escaper_attribute() {
+    mod latex {
+        use std::fmt;
+
+        #[derive(Clone, Copy)]
+        pub struct Latex;
+
+        impl rinja::filters::Escaper for Latex {
+            fn write_escaped_str<W: fmt::Write>(&self, mut fmt: W, string: &str) -> fmt::Result {
+                for c in string.chars() {
+                    match c {
+                        '&' | '%' | '$' | '#' | '_' | '{' | '}' => write!(fmt, "\\{c}")?,
+                        c => fmt.write_char(c)?,
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // The extension has no configured escaper, and is not needed.
+    #[derive(Template)]
+    #[template(
+        source = "{{ s }} {{ s|safe }} {{ s|escape }}",
+        ext = "tex",
+        escaper = latex::Latex
+    )]
+    struct LatexTemplate<'a> {
+        s: &'a str,
+    }
+
+    // The escaper overrides the one of the extension and of the MIME type.
+    #[derive(Template)]
+    #[template(
+        source = "{{ s }}",
+        ext = "html",
+        mime = "text/html",
+        escaper = rinja::filters::Text
+    )]
+    struct TextTemplate<'a> {
+        s: &'a str,
+    }
+
+    assert_eq!(
+        LatexTemplate { s: "50% & <b>" }.render().unwrap(),
+        "50\\% \\& <b> 50% & <b> 50\\% \\& <b>"
+    );
+    assert_eq!(TextTemplate { s: "<&>" }.render().unwrap(), "<&>");
+}
+
+#[test]
+fn test_display_attribute() {
+    #[derive(Template)]
+    #[template(source = "Hello, {{ name }}!", ext = "txt", display = true)]
+    struct WithDisplay<'a> {
+        name: &'a str,
+    }
+
+    #[derive(Template)]
+    #[template(source = "Hello, {{ name }}!", ext = "txt", display = false)]
+    struct WithoutDisplay<'a> {
+        name: &'a str,
+    }
+
+    impl fmt::Display for WithoutDisplay<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("[")?;
+            self.render_into(f).map_err(|_| fmt::Error)?;
+            f.write_str("]")
+        }
+    }
+
+    assert_eq!(
+        format!("{}", WithDisplay { name: "world" }),
+        "Hello, world!"
+    );
+    let t = WithoutDisplay { name: "world" };
+    assert_eq!(format!("{t}"), "[Hello, world!]");
+    assert_eq!(t.render().unwrap(), "Hello, world!");
+}
+
+#[test]
+fn test_warn_unused_all_fields_used() {
+    // Every field is read somewhere in the template, so no warning is emitted.
+    #[derive(Template)]
+    #[template(
+        source = r#"{%- macro greet(who) -%}Hello, {{ who }}{%- endmacro -%}
+{%- call greet(name) -%}
+{%- for item in items %} {{ item }}{% endfor -%}
+{%- if let Some(suffix) = suffix %}{{ suffix }}{% endif -%}"#,
+        ext = "txt",
+        warn_unused = true
+    )]
+    struct AllUsed<'a> {
+        name: &'a str,
+        items: &'a [u32],
+        suffix: Option<&'a str>,
+    }
+
+    let t = AllUsed {
+        name: "world",
+        items: &[1, 2],
+        suffix: Some("!"),
+    };
+    assert_eq!(t.render().unwrap(), "Hello, world 1 2!");
+}
+
+#[test]
+fn test_extension() {
+    #[derive(Template)]
+    #[template(path = "hello.html")]
+    struct Hello<'a> {
+        name: &'a str,
+    }
+
+    #[derive(Template)]
+    #[template(path = "foo.html.jinja")]
+    struct Jinja;
+
+    #[derive(Template)]
+    #[template(source = "{{ self.0 }}", ext = "txt")]
+    struct Source(u32);
+
+    assert_eq!(Hello::EXTENSION, Some("html"));
+    assert_eq!(Jinja::EXTENSION, Some("jinja"));
+    assert_eq!(Source::EXTENSION, Some("txt"));
+    assert_eq!(<&Source as Template>::EXTENSION, Some("txt"));
+
+    let hello: &dyn rinja::DynTemplate = &Hello { name: "world" };
+    assert_eq!(hello.extension(), Some("html"));
+}
+
+#[test]
+fn test_mime_type() {
+    #[derive(Template)]
+    #[template(path = "hello.html")]
+    struct Hello<'a> {
+        name: &'a str,
+    }
+
+    #[derive(Template)]
+    #[template(path = "foo.html.jinja")]
+    struct Jinja;
+
+    #[derive(Template)]
+    #[template(source = "{{ self.0 }}", ext = "txt")]
+    struct Source(u32);
+
+    #[derive(Template)]
+    #[template(source = "{{ self.0 }}", ext = "tmpl", mime = "application/xhtml+xml")]
+    struct Mime(u32);
+
+    assert_eq!(Hello::MIME_TYPE, "text/html; charset=utf-8");
+    assert_eq!(Jinja::MIME_TYPE, "text/html; charset=utf-8");
+    assert_eq!(Source::MIME_TYPE, "text/plain; charset=utf-8");
+    assert_eq!(
+        <&Source as Template>::MIME_TYPE,
+        "text/plain; charset=utf-8"
+    );
+    assert_eq!(Mime::MIME_TYPE, "application/xhtml+xml");
+
+    let hello = Hello { name: "world" };
+    assert_eq!(
+        hello.render_response().unwrap(),
+        ("Hello, world!".to_owned(), "text/html; charset=utf-8")
+    );
+    assert_eq!(
+        Mime(42).render_response().unwrap(),
+        ("42".to_owned(), "application/xhtml+xml")
+    );
+
+    let hello: &dyn rinja::DynTemplate = &hello;
+    assert_eq!(hello.mime_type(), "text/html; charset=utf-8");
+}
+
+#[test]
+fn test_expose_source() {
+    #[derive(Template)]
+    #[template(path = "hello.html", expose_source = true)]
+    struct Hello<'a> {
+        name: &'a str,
+    }
+
+    #[derive(Template)]
+    #[template(source = "{{ self.0 }}\n", ext = "txt", expose_source = true)]
+    struct Source(u32);
+
+    #[derive(Template)]
+    #[template(source = "{{ self.0 }}", ext = "txt", expose_source = false)]
+    struct Hidden(u32);
+
+    #[derive(Template)]
+    #[template(source = "{{ self.0 }}", ext = "txt")]
+    struct Default(u32);
+
+    assert_eq!(Hello::SOURCE, Some("Hello, {{ name }}!"));
+    assert_eq!(Source::SOURCE, Some("{{ self.0 }}\n"));
+    assert_eq!(<&Source as Template>::SOURCE, Some("{{ self.0 }}\n"));
+    assert_eq!(Hidden::SOURCE, None);
+    assert_eq!(Default::SOURCE, None);
+}
+
+#[test]
+fn test_crlf_line_endings() {
+    // The template files use CRLF line endings, which are normalized when the files are read.
+    #[derive(Template)]
+    #[template(path = "hello-crlf.html")]
+    struct HelloCrlf<'a> {
+        name: &'a str,
+    }
+
+    assert_eq!(
+        HelloCrlf { name: "world" }.render().unwrap(),
+        "Hello,\nworld\n[world]"
+    );
+}
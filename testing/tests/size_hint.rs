@@ -46,3 +46,34 @@ fn test_super_size_hint() {
 
     assert_eq!(T::SIZE_HINT, 5);
 }
+
+#[derive(Template)]
+#[template(
+    source = "{% for item in items %}<li>{{ item }}</li>{% endfor %}",
+    ext = "html"
+)]
+struct List<'a> {
+    items: &'a [&'a str],
+}
+
+#[cfg(feature = "adaptive-size-hint")]
+#[test]
+fn test_adaptive_size_hint() {
+    assert_eq!(List::current_size_hint(), List::SIZE_HINT);
+
+    let long = List { items: &["a"; 100] };
+    let output = long.render().unwrap();
+    assert_eq!(List::current_size_hint(), output.len());
+
+    // Shorter outputs do not reduce the hint.
+    List { items: &[] }.render().unwrap();
+    assert_eq!(List::current_size_hint(), output.len());
+    assert_eq!(rinja::DynTemplate::size_hint(&long), output.len());
+}
+
+#[cfg(not(feature = "adaptive-size-hint"))]
+#[test]
+fn test_adaptive_size_hint() {
+    List { items: &["a"; 100] }.render().unwrap();
+    assert_eq!(List::current_size_hint(), List::SIZE_HINT);
+}
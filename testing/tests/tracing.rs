@@ -0,0 +1,95 @@
+use std::fmt;
+use std::sync::Mutex;
+
+use rinja::Template;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+type SpanFields = Vec<(&'static str, String)>;
+
+/// Records the name and the fields of every span
+#[derive(Default)]
+struct Recorder {
+    spans: Mutex<Vec<(&'static str, SpanFields)>>,
+}
+
+struct Fields<'a>(&'a mut SpanFields);
+
+impl Visit for Fields<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.push((field.name(), format!("{value:?}")));
+    }
+}
+
+impl Subscriber for &'static Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = Vec::new();
+        span.record(&mut Fields(&mut fields));
+        let mut spans = self.spans.lock().unwrap();
+        spans.push((span.metadata().name(), fields));
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut spans = self.spans.lock().unwrap();
+        let (_, fields) = &mut spans[span.into_u64() as usize - 1];
+        values.record(&mut Fields(fields));
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[derive(Template)]
+#[template(path = "hello.html")]
+struct HelloTemplate<'a> {
+    name: &'a str,
+}
+
+#[derive(Template)]
+#[template(source = "{% for item in items %}{{ item }}{% endfor %}", ext = "txt")]
+struct ItemsTemplate<'a> {
+    items: &'a [u32],
+}
+
+#[test]
+fn test_render_span() {
+    let recorder: &'static Recorder = Box::leak(Box::default());
+    tracing::subscriber::with_default(recorder, || {
+        assert_eq!(
+            HelloTemplate { name: "world" }.render().unwrap(),
+            "Hello, world!"
+        );
+        assert_eq!(
+            ItemsTemplate {
+                items: &[1, 22, 333]
+            }
+            .render()
+            .unwrap(),
+            "122333"
+        );
+    });
+
+    let spans = recorder.spans.lock().unwrap();
+    assert_eq!(spans.len(), 2);
+    assert_eq!(spans[0].0, "hello.html");
+    assert_eq!(spans[0].1, [
+        ("size_hint", HelloTemplate::SIZE_HINT.to_string()),
+        ("len", "13".to_owned()),
+    ]);
+    assert_eq!(spans[1].0, "ItemsTemplate.txt");
+    assert_eq!(spans[1].1, [
+        ("size_hint", ItemsTemplate::SIZE_HINT.to_string()),
+        ("len", "6".to_owned()),
+    ]);
+}
@@ -0,0 +1,98 @@
+use rinja::Template;
+use rinja::fluent::{FluentBundle, FluentResource};
+
+fn bundle(ftl: &str) -> FluentBundle<FluentResource> {
+    let mut bundle = FluentBundle::new(vec!["en-US".parse().unwrap()]);
+    // Fluent wraps arguments in Unicode isolation marks by default.
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(FluentResource::try_new(ftl.to_owned()).unwrap())
+        .unwrap();
+    bundle
+}
+
+#[derive(Template)]
+#[template(
+    source = "{% trans %}Hello, {{ name }}!{% endtrans %}",
+    ext = "html",
+    fluent_bundle = "bundle"
+)]
+struct Hello<'a> {
+    bundle: &'a FluentBundle<FluentResource>,
+    name: &'a str,
+}
+
+#[test]
+fn test_trans() {
+    let bundle = bundle("hello-name = Hallo, { $name }!");
+    let t = Hello {
+        bundle: &bundle,
+        name: "<Rinja>",
+    };
+    assert_eq!(t.render().unwrap(), "Hallo, &#60;Rinja&#62;!");
+}
+
+// Without a translation, the content of the block is rendered.
+#[test]
+fn test_trans_fallback() {
+    let bundle = bundle("");
+    let t = Hello {
+        bundle: &bundle,
+        name: "<Rinja>",
+    };
+    assert_eq!(t.render().unwrap(), "Hello, &#60;Rinja&#62;!");
+}
+
+#[derive(Template)]
+#[template(
+    source = "{% trans \"apples\" %}One apple{% pluralize count %}{{ count }} apples{% endtrans %}",
+    ext = "txt",
+    fluent_bundle = "bundle"
+)]
+struct Apples {
+    bundle: FluentBundle<FluentResource>,
+    count: usize,
+}
+
+#[test]
+fn test_trans_pluralize() {
+    let ftl = "\
+apples = { $count ->
+    [one] Ein Apfel
+   *[other] { $count } Äpfel
+}
+";
+    let t = Apples {
+        bundle: bundle(ftl),
+        count: 1,
+    };
+    assert_eq!(t.render().unwrap(), "Ein Apfel");
+    let t = Apples {
+        bundle: bundle(ftl),
+        count: 3,
+    };
+    assert_eq!(t.render().unwrap(), "3 Äpfel");
+
+    let t = Apples {
+        bundle: bundle(""),
+        count: 1,
+    };
+    assert_eq!(t.render().unwrap(), "One apple");
+    let t = Apples {
+        bundle: bundle(""),
+        count: 3,
+    };
+    assert_eq!(t.render().unwrap(), "3 apples");
+}
+
+// A message that cannot be formatted, e.g. because of a missing argument, is an error.
+#[test]
+fn test_trans_error() {
+    let bundle = bundle("hello-name = Hallo, { $user }!");
+    let t = Hello {
+        bundle: &bundle,
+        name: "Rinja",
+    };
+    let err = t.render().unwrap_err().to_string();
+    assert!(err.contains("hello-name"), "{err}");
+}
@@ -231,3 +231,85 @@ fn error_conversion_from_wrapped_in_io() {
     };
     assert!(err.is::<CustomError>());
 }
+
+#[derive(Template)]
+#[template(
+    source = r#"{%- macro item() -%}
+        <{{ caller() }}>
+    {%- endmacro -%}
+    {%- block list -%}
+        {%- for v in values -%}
+            [{{ v.get()? }}]
+        {%- endfor -%}
+    {%- endblock -%}
+    {%- call item() -%}
+        {{ title.get()? }}
+    {%- endcall -%}
+    "#,
+    ext = "html",
+    blocks = ["list"],
+    error_placeholder = "<!-- error -->"
+)]
+struct CollectingTemplate<'a> {
+    values: &'a [Value],
+    title: Value,
+}
+
+struct Value(Result<&'static str, &'static str>);
+
+impl Value {
+    fn get(&self) -> Result<&'static str, rinja::Error> {
+        self.0.map_err(|err| rinja::Error::custom(err.to_owned()))
+    }
+}
+
+#[test]
+fn test_render_collecting() {
+    let values = [Value(Err("first")), Value(Ok("<b>")), Value(Err("second"))];
+    let tmpl = CollectingTemplate {
+        values: &values,
+        title: Value(Err("title")),
+    };
+
+    let (output, errors) = tmpl.render_collecting();
+    assert_eq!(
+        output,
+        "[<!-- error -->][&#60;b&#62;][<!-- error -->]<<!-- error -->>",
+    );
+    let errors = errors.iter().map(|err| err.to_string()).collect::<Vec<_>>();
+    assert_eq!(errors, ["first", "second", "title"]);
+
+    // The fail-fast methods still report the first error.
+    assert_eq!(tmpl.render().unwrap_err().to_string(), "first");
+    assert_eq!(tmpl.render_block("list").unwrap_err().to_string(), "first");
+
+    let values = [Value(Ok("a")), Value(Ok("b"))];
+    let tmpl = CollectingTemplate {
+        values: &values,
+        title: Value(Ok("c")),
+    };
+    assert_eq!(tmpl.render().unwrap(), "[a][b]<c>");
+    let (output, errors) = tmpl.render_collecting();
+    assert_eq!(output, "[a][b]<c>");
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn test_render_collecting_fail_fast() {
+    // Without `error_placeholder`, the first error stops the rendering.
+    #[derive(Template)]
+    #[template(source = "[{{ a.get()? }}][{{ b.get()? }}]", ext = "txt")]
+    struct FailFast {
+        a: Value,
+        b: Value,
+    }
+
+    let tmpl = FailFast {
+        a: Value(Err("a")),
+        b: Value(Err("b")),
+    };
+    let (output, errors) = tmpl.render_collecting();
+    assert_eq!(output, "[");
+    let errors = errors.iter().map(|err| err.to_string()).collect::<Vec<_>>();
+    assert_eq!(errors, ["a"]);
+}
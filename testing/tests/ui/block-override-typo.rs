@@ -0,0 +1,39 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(
+    source = r#"{% extends "base.html" %}
+{% block contnet %}Hello{% endblock %}"#,
+    ext = "html",
+    config = "check-block-overrides.toml"
+)]
+struct Typo<'a> {
+    title: &'a str,
+}
+
+#[derive(Template)]
+#[template(
+    source = r#"{% extends "base.html" %}
+{% block content %}{% block new %}nested blocks are new definitions{% endblock %}{% endblock %}
+{% if true %}{% block unknown %}{% endblock %}{% endif %}"#,
+    ext = "html",
+    config = "check-block-overrides.toml"
+)]
+struct Nested<'a> {
+    title: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "named-end.html", config = "check-block-overrides.toml")]
+struct NamedEnd<'a> {
+    title: &'a str,
+}
+
+#[derive(Template)]
+#[template(
+    path = "block_in_include_extended.html",
+    config = "check-block-overrides.toml"
+)]
+struct BlockInInclude;
+
+fn main() {}
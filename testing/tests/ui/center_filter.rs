@@ -0,0 +1,21 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ s|center }}"#)]
+struct MissingWidth<'a> {
+    s: &'a str,
+}
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ s|center(5, "ab") }}"#)]
+struct LongFill<'a> {
+    s: &'a str,
+}
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ s|center(5, "") }}"#)]
+struct EmptyFill<'a> {
+    s: &'a str,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ items|columns(0)|length }}"#)]
+struct ZeroColumns {
+    items: Vec<u32>,
+}
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ items|columns|length }}"#)]
+struct MissingCount {
+    items: Vec<u32>,
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(source = "", ext = "txt", config_source = "[general\ndirs = []")]
+struct InvalidToml;
+
+#[derive(Template)]
+#[template(
+    source = "",
+    ext = "txt",
+    config_source = r#"
+[[syntax]]
+name = "short"
+block_start = "<"
+"#
+)]
+struct InvalidSyntax;
+
+#[derive(Template)]
+#[template(source = "", ext = "txt", config = "rinja.toml", config_source = "")]
+struct BothConfigs;
+
+fn main() {}
@@ -0,0 +1,10 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(ext = "txt", source = "{{ day|date }}")]
+struct NoPattern {
+    day: chrono::NaiveDate,
+}
+
+fn main() {
+}
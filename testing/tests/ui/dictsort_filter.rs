@@ -0,0 +1,17 @@
+use std::collections::HashMap;
+
+use rinja::Template;
+
+#[derive(Template)]
+#[template(source = r#"{{ map|dictsort(by="name") }}"#, ext = "txt")]
+struct UnknownBy {
+    map: HashMap<String, u32>,
+}
+
+#[derive(Template)]
+#[template(source = r#"{{ map|dictsort(sort="key") }}"#, ext = "txt")]
+struct UnknownArgument {
+    map: HashMap<String, u32>,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(source = "", ext = "txt", display = "yes")]
+struct NotABool;
+
+#[derive(Template)]
+#[template(source = "", ext = "txt", display = false, display = true)]
+struct SetTwice;
+
+fn main() {}
@@ -0,0 +1,24 @@
+use rinja::Template;
+use rinja::filters::EscapeMode;
+
+#[derive(Template)]
+#[template(source = "{{ s }}", ext = "txt", escape_field = "mode")]
+struct NoSuchField<'a> {
+    s: &'a str,
+}
+
+#[derive(Template)]
+#[template(source = "{{ s }}", ext = "txt", escape = "html", escape_field = "mode")]
+struct EscapeAndEscapeField<'a> {
+    s: &'a str,
+    mode: EscapeMode,
+}
+
+#[derive(Template)]
+#[template(source = "{{ s }}", ext = "txt", escape_field = "mode")]
+struct NotAnEscapeMode<'a> {
+    s: &'a str,
+    mode: bool,
+}
+
+fn main() {}
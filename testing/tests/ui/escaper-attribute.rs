@@ -0,0 +1,15 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(source = "{{ s }}", ext = "txt", escaper = "rinja::filters::Html")]
+struct EscaperString<'a> {
+    s: &'a str,
+}
+
+#[derive(Template)]
+#[template(source = "{{ s }}", ext = "txt", escape = "html", escaper = rinja::filters::Html)]
+struct EscapeAndEscaper<'a> {
+    s: &'a str,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{% include_all "components/*.xml" %}"#)]
+struct NoMatch;
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{% include_all "*/*.html" %}"#)]
+struct WildcardInDirectory;
+
+fn main() {}
@@ -0,0 +1,25 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(
+    source = r#"{% include "included/widget.html" with count = 1 only %}"#,
+    ext = "html"
+)]
+struct MissingValue<'a> {
+    title: &'a str,
+}
+
+#[derive(Template)]
+#[template(
+    source = r#"{% include "included/widget.html" with title = self.title only %}"#,
+    ext = "html"
+)]
+struct Allowed<'a> {
+    title: &'a str,
+}
+
+#[derive(Template)]
+#[template(source = r#"{% include "include-self.html" only %}"#, ext = "html")]
+struct NoSelf;
+
+fn main() {}
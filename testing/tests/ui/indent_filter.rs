@@ -0,0 +1,27 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(source = r#"{{ s|indent(2, firts=true) }}"#, ext = "txt")]
+struct UnknownArgument<'a> {
+    s: &'a str,
+}
+
+#[derive(Template)]
+#[template(source = r#"{{ s|indent(first=true, 2) }}"#, ext = "txt")]
+struct PositionalAfterNamed<'a> {
+    s: &'a str,
+}
+
+#[derive(Template)]
+#[template(source = r#"{{ s|indent(2, width=4) }}"#, ext = "txt")]
+struct DuplicatedArgument<'a> {
+    s: &'a str,
+}
+
+#[derive(Template)]
+#[template(source = r#"{{ s|indent(2, true, true, true) }}"#, ext = "txt")]
+struct TooManyArguments<'a> {
+    s: &'a str,
+}
+
+fn main() {}
@@ -0,0 +1,39 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(ext = "txt", source = "{{ x is blue }}")]
+struct UnknownTest {
+    x: u32,
+}
+
+#[derive(Template)]
+#[template(ext = "txt", source = "{{ x is none(1) }}")]
+struct UnexpectedArgument {
+    x: Option<u32>,
+}
+
+#[derive(Template)]
+#[template(ext = "txt", source = "{{ x is gt }}")]
+struct MissingArgument {
+    x: u32,
+}
+
+#[derive(Template)]
+#[template(ext = "txt", source = "{{ x is eq(1, 2) }}")]
+struct TooManyArguments {
+    x: u32,
+}
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ items|selectattr("0", "gt")|length }}"#)]
+struct SelectMissingValue {
+    items: Vec<(u32,)>,
+}
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ items|selectattr("0", "none", 1)|length }}"#)]
+struct SelectUnexpectedValue {
+    items: Vec<(u32,)>,
+}
+
+fn main() {}
@@ -0,0 +1,30 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(
+    source = "{% for x in xs %}{% break outer %}{% endfor %}",
+    ext = "txt"
+)]
+struct UnknownLabel {
+    xs: Vec<u32>,
+}
+
+#[derive(Template)]
+#[template(
+    source = "{% for x in xs as outer %}{% endfor %}{% for y in xs %}{% continue outer %}{% endfor %}",
+    ext = "txt"
+)]
+struct LabelOutOfScope {
+    xs: Vec<u32>,
+}
+
+#[derive(Template)]
+#[template(
+    source = "{% for x in xs as outer %}{% for y in xs as outer %}{% endfor %}{% endfor %}",
+    ext = "txt"
+)]
+struct DuplicatedLabel {
+    xs: Vec<u32>,
+}
+
+fn main() {}
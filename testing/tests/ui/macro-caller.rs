@@ -0,0 +1,25 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(
+    source = "{% macro a() %}{{ caller() }}{% endmacro %}{% call a() %}",
+    ext = "html"
+)]
+struct CallerWithoutBody;
+
+#[derive(Template)]
+#[template(
+    source = "{% macro a() %}{{ caller(1) }}{% endmacro %}{% call a() %}{% endcall %}",
+    ext = "html"
+)]
+struct CallerWithArguments;
+
+#[derive(Template)]
+#[template(
+    source = "{% macro a() %}{{ caller() }}{% endmacro %}
+        {% for i in 0..3 %}{% call a() %}{% break %}{% endcall %}{% endfor %}",
+    ext = "html"
+)]
+struct BreakInCaller;
+
+fn main() {}
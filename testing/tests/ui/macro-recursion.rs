@@ -3,39 +3,33 @@ use rinja::Template;
 #[derive(Template)]
 #[template(
     source = "
-        {% macro one %}{% call one %}{% endmacro %}
-        {% call one %}
+        {% macro one(n) %}{% call one(n) %}{% endmacro %}
+        {% call one(1) %}
     ",
     ext = "html"
 )]
-struct Direct;
+struct UntypedArgument;
 
 #[derive(Template)]
 #[template(
     source = "
-        {% macro one %}{% call two %}{% endmacro %}
-        {% macro two %}{% call three %}{% endmacro %}
-        {% macro three %}{% call four %}{% endmacro %}
-        {% macro four %}{% call five %}{% endmacro %}
-        {% macro five %}{% call one %}{% endmacro %}
-        {% call one %}
+        {% macro one(n: u32) %}{% call two(n) %}{% endmacro %}
+        {% macro two(n) %}{% call one(n) %}{% endmacro %}
+        {% call two(1) %}
     ",
     ext = "html"
 )]
-struct Indirect;
+struct UntypedArgumentIndirect;
 
 #[derive(Template)]
 #[template(
-    source = r#"
-        {% import "macro-recursion-1.html" as next %}
-        {% macro some_macro %}
-            {% call next::some_macro %}
-        {% endmacro %}
-        {% call some_macro %}
-    "#,
+    source = "
+        {% macro one(n: u32) %}{{ caller() }}{% call one(n) %}{% endcall %}{% endmacro %}
+        {% call one(1) %}{% endcall %}
+    ",
     ext = "html"
 )]
-struct AcrossImports;
+struct WithCaller;
 
 fn main() {
 }
@@ -0,0 +1,24 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(
+    source = "{% macro card(title: &str, count: u32 u32) %}{% endmacro %}",
+    ext = "html"
+)]
+struct InvalidType;
+
+#[derive(Template)]
+#[template(
+    source = "{% macro card(title: impl std::fmt::Display) %}{% endmacro %}",
+    ext = "html"
+)]
+struct ImplTrait;
+
+#[derive(Template)]
+#[template(
+    source = "{% macro card(count: u32) %}{{ count }}{% endmacro %}{% call card(\"one\") %}{% endcall %}",
+    ext = "html"
+)]
+struct Mismatch;
+
+fn main() {}
@@ -14,5 +14,12 @@ struct InvalidDefault1;
 {%- call thrice(1, 2, 3) -%}", ext = "html")]
 struct InvalidDefault2;
 
+#[derive(Template)]
+#[template(source = "{%- macro card(title, body, footer=\"\") -%}
+{{ title }} {{ body }} {{ footer }}
+{%- endmacro -%}
+{%- call card(title=\"x\", footer=\"z\") -%}", ext = "html")]
+struct MissingNamed;
+
 fn main() {
 }
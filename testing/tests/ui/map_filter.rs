@@ -0,0 +1,27 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(ext = "html", source = r#"{{ items|map("name")|join(", ") }}"#)]
+struct MissingKind {
+    items: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(ext = "html", source = r#"{{ items|map(attr="a..b")|join(", ") }}"#)]
+struct InvalidAttr {
+    items: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(ext = "html", source = r#"{{ items|map(filter=name)|join(", ") }}"#)]
+struct InvalidFilter {
+    items: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(ext = "html", source = r#"{{ items|truncate(length=2) }}"#)]
+struct NamedArgument {
+    items: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(source = "{{ s }}", ext = "tmpl", mime = "application/json")]
+struct UnknownMime<'a> {
+    s: &'a str,
+}
+
+fn main() {}
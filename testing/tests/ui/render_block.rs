@@ -0,0 +1,21 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(path = "fragment-simple.html", blocks = ["body", "title"])]
+struct UnknownBlock<'a> {
+    name: &'a str,
+}
+
+#[derive(Template)]
+#[template(source = "{{ name }}", ext = "txt", blocks = ["body"])]
+struct NoBlocks<'a> {
+    name: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "fragment-simple.html", blocks = "body")]
+struct NotAnArray<'a> {
+    name: &'a str,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ 1.5|round(0, "up") }}"#)]
+struct UnknownMethod;
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ 1.5|round(0, method) }}"#)]
+struct MethodNotALiteral {
+    method: &'static str,
+}
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ 1.5|round(digits=2) }}"#)]
+struct UnknownArgument;
+
+fn main() {}
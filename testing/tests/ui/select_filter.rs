@@ -0,0 +1,21 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(ext = "html", source = r#"{{ items|selectattr("len()", "is", 2)|join(", ") }}"#)]
+struct UnknownTest {
+    items: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(ext = "html", source = r#"{{ items|select("len")|join(", ") }}"#)]
+struct MissingAttr {
+    items: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(ext = "html", source = r#"{{ items|rejectattr("len()", "eq")|join(", ") }}"#)]
+struct MissingValue {
+    items: Vec<String>,
+}
+
+fn main() {}
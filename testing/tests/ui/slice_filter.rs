@@ -0,0 +1,21 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ items|slice(0, 2, 0)|join(",") }}"#)]
+struct ZeroStep {
+    items: Vec<u32>,
+}
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ items|slice(0, 2, 1, 4)|join(",") }}"#)]
+struct TooManyArguments {
+    items: Vec<u32>,
+}
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ items|slice(end=2)|join(",") }}"#)]
+struct UnknownArgument {
+    items: Vec<u32>,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ items|sort(attr="") }}"#)]
+struct EmptyAttr {
+    items: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ items|sort(key="a") }}"#)]
+struct UnknownArgument {
+    items: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(source = "{{ name }}")]
+struct MissingExt {
+    name: &'static str,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(
+    source = "{% block scripts %}{{ super()|upper }}{% endblock %}",
+    ext = "txt"
+)]
+struct SuperInExpr;
+
+#[derive(Template)]
+#[template(source = "{{ super() }}", ext = "txt")]
+struct SuperOutsideBlock;
+
+fn main() {}
@@ -0,0 +1,50 @@
+use rinja::Template;
+use rinja::fluent::{FluentBundle, FluentResource};
+
+#[derive(Template)]
+#[template(source = "{% trans %}Hello{% endtrans %}", ext = "txt")]
+struct NoBundle;
+
+#[derive(Template)]
+#[template(
+    source = "{% trans %}Hello, {{ user.name }}!{% endtrans %}",
+    ext = "txt",
+    fluent_bundle = "bundle"
+)]
+struct NotAVariable<'a> {
+    bundle: FluentBundle<FluentResource>,
+    user: &'a str,
+}
+
+#[derive(Template)]
+#[template(
+    source = "{% trans %}Hello, {% if a %}you{% endif %}!{% endtrans %}",
+    ext = "txt",
+    fluent_bundle = "bundle"
+)]
+struct NotText {
+    bundle: FluentBundle<FluentResource>,
+    a: bool,
+}
+
+#[derive(Template)]
+#[template(
+    source = "{% trans %}…{% endtrans %}",
+    ext = "txt",
+    fluent_bundle = "bundle"
+)]
+struct NoId {
+    bundle: FluentBundle<FluentResource>,
+}
+
+#[derive(Template)]
+#[template(
+    source = "{% trans %}Hello{% endtrans %}",
+    ext = "txt",
+    fluent_bundle = "missing"
+)]
+struct UnknownField {
+    bundle: FluentBundle<FluentResource>,
+}
+
+fn main() {}
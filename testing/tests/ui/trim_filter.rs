@@ -0,0 +1,11 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ "/a/"|trim("/", "a") }}"#)]
+struct TooManyArguments;
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ "/a/"|trim_end(char="/") }}"#)]
+struct UnknownArgument;
+
+fn main() {}
@@ -0,0 +1,15 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ items|unique(attr="a..b")|join(",") }}"#)]
+struct InvalidAttr {
+    items: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(ext = "txt", source = r#"{{ items|unique(key="a")|join(",") }}"#)]
+struct UnknownArgument {
+    items: Vec<String>,
+}
+
+fn main() {}
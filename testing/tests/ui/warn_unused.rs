@@ -0,0 +1,27 @@
+#![deny(deprecated)]
+
+use rinja::Template;
+
+#[derive(Template)]
+#[template(ext = "txt", source = "{{ name }}{% if let Some(age) = age %} ({{ age }}){% endif %}", warn_unused = true)]
+struct Person<'a> {
+    name: &'a str,
+    age: Option<u32>,
+    nickname: &'a str,
+    name_len: usize,
+}
+
+#[derive(Template)]
+#[template(ext = "txt", source = "{{ name }}", warn_unused = false)]
+struct Quiet<'a> {
+    name: &'a str,
+    nickname: &'a str,
+}
+
+#[derive(Template)]
+#[template(ext = "txt", source = "{{ name }}", warn_unused = "yes")]
+struct NotABool<'a> {
+    name: &'a str,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(source = "{% with a = 1 %}{{ a }}{% endwith %}{{ a }}", ext = "txt")]
+struct WithScope;
+
+fn main() {}
@@ -0,0 +1,84 @@
+use std::collections::{BTreeMap, HashMap};
+
+use rinja::Template;
+
+#[derive(Template)]
+#[template(
+    source = "{{ greeting }}, {{ values.name }}{{ values.punct }}",
+    ext = "html"
+)]
+struct Greeting<'a> {
+    greeting: &'a str,
+}
+
+#[test]
+fn test_render_with_values() {
+    let t = Greeting { greeting: "Hello" };
+
+    let values: HashMap<&str, String> =
+        HashMap::from([("name", "<world>".to_owned()), ("punct", "!".to_owned())]);
+    assert_eq!(
+        t.render_with_values(&values).unwrap(),
+        "Hello, &#60;world&#62;!"
+    );
+
+    let values = BTreeMap::from([("name", 42), ("punct", 0)]);
+    assert_eq!(t.render_with_values(&values).unwrap(), "Hello, 420");
+
+    let values = [("punct", "?"), ("name", "you")];
+    assert_eq!(t.render_with_values(&values).unwrap(), "Hello, you?");
+}
+
+#[test]
+fn test_render_with_values_missing() {
+    let t = Greeting { greeting: "Hello" };
+
+    let err = t.render_with_values(&[("name", "you")]).unwrap_err();
+    assert!(matches!(err, rinja::Error::ValueMissing("punct")));
+    assert_eq!(err.to_string(), "missing template value `punct`");
+
+    // Without values, every referenced key is missing.
+    let err = t.render().unwrap_err();
+    assert!(matches!(err, rinja::Error::ValueMissing("name")));
+}
+
+#[test]
+fn test_render_with_values_in_blocks() {
+    #[derive(Template)]
+    #[template(
+        source = "{% for i in 0..2 %}{% if i == 1 %}{{ values.x }}{% endif %}{% endfor %}",
+        ext = "txt"
+    )]
+    struct InLoop;
+
+    assert_eq!(InLoop.render_with_values(&[("x", 'x')]).unwrap(), "x");
+}
+
+#[test]
+fn test_values_field_shadows() {
+    struct Point {
+        x: u32,
+    }
+
+    #[derive(Template)]
+    #[template(source = "{{ values.x }}", ext = "txt")]
+    struct Shadowed {
+        values: Point,
+    }
+
+    let t = Shadowed {
+        values: Point { x: 2 },
+    };
+    assert_eq!(t.render_with_values(&[("x", 5)]).unwrap(), "2");
+}
+
+#[test]
+fn test_values_ignored_when_unused() {
+    #[derive(Template)]
+    #[template(source = "{{ a }}", ext = "txt")]
+    struct Unused {
+        a: u32,
+    }
+
+    assert_eq!(Unused { a: 1 }.render_with_values(&()).unwrap(), "1");
+}
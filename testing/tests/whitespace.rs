@@ -143,3 +143,67 @@ fn test_template_whitespace_config() {
     );
     test_template_ws_config!("test_minimize.toml", "suppress", "\n1{# #}\n\n\n2", "\n12");
 }
+
+macro_rules! test_template_syntax {
+    ($syntax:literal, $source:literal, $rendered: literal) => {{
+        #[derive(Template)]
+        #[template(source = $source, ext = "txt", config = "syntax-whitespace.toml", syntax = $syntax)]
+        struct CondWs;
+
+        assert_eq!(CondWs.render().unwrap(), $rendered);
+    }};
+}
+
+#[test]
+fn test_syntax_whitespace_config() {
+    test_template_syntax!("preserving", "\t1{# #}\t2", "\t1\t2");
+    test_template_syntax!("suppressing", " 1{# #}  2", " 12");
+    // a syntax without `whitespace` uses the default of the `[general]` section
+    test_template_syntax!("plain", " 1{# #}  2", " 1 2");
+    test_template_syntax!("default", " 1{# #}  2", " 1 2");
+
+    // the `whitespace` argument of the template takes precedence
+    #[derive(Template)]
+    #[template(
+        source = "\t1{# #}\t2",
+        ext = "txt",
+        config = "syntax-whitespace.toml",
+        syntax = "suppressing",
+        whitespace = "preserve"
+    )]
+    struct Overridden;
+
+    assert_eq!(Overridden.render().unwrap(), "\t1\t2");
+}
+
+#[test]
+fn test_multiline_expression() {
+    struct Post {
+        title: &'static str,
+    }
+
+    #[derive(Template)]
+    #[template(
+        ext = "txt",
+        source = "<p>
+    {{-
+        posts
+            |map(attr=\"title\")
+            |join(
+                \", \"
+            )
+            |upper
+    -}}
+</p>
+<p>{{ posts.len()
+    + 1 }}</p>"
+    )]
+    struct Multiline {
+        posts: Vec<Post>,
+    }
+
+    let t = Multiline {
+        posts: vec![Post { title: "a" }, Post { title: "b" }],
+    };
+    assert_eq!(t.render().unwrap(), "<p>A, B</p>\n<p>3</p>");
+}
@@ -0,0 +1,69 @@
+use rinja::Template;
+
+#[derive(Template)]
+#[template(
+    source = "{% with name = user.to_uppercase(), len = user.len() %}{{ name }}: {{ len }}{% endwith %}",
+    ext = "txt"
+)]
+struct WithVars<'a> {
+    user: &'a str,
+}
+
+#[test]
+fn test_with_vars() {
+    let t = WithVars { user: "alice" };
+    assert_eq!(t.render().unwrap(), "ALICE: 5");
+}
+
+#[derive(Template)]
+#[template(
+    source = "{{ s }} {% with s = s.len() %}{{ s }}{% endwith %} {{ s }}",
+    ext = "txt"
+)]
+struct WithShadowField<'a> {
+    s: &'a str,
+}
+
+// The binding shadows the field only until `endwith`.
+#[test]
+fn test_with_shadow_field() {
+    let t = WithShadowField { s: "abc" };
+    assert_eq!(t.render().unwrap(), "abc 3 abc");
+}
+
+#[derive(Template)]
+#[template(
+    source = "{% let x = 1 %}{% with x = x + 1, y = x * 10 %}{{ x }} {{ y }}{% endwith %} {{ x }}",
+    ext = "txt"
+)]
+struct WithShadowLocal;
+
+// Each binding can use the ones before it, and the outer variable is restored after `endwith`.
+#[test]
+fn test_with_shadow_local() {
+    assert_eq!(WithShadowLocal.render().unwrap(), "2 20 1");
+}
+
+#[derive(Template)]
+#[template(
+    source = "{% with (a, b) = pair %}{{ b }}{{ a }}{% endwith %}",
+    ext = "txt"
+)]
+struct WithDestructure {
+    pair: (usize, char),
+}
+
+#[test]
+fn test_with_destructure() {
+    let t = WithDestructure { pair: (3, 'x') };
+    assert_eq!(t.render().unwrap(), "x3");
+}
+
+#[derive(Template)]
+#[template(source = "<{% with a = 1 -%}\n  {{ a }}\n{%- endwith %}>", ext = "txt")]
+struct WithWhitespace;
+
+#[test]
+fn test_with_whitespace() {
+    assert_eq!(WithWhitespace.render().unwrap(), "<1>");
+}
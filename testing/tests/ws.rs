@@ -513,3 +513,129 @@ fn test_match_ws() {
     test_match!("before {%  match item  %}{%- when Some with (item) -%}  foo   {%- when None -%}    bar     {%  endmatch  %}      after", "before foo      after", "before bar           after");
     test_match!("before {%  match item  %}{%- when Some with (item) -%}  foo   {%- when None -%}    bar     {%- endmatch  %}      after", "before foo      after", "before bar      after");
 }
+
+macro_rules! test_tag {
+    ($source:literal, $rendered:expr) => {{
+        #[derive(Template)]
+        #[template(source = $source, ext = "txt")]
+        struct TagWs;
+
+        assert_eq!(TagWs.render().unwrap(), $rendered);
+    }};
+}
+
+#[rustfmt::skip]
+#[test]
+fn test_tag_ws() {
+    test_tag!("before \n {%  for _ in 0..1  %} \n body \n {%  endfor  %} \n after", "before \n  \n body \n  \n after");
+    test_tag!("before \n {%  for _ in 0..1  %} \n body \n {%  endfor -%} \n after", "before \n  \n body \n after");
+    test_tag!("before \n {%  for _ in 0..1  %} \n body \n {%- endfor  %} \n after", "before \n  \n body \n after");
+    test_tag!("before \n {%  for _ in 0..1  %} \n body \n {%- endfor -%} \n after", "before \n  \n bodyafter");
+    test_tag!("before \n {%  for _ in 0..1 -%} \n body \n {%  endfor  %} \n after", "before \n body \n  \n after");
+    test_tag!("before \n {%  for _ in 0..1 -%} \n body \n {%  endfor -%} \n after", "before \n body \n after");
+    test_tag!("before \n {%  for _ in 0..1 -%} \n body \n {%- endfor  %} \n after", "before \n body \n after");
+    test_tag!("before \n {%  for _ in 0..1 -%} \n body \n {%- endfor -%} \n after", "before \n bodyafter");
+    test_tag!("before \n {%- for _ in 0..1  %} \n body \n {%  endfor  %} \n after", "before \n body \n  \n after");
+    test_tag!("before \n {%- for _ in 0..1  %} \n body \n {%  endfor -%} \n after", "before \n body \n after");
+    test_tag!("before \n {%- for _ in 0..1  %} \n body \n {%- endfor  %} \n after", "before \n body \n after");
+    test_tag!("before \n {%- for _ in 0..1  %} \n body \n {%- endfor -%} \n after", "before \n bodyafter");
+    test_tag!("before \n {%- for _ in 0..1 -%} \n body \n {%  endfor  %} \n after", "beforebody \n  \n after");
+    test_tag!("before \n {%- for _ in 0..1 -%} \n body \n {%  endfor -%} \n after", "beforebody \n after");
+    test_tag!("before \n {%- for _ in 0..1 -%} \n body \n {%- endfor  %} \n after", "beforebody \n after");
+    test_tag!("before \n {%- for _ in 0..1 -%} \n body \n {%- endfor -%} \n after", "beforebodyafter");
+
+    test_tag!("before \n {%  filter lower  %} \n body \n {%  endfilter  %} \n after", "before \n  \n body \n  \n after");
+    test_tag!("before \n {%  filter lower  %} \n body \n {%  endfilter -%} \n after", "before \n  \n body \n after");
+    test_tag!("before \n {%  filter lower  %} \n body \n {%- endfilter  %} \n after", "before \n  \n body \n after");
+    test_tag!("before \n {%  filter lower  %} \n body \n {%- endfilter -%} \n after", "before \n  \n bodyafter");
+    test_tag!("before \n {%  filter lower -%} \n body \n {%  endfilter  %} \n after", "before \n body \n  \n after");
+    test_tag!("before \n {%  filter lower -%} \n body \n {%  endfilter -%} \n after", "before \n body \n after");
+    test_tag!("before \n {%  filter lower -%} \n body \n {%- endfilter  %} \n after", "before \n body \n after");
+    test_tag!("before \n {%  filter lower -%} \n body \n {%- endfilter -%} \n after", "before \n bodyafter");
+    test_tag!("before \n {%- filter lower  %} \n body \n {%  endfilter  %} \n after", "before \n body \n  \n after");
+    test_tag!("before \n {%- filter lower  %} \n body \n {%  endfilter -%} \n after", "before \n body \n after");
+    test_tag!("before \n {%- filter lower  %} \n body \n {%- endfilter  %} \n after", "before \n body \n after");
+    test_tag!("before \n {%- filter lower  %} \n body \n {%- endfilter -%} \n after", "before \n bodyafter");
+    test_tag!("before \n {%- filter lower -%} \n body \n {%  endfilter  %} \n after", "beforebody \n  \n after");
+    test_tag!("before \n {%- filter lower -%} \n body \n {%  endfilter -%} \n after", "beforebody \n after");
+    test_tag!("before \n {%- filter lower -%} \n body \n {%- endfilter  %} \n after", "beforebody \n after");
+    test_tag!("before \n {%- filter lower -%} \n body \n {%- endfilter -%} \n after", "beforebodyafter");
+
+    test_tag!("before \n {%  autoescape false  %} \n body \n {%  endautoescape  %} \n after", "before \n  \n body \n  \n after");
+    test_tag!("before \n {%  autoescape false  %} \n body \n {%  endautoescape -%} \n after", "before \n  \n body \n after");
+    test_tag!("before \n {%  autoescape false  %} \n body \n {%- endautoescape  %} \n after", "before \n  \n body \n after");
+    test_tag!("before \n {%  autoescape false  %} \n body \n {%- endautoescape -%} \n after", "before \n  \n bodyafter");
+    test_tag!("before \n {%  autoescape false -%} \n body \n {%  endautoescape  %} \n after", "before \n body \n  \n after");
+    test_tag!("before \n {%  autoescape false -%} \n body \n {%  endautoescape -%} \n after", "before \n body \n after");
+    test_tag!("before \n {%  autoescape false -%} \n body \n {%- endautoescape  %} \n after", "before \n body \n after");
+    test_tag!("before \n {%  autoescape false -%} \n body \n {%- endautoescape -%} \n after", "before \n bodyafter");
+    test_tag!("before \n {%- autoescape false  %} \n body \n {%  endautoescape  %} \n after", "before \n body \n  \n after");
+    test_tag!("before \n {%- autoescape false  %} \n body \n {%  endautoescape -%} \n after", "before \n body \n after");
+    test_tag!("before \n {%- autoescape false  %} \n body \n {%- endautoescape  %} \n after", "before \n body \n after");
+    test_tag!("before \n {%- autoescape false  %} \n body \n {%- endautoescape -%} \n after", "before \n bodyafter");
+    test_tag!("before \n {%- autoescape false -%} \n body \n {%  endautoescape  %} \n after", "beforebody \n  \n after");
+    test_tag!("before \n {%- autoescape false -%} \n body \n {%  endautoescape -%} \n after", "beforebody \n after");
+    test_tag!("before \n {%- autoescape false -%} \n body \n {%- endautoescape  %} \n after", "beforebody \n after");
+    test_tag!("before \n {%- autoescape false -%} \n body \n {%- endautoescape -%} \n after", "beforebodyafter");
+
+    test_tag!("before \n {%  with x = 1  %} \n body \n {%  endwith  %} \n after", "before \n  \n body \n  \n after");
+    test_tag!("before \n {%  with x = 1  %} \n body \n {%  endwith -%} \n after", "before \n  \n body \n after");
+    test_tag!("before \n {%  with x = 1  %} \n body \n {%- endwith  %} \n after", "before \n  \n body \n after");
+    test_tag!("before \n {%  with x = 1  %} \n body \n {%- endwith -%} \n after", "before \n  \n bodyafter");
+    test_tag!("before \n {%  with x = 1 -%} \n body \n {%  endwith  %} \n after", "before \n body \n  \n after");
+    test_tag!("before \n {%  with x = 1 -%} \n body \n {%  endwith -%} \n after", "before \n body \n after");
+    test_tag!("before \n {%  with x = 1 -%} \n body \n {%- endwith  %} \n after", "before \n body \n after");
+    test_tag!("before \n {%  with x = 1 -%} \n body \n {%- endwith -%} \n after", "before \n bodyafter");
+    test_tag!("before \n {%- with x = 1  %} \n body \n {%  endwith  %} \n after", "before \n body \n  \n after");
+    test_tag!("before \n {%- with x = 1  %} \n body \n {%  endwith -%} \n after", "before \n body \n after");
+    test_tag!("before \n {%- with x = 1  %} \n body \n {%- endwith  %} \n after", "before \n body \n after");
+    test_tag!("before \n {%- with x = 1  %} \n body \n {%- endwith -%} \n after", "before \n bodyafter");
+    test_tag!("before \n {%- with x = 1 -%} \n body \n {%  endwith  %} \n after", "beforebody \n  \n after");
+    test_tag!("before \n {%- with x = 1 -%} \n body \n {%  endwith -%} \n after", "beforebody \n after");
+    test_tag!("before \n {%- with x = 1 -%} \n body \n {%- endwith  %} \n after", "beforebody \n after");
+    test_tag!("before \n {%- with x = 1 -%} \n body \n {%- endwith -%} \n after", "beforebodyafter");
+
+    test_tag!("before \n {%  block b  %} \n body \n {%  endblock  %} \n after", "before \n  \n body \n  \n after");
+    test_tag!("before \n {%  block b  %} \n body \n {%  endblock -%} \n after", "before \n  \n body \n after");
+    test_tag!("before \n {%  block b  %} \n body \n {%- endblock  %} \n after", "before \n  \n body \n after");
+    test_tag!("before \n {%  block b  %} \n body \n {%- endblock -%} \n after", "before \n  \n bodyafter");
+    test_tag!("before \n {%  block b -%} \n body \n {%  endblock  %} \n after", "before \n body \n  \n after");
+    test_tag!("before \n {%  block b -%} \n body \n {%  endblock -%} \n after", "before \n body \n after");
+    test_tag!("before \n {%  block b -%} \n body \n {%- endblock  %} \n after", "before \n body \n after");
+    test_tag!("before \n {%  block b -%} \n body \n {%- endblock -%} \n after", "before \n bodyafter");
+    test_tag!("before \n {%- block b  %} \n body \n {%  endblock  %} \n after", "before \n body \n  \n after");
+    test_tag!("before \n {%- block b  %} \n body \n {%  endblock -%} \n after", "before \n body \n after");
+    test_tag!("before \n {%- block b  %} \n body \n {%- endblock  %} \n after", "before \n body \n after");
+    test_tag!("before \n {%- block b  %} \n body \n {%- endblock -%} \n after", "before \n bodyafter");
+    test_tag!("before \n {%- block b -%} \n body \n {%  endblock  %} \n after", "beforebody \n  \n after");
+    test_tag!("before \n {%- block b -%} \n body \n {%  endblock -%} \n after", "beforebody \n after");
+    test_tag!("before \n {%- block b -%} \n body \n {%- endblock  %} \n after", "beforebody \n after");
+    test_tag!("before \n {%- block b -%} \n body \n {%- endblock -%} \n after", "beforebodyafter");
+
+    test_tag!("before \n {%  raw  %} \n body \n {%  endraw  %} \n after", "before \n  \n body \n  \n after");
+    test_tag!("before \n {%  raw  %} \n body \n {%  endraw -%} \n after", "before \n  \n body \n after");
+    test_tag!("before \n {%  raw  %} \n body \n {%- endraw  %} \n after", "before \n  \n body \n after");
+    test_tag!("before \n {%  raw  %} \n body \n {%- endraw -%} \n after", "before \n  \n bodyafter");
+    test_tag!("before \n {%  raw -%} \n body \n {%  endraw  %} \n after", "before \n body \n  \n after");
+    test_tag!("before \n {%  raw -%} \n body \n {%  endraw -%} \n after", "before \n body \n after");
+    test_tag!("before \n {%  raw -%} \n body \n {%- endraw  %} \n after", "before \n body \n after");
+    test_tag!("before \n {%  raw -%} \n body \n {%- endraw -%} \n after", "before \n bodyafter");
+    test_tag!("before \n {%- raw  %} \n body \n {%  endraw  %} \n after", "before \n body \n  \n after");
+    test_tag!("before \n {%- raw  %} \n body \n {%  endraw -%} \n after", "before \n body \n after");
+    test_tag!("before \n {%- raw  %} \n body \n {%- endraw  %} \n after", "before \n body \n after");
+    test_tag!("before \n {%- raw  %} \n body \n {%- endraw -%} \n after", "before \n bodyafter");
+    test_tag!("before \n {%- raw -%} \n body \n {%  endraw  %} \n after", "beforebody \n  \n after");
+    test_tag!("before \n {%- raw -%} \n body \n {%  endraw -%} \n after", "beforebody \n after");
+    test_tag!("before \n {%- raw -%} \n body \n {%- endraw  %} \n after", "beforebody \n after");
+    test_tag!("before \n {%- raw -%} \n body \n {%- endraw -%} \n after", "beforebodyafter");
+
+    test_tag!("before \n {%  let x = 1  %} \n after{% macro m() %}{% endmacro %}", "before \n  \n after");
+    test_tag!("before \n {%  let x = 1 -%} \n after{% macro m() %}{% endmacro %}", "before \n after");
+    test_tag!("before \n {%- let x = 1  %} \n after{% macro m() %}{% endmacro %}", "before \n after");
+    test_tag!("before \n {%- let x = 1 -%} \n after{% macro m() %}{% endmacro %}", "beforeafter");
+
+    test_tag!("before \n {%  call m()  %} \n after{% macro m() %}{% endmacro %}", "before \n  \n after");
+    test_tag!("before \n {%  call m() -%} \n after{% macro m() %}{% endmacro %}", "before \n after");
+    test_tag!("before \n {%- call m()  %} \n after{% macro m() %}{% endmacro %}", "before \n after");
+    test_tag!("before \n {%- call m() -%} \n after{% macro m() %}{% endmacro %}", "beforeafter");
+}